@@ -0,0 +1,104 @@
+//! Auto Gain
+//!
+//! Loudness match between the plugin's input and output: both levels are
+//! tracked as a rolling RMS, and a slow servo trims the output so that
+//! switching patches doesn't jump in level.
+
+/// Length of the rolling RMS window in seconds
+const WINDOW_SECONDS: f32 = 3.0;
+
+/// Time the trim takes to settle, in seconds
+const SERVO_SECONDS: f32 = 2.0;
+
+/// Largest correction either way, in dB
+const MAX_TRIM_DB: f32 = 12.0;
+
+/// Input level below which the trim holds (silence says nothing about
+/// the patch's gain)
+const GATE_DB: f32 = -60.0;
+
+/// Rolling RMS comparison with a gain servo
+pub struct AutoGain {
+    input_ms: f32,
+    output_ms: f32,
+    window_coeff: f32,
+    servo_coeff: f32,
+    /// Mean square of `GATE_DB` and linear `MAX_TRIM_DB`
+    gate: f32,
+    limit: f32,
+    /// Current trim (linear)
+    gain: f32,
+}
+
+impl AutoGain {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut auto_gain = Self {
+            input_ms: 0.0,
+            output_ms: 0.0,
+            window_coeff: 0.0,
+            servo_coeff: 0.0,
+            gate: 10.0_f32.powf(GATE_DB / 10.0),
+            limit: 10.0_f32.powf(MAX_TRIM_DB / 20.0),
+            gain: 1.0,
+        };
+        auto_gain.set_sample_rate(sample_rate);
+        auto_gain
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.window_coeff = (-1.0 / (WINDOW_SECONDS * sample_rate)).exp();
+        self.servo_coeff = (-1.0 / (SERVO_SECONDS * sample_rate)).exp();
+    }
+
+    /// Track one input/output sample pair (mono sums) and return the trim
+    /// to apply to the output
+    #[inline]
+    pub fn process(&mut self, input: f32, output: f32) -> f32 {
+        self.input_ms = input * input + self.window_coeff * (self.input_ms - input * input);
+        self.output_ms = output * output + self.window_coeff * (self.output_ms - output * output);
+
+        if self.input_ms > self.gate && self.output_ms > 0.0 {
+            let target = (self.input_ms / self.output_ms)
+                .sqrt()
+                .clamp(1.0 / self.limit, self.limit);
+            self.gain = target + self.servo_coeff * (self.gain - target);
+        }
+        self.gain
+    }
+
+    /// Current trim in dB
+    pub fn trim_db(&self) -> f32 {
+        20.0 * self.gain.log10()
+    }
+
+    pub fn reset(&mut self) {
+        self.input_ms = 0.0;
+        self.output_ms = 0.0;
+        self.gain = 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_levels() {
+        let mut auto_gain = AutoGain::new(1000.0);
+        // Output 6 dB hotter than the input
+        for i in 0..20_000 {
+            let input = if i % 2 == 0 { 0.25 } else { -0.25 };
+            auto_gain.process(input, input * 2.0);
+        }
+        assert!((auto_gain.trim_db() + 6.02).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_holds_in_silence() {
+        let mut auto_gain = AutoGain::new(1000.0);
+        for _ in 0..20_000 {
+            auto_gain.process(0.0, 0.5);
+        }
+        assert_eq!(auto_gain.trim_db(), 0.0);
+    }
+}
@@ -142,6 +142,19 @@ impl DspModule for Delay {
         input.mix(delayed, self.mix)
     }
 
+    /// Echoes until they fall below -60 dB (the high-cut only shortens it)
+    fn tail(&self) -> usize {
+        if self.bypassed {
+            return 0;
+        }
+        let repeats = if self.feedback > 0.0 {
+            1.0 + (-3.0 / self.feedback.log10()).ceil()
+        } else {
+            1.0
+        };
+        (self.delay_samples * repeats) as usize
+    }
+
     fn set_sample_rate(&mut self, rate: f32) {
         if (rate - self.sample_rate).abs() > 0.1 {
             self.sample_rate = rate;
@@ -196,6 +209,21 @@ mod tests {
         assert!(out.left > 0.9);
     }
 
+    #[test]
+    fn test_tail() {
+        let mut delay = Delay::new(1000.0);
+        delay.set_time_ms(100.0);
+        delay.set_feedback(0.0);
+        assert_eq!(delay.tail(), 100);
+
+        // -40 dB per repeat reaches -60 dB after 2 more echoes
+        delay.set_feedback(0.01);
+        assert_eq!(delay.tail(), 300);
+
+        delay.set_bypassed(true);
+        assert_eq!(delay.tail(), 0);
+    }
+
     #[test]
     fn test_delay_feedback() {
         let mut delay = Delay::new(44100.0);
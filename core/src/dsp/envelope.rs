@@ -0,0 +1,94 @@
+//! Envelope Follower
+//!
+//! Tracks the level of the input with separate attack and release times,
+//! for use as a modulation source.
+
+/// Peak envelope follower (one-pole smoothing of the rectified input)
+pub struct EnvelopeFollower {
+    level: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    sample_rate: f32,
+}
+
+impl EnvelopeFollower {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut follower = Self {
+            level: 0.0,
+            attack_coeff: 0.0,
+            release_coeff: 0.0,
+            attack_ms: 5.0,
+            release_ms: 150.0,
+            sample_rate,
+        };
+        follower.update_coeffs();
+        follower
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.update_coeffs();
+    }
+
+    /// Attack and release times in milliseconds
+    pub fn set_times(&mut self, attack_ms: f32, release_ms: f32) {
+        if attack_ms != self.attack_ms || release_ms != self.release_ms {
+            self.attack_ms = attack_ms;
+            self.release_ms = release_ms;
+            self.update_coeffs();
+        }
+    }
+
+    fn update_coeffs(&mut self) {
+        let coeff = |ms: f32| (-1.0 / (ms.max(0.01) * 0.001 * self.sample_rate)).exp();
+        self.attack_coeff = coeff(self.attack_ms);
+        self.release_coeff = coeff(self.release_ms);
+    }
+
+    #[inline]
+    pub fn process(&mut self, input: f32) {
+        let rectified = input.abs();
+        let coeff = if rectified > self.level {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+        self.level = rectified + coeff * (self.level - rectified);
+    }
+
+    /// Current envelope level (linear)
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    pub fn reset(&mut self) {
+        self.level = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attack_and_release() {
+        let mut follower = EnvelopeFollower::new(1000.0);
+        follower.set_times(1.0, 100.0);
+
+        for _ in 0..20 {
+            follower.process(0.8);
+        }
+        assert!((follower.level() - 0.8).abs() < 0.01);
+
+        // Release is much slower than attack
+        for _ in 0..20 {
+            follower.process(0.0);
+        }
+        assert!(follower.level() > 0.6);
+
+        follower.reset();
+        assert_eq!(follower.level(), 0.0);
+    }
+}
@@ -29,6 +29,25 @@ impl Default for BiquadCoeffs {
     }
 }
 
+impl BiquadCoeffs {
+    /// Magnitude response in dB at `freq`
+    fn magnitude_db(&self, freq: f32, sample_rate: f32) -> f32 {
+        // Evaluate H(z) on the unit circle: z^-1 = e^-jw
+        let w = 2.0 * PI * freq / sample_rate;
+        let (sin1, cos1) = w.sin_cos();
+        let (sin2, cos2) = (2.0 * w).sin_cos();
+
+        let num_re = self.b0 + self.b1 * cos1 + self.b2 * cos2;
+        let num_im = -self.b1 * sin1 - self.b2 * sin2;
+        let den_re = 1.0 + self.a1 * cos1 + self.a2 * cos2;
+        let den_im = -self.a1 * sin1 - self.a2 * sin2;
+
+        let num = num_re * num_re + num_im * num_im;
+        let den = den_re * den_re + den_im * den_im;
+        10.0 * (num / den).log10()
+    }
+}
+
 /// Stereo biquad filter state
 #[derive(Default)]
 struct BiquadState {
@@ -139,6 +158,41 @@ fn calc_peak(freq: f32, gain_db: f32, q: f32, sample_rate: f32) -> BiquadCoeffs
     }
 }
 
+/// Band settings, for drawing the response outside the audio thread
+#[derive(Clone, Copy)]
+pub struct EqBands {
+    pub low_freq: f32,
+    pub low_gain: f32,
+    pub mid_freq: f32,
+    pub mid_gain: f32,
+    pub mid_q: f32,
+    pub high_freq: f32,
+    pub high_gain: f32,
+    pub low_enabled: bool,
+    pub mid_enabled: bool,
+    pub high_enabled: bool,
+}
+
+impl EqBands {
+    /// Combined magnitude response of the enabled bands in dB
+    pub fn response_db(&self, freq: f32, sample_rate: f32) -> f32 {
+        let mut db = 0.0;
+        if self.low_enabled {
+            db += calc_low_shelf(self.low_freq, self.low_gain, sample_rate)
+                .magnitude_db(freq, sample_rate);
+        }
+        if self.mid_enabled {
+            db += calc_peak(self.mid_freq, self.mid_gain, self.mid_q, sample_rate)
+                .magnitude_db(freq, sample_rate);
+        }
+        if self.high_enabled {
+            db += calc_high_shelf(self.high_freq, self.high_gain, sample_rate)
+                .magnitude_db(freq, sample_rate);
+        }
+        db
+    }
+}
+
 /// 3-Band EQ: Low Shelf + Mid Peak + High Shelf
 pub struct Eq {
     // Filter states
@@ -160,6 +214,11 @@ pub struct Eq {
     high_freq: f32,
     high_gain: f32,
 
+    // Disabled bands skip their biquad entirely
+    low_enabled: bool,
+    mid_enabled: bool,
+    high_enabled: bool,
+
     // State
     sample_rate: f32,
     bypassed: bool,
@@ -182,6 +241,9 @@ impl Eq {
             mid_q: 1.0,
             high_freq: 4000.0,
             high_gain: 0.0,
+            low_enabled: true,
+            mid_enabled: true,
+            high_enabled: true,
             sample_rate,
             bypassed: false,
             coeffs_dirty: true,
@@ -253,6 +315,31 @@ impl Eq {
         }
     }
 
+    /// Enable or disable the low shelf
+    pub fn set_low_enabled(&mut self, enabled: bool) {
+        if enabled && !self.low_enabled {
+            // Don't resume from the history left when it was switched off
+            self.low_state.reset();
+        }
+        self.low_enabled = enabled;
+    }
+
+    /// Enable or disable the mid peak
+    pub fn set_mid_enabled(&mut self, enabled: bool) {
+        if enabled && !self.mid_enabled {
+            self.mid_state.reset();
+        }
+        self.mid_enabled = enabled;
+    }
+
+    /// Enable or disable the high shelf
+    pub fn set_high_enabled(&mut self, enabled: bool) {
+        if enabled && !self.high_enabled {
+            self.high_state.reset();
+        }
+        self.high_enabled = enabled;
+    }
+
     /// Recalculate filter coefficients if parameters changed
     fn update_coefficients(&mut self) {
         if !self.coeffs_dirty {
@@ -272,10 +359,36 @@ impl DspModule for Eq {
         // Update coefficients if needed
         self.update_coefficients();
 
-        // Process through all three bands in series
-        let after_low = self.low_state.process(input, &self.low_coeffs);
-        let after_mid = self.mid_state.process(after_low, &self.mid_coeffs);
-        self.high_state.process(after_mid, &self.high_coeffs)
+        // Process through the enabled bands in series
+        let mut output = input;
+        if self.low_enabled {
+            output = self.low_state.process(output, &self.low_coeffs);
+        }
+        if self.mid_enabled {
+            output = self.mid_state.process(output, &self.mid_coeffs);
+        }
+        if self.high_enabled {
+            output = self.high_state.process(output, &self.high_coeffs);
+        }
+        output
+    }
+
+    /// One band at a time over the whole block
+    fn process_block(&mut self, block: &mut [StereoSample]) {
+        self.update_coefficients();
+
+        let bands = [
+            (self.low_enabled, &mut self.low_state, &self.low_coeffs),
+            (self.mid_enabled, &mut self.mid_state, &self.mid_coeffs),
+            (self.high_enabled, &mut self.high_state, &self.high_coeffs),
+        ];
+        for (enabled, state, coeffs) in bands {
+            if enabled {
+                for sample in block.iter_mut() {
+                    *sample = state.process(*sample, coeffs);
+                }
+            }
+        }
     }
 
     fn set_sample_rate(&mut self, rate: f32) {
@@ -342,6 +455,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_block_matches_samples() {
+        let mut per_sample = Eq::new(44100.0);
+        let mut per_block = Eq::new(44100.0);
+        for eq in [&mut per_sample, &mut per_block] {
+            eq.set_low_gain(6.0);
+            eq.set_mid_gain(-4.0);
+            eq.set_high_gain(3.0);
+        }
+
+        let mut block: Vec<StereoSample> = (0..256)
+            .map(|n| StereoSample::new((n as f32 * 0.1).sin(), (n as f32 * 0.37).cos()))
+            .collect();
+        let expected: Vec<StereoSample> = block.iter().map(|&s| per_sample.process(s)).collect();
+        per_block.process_block(&mut block);
+
+        for (got, want) in block.iter().zip(&expected) {
+            assert!((got.left - want.left).abs() < 1e-6);
+            assert!((got.right - want.right).abs() < 1e-6);
+        }
+    }
+
     #[test]
     fn test_bypass() {
         let mut eq = Eq::new(44100.0);
@@ -356,4 +491,43 @@ mod tests {
         // Bypassed, output should equal input
         assert!((output.left - input.left).abs() < 0.001);
     }
+
+    #[test]
+    fn test_disabled_bands_pass_through() {
+        let mut eq = Eq::new(44100.0);
+        eq.set_low_gain(12.0);
+        eq.set_mid_gain(12.0);
+        eq.set_high_gain(12.0);
+        eq.set_low_enabled(false);
+        eq.set_mid_enabled(false);
+        eq.set_high_enabled(false);
+
+        let input = StereoSample::new(0.5, -0.25);
+        let output = eq.process(input);
+        assert_eq!((output.left, output.right), (input.left, input.right));
+    }
+
+    #[test]
+    fn test_response_matches_band_gains() {
+        let bands = EqBands {
+            low_freq: 200.0,
+            low_gain: 0.0,
+            mid_freq: 1000.0,
+            mid_gain: 6.0,
+            mid_q: 1.0,
+            high_freq: 4000.0,
+            high_gain: 0.0,
+            low_enabled: true,
+            mid_enabled: true,
+            high_enabled: true,
+        };
+        assert!((bands.response_db(1000.0, 44100.0) - 6.0).abs() < 0.1);
+        assert!(bands.response_db(50.0, 44100.0).abs() < 0.5);
+
+        let flat = EqBands {
+            mid_enabled: false,
+            ..bands
+        };
+        assert!(flat.response_db(1000.0, 44100.0).abs() < 0.1);
+    }
 }
@@ -0,0 +1,106 @@
+//! Small radix-2 FFT for the spectrum analyzer
+//!
+//! Runs on the GUI thread only, so plain allocation is fine here.
+
+use std::f32::consts::PI;
+
+/// In-place complex FFT; the length must be a power of two
+pub fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    // Butterflies
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        let (w_im, w_re) = angle.sin_cos();
+        for start in (0..n).step_by(len) {
+            let (mut cur_re, mut cur_im) = (1.0f32, 0.0f32);
+            for k in 0..len / 2 {
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * cur_re - im[b] * cur_im;
+                let t_im = re[b] * cur_im + im[b] * cur_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+
+                let next_re = cur_re * w_re - cur_im * w_im;
+                cur_im = cur_re * w_im + cur_im * w_re;
+                cur_re = next_re;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Hann-windowed magnitude spectrum of real samples (bins 0..n/2)
+///
+/// Scaled so a full-scale sine on a bin centre reads 1.0.
+pub fn magnitude_spectrum(samples: &[f32]) -> Vec<f32> {
+    let n = samples.len();
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| x * (0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+
+    // Hann window has a coherent gain of 0.5; a real sine splits over +/- bins
+    let scale = 4.0 / n as f32;
+    re.iter()
+        .zip(&im)
+        .take(n / 2)
+        .map(|(r, i)| (r * r + i * i).sqrt() * scale)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impulse_is_flat() {
+        let mut re = vec![0.0; 8];
+        let mut im = vec![0.0; 8];
+        re[0] = 1.0;
+        fft(&mut re, &mut im);
+        assert!(re.iter().all(|&x| (x - 1.0).abs() < 1e-6));
+        assert!(im.iter().all(|&x| x.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_sine_peaks_at_its_bin() {
+        let n = 1024;
+        let bin = 37;
+        let samples: Vec<f32> = (0..n)
+            .map(|i| (2.0 * PI * bin as f32 * i as f32 / n as f32).sin())
+            .collect();
+        let spectrum = magnitude_spectrum(&samples);
+
+        let peak = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, _)| index);
+        assert_eq!(peak, Some(bin));
+        assert!((spectrum[bin] - 1.0).abs() < 0.01);
+    }
+}
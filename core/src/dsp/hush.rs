@@ -0,0 +1,156 @@
+//! Panic/Hush Gain Ramp
+//!
+//! Fades the wet signal to silence when a live-coding accident happens,
+//! holds it silent, and fades it back in once released.
+
+use super::{DspModule, StereoSample};
+
+/// Fade-out time when hushing
+const FADE_OUT_MS: f32 = 200.0;
+
+/// Fade-in time when releasing
+const FADE_IN_MS: f32 = 20.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum HushState {
+    /// Passing audio at unity gain
+    Open,
+    /// Ramping down to silence
+    FadingOut,
+    /// Fully silent, waiting for release
+    Silent,
+    /// Ramping back up to unity
+    FadingIn,
+}
+
+/// Wet-path gain ramp used by the panic control
+pub struct Hush {
+    gain: f32,
+    fade_out_step: f32,
+    fade_in_step: f32,
+    state: HushState,
+    /// Set when the fade-out reaches silence, cleared by `take_silenced()`
+    just_silenced: bool,
+    bypassed: bool,
+}
+
+impl Hush {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut hush = Self {
+            gain: 1.0,
+            fade_out_step: 0.0,
+            fade_in_step: 0.0,
+            state: HushState::Open,
+            just_silenced: false,
+            bypassed: false,
+        };
+        hush.set_sample_rate(sample_rate);
+        hush
+    }
+
+    /// Start fading to silence
+    pub fn trigger(&mut self) {
+        if matches!(self.state, HushState::Open | HushState::FadingIn) {
+            self.state = HushState::FadingOut;
+        }
+    }
+
+    /// Fade back in after a panic
+    pub fn release(&mut self) {
+        if matches!(self.state, HushState::FadingOut | HushState::Silent) {
+            self.state = HushState::FadingIn;
+        }
+    }
+
+    /// Returns true once when the fade-out has just reached silence
+    ///
+    /// This is the moment to clear buffers without audible clicks.
+    pub fn take_silenced(&mut self) -> bool {
+        std::mem::take(&mut self.just_silenced)
+    }
+}
+
+impl DspModule for Hush {
+    fn process(&mut self, input: StereoSample) -> StereoSample {
+        match self.state {
+            HushState::Open => return input,
+            HushState::FadingOut => {
+                self.gain -= self.fade_out_step;
+                if self.gain <= 0.0 {
+                    self.gain = 0.0;
+                    self.state = HushState::Silent;
+                    self.just_silenced = true;
+                }
+            }
+            HushState::Silent => self.gain = 0.0,
+            HushState::FadingIn => {
+                self.gain += self.fade_in_step;
+                if self.gain >= 1.0 {
+                    self.gain = 1.0;
+                    self.state = HushState::Open;
+                }
+            }
+        }
+
+        StereoSample::new(input.left * self.gain, input.right * self.gain)
+    }
+
+    fn set_sample_rate(&mut self, rate: f32) {
+        self.fade_out_step = 1000.0 / (FADE_OUT_MS * rate);
+        self.fade_in_step = 1000.0 / (FADE_IN_MS * rate);
+    }
+
+    fn reset(&mut self) {
+        // A transport reset clears any panic
+        self.gain = 1.0;
+        self.state = HushState::Open;
+        self.just_silenced = false;
+    }
+
+    fn is_bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    fn set_bypassed(&mut self, bypassed: bool) {
+        self.bypassed = bypassed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hush_fades_to_silence() {
+        let mut hush = Hush::new(44100.0);
+        hush.trigger();
+
+        // 200ms at 44.1kHz = 8820 samples
+        let mut last = 1.0;
+        for _ in 0..8820 {
+            let out = hush.process(StereoSample::new(1.0, 1.0));
+            assert!(out.left <= last);
+            last = out.left;
+        }
+        let out = hush.process(StereoSample::new(1.0, 1.0));
+        assert_eq!(out.left, 0.0);
+        assert!(hush.take_silenced());
+        assert!(!hush.take_silenced());
+    }
+
+    #[test]
+    fn test_release_restores_unity() {
+        let mut hush = Hush::new(44100.0);
+        hush.trigger();
+        for _ in 0..10000 {
+            hush.process(StereoSample::new(1.0, 1.0));
+        }
+        hush.release();
+        for _ in 0..1000 {
+            hush.process(StereoSample::new(1.0, 1.0));
+        }
+        let out = hush.process(StereoSample::new(0.5, 0.5));
+        assert!((out.left - 0.5).abs() < 0.001);
+        assert_eq!(hush.state, HushState::Open);
+    }
+}
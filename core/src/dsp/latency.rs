@@ -0,0 +1,79 @@
+//! Latency compensation for the dry path
+//!
+//! The wet signal comes back from Glicol a few samples late (the buffer
+//! bridge waits for whole 128-sample blocks). Delaying the dry signal by the
+//! same amount keeps the dry/wet mix phase-coherent.
+
+use super::StereoSample;
+
+/// Longest delay the line can hold, in samples
+pub const MAX_LATENCY: usize = 4096;
+
+/// Whole-sample stereo delay line
+pub struct LatencyDelay {
+    buffer: Vec<StereoSample>,
+    write_pos: usize,
+    delay: usize,
+}
+
+impl LatencyDelay {
+    pub fn new() -> Self {
+        Self {
+            buffer: vec![StereoSample::default(); MAX_LATENCY + 1],
+            write_pos: 0,
+            delay: 0,
+        }
+    }
+
+    /// Set the delay in samples (clamped to `MAX_LATENCY`)
+    pub fn set_delay(&mut self, samples: usize) {
+        self.delay = samples.min(MAX_LATENCY);
+    }
+
+    /// Current delay in samples
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    /// Push one sample and return the one from `delay` samples ago
+    pub fn process(&mut self, input: StereoSample) -> StereoSample {
+        let len = self.buffer.len();
+        self.buffer[self.write_pos] = input;
+        let read_pos = (self.write_pos + len - self.delay) % len;
+        self.write_pos = (self.write_pos + 1) % len;
+        self.buffer[read_pos]
+    }
+
+    pub fn reset(&mut self) {
+        self.buffer.fill(StereoSample::default());
+        self.write_pos = 0;
+    }
+}
+
+impl Default for LatencyDelay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delays_by_whole_samples() {
+        let mut line = LatencyDelay::new();
+        line.set_delay(3);
+        let output: Vec<f32> = (1..=6)
+            .map(|i| line.process(StereoSample::from_mono(i as f32)).left)
+            .collect();
+        assert_eq!(output, vec![0.0, 0.0, 0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_zero_delay_passes_through() {
+        let mut line = LatencyDelay::new();
+        let output = line.process(StereoSample::new(0.5, -0.5));
+        assert_eq!((output.left, output.right), (0.5, -0.5));
+    }
+}
@@ -0,0 +1,120 @@
+//! Output Limiter
+//!
+//! Stereo-linked peak limiter with instant attack and no lookahead, so it
+//! adds no latency. Engaged by safe mode as a last line of defence against
+//! runaway feedback in a patch. NaN and infinite samples are replaced with
+//! silence rather than let through.
+
+use super::{DspModule, StereoSample};
+
+/// Output ceiling in dBFS
+const CEILING_DB: f32 = -1.0;
+
+/// Gain recovery time in milliseconds
+const RELEASE_MS: f32 = 100.0;
+
+/// Peak limiter on the final output
+pub struct Limiter {
+    ceiling: f32,
+    /// Current gain reduction (1.0 = none)
+    gain: f32,
+    release_coeff: f32,
+    bypassed: bool,
+}
+
+impl Limiter {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut limiter = Self {
+            ceiling: 10.0_f32.powf(CEILING_DB / 20.0),
+            gain: 1.0,
+            release_coeff: 0.0,
+            bypassed: true,
+        };
+        limiter.set_sample_rate(sample_rate);
+        limiter
+    }
+}
+
+impl DspModule for Limiter {
+    fn process(&mut self, input: StereoSample) -> StereoSample {
+        // A broken sample is silenced and doesn't move the gain
+        let finite = |sample: f32| if sample.is_finite() { sample } else { 0.0 };
+        let input = StereoSample::new(finite(input.left), finite(input.right));
+        let peak = input.left.abs().max(input.right.abs());
+        let target = if peak > self.ceiling {
+            self.ceiling / peak
+        } else {
+            1.0
+        };
+        // Clamp down at once, recover slowly
+        self.gain = if target < self.gain {
+            target
+        } else {
+            target + self.release_coeff * (self.gain - target)
+        };
+        StereoSample::new(
+            (input.left * self.gain).clamp(-self.ceiling, self.ceiling),
+            (input.right * self.gain).clamp(-self.ceiling, self.ceiling),
+        )
+    }
+
+    fn set_sample_rate(&mut self, rate: f32) {
+        self.release_coeff = (-1.0 / (RELEASE_MS * 0.001 * rate)).exp();
+    }
+
+    fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+
+    fn is_bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    fn set_bypassed(&mut self, bypassed: bool) {
+        self.bypassed = bypassed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holds_ceiling() {
+        let mut limiter = Limiter::new(44100.0);
+        limiter.set_bypassed(false);
+        for _ in 0..100 {
+            let output = limiter.process_with_bypass(StereoSample::new(4.0, -2.0));
+            assert!(output.left.abs() <= limiter.ceiling + 1e-6);
+            assert!(output.right.abs() <= limiter.ceiling + 1e-6);
+        }
+
+        // Quiet signals pass once the gain has recovered
+        for _ in 0..44100 {
+            limiter.process(StereoSample::new(0.1, 0.1));
+        }
+        let output = limiter.process(StereoSample::new(0.5, 0.5));
+        assert!((output.left - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_silences_nan_and_infinity() {
+        let mut limiter = Limiter::new(44100.0);
+        limiter.set_bypassed(false);
+        let output = limiter.process_with_bypass(StereoSample::new(f32::NAN, 0.5));
+        assert_eq!((output.left, output.right), (0.0, 0.5));
+        let output = limiter.process_with_bypass(StereoSample::new(0.5, f32::INFINITY));
+        assert_eq!((output.left, output.right), (0.5, 0.0));
+        let output = limiter.process_with_bypass(StereoSample::new(f32::NEG_INFINITY, f32::NAN));
+        assert_eq!((output.left, output.right), (0.0, 0.0));
+        // The gain wasn't pulled down by them
+        assert_eq!(limiter.gain, 1.0);
+    }
+
+    #[test]
+    fn test_bypassed_by_default() {
+        let mut limiter = Limiter::new(44100.0);
+        let output = limiter.process_with_bypass(StereoSample::new(4.0, 4.0));
+        assert_eq!(output.left, 4.0);
+    }
+}
@@ -0,0 +1,339 @@
+//! DSP Module Framework
+//!
+//! Provides the trait and utilities for building stereo DSP processing modules.
+//! Each module can be bypassed independently and processes stereo audio.
+
+pub mod auto_gain;
+pub mod delay;
+pub mod envelope;
+pub mod eq;
+pub mod fft;
+pub mod hush;
+pub mod latency;
+pub mod limiter;
+pub mod oversampler;
+pub mod pitch;
+pub mod tail;
+pub mod test_signal;
+
+use serde::{Deserialize, Serialize};
+
+/// Stereo audio sample
+#[derive(Clone, Copy, Default)]
+pub struct StereoSample {
+    pub left: f32,
+    pub right: f32,
+}
+
+impl StereoSample {
+    pub fn new(left: f32, right: f32) -> Self {
+        Self { left, right }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_mono(value: f32) -> Self {
+        Self {
+            left: value,
+            right: value,
+        }
+    }
+
+    /// Scale the side signal: 0.0 is mono, 1.0 unchanged, above 1.0 wider
+    pub fn with_width(&self, width: f32) -> StereoSample {
+        let mid = (self.left + self.right) * 0.5;
+        let side = (self.left - self.right) * 0.5 * width;
+        StereoSample {
+            left: mid + side,
+            right: mid - side,
+        }
+    }
+
+    /// Average of both channels, for mono outputs. Keeps the level of a
+    /// centred signal and can't clip where the channels didn't.
+    pub fn to_mono(&self) -> f32 {
+        (self.left + self.right) * 0.5
+    }
+
+    pub fn mix(&self, other: StereoSample, wet: f32) -> StereoSample {
+        let dry = 1.0 - wet;
+        StereoSample {
+            left: self.left * dry + other.left * wet,
+            right: self.right * dry + other.right * wet,
+        }
+    }
+}
+
+/// Trait for stereo DSP processing modules
+pub trait DspModule: Send {
+    /// Process a single stereo sample
+    fn process(&mut self, input: StereoSample) -> StereoSample;
+
+    /// Set the sample rate (called when audio config changes)
+    fn set_sample_rate(&mut self, rate: f32);
+
+    /// Reset internal state (called on transport stop, etc.)
+    fn reset(&mut self);
+
+    /// Check if module is bypassed
+    fn is_bypassed(&self) -> bool;
+
+    /// Set bypass state
+    fn set_bypassed(&mut self, bypassed: bool);
+
+    /// Delay the module adds in samples (lookahead, oversampling filters)
+    fn latency(&self) -> usize {
+        0
+    }
+
+    /// How long the module keeps sounding after its input stops, in
+    /// samples, with the current settings
+    fn tail(&self) -> usize {
+        0
+    }
+
+    /// Process a block in place. Override when per-block work (coefficient
+    /// updates, branching on settings) can be hoisted out of the sample loop.
+    fn process_block(&mut self, block: &mut [StereoSample]) {
+        for sample in block {
+            *sample = self.process(*sample);
+        }
+    }
+
+    /// Process with automatic bypass handling
+    fn process_with_bypass(&mut self, input: StereoSample) -> StereoSample {
+        if self.is_bypassed() {
+            input
+        } else {
+            self.process(input)
+        }
+    }
+
+    /// Block version of `process_with_bypass`
+    fn process_block_with_bypass(&mut self, block: &mut [StereoSample]) {
+        if !self.is_bypassed() {
+            self.process_block(block);
+        }
+    }
+}
+
+/// Modules of the signal path that can be reordered
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ModuleKind {
+    Eq,
+    Glicol,
+    Delay,
+}
+
+impl ModuleKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ModuleKind::Eq => "EQ",
+            ModuleKind::Glicol => "Glicol",
+            ModuleKind::Delay => "Delay",
+        }
+    }
+}
+
+/// Processing order of the modules
+///
+/// Modules before Glicol run on the mono input (and the dry signal), the
+/// ones after it on Glicol's stereo output.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleOrder(pub [ModuleKind; 3]);
+
+impl Default for ModuleOrder {
+    fn default() -> Self {
+        Self([ModuleKind::Eq, ModuleKind::Glicol, ModuleKind::Delay])
+    }
+}
+
+impl ModuleOrder {
+    /// Modules before and after Glicol
+    pub fn split(&self) -> (&[ModuleKind], &[ModuleKind]) {
+        let glicol = self
+            .0
+            .iter()
+            .position(|&kind| kind == ModuleKind::Glicol)
+            .unwrap_or(0);
+        (&self.0[..glicol], &self.0[glicol + 1..])
+    }
+
+    /// Move the module at `from` to position `to`, shifting the others
+    pub fn move_module(&mut self, from: usize, to: usize) {
+        if from >= self.0.len() || to >= self.0.len() {
+            return;
+        }
+        let kind = self.0[from];
+        if from < to {
+            self.0.copy_within(from + 1..=to, from);
+        } else {
+            self.0.copy_within(to..from, to + 1);
+        }
+        self.0[to] = kind;
+    }
+}
+
+/// Chain of DSP modules processed in series
+#[allow(dead_code)]
+pub struct ModuleChain {
+    modules: Vec<Box<dyn DspModule>>,
+}
+
+#[allow(dead_code)]
+impl ModuleChain {
+    pub fn new() -> Self {
+        Self {
+            modules: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, module: Box<dyn DspModule>) {
+        self.modules.push(module);
+    }
+
+    pub fn process(&mut self, input: StereoSample) -> StereoSample {
+        let mut sample = input;
+        for module in &mut self.modules {
+            sample = module.process_with_bypass(sample);
+        }
+        sample
+    }
+
+    pub fn set_sample_rate(&mut self, rate: f32) {
+        for module in &mut self.modules {
+            module.set_sample_rate(rate);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for module in &mut self.modules {
+            module.reset();
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Default for ModuleChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestModule {
+        gain: f32,
+        bypassed: bool,
+    }
+
+    impl DspModule for TestModule {
+        fn process(&mut self, input: StereoSample) -> StereoSample {
+            StereoSample {
+                left: input.left * self.gain,
+                right: input.right * self.gain,
+            }
+        }
+
+        fn set_sample_rate(&mut self, _rate: f32) {}
+        fn reset(&mut self) {}
+        fn is_bypassed(&self) -> bool {
+            self.bypassed
+        }
+        fn set_bypassed(&mut self, bypassed: bool) {
+            self.bypassed = bypassed;
+        }
+    }
+
+    #[test]
+    fn test_module_chain() {
+        let mut chain = ModuleChain::new();
+        chain.add(Box::new(TestModule {
+            gain: 0.5,
+            bypassed: false,
+        }));
+        chain.add(Box::new(TestModule {
+            gain: 2.0,
+            bypassed: false,
+        }));
+
+        let input = StereoSample::new(1.0, 1.0);
+        let output = chain.process(input);
+
+        // 1.0 * 0.5 * 2.0 = 1.0
+        assert!((output.left - 1.0).abs() < 0.001);
+        assert!((output.right - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_process_block() {
+        let mut module = TestModule {
+            gain: 0.5,
+            bypassed: false,
+        };
+        let mut block = [StereoSample::new(1.0, -1.0); 4];
+        module.process_block_with_bypass(&mut block);
+        assert!(block.iter().all(|s| (s.left, s.right) == (0.5, -0.5)));
+
+        module.set_bypassed(true);
+        module.process_block_with_bypass(&mut block);
+        assert!(block.iter().all(|s| (s.left, s.right) == (0.5, -0.5)));
+    }
+
+    #[test]
+    fn test_width() {
+        let sample = StereoSample::new(1.0, 0.0);
+
+        let mono = sample.with_width(0.0);
+        assert_eq!((mono.left, mono.right), (0.5, 0.5));
+
+        let unchanged = sample.with_width(1.0);
+        assert_eq!((unchanged.left, unchanged.right), (1.0, 0.0));
+
+        let wide = sample.with_width(2.0);
+        assert_eq!((wide.left, wide.right), (1.5, -0.5));
+
+        assert_eq!(sample.to_mono(), mono.left);
+    }
+
+    #[test]
+    fn test_module_order() {
+        let mut order = ModuleOrder::default();
+        assert_eq!(
+            order.split(),
+            (&[ModuleKind::Eq][..], &[ModuleKind::Delay][..])
+        );
+
+        order.move_module(2, 0);
+        assert_eq!(
+            order.0,
+            [ModuleKind::Delay, ModuleKind::Eq, ModuleKind::Glicol]
+        );
+        assert_eq!(
+            order.split(),
+            (&[ModuleKind::Delay, ModuleKind::Eq][..], &[][..])
+        );
+
+        order.move_module(0, 2);
+        assert_eq!(
+            order.0,
+            [ModuleKind::Eq, ModuleKind::Glicol, ModuleKind::Delay]
+        );
+    }
+
+    #[test]
+    fn test_bypass() {
+        let mut chain = ModuleChain::new();
+        chain.add(Box::new(TestModule {
+            gain: 0.5,
+            bypassed: true,
+        }));
+
+        let input = StereoSample::new(1.0, 1.0);
+        let output = chain.process(input);
+
+        // Bypassed, so output == input
+        assert!((output.left - 1.0).abs() < 0.001);
+    }
+}
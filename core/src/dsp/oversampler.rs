@@ -0,0 +1,238 @@
+//! Polyphase half-band oversampling
+//!
+//! Each 2x stage is a 31-tap half-band FIR (Blackman-windowed sinc) split
+//! into its two phases: every other tap is zero and the centre tap is 0.5,
+//! so upsampling only filters one of the two output samples and
+//! downsampling only filters one of the two input phases. 4x cascades two
+//! stages. The filters are linear phase and the round trip is padded to a
+//! whole number of host samples, so the latency can be reported exactly.
+
+use std::f32::consts::PI;
+
+/// Half the number of non-zero side taps per phase
+const HALF_TAPS: usize = 8;
+
+/// Non-zero side taps of one stage
+const TAPS: usize = 2 * HALF_TAPS;
+
+/// Up + down through one stage, in samples at the lower rate
+const STAGE_LATENCY: usize = TAPS - 1;
+
+/// Highest supported factor
+pub const MAX_FACTOR: usize = 4;
+
+/// One 2x up/down stage
+struct HalfbandStage {
+    /// Side taps, doubled for upsampling (`h(2i - TAPS + 1) * 2`)
+    coeffs: [f32; TAPS],
+    /// Upsampler input history, newest first
+    up_history: [f32; TAPS],
+    /// Downsampler history of the filtered phase, newest first
+    down_even: [f32; TAPS],
+    /// Downsampler history of the centre-tap phase, newest first
+    down_odd: [f32; HALF_TAPS + 1],
+}
+
+impl HalfbandStage {
+    fn new() -> Self {
+        let mut coeffs = [0.0; TAPS];
+        for (i, coeff) in coeffs.iter_mut().enumerate() {
+            let offset = (2 * i) as f32 - TAPS as f32 + 1.0;
+            let sinc = (PI * offset / 2.0).sin() / (PI * offset / 2.0);
+            let phase = PI * offset / TAPS as f32;
+            let window = 0.42 + 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+            *coeff = sinc * window;
+        }
+        // Unity gain at DC: the side taps sum to 0.5 next to the centre's 0.5
+        let sum: f32 = coeffs.iter().sum();
+        for coeff in &mut coeffs {
+            *coeff /= sum;
+        }
+        Self {
+            coeffs,
+            up_history: [0.0; TAPS],
+            down_even: [0.0; TAPS],
+            down_odd: [0.0; HALF_TAPS + 1],
+        }
+    }
+
+    /// One sample in, two out
+    fn up(&mut self, input: f32) -> [f32; 2] {
+        self.up_history.copy_within(..TAPS - 1, 1);
+        self.up_history[0] = input;
+        let filtered: f32 = self
+            .coeffs
+            .iter()
+            .zip(&self.up_history)
+            .map(|(coeff, sample)| coeff * sample)
+            .sum();
+        [filtered, self.up_history[HALF_TAPS - 1]]
+    }
+
+    /// Two samples in, one out
+    fn down(&mut self, input: [f32; 2]) -> f32 {
+        self.down_even.copy_within(..TAPS - 1, 1);
+        self.down_even[0] = input[0];
+        self.down_odd.copy_within(..HALF_TAPS, 1);
+        self.down_odd[0] = input[1];
+        let filtered: f32 = self
+            .coeffs
+            .iter()
+            .zip(&self.down_even)
+            .map(|(coeff, sample)| coeff * sample)
+            .sum();
+        (filtered + self.down_odd[HALF_TAPS]) * 0.5
+    }
+
+    fn reset(&mut self) {
+        self.up_history.fill(0.0);
+        self.down_even.fill(0.0);
+        self.down_odd.fill(0.0);
+    }
+}
+
+/// Single-channel 1x/2x/4x oversampler
+pub struct Oversampler {
+    factor: usize,
+    /// Host rate <-> 2x
+    outer: HalfbandStage,
+    /// 2x <-> 4x
+    inner: HalfbandStage,
+    /// One 2x-rate sample of delay that rounds the 4x latency to whole
+    /// host samples
+    inner_pad: f32,
+}
+
+impl Oversampler {
+    pub fn new() -> Self {
+        Self {
+            factor: 1,
+            outer: HalfbandStage::new(),
+            inner: HalfbandStage::new(),
+            inner_pad: 0.0,
+        }
+    }
+
+    /// Set the factor (1, 2 or 4), clearing the filters when it changes
+    pub fn set_factor(&mut self, factor: usize) {
+        debug_assert!(matches!(factor, 1 | 2 | 4));
+        if factor != self.factor {
+            self.factor = factor;
+            self.reset();
+        }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Delay of an up/down round trip, in host samples
+    pub fn latency(&self) -> usize {
+        match self.factor {
+            1 => 0,
+            2 => STAGE_LATENCY,
+            // The inner stage takes half as many host samples, plus the pad
+            _ => STAGE_LATENCY + (STAGE_LATENCY + 1) / 2,
+        }
+    }
+
+    /// Write `factor` samples for one host-rate sample
+    pub fn upsample(&mut self, input: f32, output: &mut [f32]) {
+        match self.factor {
+            1 => output[0] = input,
+            2 => output[..2].copy_from_slice(&self.outer.up(input)),
+            _ => {
+                let [first, second] = self.outer.up(input);
+                output[..2].copy_from_slice(&self.inner.up(first));
+                output[2..4].copy_from_slice(&self.inner.up(second));
+            }
+        }
+    }
+
+    /// Reduce `factor` samples to one host-rate sample
+    pub fn downsample(&mut self, input: &[f32]) -> f32 {
+        match self.factor {
+            1 => input[0],
+            2 => self.outer.down([input[0], input[1]]),
+            _ => {
+                let first = self.inner.down([input[0], input[1]]);
+                let second = self.inner.down([input[2], input[3]]);
+                let padded = [self.inner_pad, first];
+                self.inner_pad = second;
+                self.outer.down(padded)
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.outer.reset();
+        self.inner.reset();
+        self.inner_pad = 0.0;
+    }
+}
+
+impl Default for Oversampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a signal up and back down
+    fn round_trip(oversampler: &mut Oversampler, input: &[f32]) -> Vec<f32> {
+        let mut high = [0.0; MAX_FACTOR];
+        input
+            .iter()
+            .map(|&sample| {
+                let factor = oversampler.factor();
+                oversampler.upsample(sample, &mut high[..factor]);
+                oversampler.downsample(&high[..factor])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_impulse_latency() {
+        for factor in [1, 2, 4] {
+            let mut oversampler = Oversampler::new();
+            oversampler.set_factor(factor);
+            let mut input = vec![0.0; 64];
+            input[0] = 1.0;
+            let output = round_trip(&mut oversampler, &input);
+
+            let peak = output
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+                .unwrap()
+                .0;
+            assert_eq!(peak, oversampler.latency(), "factor {}", factor);
+        }
+    }
+
+    #[test]
+    fn test_passes_low_frequencies() {
+        for factor in [2, 4] {
+            let mut oversampler = Oversampler::new();
+            oversampler.set_factor(factor);
+            // 1 kHz at 48 kHz
+            let input: Vec<f32> = (0..2048)
+                .map(|n| (2.0 * PI * 1000.0 * n as f32 / 48000.0).sin())
+                .collect();
+            let output = round_trip(&mut oversampler, &input);
+
+            let latency = oversampler.latency();
+            for n in 1024..2048 {
+                assert!(
+                    (output[n] - input[n - latency]).abs() < 0.01,
+                    "factor {} sample {}",
+                    factor,
+                    n
+                );
+            }
+        }
+    }
+}
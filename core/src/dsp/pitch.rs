@@ -0,0 +1,122 @@
+//! Monophonic pitch detection (YIN) and note naming for the tuner
+//!
+//! Runs on the GUI thread over the input tap, so plain allocation is fine.
+
+/// Detection range: a little below drop-B up to the 12th fret of high E
+const MIN_FREQ: f32 = 55.0;
+const MAX_FREQ: f32 = 1400.0;
+
+/// YIN dip threshold (lower = stricter)
+const THRESHOLD: f32 = 0.15;
+
+/// Below this RMS the input counts as silence
+const SILENCE_RMS: f32 = 0.003;
+
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Fundamental frequency of `samples` in Hz, if there is a clear pitch
+pub fn detect_pitch(samples: &[f32], sample_rate: f32) -> Option<f32> {
+    let min_lag = ((sample_rate / MAX_FREQ) as usize).max(2);
+    let max_lag = ((sample_rate / MIN_FREQ) as usize).min(samples.len() / 2);
+    if max_lag <= min_lag + 1 {
+        return None;
+    }
+
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    if rms < SILENCE_RMS {
+        return None;
+    }
+
+    // Difference function and its cumulative mean normalization
+    let window = samples.len() - max_lag;
+    let mut normalized = vec![1.0f32; max_lag + 1];
+    let mut running_sum = 0.0;
+    for lag in 1..=max_lag {
+        let difference: f32 = (0..window)
+            .map(|i| {
+                let delta = samples[i] - samples[i + lag];
+                delta * delta
+            })
+            .sum();
+        running_sum += difference;
+        if running_sum > 0.0 {
+            normalized[lag] = difference * lag as f32 / running_sum;
+        }
+    }
+
+    // First dip under the threshold, then down to its bottom
+    let mut lag = (min_lag..max_lag).find(|&lag| normalized[lag] < THRESHOLD)?;
+    while lag + 1 < max_lag && normalized[lag + 1] < normalized[lag] {
+        lag += 1;
+    }
+
+    // Parabolic interpolation between neighbouring lags
+    let (a, b, c) = (normalized[lag - 1], normalized[lag], normalized[lag + 1]);
+    let curvature = a - 2.0 * b + c;
+    let shift = if curvature.abs() > 1e-9 {
+        0.5 * (a - c) / curvature
+    } else {
+        0.0
+    };
+    Some(sample_rate / (lag as f32 + shift))
+}
+
+/// Nearest equal-tempered note (A4 = 440 Hz)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Note {
+    pub name: &'static str,
+    pub octave: i32,
+    /// Deviation from the note, -50 to +50
+    pub cents: f32,
+}
+
+pub fn nearest_note(freq: f32) -> Note {
+    let midi = 69.0 + 12.0 * (freq / 440.0).log2();
+    let nearest = midi.round();
+    let number = nearest as i32;
+    Note {
+        name: NOTE_NAMES[number.rem_euclid(12) as usize],
+        octave: number.div_euclid(12) - 1,
+        cents: (midi - nearest) * 100.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, sample_rate: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_guitar_strings() {
+        for freq in [82.41, 110.0, 196.0, 329.63] {
+            let detected = detect_pitch(&sine(freq, 48000.0, 2048), 48000.0).unwrap();
+            assert!(
+                (detected - freq).abs() / freq < 0.005,
+                "{} -> {}",
+                freq,
+                detected
+            );
+        }
+        assert_eq!(detect_pitch(&[0.0; 2048], 48000.0), None);
+    }
+
+    #[test]
+    fn test_nearest_note() {
+        let a4 = nearest_note(440.0);
+        assert_eq!((a4.name, a4.octave), ("A", 4));
+        assert!(a4.cents.abs() < 1e-3);
+
+        let low_e = nearest_note(82.41);
+        assert_eq!((low_e.name, low_e.octave), ("E", 2));
+
+        let sharp = nearest_note(440.0 * 2f32.powf(20.0 / 1200.0));
+        assert!((sharp.cents - 20.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,141 @@
+//! Tail tracking
+//!
+//! Hosts may stop processing a plugin once its input goes quiet, which
+//! would cut off echoes and reverb. The estimator combines two things: the
+//! decay the module settings imply (delay repeats, plus the latency before
+//! anything comes out), counted from the last input, and the output level,
+//! which keeps the plugin alive while a Glicol reverb is still ringing. A
+//! patch that keeps sounding long after its input stopped is generating
+//! sound on its own and should never be suspended.
+
+/// Level below which a signal counts as silence
+const SILENCE_DB: f32 = -90.0;
+
+/// How long the output must stay quiet before it counts as finished, in
+/// seconds (covers the gaps between sparse Glicol events)
+const HOLD_SECONDS: f32 = 0.5;
+
+/// Sounding this long past the expected tail means the patch is generating
+const GENERATING_SECONDS: f32 = 5.0;
+
+/// What the plugin still has to play
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TailState {
+    /// Nothing left to hear; the host may suspend the plugin
+    Silent,
+    /// Still decaying, for at most this many more samples
+    Decaying(u32),
+    /// Sounding without input; keep processing
+    Generating,
+}
+
+pub struct TailEstimator {
+    threshold: f32,
+    hold: usize,
+    generating_after: usize,
+    /// Tail implied by the current settings, in samples
+    decay: usize,
+    /// Samples since the input and the output were last above the threshold
+    since_input: usize,
+    since_output: usize,
+}
+
+impl TailEstimator {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut estimator = Self {
+            threshold: 10.0_f32.powf(SILENCE_DB / 20.0),
+            hold: 0,
+            generating_after: 0,
+            decay: 0,
+            since_input: 0,
+            since_output: 0,
+        };
+        estimator.set_sample_rate(sample_rate);
+        estimator.reset();
+        estimator
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.hold = (HOLD_SECONDS * sample_rate) as usize;
+        self.generating_after = (GENERATING_SECONDS * sample_rate) as usize;
+    }
+
+    /// Set the tail the settings imply, in samples
+    pub fn set_decay(&mut self, samples: usize) {
+        self.decay = samples;
+    }
+
+    /// Account for one buffer, given the peak input and output levels
+    pub fn update(&mut self, input_peak: f32, output_peak: f32, num_samples: usize) -> TailState {
+        self.since_input = if input_peak > self.threshold {
+            0
+        } else {
+            self.since_input.saturating_add(num_samples)
+        };
+        self.since_output = if output_peak > self.threshold {
+            0
+        } else {
+            self.since_output.saturating_add(num_samples)
+        };
+
+        let expected = self.decay + self.hold;
+        if self.since_output == 0 && self.since_input > expected + self.generating_after {
+            return TailState::Generating;
+        }
+        let remaining = expected
+            .saturating_sub(self.since_input)
+            .max(self.hold.saturating_sub(self.since_output));
+        match remaining {
+            0 => TailState::Silent,
+            samples => TailState::Decaying(samples.min(u32::MAX as usize) as u32),
+        }
+    }
+
+    /// Forget the history: treat the plugin as having been silent
+    pub fn reset(&mut self) {
+        self.since_input = usize::MAX;
+        self.since_output = usize::MAX;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_down_after_input() {
+        let mut tail = TailEstimator::new(1000.0);
+        tail.set_decay(1000);
+        assert_eq!(tail.update(0.0, 0.0, 100), TailState::Silent);
+
+        // Hold (500) plus decay (1000) from the last input
+        assert_eq!(tail.update(0.5, 0.5, 100), TailState::Decaying(1500));
+        assert_eq!(tail.update(0.0, 0.0, 100), TailState::Decaying(1400));
+        for _ in 0..14 {
+            tail.update(0.0, 0.0, 100);
+        }
+        assert_eq!(tail.update(0.0, 0.0, 100), TailState::Silent);
+    }
+
+    #[test]
+    fn test_output_keeps_tail_alive() {
+        let mut tail = TailEstimator::new(1000.0);
+        tail.update(0.5, 0.5, 100);
+        for _ in 0..10 {
+            tail.update(0.0, 0.0, 100);
+        }
+        // A reverb still ringing after the settings' tail ran out
+        assert_eq!(tail.update(0.0, 0.01, 100), TailState::Decaying(500));
+    }
+
+    #[test]
+    fn test_detects_generating_patch() {
+        let mut tail = TailEstimator::new(1000.0);
+        tail.update(0.5, 0.5, 100);
+        let mut state = TailState::Silent;
+        for _ in 0..70 {
+            state = tail.update(0.0, 0.2, 100);
+        }
+        assert_eq!(state, TailState::Generating);
+    }
+}
@@ -0,0 +1,316 @@
+//! Test Signals
+//!
+//! Sources that stand in for an instrument while editing patches: a sine,
+//! white noise, a looped Karplus-Strong arpeggio (the synth behind
+//! `test_audio/test_guitar.wav`) or a loaded sample.
+
+use std::f32::consts::TAU;
+use std::path::Path;
+
+use hound::{SampleFormat, WavReader};
+
+/// Sine frequency (A3)
+const SINE_FREQ: f32 = 220.0;
+
+/// Peak level of the sine and the noise
+const LEVEL: f32 = 0.5;
+
+/// E minor arpeggio: note frequency and start time in seconds
+const ARPEGGIO: [(f32, f32); 13] = [
+    (82.41, 0.0),  // E2
+    (98.00, 0.3),  // G2
+    (123.47, 0.6), // B2
+    (164.81, 0.9), // E3
+    (196.00, 1.2), // G3
+    (246.94, 1.5), // B3
+    (329.63, 1.8), // E4
+    (246.94, 2.4), // B3
+    (196.00, 2.7), // G3
+    (164.81, 3.0), // E3
+    (123.47, 3.3), // B2
+    (98.00, 3.6),  // G2
+    (82.41, 3.9),  // E2
+];
+
+/// Length of the arpeggio, and of the pluck loop
+pub const ARPEGGIO_SECONDS: f32 = 5.0;
+
+/// Sources the generator can play
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignalKind {
+    Sine,
+    Noise,
+    Pluck,
+    /// The loaded sample, silent without one
+    Sample,
+}
+
+/// Mono audio for `SignalKind::Sample`, at its own sample rate
+#[derive(Debug, Clone)]
+pub struct TestSample {
+    pub samples: Vec<f32>,
+    pub sample_rate: f32,
+}
+
+impl TestSample {
+    /// Read a WAV file, mixing its channels down to mono
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let read_error = |e: hound::Error| format!("Couldn't read {}: {}", path.display(), e);
+        let reader = WavReader::open(path).map_err(read_error)?;
+        let spec = reader.spec();
+        let interleaved: Vec<f32> = match spec.sample_format {
+            SampleFormat::Float => reader
+                .into_samples::<f32>()
+                .collect::<Result<_, _>>()
+                .map_err(read_error)?,
+            SampleFormat::Int => {
+                let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|sample| sample.map(|value| value as f32 * scale))
+                    .collect::<Result<_, _>>()
+                    .map_err(read_error)?
+            }
+        };
+        let channels = spec.channels.max(1) as usize;
+        let samples = interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect();
+        Ok(Self {
+            samples,
+            sample_rate: spec.sample_rate as f32,
+        })
+    }
+}
+
+/// Looping test signal generator
+pub struct TestSignal {
+    sample_rate: f32,
+    phase: f32,
+    noise_index: u32,
+    /// One pass of the arpeggio at `sample_rate`
+    pluck: Vec<f32>,
+    pluck_pos: usize,
+    sample: Option<TestSample>,
+    /// Read position in the sample, in its own frames
+    sample_pos: f64,
+}
+
+impl TestSignal {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            phase: 0.0,
+            noise_index: 0,
+            pluck: arpeggio(sample_rate),
+            pluck_pos: 0,
+            sample: None,
+            sample_pos: 0.0,
+        }
+    }
+
+    /// Set the sample rate, rebuilding the pluck loop (allocates)
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.pluck = arpeggio(sample_rate);
+            self.pluck_pos = 0;
+        }
+    }
+
+    /// Swap in a new sample, returning the old one so the caller decides
+    /// where it is freed
+    pub fn set_sample(&mut self, sample: Option<TestSample>) -> Option<TestSample> {
+        self.sample_pos = 0.0;
+        std::mem::replace(&mut self.sample, sample)
+    }
+
+    pub fn has_sample(&self) -> bool {
+        self.sample.is_some()
+    }
+
+    /// Restart every source from the top
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+        self.noise_index = 0;
+        self.pluck_pos = 0;
+        self.sample_pos = 0.0;
+    }
+
+    /// Next sample of `kind`
+    pub fn next(&mut self, kind: SignalKind) -> f32 {
+        match kind {
+            SignalKind::Sine => {
+                let value = (self.phase * TAU).sin() * LEVEL;
+                self.phase = (self.phase + SINE_FREQ / self.sample_rate).fract();
+                value
+            }
+            SignalKind::Noise => {
+                self.noise_index = self.noise_index.wrapping_add(1);
+                (simple_hash(self.noise_index) as f32 / u32::MAX as f32 * 2.0 - 1.0) * LEVEL
+            }
+            SignalKind::Pluck => {
+                let Some(&value) = self.pluck.get(self.pluck_pos) else {
+                    return 0.0;
+                };
+                self.pluck_pos = (self.pluck_pos + 1) % self.pluck.len();
+                value
+            }
+            SignalKind::Sample => self.next_sample(),
+        }
+    }
+
+    /// Read the loaded sample at the host rate, with linear interpolation
+    fn next_sample(&mut self) -> f32 {
+        let Some(sample) = &self.sample else {
+            return 0.0;
+        };
+        let len = sample.samples.len();
+        if len == 0 {
+            return 0.0;
+        }
+        let index = self.sample_pos as usize % len;
+        let frac = self.sample_pos.fract() as f32;
+        let current = sample.samples[index];
+        let next = sample.samples[(index + 1) % len];
+        self.sample_pos += (sample.sample_rate / self.sample_rate) as f64;
+        if self.sample_pos >= len as f64 {
+            self.sample_pos -= len as f64;
+        }
+        current + (next - current) * frac
+    }
+}
+
+/// The E minor arpeggio, normalized and softly clipped
+pub fn arpeggio(sample_rate: f32) -> Vec<f32> {
+    pluck_sequence(&ARPEGGIO, sample_rate, ARPEGGIO_SECONDS)
+}
+
+/// Plucked notes, each a frequency and start time in seconds, mixed into
+/// `seconds` of audio, normalized and softly clipped
+pub fn pluck_sequence(notes: &[(f32, f32)], sample_rate: f32, seconds: f32) -> Vec<f32> {
+    let total_samples = (sample_rate * seconds) as usize;
+    let mut output = vec![0.0f32; total_samples];
+
+    for &(freq, start_time) in notes {
+        let start_sample = (start_time * sample_rate) as usize;
+        // Long decay, high damping
+        let note_samples = karplus_strong(freq, sample_rate, 1.8, 0.996);
+        for (i, &sample) in note_samples.iter().enumerate() {
+            let idx = start_sample + i;
+            if idx < total_samples {
+                output[idx] += sample * 0.4; // Mix level
+            }
+        }
+    }
+
+    // Normalize and add slight compression
+    let max_val = output.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
+    if max_val > 0.0 {
+        for sample in &mut output {
+            *sample /= max_val;
+            // Soft clipping for natural compression
+            *sample = (*sample * 0.8).tanh();
+        }
+    }
+    output
+}
+
+/// Karplus-Strong plucked string synthesis
+/// Returns samples for a single note
+pub fn karplus_strong(
+    frequency: f32,
+    sample_rate: f32,
+    duration_secs: f32,
+    decay: f32,
+) -> Vec<f32> {
+    let num_samples = (sample_rate * duration_secs) as usize;
+    let period = ((sample_rate / frequency) as usize).max(2);
+
+    // Initialize with noise burst (simulates pick attack)
+    let mut buffer: Vec<f32> = (0..period)
+        .map(|i| {
+            // Mix of noise and initial harmonic content
+            let noise = (simple_hash(i as u32) as f32 / u32::MAX as f32) * 2.0 - 1.0;
+            let harmonic = (TAU * i as f32 / period as f32).sin();
+            noise * 0.7 + harmonic * 0.3
+        })
+        .collect();
+
+    let mut output = Vec::with_capacity(num_samples);
+    let mut index = 0;
+
+    // Attack envelope
+    let attack_samples = ((0.003 * sample_rate) as usize).max(1); // 3ms attack
+
+    for i in 0..num_samples {
+        let sample = buffer[index];
+
+        // Apply attack envelope
+        let envelope = if i < attack_samples {
+            i as f32 / attack_samples as f32
+        } else {
+            1.0
+        };
+
+        output.push(sample * envelope);
+
+        // Lowpass filter (average with next sample) + decay
+        let next_index = (index + 1) % period;
+        buffer[index] = (buffer[index] + buffer[next_index]) * 0.5 * decay;
+
+        index = next_index;
+    }
+
+    output
+}
+
+/// Simple deterministic hash for reproducible "random" noise
+fn simple_hash(mut x: u32) -> u32 {
+    x = x.wrapping_mul(0x45d9f3b);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x45d9f3b);
+    x ^= x >> 16;
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pluck_loops() {
+        let mut signal = TestSignal::new(8000.0);
+        let first: Vec<f32> = (0..100).map(|_| signal.next(SignalKind::Pluck)).collect();
+        for _ in 100..(8000.0 * ARPEGGIO_SECONDS) as usize {
+            signal.next(SignalKind::Pluck);
+        }
+        let again: Vec<f32> = (0..100).map(|_| signal.next(SignalKind::Pluck)).collect();
+        assert_eq!(first, again);
+        assert!(first.iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn test_levels_stay_in_range() {
+        let mut signal = TestSignal::new(48000.0);
+        for kind in [SignalKind::Sine, SignalKind::Noise, SignalKind::Pluck] {
+            for _ in 0..48000 {
+                assert!(signal.next(kind).abs() <= 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_plays_at_its_own_rate() {
+        let mut signal = TestSignal::new(48000.0);
+        assert_eq!(signal.next(SignalKind::Sample), 0.0);
+        signal.set_sample(Some(TestSample {
+            samples: vec![0.0, 1.0, 0.0, -1.0],
+            sample_rate: 24000.0,
+        }));
+        // Half speed: every other output falls between two frames
+        let out: Vec<f32> = (0..4).map(|_| signal.next(SignalKind::Sample)).collect();
+        assert_eq!(out, vec![0.0, 0.5, 1.0, 0.5]);
+    }
+}
@@ -17,10 +17,16 @@ pub type RbConsumer<T> = ringbuf::HeapCons<T>;
 /// DAWs send variable buffer sizes (64, 256, 512 samples).
 /// Glicol processes fixed 128-sample blocks.
 /// This struct accumulates input samples and provides complete blocks.
+///
+/// The output starts primed with `LATENCY` samples of silence, which is
+/// exactly enough to never run dry: at most a block minus one sample waits
+/// for its block. The delay is therefore fixed and can be reported to the host.
 pub struct BufferBridge {
-    // Input: DAW -> Glicol (mono)
+    // Input: DAW -> Glicol (stereo, both channels equal for mono sources)
     input_producer: RbProducer<f32>,
     input_consumer: RbConsumer<f32>,
+    input_right_producer: RbProducer<f32>,
+    input_right_consumer: RbConsumer<f32>,
 
     // Output: Glicol -> DAW (stereo)
     output_left_producer: RbProducer<f32>,
@@ -28,43 +34,62 @@ pub struct BufferBridge {
     output_right_producer: RbProducer<f32>,
     output_right_consumer: RbConsumer<f32>,
 
-    // Temporary buffer for Glicol processing
+    // Temporary buffers for Glicol processing
     input_block: [f32; GLICOL_BLOCK_SIZE],
+    input_right_block: [f32; GLICOL_BLOCK_SIZE],
 
     // Underrun tracking
     underrun_count: u32,
-    samples_since_underrun_log: u32,
 }
 
 impl BufferBridge {
+    /// Delay through the bridge in samples, whatever the host buffer size
+    pub const LATENCY: usize = GLICOL_BLOCK_SIZE - 1;
+
     pub fn new() -> Self {
         let input_rb = HeapRb::<f32>::new(RING_BUFFER_SIZE);
         let (input_prod, input_cons) = input_rb.split();
 
+        let input_right_rb = HeapRb::<f32>::new(RING_BUFFER_SIZE);
+        let (in_r_prod, in_r_cons) = input_right_rb.split();
+
         let output_left_rb = HeapRb::<f32>::new(RING_BUFFER_SIZE);
         let (out_l_prod, out_l_cons) = output_left_rb.split();
 
         let output_right_rb = HeapRb::<f32>::new(RING_BUFFER_SIZE);
         let (out_r_prod, out_r_cons) = output_right_rb.split();
 
-        Self {
+        let mut bridge = Self {
             input_producer: input_prod,
             input_consumer: input_cons,
+            input_right_producer: in_r_prod,
+            input_right_consumer: in_r_cons,
             output_left_producer: out_l_prod,
             output_left_consumer: out_l_cons,
             output_right_producer: out_r_prod,
             output_right_consumer: out_r_cons,
             input_block: [0.0; GLICOL_BLOCK_SIZE],
+            input_right_block: [0.0; GLICOL_BLOCK_SIZE],
             underrun_count: 0,
-            samples_since_underrun_log: 0,
+        };
+        bridge.prime();
+        bridge
+    }
+
+    /// Fill the output with the silence that makes up `LATENCY`
+    fn prime(&mut self) {
+        for _ in 0..Self::LATENCY {
+            let _ = self.output_left_producer.try_push(0.0);
+            let _ = self.output_right_producer.try_push(0.0);
         }
     }
 
-    /// Push a mono input sample from DAW
+    /// Push a stereo input sample pair from DAW
     #[inline]
-    pub fn push_input(&mut self, sample: f32) {
+    pub fn push_input(&mut self, left: f32, right: f32) {
         // Drop samples if buffer full (prevents blocking)
-        let _ = self.input_producer.try_push(sample);
+        let _ = self.input_producer.try_push(left);
+        let _ = self.input_right_producer.try_push(right);
     }
 
     /// Check if we have enough samples for a Glicol block
@@ -74,12 +99,13 @@ impl BufferBridge {
     }
 
     /// Pop a block of samples for Glicol processing
-    /// Returns a slice of exactly GLICOL_BLOCK_SIZE samples
-    pub fn pop_input_block(&mut self) -> &[f32] {
+    /// Returns (left, right) slices of exactly GLICOL_BLOCK_SIZE samples
+    pub fn pop_input_block(&mut self) -> (&[f32], &[f32]) {
         for i in 0..GLICOL_BLOCK_SIZE {
             self.input_block[i] = self.input_consumer.try_pop().unwrap_or(0.0);
+            self.input_right_block[i] = self.input_right_consumer.try_pop().unwrap_or(0.0);
         }
-        &self.input_block
+        (&self.input_block, &self.input_right_block)
     }
 
     /// Push stereo output from Glicol
@@ -103,7 +129,7 @@ impl BufferBridge {
 
     /// Pop a stereo sample pair for DAW output
     /// Returns (left, right), or (0.0, 0.0) if buffer is empty (underrun)
-    /// Tracks underruns and logs periodically (rate-limited to avoid spam)
+    /// Underruns are counted; the plugin reports them to the diagnostics log
     #[inline]
     pub fn pop_output(&mut self) -> (f32, f32) {
         match (
@@ -114,17 +140,6 @@ impl BufferBridge {
             _ => {
                 // Underrun - one or both channels empty
                 self.underrun_count += 1;
-                self.samples_since_underrun_log += 1;
-
-                // Log underruns periodically (~once per second at 44.1kHz)
-                if self.samples_since_underrun_log >= 44100 {
-                    eprintln!(
-                        "[GlicolVerb] Buffer underrun: {} total underruns",
-                        self.underrun_count
-                    );
-                    self.samples_since_underrun_log = 0;
-                }
-
                 (0.0, 0.0)
             }
         }
@@ -132,7 +147,6 @@ impl BufferBridge {
 
     /// Get the total number of underrun samples since last reset
     #[inline]
-    #[allow(dead_code)]
     pub fn underrun_count(&self) -> u32 {
         self.underrun_count
     }
@@ -143,6 +157,12 @@ impl BufferBridge {
         self.underrun_count = 0;
     }
 
+    /// Input samples waiting for a complete block
+    #[inline]
+    pub fn input_pending(&self) -> usize {
+        self.input_consumer.occupied_len()
+    }
+
     /// Check how many output samples are available
     #[inline]
     pub fn output_available(&self) -> usize {
@@ -155,11 +175,13 @@ impl BufferBridge {
     pub fn clear(&mut self) {
         // Clear by consuming all samples
         while self.input_consumer.try_pop().is_some() {}
+        while self.input_right_consumer.try_pop().is_some() {}
         while self.output_left_consumer.try_pop().is_some() {}
         while self.output_right_consumer.try_pop().is_some() {}
         self.input_block = [0.0; GLICOL_BLOCK_SIZE];
+        self.input_right_block = [0.0; GLICOL_BLOCK_SIZE];
         // Don't reset underrun_count - keep tracking across resets for diagnostics
-        self.samples_since_underrun_log = 0;
+        self.prime();
     }
 }
 
@@ -168,3 +190,38 @@ impl Default for BufferBridge {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_latency_without_underruns() {
+        let mut bridge = BufferBridge::new();
+        let mut output = Vec::new();
+        let mut sample = 0;
+        // Host buffer sizes that don't line up with the block size
+        for buffer_size in [64, 200, 1, 127, 300, 96].into_iter().cycle().take(60) {
+            for _ in 0..buffer_size {
+                sample += 1;
+                bridge.push_input(sample as f32, 0.0);
+            }
+            while bridge.has_block() {
+                let (left, right) = bridge.pop_input_block();
+                let (left, right) = (left.to_vec(), right.to_vec());
+                bridge.push_output(&left, &right);
+            }
+            for _ in 0..buffer_size {
+                output.push(bridge.pop_output().0);
+            }
+            assert_eq!(
+                bridge.input_pending() + bridge.output_available(),
+                BufferBridge::LATENCY
+            );
+        }
+
+        assert_eq!(bridge.underrun_count(), 0);
+        assert_eq!(output[BufferBridge::LATENCY - 1], 0.0);
+        assert_eq!(output[BufferBridge::LATENCY], 1.0);
+    }
+}
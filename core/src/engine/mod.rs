@@ -0,0 +1,28 @@
+mod buffer_bridge;
+mod oversampled;
+mod param_injector;
+mod preprocessor;
+mod validate;
+mod wrapper;
+
+pub use buffer_bridge::BufferBridge;
+pub use oversampled::EngineOversampler;
+pub use param_injector::{
+    write_message, KnobNames, ParamInjector, KNOB_COUNT, PARAM_NAMES, PARAM_RANGES,
+};
+pub use preprocessor::{included_snippets, preprocess, preprocess_mapped, SourceMap};
+pub use validate::validate;
+pub use wrapper::GlicolWrapper;
+
+/// Glicol's fixed block size
+pub const GLICOL_BLOCK_SIZE: usize = 128;
+
+/// A Glicol compile error, located in the user's code where possible
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileError {
+    pub message: String,
+    /// 1-based line in the user's code
+    pub line: Option<usize>,
+    /// 1-based column
+    pub column: Option<usize>,
+}
@@ -0,0 +1,89 @@
+use super::{GlicolWrapper, GLICOL_BLOCK_SIZE};
+use crate::dsp::oversampler::{Oversampler, MAX_FACTOR};
+
+/// Runs a Glicol engine at a multiple of the host rate
+///
+/// Each host-rate block is upsampled, processed as `factor` engine blocks
+/// (the engine must have been created at the oversampled rate) and
+/// downsampled back, so the buffer bridge and scene switching still see
+/// one 128-sample block.
+pub struct EngineOversampler {
+    channels: [Oversampler; 2],
+    input: [[f32; GLICOL_BLOCK_SIZE * MAX_FACTOR]; 2],
+    output: [[f32; GLICOL_BLOCK_SIZE]; 2],
+}
+
+impl EngineOversampler {
+    pub fn new() -> Self {
+        Self {
+            channels: [Oversampler::new(), Oversampler::new()],
+            input: [[0.0; GLICOL_BLOCK_SIZE * MAX_FACTOR]; 2],
+            output: [[0.0; GLICOL_BLOCK_SIZE]; 2],
+        }
+    }
+
+    /// Set the factor (1, 2 or 4)
+    pub fn set_factor(&mut self, factor: usize) {
+        for channel in &mut self.channels {
+            channel.set_factor(factor);
+        }
+    }
+
+    pub fn factor(&self) -> usize {
+        self.channels[0].factor()
+    }
+
+    /// Filter delay added to the engine, in host samples
+    pub fn latency(&self) -> usize {
+        self.channels[0].latency()
+    }
+
+    /// Process one host-rate block of `GLICOL_BLOCK_SIZE` samples
+    pub fn process<'a>(
+        &'a mut self,
+        engine: &'a mut GlicolWrapper,
+        left: &[f32],
+        right: &[f32],
+    ) -> (&'a [f32], &'a [f32]) {
+        let factor = self.factor();
+        if factor == 1 {
+            return engine.process(left, right);
+        }
+
+        for (channel, samples) in [left, right].into_iter().enumerate() {
+            for (i, &sample) in samples.iter().enumerate() {
+                self.channels[channel].upsample(
+                    sample,
+                    &mut self.input[channel][i * factor..(i + 1) * factor],
+                );
+            }
+        }
+
+        // Each engine block comes back down as 128 / factor host samples
+        let per_block = GLICOL_BLOCK_SIZE / factor;
+        for block in 0..factor {
+            let range = block * GLICOL_BLOCK_SIZE..(block + 1) * GLICOL_BLOCK_SIZE;
+            let (high_left, high_right) =
+                engine.process(&self.input[0][range.clone()], &self.input[1][range]);
+            for (channel, high) in [high_left, high_right].into_iter().enumerate() {
+                for i in 0..per_block {
+                    self.output[channel][block * per_block + i] =
+                        self.channels[channel].downsample(&high[i * factor..(i + 1) * factor]);
+                }
+            }
+        }
+        (&self.output[0], &self.output[1])
+    }
+
+    pub fn reset(&mut self) {
+        for channel in &mut self.channels {
+            channel.reset();
+        }
+    }
+}
+
+impl Default for EngineOversampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,370 @@
+//! Parameter injection for Glicol code
+//!
+//! Prepends `~name: sig value` definitions for parameters referenced in user code.
+//! This allows GUI sliders to control Glicol variables like ~drive, ~knob1, etc.
+//! Knobs the user has named are also injected under that name (~shimmer).
+//! `~expr` carries the expression pedal and `~sidechain` the envelope of
+//! the sidechain input.
+//!
+//! Once the code is running, the same chains are updated in place with
+//! Glicol messages (see `write_message()`), so values can follow
+//! automation without recompiling.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// All injectable parameter names
+pub const PARAM_NAMES: &[&str] = &[
+    "knob1",
+    "knob2",
+    "knob3",
+    "knob4",
+    "knob5",
+    "knob6",
+    "knob7",
+    "knob8",
+    "drive",
+    "feedback",
+    "mix",
+    "rate",
+    "expr",
+    "sidechain",
+];
+
+/// (min, default, max) of each parameter as the plugin defines it, indexed
+/// like `PARAM_NAMES`; for tools that run code outside the plugin
+pub const PARAM_RANGES: [(f32, f32, f32); PARAM_NAMES.len()] = [
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (0.0, 0.5, 1.0),
+    (1.0, 1.0, 10.0),
+    (0.0, 0.3, 0.95),
+    (0.0, 0.5, 1.0),
+    (0.1, 1.0, 20.0),
+    (0.0, 0.0, 1.0),
+    // The sidechain envelope
+    (0.0, 0.0, 1.0),
+];
+
+/// Number of mappable knobs (`~knob1` to `~knob8`)
+pub const KNOB_COUNT: usize = 8;
+
+/// User names for the mappable knobs, indexed by knob
+///
+/// Empty entries (and names clashing with built-in references) fall back to
+/// the generic `knobN` name.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct KnobNames(pub Vec<String>);
+
+impl KnobNames {
+    /// Custom name of knob `index` (0-based), without the `~`
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.0
+            .get(index)
+            .map(String::as_str)
+            .filter(|name| !name.is_empty() && *name != "input" && !PARAM_NAMES.contains(name))
+    }
+
+    /// Rename knob `index`, keeping only characters valid in a reference
+    pub fn set(&mut self, index: usize, name: &str) {
+        if index >= KNOB_COUNT {
+            return;
+        }
+        if self.0.len() < KNOB_COUNT {
+            self.0.resize(KNOB_COUNT, String::new());
+        }
+        self.0[index] = name
+            .trim()
+            .trim_start_matches('~')
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+    }
+
+    /// Name the GUI shows for knob `index`, e.g. `~shimmer` or `~knob3`
+    pub fn label(&self, index: usize) -> String {
+        match self.get(index) {
+            Some(name) => format!("~{}", name),
+            None => format!("~knob{}", index + 1),
+        }
+    }
+
+    /// Every custom name in use (for completion and reference checks)
+    pub fn aliases(&self) -> Vec<&str> {
+        (0..KNOB_COUNT)
+            .filter_map(|index| self.get(index))
+            .collect()
+    }
+}
+
+/// Parameter values for injection
+#[derive(Default)]
+pub struct ParamInjector {
+    pub knob1: f32,
+    pub knob2: f32,
+    pub knob3: f32,
+    pub knob4: f32,
+    pub knob5: f32,
+    pub knob6: f32,
+    pub knob7: f32,
+    pub knob8: f32,
+    pub drive: f32,
+    pub feedback: f32,
+    pub mix: f32,
+    pub rate: f32,
+    /// Expression pedal (0.0-1.0)
+    pub expr: f32,
+    /// Sidechain envelope (0.0-1.0)
+    pub sidechain: f32,
+    /// Custom knob names, injected alongside `~knobN`
+    pub knob_names: KnobNames,
+}
+
+impl ParamInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every value at its default in a fresh plugin instance
+    pub fn with_plugin_defaults() -> Self {
+        let mut injector = Self::new();
+        for (name, (_, default, _)) in PARAM_NAMES.iter().zip(PARAM_RANGES) {
+            injector.set(name, default);
+        }
+        injector
+    }
+
+    /// Inject parameter definitions into user code
+    ///
+    /// Scans the code for `~name` references and prepends `~name: sig value`
+    /// for each referenced parameter.
+    ///
+    /// Example:
+    /// - User writes: `out: ~input >> mul ~drive`
+    /// - With drive=2.0, becomes:
+    ///   ```
+    ///   ~drive: sig 2.0
+    ///   out: ~input >> mul ~drive
+    ///   ```
+    pub fn inject(&self, user_code: &str) -> String {
+        let values = self.values();
+        let injected_lines: Vec<String> = self
+            .references(user_code)
+            .iter()
+            .map(|(name, index)| format!("~{}: sig {:.6}", name, values[*index]))
+            .collect();
+
+        if injected_lines.is_empty() {
+            user_code.to_string()
+        } else {
+            // Prepend injected definitions before user code
+            format!("{}\n{}", injected_lines.join("\n"), user_code)
+        }
+    }
+
+    /// Chains `inject()` defines for this code, in order: the name without
+    /// `~` and the index of its value in `values()`
+    pub fn references(&self, user_code: &str) -> Vec<(String, usize)> {
+        let mut references: Vec<(String, usize)> = PARAM_NAMES
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| user_code.contains(&format!("~{}", name)))
+            .map(|(index, name)| (name.to_string(), index))
+            .collect();
+        // Named knobs share the value of their `~knobN`
+        for index in 0..KNOB_COUNT {
+            let Some(name) = self.knob_names.get(index) else {
+                continue;
+            };
+            if user_code.contains(&format!("~{}", name)) {
+                references.push((name.to_string(), index));
+            }
+        }
+        references
+    }
+
+    /// Set a value by its `PARAM_NAMES` name, returning false for unknown
+    /// names
+    pub fn set(&mut self, name: &str, value: f32) -> bool {
+        let field = match name {
+            "knob1" => &mut self.knob1,
+            "knob2" => &mut self.knob2,
+            "knob3" => &mut self.knob3,
+            "knob4" => &mut self.knob4,
+            "knob5" => &mut self.knob5,
+            "knob6" => &mut self.knob6,
+            "knob7" => &mut self.knob7,
+            "knob8" => &mut self.knob8,
+            "drive" => &mut self.drive,
+            "feedback" => &mut self.feedback,
+            "mix" => &mut self.mix,
+            "rate" => &mut self.rate,
+            "expr" => &mut self.expr,
+            "sidechain" => &mut self.sidechain,
+            _ => return false,
+        };
+        *field = value;
+        true
+    }
+
+    /// All values, indexed like `PARAM_NAMES`
+    pub fn values(&self) -> [f32; PARAM_NAMES.len()] {
+        [
+            self.knob1,
+            self.knob2,
+            self.knob3,
+            self.knob4,
+            self.knob5,
+            self.knob6,
+            self.knob7,
+            self.knob8,
+            self.drive,
+            self.feedback,
+            self.mix,
+            self.rate,
+            self.expr,
+            self.sidechain,
+        ]
+    }
+}
+
+/// Append a message setting the injected chain `name` to `value`
+///
+/// Injected chains are a single `sig` node, so the message targets node 0,
+/// parameter 0. Messages end in `;` and several can go in one `send_msg()`.
+pub fn write_message(messages: &mut String, name: &str, value: f32) {
+    let _ = write!(messages, "~{},0,0,{:.6};", name, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_single_param() {
+        let mut injector = ParamInjector::new();
+        injector.drive = 2.5;
+
+        let code = "out: ~input >> mul ~drive";
+        let result = injector.inject(code);
+
+        assert!(result.contains("~drive: sig 2.5"));
+        assert!(result.ends_with(code));
+    }
+
+    #[test]
+    fn test_inject_multiple_params() {
+        let mut injector = ParamInjector::new();
+        injector.drive = 2.0;
+        injector.feedback = 0.5;
+
+        let code = "out: ~input >> mul ~drive >> delay ~feedback";
+        let result = injector.inject(code);
+
+        assert!(result.contains("~drive: sig 2.0"));
+        assert!(result.contains("~feedback: sig 0.5"));
+    }
+
+    #[test]
+    fn test_no_injection_when_not_referenced() {
+        let injector = ParamInjector::new();
+
+        let code = "out: ~input >> mul 0.5";
+        let result = injector.inject(code);
+
+        // Should be unchanged
+        assert_eq!(result, code);
+    }
+
+    #[test]
+    fn test_knobs() {
+        let mut injector = ParamInjector::new();
+        injector.knob1 = 0.25;
+        injector.knob2 = 0.75;
+
+        let code = "out: ~input >> lpf ~knob1 ~knob2";
+        let result = injector.inject(code);
+
+        assert!(result.contains("~knob1: sig 0.25"));
+        assert!(result.contains("~knob2: sig 0.75"));
+    }
+
+    #[test]
+    fn test_expr() {
+        let mut injector = ParamInjector::new();
+        injector.expr = 0.8;
+
+        let result = injector.inject("out: ~input >> mul ~expr");
+        assert!(result.starts_with("~expr: sig 0.8"));
+    }
+
+    #[test]
+    fn test_sidechain() {
+        let mut injector = ParamInjector::new();
+        injector.sidechain = 0.6;
+
+        let result = injector.inject("out: ~input >> mul ~sidechain");
+        assert!(result.starts_with("~sidechain: sig 0.6"));
+    }
+
+    #[test]
+    fn test_named_knobs() {
+        let mut injector = ParamInjector::new();
+        injector.knob7 = 0.4;
+        injector.knob_names.set(6, " ~shim-mer ");
+        assert_eq!(injector.knob_names.label(6), "~shimmer");
+        assert_eq!(injector.knob_names.label(0), "~knob1");
+
+        let code = "out: ~input >> plate ~shimmer >> mul ~knob7";
+        let result = injector.inject(code);
+        assert!(result.contains("~shimmer: sig 0.4"));
+        assert!(result.contains("~knob7: sig 0.4"));
+
+        // Built-in names can't be taken
+        injector.knob_names.set(0, "drive");
+        assert_eq!(injector.knob_names.get(0), None);
+        assert_eq!(injector.knob_names.aliases(), vec!["shimmer"]);
+    }
+
+    #[test]
+    fn test_references() {
+        let mut injector = ParamInjector::new();
+        injector.knob_names.set(1, "tone");
+
+        let references = injector.references("out: ~input >> lpf ~tone 1.0 >> mul ~drive");
+        assert_eq!(
+            references,
+            vec![("drive".to_string(), 8), ("tone".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_set_by_name() {
+        let mut injector = ParamInjector::new();
+        for (index, name) in PARAM_NAMES.iter().enumerate() {
+            assert!(injector.set(name, index as f32));
+        }
+        let expected: Vec<f32> = (0..PARAM_NAMES.len()).map(|i| i as f32).collect();
+        assert_eq!(injector.values().to_vec(), expected);
+        assert!(!injector.set("input", 1.0));
+
+        let defaults = ParamInjector::with_plugin_defaults();
+        assert_eq!(
+            (defaults.knob1, defaults.drive, defaults.rate),
+            (0.5, 1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_write_message() {
+        let mut messages = String::new();
+        write_message(&mut messages, "knob1", 0.25);
+        write_message(&mut messages, "drive", 2.0);
+        assert_eq!(messages, "~knob1,0,0,0.250000;~drive,0,0,2.000000;");
+    }
+}
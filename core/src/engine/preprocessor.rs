@@ -0,0 +1,405 @@
+//! Glicol code preprocessor
+//!
+//! Runs before parameter injection and expands two directives:
+//! - `#include "name"` pastes a snippet from the user snippet library
+//! - `#define name value` declares a constant, referenced as `$name`
+//!
+//! `#define` lines are replaced with blank lines. Includes do shift the
+//! lines that follow, so [`preprocess_mapped`] also returns a [`SourceMap`]
+//! to point compile errors back at the user's code.
+//!
+//! `$name` references are not substituted inside `//` comments.
+
+use std::collections::BTreeMap;
+
+use super::CompileError;
+
+/// Maximum include nesting depth (also catches include cycles)
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Expand includes and constants in user code
+///
+/// Example:
+/// - User writes:
+///   ```text
+///   #define cutoff 1200.0
+///   #include "wobble"
+///   out: ~input >> lpf $cutoff 0.7 >> mul ~wobble
+///   ```
+/// - With snippet `wobble` = `~wobble: sin 2.0 >> mul 0.5 >> add 0.5`, becomes:
+///   ```text
+///
+///   ~wobble: sin 2.0 >> mul 0.5 >> add 0.5
+///   out: ~input >> lpf 1200.0 0.7 >> mul ~wobble
+///   ```
+pub fn preprocess(code: &str, snippets: &BTreeMap<String, String>) -> Result<String, String> {
+    preprocess_mapped(code, snippets).map(|(expanded, _)| expanded)
+}
+
+/// [`preprocess`], plus where each expanded line came from
+pub fn preprocess_mapped(
+    code: &str,
+    snippets: &BTreeMap<String, String>,
+) -> Result<(String, SourceMap), String> {
+    // Fast path: nothing to expand
+    if !code.contains('#') && !code.contains('$') {
+        let lines = (1..=code.lines().count())
+            .map(|line| (line, false))
+            .collect();
+        return Ok((code.to_string(), SourceMap { lines, header: 0 }));
+    }
+
+    let mut constants = BTreeMap::new();
+    let mut output = Vec::new();
+    let mut lines = Vec::new();
+    let mut include_stack = Vec::new();
+    expand(
+        code,
+        snippets,
+        &mut constants,
+        &mut include_stack,
+        &mut output,
+        &mut lines,
+        None,
+    )?;
+    Ok((output.join("\n"), SourceMap { lines, header: 0 }))
+}
+
+/// The snippets `code` includes, directly or through other snippets
+///
+/// Names missing from `snippets` are skipped; preprocessing reports them.
+pub fn included_snippets(
+    code: &str,
+    snippets: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut included = BTreeMap::new();
+    let mut pending = vec![code];
+    while let Some(source) = pending.pop() {
+        for line in source.lines() {
+            let Some(name) = line
+                .trim()
+                .strip_prefix("#include")
+                .and_then(parse_include_name)
+            else {
+                continue;
+            };
+            if included.contains_key(name) {
+                continue;
+            }
+            if let Some((name, snippet)) = snippets.get_key_value(name) {
+                included.insert(name.clone(), snippet.clone());
+                pending.push(snippet);
+            }
+        }
+    }
+    included
+}
+
+/// User code line of each line of preprocessed code
+///
+/// Lines pasted in by `#include` map to the `#include` line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    /// 1-based user line, and whether the line came from a snippet
+    lines: Vec<(usize, bool)>,
+    /// Lines prepended after preprocessing (parameter injection)
+    header: usize,
+}
+
+impl SourceMap {
+    /// The same map for code with `lines` more lines prepended
+    pub fn with_header(mut self, lines: usize) -> Self {
+        self.header = lines;
+        self
+    }
+
+    /// Point an error's line (1-based, in the compiled code) at the user's
+    /// code. Errors in an included snippet point at the `#include`, without
+    /// a column; errors in the header lose their line.
+    pub fn locate(&self, error: &mut CompileError) {
+        let source = error
+            .line
+            .and_then(|line| line.checked_sub(self.header + 1))
+            .and_then(|index| self.lines.get(index).copied());
+        error.line = source.map(|(line, _)| line);
+        if source.map_or(true, |(_, included)| included) {
+            error.column = None;
+        }
+    }
+}
+
+/// Expand one source (user code or snippet) into `output`
+fn expand(
+    source: &str,
+    snippets: &BTreeMap<String, String>,
+    constants: &mut BTreeMap<String, String>,
+    include_stack: &mut Vec<String>,
+    output: &mut Vec<String>,
+    lines: &mut Vec<(usize, bool)>,
+    include_line: Option<usize>,
+) -> Result<(), String> {
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        // Snippet lines all map to the user's `#include`
+        let source_line = include_line.map_or((index + 1, false), |line| (line, true));
+
+        if let Some(rest) = trimmed.strip_prefix("#include") {
+            let name = parse_include_name(rest).ok_or_else(|| {
+                format!(
+                    "{}: expected #include \"name\"",
+                    line_label(source_line, include_stack)
+                )
+            })?;
+
+            if include_stack.iter().any(|n| n == name) {
+                return Err(format!("Include cycle: \"{}\" includes itself", name));
+            }
+            if include_stack.len() >= MAX_INCLUDE_DEPTH {
+                return Err(format!("Includes nested deeper than {}", MAX_INCLUDE_DEPTH));
+            }
+            let snippet = snippets
+                .get(name)
+                .ok_or_else(|| format!("Unknown snippet \"{}\"", name))?;
+
+            include_stack.push(name.to_string());
+            expand(
+                snippet,
+                snippets,
+                constants,
+                include_stack,
+                output,
+                lines,
+                Some(source_line.0),
+            )?;
+            include_stack.pop();
+        } else if let Some(rest) = trimmed.strip_prefix("#define") {
+            let (name, value) = parse_define(rest).ok_or_else(|| {
+                format!(
+                    "{}: expected #define name value",
+                    line_label(source_line, include_stack)
+                )
+            })?;
+            // Constants may reference earlier constants
+            let value = substitute(value, constants)?;
+            constants.insert(name.to_string(), value);
+            output.push(String::new());
+            lines.push(source_line);
+        } else {
+            output.push(substitute(line, constants)?);
+            lines.push(source_line);
+        }
+    }
+    Ok(())
+}
+
+/// "Line N" of the user's code for an error, naming the snippet (whose
+/// lines map to its `#include`) if the error is in one
+fn line_label(source_line: (usize, bool), include_stack: &[String]) -> String {
+    match include_stack.last() {
+        Some(snippet) if source_line.1 => {
+            format!("Line {} (in snippet \"{}\")", source_line.0, snippet)
+        }
+        _ => format!("Line {}", source_line.0),
+    }
+}
+
+/// Parse the quoted snippet name after `#include`
+fn parse_include_name(rest: &str) -> Option<&str> {
+    let name = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Parse `name value` after `#define` (a trailing `//` comment isn't part
+/// of the value)
+fn parse_define(rest: &str) -> Option<(&str, &str)> {
+    let rest = rest.split("//").next().unwrap_or_default().trim();
+    let split = rest.find(char::is_whitespace)?;
+    let (name, value) = rest.split_at(split);
+    if !is_identifier(name) {
+        return None;
+    }
+    Some((name, value.trim()))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Replace `$name` references with their constant values, up to any `//`
+/// comment
+fn substitute(line: &str, constants: &BTreeMap<String, String>) -> Result<String, String> {
+    if !line.contains('$') {
+        return Ok(line.to_string());
+    }
+
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if line[start..].starts_with("//") {
+            result.push_str(&line[start..]);
+            break;
+        }
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some(&(_, next)) = chars.peek() {
+            if next.is_ascii_alphanumeric() || next == '_' {
+                name.push(next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        match constants.get(&name) {
+            Some(value) => result.push_str(value),
+            None => return Err(format!("Undefined constant ${}", name)),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_passthrough_without_directives() {
+        let code = "out: ~input >> lpf 1000.0 0.7";
+        assert_eq!(preprocess(code, &BTreeMap::new()).unwrap(), code);
+    }
+
+    #[test]
+    fn test_define_constant() {
+        let code = "#define cutoff 1200.0\nout: ~input >> lpf $cutoff 0.7";
+        let result = preprocess(code, &BTreeMap::new()).unwrap();
+        assert_eq!(result, "\nout: ~input >> lpf 1200.0 0.7");
+    }
+
+    #[test]
+    fn test_include_snippet() {
+        let snippets = library(&[("wobble", "~wobble: sin 2.0")]);
+        let code = "#include \"wobble\"\nout: ~input >> mul ~wobble";
+        let result = preprocess(code, &snippets).unwrap();
+        assert!(result.contains("~wobble: sin 2.0"));
+        assert!(result.ends_with("out: ~input >> mul ~wobble"));
+    }
+
+    #[test]
+    fn test_snippet_can_define_constants() {
+        let snippets = library(&[("consts", "#define q 0.7")]);
+        let code = "#include \"consts\"\nout: ~input >> lpf 800.0 $q";
+        let result = preprocess(code, &snippets).unwrap();
+        assert!(result.ends_with("out: ~input >> lpf 800.0 0.7"));
+    }
+
+    #[test]
+    fn test_included_snippets_follow_nested_includes() {
+        let snippets = library(&[
+            ("outer", "#include \"inner\"\n~outer: ~inner >> mul 0.5"),
+            ("inner", "~inner: sin 2.0"),
+            ("unused", "~unused: sin 1.0"),
+        ]);
+        let code = "#include \"outer\"\n#include \"missing\"\nout: ~input >> mul ~outer";
+        let included = included_snippets(code, &snippets);
+        assert_eq!(included.keys().collect::<Vec<_>>(), ["inner", "outer"]);
+        assert!(included_snippets("out: ~input", &snippets).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_snippet_is_error() {
+        let result = preprocess("#include \"missing\"\nout: ~input", &BTreeMap::new());
+        assert!(result.unwrap_err().contains("missing"));
+    }
+
+    #[test]
+    fn test_undefined_constant_is_error() {
+        let result = preprocess("out: ~input >> mul $gain", &BTreeMap::new());
+        assert!(result.unwrap_err().contains("$gain"));
+    }
+
+    #[test]
+    fn test_constants_are_not_substituted_in_comments() {
+        let code = "#define gain 0.5\nout: ~input >> mul $gain // costs $5, not $gain";
+        let result = preprocess(code, &BTreeMap::new()).unwrap();
+        assert_eq!(result, "\nout: ~input >> mul 0.5 // costs $5, not $gain");
+        assert!(preprocess("// $5\nout: ~input", &BTreeMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_define_value_stops_at_comment() {
+        let code = "#define gain 0.5 // note\nout: ~input >> mul $gain >> lpf 800";
+        let result = preprocess(code, &BTreeMap::new()).unwrap();
+        assert_eq!(result, "\nout: ~input >> mul 0.5 >> lpf 800");
+    }
+
+    #[test]
+    fn test_snippet_errors_point_at_the_include() {
+        let snippets = library(&[("broken", "~a: sin 2.0\n#define")]);
+        let code = "out: ~input\n\n#include \"broken\"";
+        let error = preprocess(code, &snippets).unwrap_err();
+        assert!(
+            error.starts_with("Line 3 (in snippet \"broken\")"),
+            "{}",
+            error
+        );
+        let error = preprocess("out: ~input\n#include broken", &snippets).unwrap_err();
+        assert!(error.starts_with("Line 2:"), "{}", error);
+    }
+
+    #[test]
+    fn test_source_map_skips_included_lines() {
+        let snippets = library(&[("pair", "~a: sin 2.0\n~b: sin 3.0")]);
+        let code = "#define q 0.7\n#include \"pair\"\nout: ~input >> lpf 800.0 $q\nbad line";
+        let (expanded, map) = preprocess_mapped(code, &snippets).unwrap();
+        assert_eq!(expanded.lines().count(), 5);
+        // Two injected lines in front, then the error on expanded line 5
+        let map = map.with_header(2);
+        let mut error = CompileError {
+            message: "parse error".to_string(),
+            line: Some(7),
+            column: Some(3),
+        };
+        map.locate(&mut error);
+        assert_eq!((error.line, error.column), (Some(4), Some(3)));
+        // An error inside the snippet points at the include
+        let mut error = CompileError {
+            message: "parse error".to_string(),
+            line: Some(5),
+            column: Some(3),
+        };
+        map.locate(&mut error);
+        assert_eq!((error.line, error.column), (Some(2), None));
+        // Injected lines aren't the user's
+        let mut error = CompileError {
+            message: "parse error".to_string(),
+            line: Some(1),
+            column: Some(3),
+        };
+        map.locate(&mut error);
+        assert_eq!(error.line, None);
+    }
+
+    #[test]
+    fn test_include_cycle_is_error() {
+        let snippets = library(&[("a", "#include \"b\""), ("b", "#include \"a\"")]);
+        let result = preprocess("#include \"a\"\nout: ~input", &snippets);
+        assert!(result.unwrap_err().contains("cycle"));
+    }
+}
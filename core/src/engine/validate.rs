@@ -0,0 +1,53 @@
+//! Quick checks on Glicol code before it is compiled
+//!
+//! Catches the mistakes Glicol itself reports poorly (no code, no output
+//! node) and warns about code that ignores the input.
+
+/// Validate Glicol code before sending
+///
+/// Returns `Ok(None)` if valid, `Ok(Some(warning))` for code that runs but
+/// is probably not what was meant, or `Err` with a user-friendly message.
+pub fn validate(code: &str) -> Result<Option<String>, String> {
+    let trimmed = code.trim();
+
+    // Check for empty code
+    if trimmed.is_empty() {
+        return Err("Code cannot be empty".to_string());
+    }
+
+    // Check for output node - must have "out:" (not "~out:")
+    // Allow for whitespace variations like "out :" or "out  :"
+    let has_output = trimmed.lines().any(|line| {
+        let line = line.trim();
+        // Must start with "out" followed by optional whitespace and ":"
+        // But NOT start with "~out"
+        line.starts_with("out") && !line.starts_with("~out") && line.contains(':')
+    });
+
+    if !has_output {
+        return Err("Missing 'out:' - code must define an output node (not ~out:)".to_string());
+    }
+
+    // Warning (not error) if ~input is not referenced
+    let has_input = trimmed.contains("~input");
+    if !has_input {
+        return Ok(Some(
+            "Note: Code doesn't use ~input (live audio)".to_string(),
+        ));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate("out: ~input >> mul 0.5"), Ok(None));
+        assert!(validate("  \n").is_err());
+        assert!(validate("~out: ~input").is_err());
+        assert!(matches!(validate("out: sin 440"), Ok(Some(_))));
+    }
+}
@@ -0,0 +1,228 @@
+use glicol::Engine;
+
+use super::{CompileError, GLICOL_BLOCK_SIZE};
+
+/// Tempo until `set_bpm()` is called
+const DEFAULT_BPM: f32 = 120.0;
+
+/// Safe wrapper around Glicol's Engine<128>
+///
+/// Handles initialization, code hot-swapping, and block processing.
+///
+/// `process()` and `send_msg()` allocate a little on every call (Glicol
+/// takes its inputs as a `Vec` and parses messages into strings). Real-time
+/// callers that check for allocations must permit them around those calls.
+pub struct GlicolWrapper {
+    engine: Engine<GLICOL_BLOCK_SIZE>,
+    /// Temporary buffer for stereo output
+    left_buffer: [f32; GLICOL_BLOCK_SIZE],
+    right_buffer: [f32; GLICOL_BLOCK_SIZE],
+    /// Set by `update_code()`; Glicol compiles on the next processed block
+    compile_pending: bool,
+    /// Outcome of the last compile, taken by `take_compile_result()`
+    compile_result: Option<Result<(), CompileError>>,
+    /// Channels Glicol returned for the last block
+    output_channels: usize,
+    /// Tempo last given to Glicol
+    bpm: f32,
+}
+
+impl GlicolWrapper {
+    /// Create a new Glicol engine wrapper
+    pub fn new(sample_rate: f32) -> Self {
+        let mut engine = Engine::<GLICOL_BLOCK_SIZE>::new();
+        engine.set_sr(sample_rate as usize);
+        engine.set_bpm(DEFAULT_BPM);
+
+        // Initialize with plate reverb - no ~ prefix for output node!
+        engine.update_with_code("out: ~input >> plate 0.5");
+
+        Self {
+            engine,
+            left_buffer: [0.0; GLICOL_BLOCK_SIZE],
+            right_buffer: [0.0; GLICOL_BLOCK_SIZE],
+            compile_pending: false,
+            compile_result: None,
+            output_channels: 0,
+            bpm: DEFAULT_BPM,
+        }
+    }
+
+    /// Set the sample rate (call when DAW sample rate changes)
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.engine.set_sr(sample_rate as usize);
+    }
+
+    /// Set the tempo Glicol's sequencers run at (no-op if unchanged)
+    pub fn set_bpm(&mut self, bpm: f32) {
+        if bpm != self.bpm {
+            self.bpm = bpm;
+            self.engine.set_bpm(bpm);
+        }
+    }
+
+    /// Update the Glicol code (hot-swap)
+    ///
+    /// Glicol only parses the new code on the next `process()` call, so parse
+    /// errors are reported later through `take_compile_result()`.
+    /// On failure, the old code continues running.
+    pub fn update_code(&mut self, code: &str) -> Result<(), String> {
+        // Glicol's update_with_code handles diffing internally
+        self.engine.update_with_code(code);
+        self.compile_pending = true;
+        self.compile_result = None;
+        Ok(())
+    }
+
+    /// Send Glicol messages (`chain,node,param,value;` each) to the running
+    /// code, changing node parameters without a recompile
+    pub fn send_msg(&mut self, messages: &str) {
+        self.engine.send_msg(messages);
+    }
+
+    /// Number of output channels Glicol returned for the last block (0 means
+    /// it produced nothing and the block was silenced)
+    pub fn output_channels(&self) -> usize {
+        self.output_channels
+    }
+
+    /// Take the outcome of the most recent code update, once it is known
+    pub fn take_compile_result(&mut self) -> Option<Result<(), CompileError>> {
+        self.compile_result.take()
+    }
+
+    /// Process a block of audio samples
+    ///
+    /// Takes left and right input, returns references to left and right
+    /// output buffers. Input slices must be exactly GLICOL_BLOCK_SIZE samples.
+    pub fn process(&mut self, left: &[f32], right: &[f32]) -> (&[f32], &[f32]) {
+        debug_assert_eq!(left.len(), GLICOL_BLOCK_SIZE);
+        debug_assert_eq!(right.len(), GLICOL_BLOCK_SIZE);
+
+        // Glicol expects Vec of channel slices for input
+        // This small allocation (32 bytes) is unavoidable due to Glicol's API
+        let (buffers, status) = self.engine.next_block(vec![left, right]);
+
+        // Copy output to our buffers
+        // Each Buffer<N> derefs to &[f32] via Deref trait
+        self.output_channels = buffers.len();
+
+        if !buffers.is_empty() {
+            let left: &[f32] = &buffers[0]; // Deref to &[f32]
+            for (i, &sample) in left.iter().enumerate().take(GLICOL_BLOCK_SIZE) {
+                self.left_buffer[i] = sample;
+            }
+
+            // If stereo output, use second channel; otherwise duplicate mono
+            let right: &[f32] = if buffers.len() > 1 {
+                &buffers[1]
+            } else {
+                &buffers[0]
+            };
+            for (i, &sample) in right.iter().enumerate().take(GLICOL_BLOCK_SIZE) {
+                self.right_buffer[i] = sample;
+            }
+        } else {
+            // No output - fill with silence
+            self.left_buffer.fill(0.0);
+            self.right_buffer.fill(0.0);
+        }
+
+        // The first block after an update carries the compile status
+        if self.compile_pending {
+            self.compile_pending = false;
+            self.compile_result = Some(match decode_status(&status) {
+                Some(error) => Err(error),
+                None => Ok(()),
+            });
+        }
+
+        (&self.left_buffer, &self.right_buffer)
+    }
+
+    /// Reset the engine state (clear any delay lines, etc.)
+    pub fn reset(&mut self) {
+        // Re-initialize with current code would be ideal, but Glicol doesn't
+        // have a reset method. For now, just clear our buffers.
+        self.left_buffer.fill(0.0);
+        self.right_buffer.fill(0.0);
+    }
+}
+
+/// Decode Glicol's `next_block` status bytes into a compile error
+///
+/// Byte 0 is the error kind (0 = no error); the rest holds a NUL-padded
+/// UTF-8 description which, for parse errors, includes `line[N]` and `col[N]`.
+fn decode_status(status: &[u8]) -> Option<CompileError> {
+    if status.first().copied().unwrap_or(0) == 0 {
+        return None;
+    }
+
+    let text: String = String::from_utf8_lossy(&status[1..])
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    let text = text.trim();
+
+    let message = if text.is_empty() {
+        format!("Glicol error (code {})", status[0])
+    } else {
+        text.to_string()
+    };
+
+    Some(CompileError {
+        line: find_number_after(&message, "line"),
+        column: find_number_after(&message, "col"),
+        message,
+    })
+}
+
+/// Find the number following `key` in text like `line[3]` or `line 3`
+fn find_number_after(text: &str, key: &str) -> Option<usize> {
+    let start = text.find(key)? + key.len();
+    let digits: String = text[start..]
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+impl Default for GlicolWrapper {
+    fn default() -> Self {
+        Self::new(44100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_bytes(kind: u8, text: &str) -> [u8; 256] {
+        let mut status = [0u8; 256];
+        status[0] = kind;
+        status[2..2 + text.len()].copy_from_slice(text.as_bytes());
+        status
+    }
+
+    #[test]
+    fn test_decode_no_error() {
+        assert_eq!(decode_status(&[0u8; 256]), None);
+    }
+
+    #[test]
+    fn test_decode_parse_error_location() {
+        let status = status_bytes(1, "pos[12], line[2], col[5], positives[], negatives[]");
+        let error = decode_status(&status).unwrap();
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.column, Some(5));
+        assert!(error.message.starts_with("pos[12]"));
+    }
+
+    #[test]
+    fn test_decode_error_without_text() {
+        let error = decode_status(&status_bytes(2, "")).unwrap();
+        assert_eq!(error.line, None);
+        assert!(error.message.contains("code 2"));
+    }
+}
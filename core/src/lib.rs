@@ -0,0 +1,11 @@
+//! GlicolVerb's signal path without the plugin
+//!
+//! `dsp` holds the stereo processing modules and `engine` wraps the Glicol
+//! engine with its host buffer bridge, oversampler and code preprocessing.
+//! `render` runs the whole chain over a finished input.
+//! Nothing here depends on nih-plug, so offline tools and tests can run the
+//! same code the plugin does.
+
+pub mod dsp;
+pub mod engine;
+pub mod render;
@@ -0,0 +1,297 @@
+//! Offline Rendering
+//!
+//! Runs the whole signal path over a finished input, outside any host: the
+//! input gain, the EQ/Glicol/Delay modules in their order, width, dry/wet mix,
+//! output gain and limiter. Settings are fixed for the whole render and no
+//! real-time tricks apply (no bridge, no smoothing), so the same input, code
+//! and params always give the same output.
+
+use std::collections::BTreeMap;
+
+use crate::dsp::delay::Delay;
+use crate::dsp::eq::{Eq, EqBands};
+use crate::dsp::limiter::Limiter;
+use crate::dsp::{DspModule, ModuleKind, ModuleOrder, StereoSample};
+use crate::engine::{
+    preprocess_mapped, CompileError, EngineOversampler, GlicolWrapper, ParamInjector,
+    GLICOL_BLOCK_SIZE,
+};
+
+/// Delay module settings
+#[derive(Clone, Copy)]
+pub struct DelaySettings {
+    pub time_ms: f32,
+    pub feedback: f32,
+    pub mix: f32,
+    pub highcut: f32,
+}
+
+/// Everything besides the input and the code that shapes a render
+pub struct RenderParams {
+    pub sample_rate: f32,
+    /// Linear gain ahead of everything
+    pub input_gain: f32,
+    /// Linear gain after the dry/wet mix
+    pub output_gain: f32,
+    /// 0.0 = dry only, 1.0 = wet only
+    pub dry_wet: f32,
+    /// Stereo width of the wet signal (1.0 = unchanged)
+    pub width: f32,
+    /// EQ bands, or `None` to bypass the EQ
+    pub eq: Option<EqBands>,
+    /// Delay settings, or `None` to bypass the delay
+    pub delay: Option<DelaySettings>,
+    pub order: ModuleOrder,
+    /// Engine oversampling factor (1, 2 or 4)
+    pub oversampling: usize,
+    /// Values for the `~knob1`-style chains the code references
+    pub injector: ParamInjector,
+    /// Snippets available to `#include`
+    pub snippets: BTreeMap<String, String>,
+    pub limiter: bool,
+}
+
+impl RenderParams {
+    /// Unity gains, fully wet, no EQ, delay or limiter, and injected values
+    /// at their plugin defaults
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            input_gain: 1.0,
+            output_gain: 1.0,
+            dry_wet: 1.0,
+            width: 1.0,
+            eq: None,
+            delay: None,
+            order: ModuleOrder::default(),
+            oversampling: 1,
+            injector: ParamInjector::with_plugin_defaults(),
+            snippets: BTreeMap::new(),
+            limiter: false,
+        }
+    }
+}
+
+/// Render a mono input through `code` and the rest of the chain
+///
+/// The output is as long as the input and lined up with it: the latency of
+/// the wet path is rendered past the end and dropped from the start. Append
+/// silence to the input to hear a tail. Fails if the code does not
+/// preprocess or compile; compile errors point into `code`.
+pub fn render_offline(
+    input: &[f32],
+    code: &str,
+    params: &RenderParams,
+) -> Result<Vec<StereoSample>, CompileError> {
+    let sample_rate = params.sample_rate;
+    let mut eq = Eq::new(sample_rate);
+    match &params.eq {
+        Some(bands) => {
+            eq.set_low_freq(bands.low_freq);
+            eq.set_low_gain(bands.low_gain);
+            eq.set_low_enabled(bands.low_enabled);
+            eq.set_mid_freq(bands.mid_freq);
+            eq.set_mid_gain(bands.mid_gain);
+            eq.set_mid_q(bands.mid_q);
+            eq.set_mid_enabled(bands.mid_enabled);
+            eq.set_high_freq(bands.high_freq);
+            eq.set_high_gain(bands.high_gain);
+            eq.set_high_enabled(bands.high_enabled);
+        }
+        None => eq.set_bypassed(true),
+    }
+    let mut delay = Delay::new(sample_rate);
+    match &params.delay {
+        Some(settings) => {
+            delay.set_time_ms(settings.time_ms);
+            delay.set_feedback(settings.feedback);
+            delay.set_mix(settings.mix);
+            delay.set_highcut(settings.highcut);
+        }
+        None => delay.set_bypassed(true),
+    }
+    let mut limiter = Limiter::new(sample_rate);
+    limiter.set_bypassed(!params.limiter);
+
+    let mut oversampler = EngineOversampler::new();
+    oversampler.set_factor(params.oversampling);
+    let mut engine = GlicolWrapper::new(sample_rate * oversampler.factor() as f32);
+    let (expanded, source_map) =
+        preprocess_mapped(code, &params.snippets).map_err(|message| CompileError {
+            message,
+            line: None,
+            column: None,
+        })?;
+    let injected_code = params.injector.inject(&expanded);
+    let source_map = source_map.with_header(
+        injected_code
+            .lines()
+            .count()
+            .saturating_sub(expanded.lines().count()),
+    );
+    engine
+        .update_code(&injected_code)
+        .map_err(|message| CompileError {
+            message,
+            line: None,
+            column: None,
+        })?;
+
+    let (pre_glicol, post_glicol) = params.order.split();
+    let latency = oversampler.latency()
+        + post_glicol
+            .iter()
+            .map(|&kind| module_latency(&eq, &delay, kind))
+            .sum::<usize>();
+
+    // Pre-Glicol modules, on the mono input as in the plugin. This is also
+    // the dry signal.
+    let mut dry: Vec<StereoSample> = input
+        .iter()
+        .map(|&sample| StereoSample::from_mono(sample * params.input_gain))
+        .collect();
+    for &kind in pre_glicol {
+        process_module(&mut eq, &mut delay, kind, &mut dry);
+    }
+    for sample in dry.iter_mut() {
+        *sample = StereoSample::from_mono(sample.to_mono());
+    }
+
+    // Glicol, over whole blocks covering the input plus the latency
+    let wet_len = dry.len() + latency;
+    let blocks = wet_len.div_ceil(GLICOL_BLOCK_SIZE);
+    let mut wet = Vec::with_capacity(blocks * GLICOL_BLOCK_SIZE);
+    let mut block_left = [0.0; GLICOL_BLOCK_SIZE];
+    let mut block_right = [0.0; GLICOL_BLOCK_SIZE];
+    for block in 0..blocks {
+        let start = block * GLICOL_BLOCK_SIZE;
+        for i in 0..GLICOL_BLOCK_SIZE {
+            let sample = dry.get(start + i).copied().unwrap_or_default();
+            block_left[i] = sample.left;
+            block_right[i] = sample.right;
+        }
+        let (left, right) = oversampler.process(&mut engine, &block_left, &block_right);
+        wet.extend(
+            left.iter()
+                .zip(right)
+                .map(|(&left, &right)| StereoSample::new(left, right)),
+        );
+        if let Some(Err(mut error)) = engine.take_compile_result() {
+            source_map.locate(&mut error);
+            return Err(error);
+        }
+    }
+
+    // Post-Glicol modules and width, then drop the latency
+    for &kind in post_glicol {
+        process_module(&mut eq, &mut delay, kind, &mut wet);
+    }
+    let mut output: Vec<StereoSample> = wet[latency..wet_len]
+        .iter()
+        .map(|sample| sample.with_width(params.width))
+        .collect();
+
+    // Mix, output gain and limiter
+    for (sample, dry) in output.iter_mut().zip(&dry) {
+        let mixed = dry.mix(*sample, params.dry_wet);
+        *sample = StereoSample::new(
+            mixed.left * params.output_gain,
+            mixed.right * params.output_gain,
+        );
+    }
+    limiter.process_block_with_bypass(&mut output);
+    Ok(output)
+}
+
+fn module_latency(eq: &Eq, delay: &Delay, kind: ModuleKind) -> usize {
+    match kind {
+        ModuleKind::Eq => eq.latency(),
+        ModuleKind::Delay => delay.latency(),
+        ModuleKind::Glicol => 0,
+    }
+}
+
+fn process_module(eq: &mut Eq, delay: &mut Delay, kind: ModuleKind, block: &mut [StereoSample]) {
+    match kind {
+        ModuleKind::Eq => eq.process_block_with_bypass(block),
+        ModuleKind::Delay => delay.process_block_with_bypass(block),
+        ModuleKind::Glicol => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / 48000.0).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_output_matches_input_length() {
+        let input = sine(1000);
+        let output = render_offline(
+            &input,
+            "out: ~input >> mul 0.5",
+            &RenderParams::new(48000.0),
+        )
+        .unwrap();
+        assert_eq!(output.len(), input.len());
+    }
+
+    #[test]
+    fn test_dry_only_is_input() {
+        let input = sine(500);
+        let mut params = RenderParams::new(48000.0);
+        params.dry_wet = 0.0;
+        params.output_gain = 2.0;
+        let output = render_offline(&input, "out: ~input >> mul 0.5", &params).unwrap();
+        for (out, &sample) in output.iter().zip(&input) {
+            assert!((out.left - sample * 2.0).abs() < 1e-6);
+            assert!((out.right - sample * 2.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_renders_are_deterministic() {
+        let input = sine(3000);
+        let mut params = RenderParams::new(44100.0);
+        params.oversampling = 2;
+        params.delay = Some(DelaySettings {
+            time_ms: 10.0,
+            feedback: 0.5,
+            mix: 0.5,
+            highcut: 8000.0,
+        });
+        let code = "out: ~input >> plate 0.5";
+        let first = render_offline(&input, code, &params).unwrap();
+        let second = render_offline(&input, code, &params).unwrap();
+        assert!(first
+            .iter()
+            .zip(&second)
+            .all(|(a, b)| a.left == b.left && a.right == b.right));
+    }
+
+    #[test]
+    fn test_compile_error_points_into_code() {
+        let mut params = RenderParams::new(48000.0);
+        params.injector.drive = 2.0;
+        let error =
+            render_offline(&[0.0; 256], "out: ~input >> mul ~drive\nout: >>", &params).unwrap_err();
+        assert_eq!(error.line, Some(2));
+    }
+
+    #[test]
+    fn test_compile_error_after_include_points_into_code() {
+        let mut params = RenderParams::new(48000.0);
+        params.snippets.insert(
+            "lfo".to_string(),
+            "~lfo: sin 2.0\n~depth: ~lfo >> mul 0.5".to_string(),
+        );
+        let code = "#include \"lfo\"\nout: ~input >> mul ~depth\nout: >>";
+        let error = render_offline(&[0.0; 256], code, &params).unwrap_err();
+        assert_eq!(error.line, Some(3));
+    }
+}
@@ -0,0 +1,525 @@
+//! Local HTTP API for external editors
+//!
+//! An opt-in server on `127.0.0.1` that lets companion tools (a VS Code
+//! extension, a browser page) read the running code, check code and push
+//! it, and read the processing load and memory. Pushed code is compiled on
+//! the server thread first, so the caller gets the compile result back;
+//! code that compiles goes to the audio thread as a
+//! `CodeMessage::UpdateCode`, exactly like the Update button, and to the
+//! linked instances.
+//!
+//! Every request except CORS preflights needs `Authorization: Bearer
+//! <token>` with the token shown in the settings panel. Requests naming a
+//! host other than the loopback address are refused, so web pages can't
+//! reach the server through DNS rebinding, and checks and pushes are rate
+//! limited.
+
+#[cfg(feature = "editor")]
+use std::collections::hash_map::RandomState;
+#[cfg(feature = "editor")]
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+#[cfg(feature = "editor")]
+use std::time::SystemTime;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use glicol_verb_core::engine::GLICOL_BLOCK_SIZE;
+use glicol_verb_core::render::{render_offline, RenderParams};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::link::LinkHandle;
+use crate::memory;
+use crate::messages::CodeMessage;
+use crate::params::GlicolVerbParams;
+use crate::telemetry::Telemetry;
+
+/// Port used until the user picks another
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Largest request body accepted (code is small)
+const MAX_BODY: usize = 256 * 1024;
+
+/// Longest a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often the idle server checks whether it should stop
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Checks and pushes allowed in a burst, and refilled per second
+const RATE_BURST: f32 = 10.0;
+const RATE_PER_SECOND: f32 = 5.0;
+
+/// Sample rate code is test-compiled at
+const CHECK_SAMPLE_RATE: f32 = 48000.0;
+
+/// API options (persisted)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token clients must send (generated when first enabled)
+    pub token: String,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+/// A fresh random token, 32 hex digits (when the editor enables the API)
+#[cfg(feature = "editor")]
+pub fn generate_token() -> String {
+    let seed = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    (0..2)
+        .map(|half| {
+            // RandomState is seeded randomly per instance
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(seed);
+            hasher.write_u32(std::process::id());
+            hasher.write_u8(half);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// A running server thread and the flag that ends it
+struct Running {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+/// Shared by the plugin, which starts the server for sessions that have it
+/// enabled, and the editor, which turns it on and off
+pub struct ApiServer {
+    params: Arc<GlicolVerbParams>,
+    telemetry: Arc<Telemetry>,
+    code_sender: Sender<CodeMessage>,
+    link: Arc<LinkHandle>,
+    running: Mutex<Option<Running>>,
+}
+
+impl ApiServer {
+    pub fn new(
+        params: Arc<GlicolVerbParams>,
+        telemetry: Arc<Telemetry>,
+        code_sender: Sender<CodeMessage>,
+        link: Arc<LinkHandle>,
+    ) -> Self {
+        Self {
+            params,
+            telemetry,
+            code_sender,
+            link,
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Port the server listens on, if it is running
+    pub fn port(&self) -> Option<u16> {
+        self.running.lock().as_ref().map(|running| running.port)
+    }
+
+    /// Listen on the port from the settings (restarting if it changed)
+    pub fn start(&self) -> Result<u16, String> {
+        let settings = self.params.api.read().clone();
+        if self.port() == Some(settings.port) {
+            return Ok(settings.port);
+        }
+        self.stop();
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, settings.port))
+            .map_err(|e| format!("Couldn't listen on port {}: {}", settings.port, e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Couldn't start the API server: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_or(settings.port, |addr| addr.port());
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let handler = Handler {
+            params: self.params.clone(),
+            telemetry: self.telemetry.clone(),
+            code_sender: self.code_sender.clone(),
+            link: self.link.clone(),
+            port,
+            limiter: RateLimiter::new(Instant::now()),
+        };
+        let handle = {
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("glicolverb-api".to_string())
+                .spawn(move || serve(listener, handler, &stop))
+                .map_err(|e| format!("Couldn't start the API server: {}", e))?
+        };
+        *self.running.lock() = Some(Running { port, stop, handle });
+        Ok(port)
+    }
+
+    /// Stop listening and wait for the server thread
+    pub fn stop(&self) {
+        if let Some(running) = self.running.lock().take() {
+            running.stop.store(true, Ordering::Release);
+            let _ = running.handle.join();
+        }
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Accept connections one at a time until stopped
+fn serve(listener: TcpListener, mut handler: Handler, stop: &AtomicBool) {
+    while !stop.load(Ordering::Acquire) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let _ = handler.handle_connection(stream);
+            }
+            // Nothing waiting (or a failed accept): look again shortly
+            Err(_) => thread::sleep(ACCEPT_INTERVAL),
+        }
+    }
+}
+
+/// Token bucket for checks and pushes
+struct RateLimiter {
+    tokens: f32,
+    last: Instant,
+}
+
+impl RateLimiter {
+    fn new(now: Instant) -> Self {
+        Self {
+            tokens: RATE_BURST,
+            last: now,
+        }
+    }
+
+    /// Take one request's worth, or refuse when the burst is used up
+    fn allow(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f32();
+        self.last = now;
+        self.tokens = (self.tokens + elapsed * RATE_PER_SECOND).min(RATE_BURST);
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// Request line and the headers the server looks at
+#[derive(Debug, Default, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    host: Option<String>,
+    authorization: Option<String>,
+    content_length: usize,
+}
+
+/// Parse the request head (everything before the blank line)
+fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let mut request = Request {
+        method: request_line.next()?.to_string(),
+        path: request_line.next()?.to_string(),
+        ..Request::default()
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "host" => request.host = Some(value.to_string()),
+            "authorization" => request.authorization = Some(value.to_string()),
+            "content-length" => request.content_length = value.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(request)
+}
+
+/// Whether a `Host` header names this machine's loopback address
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    matches!(name, "127.0.0.1" | "localhost")
+}
+
+/// Compare tokens without returning early on the first difference
+fn token_matches(authorization: Option<&str>, token: &str) -> bool {
+    let Some(given) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Status line and JSON body of a response
+type Response = (&'static str, Value);
+
+struct Handler {
+    params: Arc<GlicolVerbParams>,
+    telemetry: Arc<Telemetry>,
+    code_sender: Sender<CodeMessage>,
+    link: Arc<LinkHandle>,
+    port: u16,
+    limiter: RateLimiter,
+}
+
+impl Handler {
+    fn handle_connection(&mut self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let response = match read_request(&mut stream) {
+            Ok((request, body)) => self.respond(&request, &body),
+            Err(response) => Some(response),
+        };
+        match response {
+            Some((status, body)) => write_response(&mut stream, status, &body.to_string()),
+            // CORS preflight
+            None => write_response(&mut stream, "204 No Content", ""),
+        }
+    }
+
+    /// Route a request; `None` answers a preflight with no body
+    fn respond(&mut self, request: &Request, body: &[u8]) -> Option<Response> {
+        if !request.host.as_deref().is_some_and(is_loopback_host) {
+            return Some(error("403 Forbidden", "Only local clients may connect"));
+        }
+        if request.method == "OPTIONS" {
+            return None;
+        }
+        let token = self.params.api.read().token.clone();
+        if !token_matches(request.authorization.as_deref(), &token) {
+            return Some(error("401 Unauthorized", "Missing or wrong token"));
+        }
+
+        Some(match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/code") => (
+                "200 OK",
+                json!({
+                    "code": *self.params.code.read(),
+                    "scene": self.params.scene.value().to_index(),
+                    "port": self.port,
+                }),
+            ),
+            ("GET", "/status") => ("200 OK", self.status()),
+            ("POST", "/check") | ("POST", "/code") => {
+                if !self.limiter.allow(Instant::now()) {
+                    return Some(error("429 Too Many Requests", "Slow down"));
+                }
+                let code = match code_from_body(body) {
+                    Ok(code) => code,
+                    Err(message) => return Some(error("400 Bad Request", &message)),
+                };
+                if let Err(result) = self.check(&code) {
+                    return Some(("200 OK", result));
+                }
+                if request.path == "/check" {
+                    return Some(("200 OK", json!({ "ok": true })));
+                }
+                match self
+                    .code_sender
+                    .try_send(CodeMessage::UpdateCode(code.clone()))
+                {
+                    Ok(()) => {
+                        let scene = self.params.scene.value().to_index();
+                        self.link.publish(scene, &code);
+                        ("200 OK", json!({ "ok": true, "applied": true }))
+                    }
+                    Err(_) => error("503 Service Unavailable", "Message queue full"),
+                }
+            }
+            (_, "/code") | (_, "/check") | (_, "/status") => {
+                error("405 Method Not Allowed", "Wrong method")
+            }
+            _ => error("404 Not Found", "Unknown endpoint"),
+        })
+    }
+
+    /// Processing load, underruns and process memory, for tools that track
+    /// patch cost
+    fn status(&self) -> Value {
+        let telemetry = &self.telemetry;
+        json!({
+            "load_percent": (telemetry.cpu_load.load() * 1000.0).round() / 10.0,
+            "underruns": telemetry.underruns.load(Ordering::Relaxed),
+            "memory_bytes": memory::resident_bytes(),
+            "sample_rate": telemetry.sample_rate.load(),
+            "latency_samples": telemetry.latency_samples.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Compile `code` with this session's snippets and knob names
+    fn check(&self, code: &str) -> Result<(), Value> {
+        let mut params = RenderParams::new(CHECK_SAMPLE_RATE);
+        params.snippets = self.params.snippets.read().clone();
+        params.injector.knob_names = self.params.knob_names.read().clone();
+        render_offline(&[0.0; GLICOL_BLOCK_SIZE], code, &params)
+            .map(|_| ())
+            .map_err(|e| {
+                json!({
+                    "ok": false,
+                    "error": { "message": e.message, "line": e.line, "column": e.column },
+                })
+            })
+    }
+}
+
+/// `{"code": "..."}` from a request body
+fn code_from_body(body: &[u8]) -> Result<String, String> {
+    #[derive(Deserialize)]
+    struct Body {
+        code: String,
+    }
+    let body: Body =
+        serde_json::from_slice(body).map_err(|e| format!("Expected {{\"code\": ...}}: {}", e))?;
+    if body.code.trim().is_empty() {
+        return Err("Code cannot be empty".to_string());
+    }
+    Ok(body.code)
+}
+
+fn error(status: &'static str, message: &str) -> Response {
+    (
+        status,
+        json!({ "ok": false, "error": { "message": message } }),
+    )
+}
+
+/// Read the head and the body it announces
+fn read_request(stream: &mut TcpStream) -> Result<(Request, Vec<u8>), Response> {
+    let bad_request = || error("400 Bad Request", "Malformed request");
+    let mut data = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+        if data.len() > MAX_BODY {
+            return Err(bad_request());
+        }
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return Err(bad_request()),
+            Ok(count) => data.extend_from_slice(&chunk[..count]),
+        }
+    };
+    let head = std::str::from_utf8(&data[..head_end]).map_err(|_| bad_request())?;
+    let request = parse_head(head).ok_or_else(bad_request)?;
+    if request.content_length > MAX_BODY {
+        return Err(error("413 Payload Too Large", "Code is too long"));
+    }
+
+    let mut body = data.split_off(head_end + 4);
+    while body.len() < request.content_length {
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return Err(bad_request()),
+            Ok(count) => body.extend_from_slice(&chunk[..count]),
+        }
+    }
+    body.truncate(request.content_length);
+    Ok((request, body))
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Authorization, Content-Type\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head(
+            "POST /code HTTP/1.1\r\nHost: 127.0.0.1:7878\r\nauthorization: Bearer abc\r\nContent-Length: 12",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/code");
+        assert_eq!(request.host.as_deref(), Some("127.0.0.1:7878"));
+        assert_eq!(request.authorization.as_deref(), Some("Bearer abc"));
+        assert_eq!(request.content_length, 12);
+        assert!(parse_head("").is_none());
+        assert!(parse_head("GET /code HTTP/1.1\r\nContent-Length: x").is_none());
+    }
+
+    #[test]
+    fn test_only_loopback_hosts() {
+        assert!(is_loopback_host("127.0.0.1:7878"));
+        assert!(is_loopback_host("localhost"));
+        assert!(!is_loopback_host("evil.example:7878"));
+        assert!(!is_loopback_host("localhost.evil.example"));
+    }
+
+    #[test]
+    fn test_token_must_match() {
+        assert!(token_matches(Some("Bearer abc"), "abc"));
+        assert!(!token_matches(Some("Bearer abd"), "abc"));
+        assert!(!token_matches(Some("abc"), "abc"));
+        assert!(!token_matches(None, "abc"));
+        // An unset token never authorizes
+        assert!(!token_matches(Some("Bearer "), ""));
+    }
+
+    #[test]
+    fn test_rate_limit_refills() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(start);
+        for _ in 0..RATE_BURST as usize {
+            assert!(limiter.allow(start));
+        }
+        assert!(!limiter.allow(start));
+        assert!(limiter.allow(start + Duration::from_millis(250)));
+    }
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn test_generated_tokens_differ() {
+        let token = generate_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_token());
+    }
+}
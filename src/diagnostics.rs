@@ -0,0 +1,245 @@
+//! Diagnostics log
+//!
+//! The audio thread reports events as small `Copy` values over a bounded
+//! channel: no formatting, no allocation, and events are dropped rather
+//! than blocking when the channel is full. The GUI drains them into a
+//! ring of formatted lines, forwards each one to nih-plug's logger at its
+//! level and shows them in the Diagnostics panel, which can export them to
+//! a file. Headless builds have no GUI to drain them, so they only keep
+//! the reporting side.
+
+#[cfg(feature = "editor")]
+use crossbeam_channel::Receiver;
+use crossbeam_channel::{bounded, Sender};
+#[cfg(feature = "editor")]
+use nih_plug::prelude::*;
+#[cfg(feature = "editor")]
+use parking_lot::Mutex;
+#[cfg(feature = "editor")]
+use std::collections::VecDeque;
+#[cfg(feature = "editor")]
+use std::path::Path;
+#[cfg(feature = "editor")]
+use std::time::Duration;
+use std::time::Instant;
+
+/// Events the audio thread can queue before the GUI drains them
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Lines kept for the panel and export
+#[cfg(feature = "editor")]
+pub const LOG_CAPACITY: usize = 500;
+
+#[cfg(feature = "editor")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+#[cfg(feature = "editor")]
+impl Level {
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/// Something the audio thread noticed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// The Glicol bridge ran dry (running total of missing samples)
+    Underrun { total: u32 },
+    /// Glicol changed how many output channels it returns
+    EngineChannels { count: usize },
+    /// Processing took longer than the buffer lasts
+    Overload { load: f32 },
+}
+
+#[cfg(feature = "editor")]
+impl Event {
+    pub fn level(&self) -> Level {
+        match self {
+            Event::Underrun { .. } | Event::Overload { .. } => Level::Warn,
+            Event::EngineChannels { count: 0 } => Level::Error,
+            Event::EngineChannels { .. } => Level::Info,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Event::Underrun { total } => {
+                format!("Glicol buffer underrun ({} samples missing so far)", total)
+            }
+            Event::EngineChannels { count: 0 } => {
+                "Glicol returned no output, playing silence".to_string()
+            }
+            Event::EngineChannels { count } => {
+                format!("Glicol outputs {} channel(s)", count)
+            }
+            Event::Overload { load } => {
+                format!("Processing overload ({:.0}% of the buffer)", load * 100.0)
+            }
+        }
+    }
+}
+
+/// One formatted log line
+#[cfg(feature = "editor")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogLine {
+    /// Time since the plugin was created
+    pub elapsed: Duration,
+    pub level: Level,
+    pub text: String,
+}
+
+#[cfg(feature = "editor")]
+impl LogLine {
+    /// Line as written to an exported file
+    pub fn format(&self) -> String {
+        format!(
+            "[{:>10.3}s] {:<5} {}",
+            self.elapsed.as_secs_f64(),
+            self.level.label(),
+            self.text
+        )
+    }
+}
+
+/// Shared between the audio thread (`report`) and the GUI (everything else)
+pub struct Diagnostics {
+    sender: Sender<(Instant, Event)>,
+    #[cfg(feature = "editor")]
+    started: Instant,
+    #[cfg(feature = "editor")]
+    receiver: Receiver<(Instant, Event)>,
+    #[cfg(feature = "editor")]
+    lines: Mutex<VecDeque<LogLine>>,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        let (sender, receiver) = bounded(CHANNEL_CAPACITY);
+        // Without the editor nothing drains them, so reporting just fails
+        #[cfg(not(feature = "editor"))]
+        drop(receiver);
+        Self {
+            sender,
+            #[cfg(feature = "editor")]
+            started: Instant::now(),
+            #[cfg(feature = "editor")]
+            receiver,
+            #[cfg(feature = "editor")]
+            lines: Mutex::new(VecDeque::with_capacity(LOG_CAPACITY)),
+        }
+    }
+}
+
+impl Diagnostics {
+    /// Queue an event (audio thread; never blocks or allocates)
+    pub fn report(&self, event: Event) {
+        let _ = self.sender.try_send((Instant::now(), event));
+    }
+}
+
+#[cfg(feature = "editor")]
+impl Diagnostics {
+    /// Log a line from the GUI thread
+    pub fn log(&self, level: Level, text: String) {
+        self.push(LogLine {
+            elapsed: self.started.elapsed(),
+            level,
+            text,
+        });
+    }
+
+    /// Move queued audio events into the log (GUI thread, every frame)
+    pub fn drain(&self) {
+        while let Ok((at, event)) = self.receiver.try_recv() {
+            self.push(LogLine {
+                elapsed: at.saturating_duration_since(self.started),
+                level: event.level(),
+                text: event.describe(),
+            });
+        }
+    }
+
+    fn push(&self, line: LogLine) {
+        match line.level {
+            Level::Info => nih_log!("{}", line.text),
+            Level::Warn => nih_warn!("{}", line.text),
+            Level::Error => nih_error!("{}", line.text),
+        }
+        let mut lines = self.lines.lock();
+        if lines.len() == LOG_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Copy of the current log, oldest first
+    pub fn lines(&self) -> Vec<LogLine> {
+        self.lines.lock().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.lines.lock().clear();
+    }
+
+    /// Write the log to a text file
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let text: String = self
+            .lines
+            .lock()
+            .iter()
+            .map(|line| line.format() + "\n")
+            .collect();
+        std::fs::write(path, text).map_err(|e| format!("Couldn't write the log: {}", e))
+    }
+}
+
+#[cfg(all(test, feature = "editor"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drains_events_in_order() {
+        let diagnostics = Diagnostics::default();
+        diagnostics.report(Event::EngineChannels { count: 2 });
+        diagnostics.report(Event::Underrun { total: 64 });
+        assert!(diagnostics.lines().is_empty());
+
+        diagnostics.drain();
+        let lines = diagnostics.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].level, Level::Info);
+        assert_eq!(lines[1].level, Level::Warn);
+        assert!(lines[1].text.contains("64 samples"));
+    }
+
+    #[test]
+    fn test_keeps_latest_lines() {
+        let diagnostics = Diagnostics::default();
+        for i in 0..LOG_CAPACITY + 10 {
+            diagnostics.log(Level::Info, i.to_string());
+        }
+        let lines = diagnostics.lines();
+        assert_eq!(lines.len(), LOG_CAPACITY);
+        assert_eq!(lines[0].text, "10");
+    }
+
+    #[test]
+    fn test_line_format() {
+        let line = LogLine {
+            elapsed: Duration::from_millis(1500),
+            level: Level::Warn,
+            text: "underrun".to_string(),
+        };
+        assert_eq!(line.format(), "[     1.500s] WARN  underrun");
+    }
+}
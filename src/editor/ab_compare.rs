@@ -0,0 +1,51 @@
+//! A/B compare buttons
+//!
+//! Each slot holds a full snapshot (editor code plus all parameters), so
+//! two versions of a patch can be flipped between while listening.
+
+use nih_plug_egui::egui;
+
+use crate::params::GlicolVerbParams;
+use crate::preset::{AbSlot, Preset};
+
+/// Draw the A / B / A→B buttons (right-to-left header layout)
+///
+/// Returns a snapshot to load when switching slots.
+pub fn ab_buttons(
+    ui: &mut egui::Ui,
+    params: &GlicolVerbParams,
+    code_buffer: &str,
+) -> Option<Preset> {
+    let mut load = None;
+    let active = params.ab_snapshots.read().active;
+
+    if ui
+        .small_button("A→B")
+        .on_hover_text("Copy snapshot A into B")
+        .clicked()
+    {
+        params
+            .ab_snapshots
+            .write()
+            .copy_a_to_b(capture(params, code_buffer));
+    }
+    for (slot, label) in [(AbSlot::B, "B"), (AbSlot::A, "A")] {
+        if ui
+            .selectable_label(active == slot, label)
+            .on_hover_text(format!("Switch to snapshot {}", label))
+            .clicked()
+        {
+            load = params
+                .ab_snapshots
+                .write()
+                .switch(slot, capture(params, code_buffer));
+        }
+    }
+
+    load
+}
+
+/// Snapshot of the current state, using the (possibly unapplied) editor code
+fn capture(params: &GlicolVerbParams, code_buffer: &str) -> Preset {
+    Preset::capture("", code_buffer, params)
+}
@@ -0,0 +1,74 @@
+//! Local HTTP API settings
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+
+use super::theme;
+use crate::api::{generate_token, ApiServer, ApiSettings};
+
+/// API switch, port and token, for the settings panel
+///
+/// Returns a status line for the editor and whether it is an error.
+pub fn api_settings(
+    ui: &mut egui::Ui,
+    server: &ApiServer,
+    api: &RwLock<ApiSettings>,
+) -> Option<(String, bool)> {
+    let mut settings = api.read().clone();
+    let mut status = None;
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut settings.enabled, "HTTP API")
+            .on_hover_text("Lets local tools read, check and push code over HTTP");
+        ui.label(egui::RichText::new("Port").color(theme::text_normal()));
+        ui.add_enabled(
+            !settings.enabled,
+            egui::DragValue::new(&mut settings.port).range(1024..=65535),
+        )
+        .on_disabled_hover_text("Turn the API off to change the port");
+    });
+    if settings.enabled {
+        if settings.token.is_empty() {
+            settings.token = generate_token();
+        }
+        ui.horizontal(|ui| {
+            let text = match server.port() {
+                Some(port) => format!("http://127.0.0.1:{}", port),
+                None => "Not running".to_string(),
+            };
+            ui.label(egui::RichText::new(text).small().color(theme::text_dim()));
+            if ui
+                .small_button("Copy token")
+                .on_hover_text("Clients send it as \"Authorization: Bearer <token>\"")
+                .clicked()
+            {
+                ui.ctx().copy_text(settings.token.clone());
+            }
+            if ui
+                .small_button("New token")
+                .on_hover_text("Connected tools need the new token")
+                .clicked()
+            {
+                settings.token = generate_token();
+            }
+        });
+    }
+
+    if settings != *api.read() {
+        let was_enabled = api.read().enabled;
+        *api.write() = settings.clone();
+        if settings.enabled && !was_enabled {
+            status = Some(match server.start() {
+                Ok(port) => (format!("HTTP API listening on port {}", port), false),
+                Err(e) => {
+                    api.write().enabled = false;
+                    (e, true)
+                }
+            });
+        } else if !settings.enabled && was_enabled {
+            server.stop();
+            status = Some(("HTTP API stopped".to_string(), false));
+        }
+    }
+    status
+}
@@ -0,0 +1,205 @@
+//! Autocomplete popup for the code editor
+//!
+//! Opens while typing (or on Ctrl+Space) with node names and `~references`.
+//! Arrow keys select, Tab/Enter accept, Escape closes.
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::lang::completion::{complete, word_before_cursor, Completion};
+
+/// Maximum number of suggestions in the popup
+const MAX_ITEMS: usize = 8;
+
+/// Keys the popup takes from the text edit while it is open
+enum PopupKey {
+    Up,
+    Down,
+    Accept,
+    Close,
+}
+
+/// Autocomplete popup state (lives in `EditorState`)
+#[derive(Default)]
+pub struct Autocomplete {
+    items: Vec<Completion>,
+    selected: usize,
+    /// Char index where the completed word starts
+    word_start: usize,
+    /// Char index of the cursor when suggestions were computed
+    cursor: usize,
+    /// Screen position just below the cursor
+    anchor: egui::Pos2,
+}
+
+impl Autocomplete {
+    fn is_open(&self) -> bool {
+        !self.items.is_empty()
+    }
+
+    fn close(&mut self) {
+        self.items.clear();
+    }
+
+    /// Handle popup keys before the text edit sees them
+    ///
+    /// Returns true when Ctrl+Space asked for suggestions explicitly.
+    pub fn handle_keys(
+        &mut self,
+        ui: &mut egui::Ui,
+        code: &mut String,
+        editor_id: egui::Id,
+    ) -> bool {
+        if !ui.memory(|m| m.has_focus(editor_id)) {
+            return false;
+        }
+
+        let triggered = ui.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::Space));
+        if !self.is_open() {
+            return triggered;
+        }
+
+        let key = ui.input_mut(|i| {
+            if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+                Some(PopupKey::Down)
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+                Some(PopupKey::Up)
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Tab)
+                || i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)
+            {
+                Some(PopupKey::Accept)
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                Some(PopupKey::Close)
+            } else {
+                None
+            }
+        });
+
+        let count = self.items.len();
+        match key {
+            Some(PopupKey::Down) => self.selected = (self.selected + 1) % count,
+            Some(PopupKey::Up) => self.selected = (self.selected + count - 1) % count,
+            Some(PopupKey::Accept) => self.accept(ui.ctx(), code, editor_id, self.selected),
+            Some(PopupKey::Close) => self.close(),
+            None => {}
+        }
+        triggered
+    }
+
+    /// Refresh suggestions after the text edit has been shown
+    ///
+    /// `aliases` are the user's knob names.
+    pub fn update(
+        &mut self,
+        output: &egui::text_edit::TextEditOutput,
+        code: &str,
+        force: bool,
+        aliases: &[&str],
+    ) {
+        let Some(range) = output.cursor_range else {
+            self.close();
+            return;
+        };
+        if !output.response.has_focus() {
+            self.close();
+            return;
+        }
+
+        let cursor = range.primary.ccursor.index;
+        if !output.response.changed() && !force {
+            // Moving the cursor away dismisses the popup
+            if cursor != self.cursor {
+                self.close();
+            }
+            return;
+        }
+
+        let (word_start, word) = word_before_cursor(code, cursor);
+        // References pop up on `~`, node names after two letters
+        let long_enough = force || word.starts_with('~') || word.chars().count() >= 2;
+        self.items = if long_enough {
+            complete(code, &word, aliases)
+        } else {
+            Vec::new()
+        };
+        self.items.truncate(MAX_ITEMS);
+        self.selected = 0;
+        self.word_start = word_start;
+        self.cursor = cursor;
+
+        let cursor_rect = output.galley.pos_from_ccursor(range.primary.ccursor);
+        self.anchor = output.galley_pos + cursor_rect.left_bottom().to_vec2();
+    }
+
+    /// Draw the popup below the cursor
+    pub fn show(&mut self, ctx: &egui::Context, code: &mut String, editor_id: egui::Id) {
+        if !self.is_open() {
+            return;
+        }
+
+        let mut clicked = None;
+        egui::Area::new(egui::Id::new("code_autocomplete"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(self.anchor)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for (index, item) in self.items.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            let label = egui::RichText::new(&item.label)
+                                .monospace()
+                                .color(theme::text_bright());
+                            if ui.selectable_label(index == self.selected, label).clicked() {
+                                clicked = Some(index);
+                            }
+                            ui.label(
+                                egui::RichText::new(&item.detail)
+                                    .color(theme::text_dim())
+                                    .small(),
+                            );
+                        });
+                    }
+                });
+            });
+
+        if let Some(index) = clicked {
+            self.accept(ctx, code, editor_id, index);
+        }
+    }
+
+    /// Replace the typed word with the chosen completion
+    fn accept(
+        &mut self,
+        ctx: &egui::Context,
+        code: &mut String,
+        editor_id: egui::Id,
+        index: usize,
+    ) {
+        let Some(item) = self.items.get(index) else {
+            return;
+        };
+
+        let start = char_to_byte(code, self.word_start);
+        let end = char_to_byte(code, self.cursor);
+        code.replace_range(start..end, &item.label);
+
+        // Put the cursor after the inserted text and keep typing focus
+        let new_cursor = self.word_start + item.label.chars().count();
+        if let Some(mut text_state) = egui::TextEdit::load_state(ctx, editor_id) {
+            let ccursor = egui::text::CCursor::new(new_cursor);
+            text_state
+                .cursor
+                .set_char_range(Some(egui::text::CCursorRange::one(ccursor)));
+            text_state.store(ctx, editor_id);
+        }
+        ctx.memory_mut(|m| m.request_focus(editor_id));
+
+        self.close();
+    }
+}
+
+/// Convert a char index into a byte index
+pub fn char_to_byte(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(byte, _)| byte)
+}
@@ -0,0 +1,172 @@
+//! Shortcut dispatch and the cheat-sheet overlay
+//!
+//! Bound shortcuts are consumed at the start of the frame so the code
+//! editor never sees them. The overlay lists every shortcut and rebinds
+//! one by clicking it and pressing the new keys.
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+use std::cmp::Reverse;
+
+use super::theme;
+use crate::shortcuts::{Action, Shortcut, ShortcutMap};
+
+/// Text editing keys that are fixed (shown for reference)
+const FIXED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Undo", "Ctrl+Z"),
+    ("Redo", "Ctrl+Shift+Z / Ctrl+Y"),
+    ("Autocomplete", "Ctrl+Space"),
+];
+
+/// Cheat-sheet overlay state (lives in `EditorState`)
+#[derive(Default)]
+pub struct CheatSheet {
+    pub open: bool,
+    /// Action waiting for its new key
+    rebinding: Option<Action>,
+    /// Result of the last rebind
+    message: Option<String>,
+}
+
+impl CheatSheet {
+    /// While rebinding, take the next key press as the new shortcut
+    ///
+    /// Returns true while capturing (shortcuts must not fire then).
+    pub fn capture(&mut self, ctx: &egui::Context, map: &RwLock<ShortcutMap>) -> bool {
+        let Some(action) = self.rebinding else {
+            return false;
+        };
+
+        let pressed = ctx.input_mut(|i| {
+            let pressed = i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            });
+            // Nothing typed while capturing reaches other widgets
+            i.events
+                .retain(|event| !matches!(event, egui::Event::Key { .. } | egui::Event::Text(_)));
+            pressed
+        });
+
+        match pressed {
+            Some((egui::Key::Escape, _)) => {
+                self.rebinding = None;
+                self.message = None;
+            }
+            Some((key, modifiers)) => {
+                let shortcut = Shortcut::new(
+                    key.name(),
+                    modifiers.command || modifiers.ctrl,
+                    modifiers.shift,
+                    modifiers.alt,
+                );
+                self.message = map
+                    .write()
+                    .set(action, shortcut)
+                    .map(|displaced| format!("\"{}\" is now unbound", displaced.label()));
+                self.rebinding = None;
+            }
+            None => {}
+        }
+        true
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, map: &RwLock<ShortcutMap>) {
+        if !self.open {
+            self.rebinding = None;
+            return;
+        }
+
+        let mut open = self.open;
+        egui::Window::new("Keyboard shortcuts")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("Click a shortcut and press the new keys (Esc cancels)")
+                        .color(theme::text_dim())
+                        .small(),
+                );
+                ui.add_space(4.0);
+
+                egui::Grid::new("shortcut_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for &action in Action::ALL {
+                            ui.label(
+                                egui::RichText::new(action.label()).color(theme::text_normal()),
+                            );
+                            let text = if self.rebinding == Some(action) {
+                                "press keys…".to_string()
+                            } else {
+                                map.read()
+                                    .get(action)
+                                    .map_or_else(|| "unbound".to_string(), |s| s.format())
+                            };
+                            if ui.button(egui::RichText::new(text).monospace()).clicked() {
+                                self.rebinding = Some(action);
+                                self.message = None;
+                            }
+                            ui.end_row();
+                        }
+                        for (label, keys) in FIXED_SHORTCUTS {
+                            ui.label(egui::RichText::new(*label).color(theme::text_dim()));
+                            ui.label(
+                                egui::RichText::new(*keys)
+                                    .monospace()
+                                    .color(theme::text_dim()),
+                            );
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(message) = &self.message {
+                    ui.colored_label(theme::status_error(), message);
+                }
+                if ui.button("Reset to defaults").clicked() {
+                    *map.write() = ShortcutMap::default();
+                    self.message = None;
+                }
+            });
+        self.open = open;
+    }
+}
+
+/// Actions whose shortcut was pressed this frame
+///
+/// Shortcuts that would type text are skipped while a text field has focus.
+pub fn pressed_actions(ctx: &egui::Context, map: &ShortcutMap) -> Vec<Action> {
+    let typing = ctx.wants_keyboard_input();
+    let mut bound: Vec<(Action, Shortcut)> = Action::ALL
+        .iter()
+        .filter_map(|&action| map.get(action).map(|shortcut| (action, shortcut)))
+        .filter(|(_, shortcut)| !(typing && shortcut.types_text()))
+        .collect();
+    // Most specific first, so a Ctrl+E binding can't take Ctrl+Shift+E
+    bound.sort_by_key(|(_, shortcut)| {
+        Reverse(shortcut.command as u8 + shortcut.shift as u8 + shortcut.alt as u8)
+    });
+
+    ctx.input_mut(|i| {
+        bound
+            .into_iter()
+            .filter_map(|(action, shortcut)| {
+                let key = egui::Key::from_name(&shortcut.key)?;
+                let mut modifiers = egui::Modifiers::NONE;
+                modifiers.command = shortcut.command;
+                modifiers.shift = shortcut.shift;
+                modifiers.alt = shortcut.alt;
+                i.consume_shortcut(&egui::KeyboardShortcut::new(modifiers, key))
+                    .then_some(action)
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,104 @@
+//! Diagnostics panel: the log of audio-thread events and compile errors,
+//! with a level filter and export to a text file
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::diagnostics::{Diagnostics, Level};
+
+/// Panel state (lives in `EditorState`)
+pub struct DiagnosticsPanel {
+    /// Lowest level shown
+    min_level: Level,
+    /// Result of the last export (message, is_error)
+    export_status: Option<(String, bool)>,
+}
+
+impl Default for DiagnosticsPanel {
+    fn default() -> Self {
+        Self {
+            min_level: Level::Info,
+            export_status: None,
+        }
+    }
+}
+
+impl DiagnosticsPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, diagnostics: &Diagnostics) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Show").color(theme::text_dim()));
+            ui.selectable_value(&mut self.min_level, Level::Info, "All");
+            ui.selectable_value(&mut self.min_level, Level::Warn, "Warnings");
+            ui.selectable_value(&mut self.min_level, Level::Error, "Errors");
+            if ui.small_button("Clear").clicked() {
+                diagnostics.clear();
+            }
+            if ui
+                .small_button("Export…")
+                .on_hover_text("Save the log to a text file")
+                .clicked()
+            {
+                self.export(diagnostics);
+            }
+        });
+        if let Some((message, is_error)) = &self.export_status {
+            let color = if *is_error {
+                theme::status_error()
+            } else {
+                theme::text_dim()
+            };
+            ui.label(egui::RichText::new(message).color(color).small());
+        }
+
+        let lines: Vec<_> = diagnostics
+            .lines()
+            .into_iter()
+            .filter(|line| line.level >= self.min_level)
+            .collect();
+        if lines.is_empty() {
+            ui.label(
+                egui::RichText::new("Nothing logged")
+                    .color(theme::text_dim())
+                    .small(),
+            );
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(160.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in &lines {
+                    let color = match line.level {
+                        Level::Info => theme::text_normal(),
+                        Level::Warn => theme::status_bypass(),
+                        Level::Error => theme::status_error(),
+                    };
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{:>8.1}s  {}",
+                            line.elapsed.as_secs_f32(),
+                            line.text
+                        ))
+                        .color(color)
+                        .monospace()
+                        .small(),
+                    );
+                }
+            });
+    }
+
+    fn export(&mut self, diagnostics: &Diagnostics) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Log", &["log", "txt"])
+            .set_file_name("glicol_verb.log")
+            .save_file()
+        else {
+            return;
+        };
+        self.export_status = Some(match diagnostics.export(&path) {
+            Ok(()) => (format!("Saved {}", path.display()), false),
+            Err(e) => (e, true),
+        });
+    }
+}
@@ -0,0 +1,181 @@
+//! Interactive EQ response curve
+//!
+//! Draws the combined response of the three bands with a handle per band:
+//! drag for frequency (X) and gain (Y), scroll over the mid handle for Q.
+
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::dsp::eq::EqBands;
+use crate::params::GlicolVerbParams;
+
+const MIN_FREQ: f32 = 20.0;
+const MAX_FREQ: f32 = 20000.0;
+/// Vertical range of the plot (+/- dB)
+const RANGE_DB: f32 = 15.0;
+const MAX_GAIN_DB: f32 = 12.0;
+const HANDLE_RADIUS: f32 = 5.0;
+const PLOT_HEIGHT: f32 = 80.0;
+
+/// One draggable band handle
+struct Handle<'a> {
+    label: &'static str,
+    freq_param: &'a FloatParam,
+    gain_param: &'a FloatParam,
+    freq_range: (f32, f32),
+}
+
+/// Draw the curve and apply handle drags to `bands` and the host params
+pub fn eq_curve(
+    ui: &mut egui::Ui,
+    params: &GlicolVerbParams,
+    setter: &ParamSetter,
+    bands: &mut EqBands,
+    sample_rate: f32,
+) {
+    let width = ui.available_width();
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, PLOT_HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, theme::bg_dark());
+    let grid = egui::Stroke::new(1.0, theme::knob_ring());
+    painter.line_segment(
+        [
+            egui::pos2(rect.left(), rect.center().y),
+            egui::pos2(rect.right(), rect.center().y),
+        ],
+        grid,
+    );
+
+    let handles = [
+        Handle {
+            label: "Low",
+            freq_param: &params.eq.low_freq,
+            gain_param: &params.eq.low_gain,
+            freq_range: (20.0, 500.0),
+        },
+        Handle {
+            label: "Mid",
+            freq_param: &params.eq.mid_freq,
+            gain_param: &params.eq.mid_gain,
+            freq_range: (200.0, 8000.0),
+        },
+        Handle {
+            label: "High",
+            freq_param: &params.eq.high_freq,
+            gain_param: &params.eq.high_gain,
+            freq_range: (2000.0, 20000.0),
+        },
+    ];
+
+    for (index, handle) in handles.iter().enumerate() {
+        let (freq, gain) = band_point(bands, index);
+        let center = egui::pos2(freq_to_x(rect, freq), db_to_y(rect, gain));
+        let handle_rect =
+            egui::Rect::from_center_size(center, egui::Vec2::splat(HANDLE_RADIUS * 4.0));
+        let response = ui
+            .interact(
+                handle_rect,
+                ui.id().with(("eq_handle", index)),
+                egui::Sense::drag(),
+            )
+            .on_hover_text(format!("{}: drag to set freq/gain", handle.label));
+
+        if response.drag_started() {
+            setter.begin_set_parameter(handle.freq_param);
+            setter.begin_set_parameter(handle.gain_param);
+        }
+        if response.dragged() {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                let freq =
+                    x_to_freq(rect, pointer.x).clamp(handle.freq_range.0, handle.freq_range.1);
+                let gain = y_to_db(rect, pointer.y).clamp(-MAX_GAIN_DB, MAX_GAIN_DB);
+                set_band_point(bands, index, freq, gain);
+                setter.set_parameter(handle.freq_param, freq);
+                setter.set_parameter(handle.gain_param, gain);
+            }
+        }
+        if response.drag_stopped() {
+            setter.end_set_parameter(handle.freq_param);
+            setter.end_set_parameter(handle.gain_param);
+        }
+
+        // Scroll over the mid handle to change its Q
+        if index == 1 && response.hovered() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                bands.mid_q = (bands.mid_q * (scroll * 0.005).exp()).clamp(0.5, 4.0);
+                setter.begin_set_parameter(&params.eq.mid_q);
+                setter.set_parameter(&params.eq.mid_q, bands.mid_q);
+                setter.end_set_parameter(&params.eq.mid_q);
+            }
+        }
+    }
+
+    // Response curve, one point per pixel column
+    let points: Vec<egui::Pos2> = (0..=rect.width() as usize)
+        .map(|column| {
+            let x = rect.left() + column as f32;
+            let db = bands.response_db(x_to_freq(rect, x), sample_rate);
+            egui::pos2(x, db_to_y(rect, db))
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.5, theme::accent()),
+    ));
+
+    for index in 0..handles.len() {
+        let (freq, gain) = band_point(bands, index);
+        let center = egui::pos2(freq_to_x(rect, freq), db_to_y(rect, gain));
+        painter.circle_filled(center, HANDLE_RADIUS, theme::knob_indicator());
+    }
+}
+
+fn band_point(bands: &EqBands, index: usize) -> (f32, f32) {
+    match index {
+        0 => (bands.low_freq, bands.low_gain),
+        1 => (bands.mid_freq, bands.mid_gain),
+        _ => (bands.high_freq, bands.high_gain),
+    }
+}
+
+fn set_band_point(bands: &mut EqBands, index: usize, freq: f32, gain: f32) {
+    match index {
+        0 => (bands.low_freq, bands.low_gain) = (freq, gain),
+        1 => (bands.mid_freq, bands.mid_gain) = (freq, gain),
+        _ => (bands.high_freq, bands.high_gain) = (freq, gain),
+    }
+}
+
+fn freq_to_x(rect: egui::Rect, freq: f32) -> f32 {
+    let t = (freq / MIN_FREQ).ln() / (MAX_FREQ / MIN_FREQ).ln();
+    rect.left() + rect.width() * t
+}
+
+fn x_to_freq(rect: egui::Rect, x: f32) -> f32 {
+    let t = (x - rect.left()) / rect.width();
+    MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(t)
+}
+
+fn db_to_y(rect: egui::Rect, db: f32) -> f32 {
+    let db = db.clamp(-RANGE_DB, RANGE_DB);
+    rect.center().y - db / RANGE_DB * rect.height() * 0.5
+}
+
+fn y_to_db(rect: egui::Rect, y: f32) -> f32 {
+    (rect.center().y - y) / (rect.height() * 0.5) * RANGE_DB
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plot_mapping_roundtrip() {
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(200.0, 80.0));
+        assert!((x_to_freq(rect, freq_to_x(rect, 1000.0)) - 1000.0).abs() < 0.5);
+        assert!((y_to_db(rect, db_to_y(rect, -6.0)) + 6.0).abs() < 0.001);
+        assert!((db_to_y(rect, 0.0) - rect.center().y).abs() < 0.001);
+    }
+}
@@ -0,0 +1,114 @@
+//! "Edit in external editor" round-trip
+//!
+//! The code is written to a temp file and opened in `$VISUAL`/`$EDITOR`
+//! (or the system's default app for text files). The file is polled while
+//! the session is active and every save is applied like an Update click.
+//! Terminal editors need a terminal the plugin doesn't have, so `$EDITOR`
+//! should name a GUI editor (e.g. `code --wait` or `gvim -f`).
+
+use nih_plug_egui::egui;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// How often the temp file is checked for saves
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// External editing session (lives in `EditorState`)
+#[derive(Default)]
+pub struct ExternalEdit {
+    path: Option<PathBuf>,
+    /// Modification time of the last version we read or wrote
+    modified: Option<SystemTime>,
+    /// Content of the last version we read or wrote
+    contents: String,
+    last_poll: f64,
+}
+
+impl ExternalEdit {
+    pub fn is_active(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Write `code` to the temp file and open it in the external editor
+    pub fn open(&mut self, code: &str) -> Result<(), String> {
+        let path = std::env::temp_dir().join(format!("glicol-verb-{}.glicol", std::process::id()));
+        std::fs::write(&path, code).map_err(|err| format!("Couldn't write temp file: {}", err))?;
+        self.modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        self.contents = code.to_string();
+
+        launch_editor(&path).map_err(|err| format!("Couldn't start editor: {}", err))?;
+        self.path = Some(path);
+        Ok(())
+    }
+
+    /// Stop watching and remove the temp file
+    pub fn close(&mut self) {
+        if let Some(path) = self.path.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Check the temp file (at most every `POLL_INTERVAL`)
+    ///
+    /// Returns the new code after the external editor saved a change.
+    pub fn poll(&mut self, ctx: &egui::Context) -> Option<String> {
+        let path = self.path.as_ref()?;
+        // Keep polling while the plugin window has no input of its own
+        ctx.request_repaint_after(POLL_INTERVAL);
+
+        let now = ctx.input(|i| i.time);
+        if now - self.last_poll < POLL_INTERVAL.as_secs_f64() {
+            return None;
+        }
+        self.last_poll = now;
+
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        if Some(modified) == self.modified {
+            return None;
+        }
+        self.modified = Some(modified);
+
+        let contents = std::fs::read_to_string(path).ok()?;
+        if contents == self.contents {
+            return None;
+        }
+        self.contents = contents.clone();
+        Some(contents)
+    }
+}
+
+impl Drop for ExternalEdit {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Start `$VISUAL`/`$EDITOR` (which may include arguments) or the default app
+fn launch_editor(path: &Path) -> std::io::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty());
+
+    let mut command = match editor {
+        Some(editor) => {
+            let mut parts = editor.split_whitespace();
+            let mut command = Command::new(parts.next().unwrap_or_default());
+            command.args(parts);
+            command
+        }
+        None if cfg!(target_os = "macos") => {
+            let mut command = Command::new("open");
+            command.arg("-t");
+            command
+        }
+        None if cfg!(target_os = "windows") => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        None => Command::new("xdg-open"),
+    };
+    command.arg(path).spawn().map(|_| ())
+}
@@ -0,0 +1,97 @@
+//! Drag-and-drop file loading
+//!
+//! Files dropped onto the window are routed by extension: `.glicol` text
+//! replaces the code buffer, preset files load code and parameters, and
+//! WAVs are rejected until sample loading exists. Whether drops arrive at
+//! all depends on the host window backend forwarding them to egui.
+
+use nih_plug_egui::egui;
+use std::path::{Path, PathBuf};
+
+use super::theme;
+use crate::preset::{file, Preset};
+
+/// Something loaded from a dropped file
+pub enum Dropped {
+    Code(String),
+    Preset(Preset),
+}
+
+#[derive(Debug, PartialEq)]
+enum FileKind {
+    Code,
+    Preset,
+    Sample,
+    Unknown,
+}
+
+fn file_kind(path: &Path) -> FileKind {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("glicol") | Some("txt") => FileKind::Code,
+        Some(ext) if ext == file::EXTENSION || ext == "json" => FileKind::Preset,
+        Some("wav") => FileKind::Sample,
+        _ => FileKind::Unknown,
+    }
+}
+
+fn load(path: &Path) -> Result<Dropped, String> {
+    match file_kind(path) {
+        FileKind::Code => std::fs::read_to_string(path)
+            .map(Dropped::Code)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e)),
+        FileKind::Preset => file::load(path).map(Dropped::Preset),
+        FileKind::Sample => Err("Sample loading isn't supported yet".to_string()),
+        FileKind::Unknown => Err(format!("Can't load {}", path.display())),
+    }
+}
+
+/// Highlight the window while files hover over it and load what was dropped
+///
+/// Only the last dropped file is used when several arrive at once.
+pub fn handle_drops(ctx: &egui::Context) -> Option<Result<Dropped, String>> {
+    let (hovering, dropped) = ctx.input(|i| {
+        let dropped: Vec<PathBuf> = i
+            .raw
+            .dropped_files
+            .iter()
+            .filter_map(|file| file.path.clone())
+            .collect();
+        (!i.raw.hovered_files.is_empty(), dropped)
+    });
+
+    if hovering {
+        let screen = ctx.screen_rect();
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("file_drop_overlay"),
+        ));
+        painter.rect_filled(screen, 0.0, theme::bg_dark().gamma_multiply(0.8));
+        painter.text(
+            screen.center(),
+            egui::Align2::CENTER_CENTER,
+            "Drop .glicol code or a preset",
+            egui::FontId::proportional(24.0),
+            theme::accent(),
+        );
+    }
+
+    dropped.last().map(|path| load(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_kind() {
+        assert_eq!(file_kind(Path::new("patch.glicol")), FileKind::Code);
+        assert_eq!(file_kind(Path::new("Hall.GLICOLPRESET")), FileKind::Preset);
+        assert_eq!(file_kind(Path::new("kick.wav")), FileKind::Sample);
+        assert_eq!(file_kind(Path::new("notes.pdf")), FileKind::Unknown);
+        assert_eq!(file_kind(Path::new("no_extension")), FileKind::Unknown);
+    }
+}
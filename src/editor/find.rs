@@ -0,0 +1,191 @@
+//! Find and replace bar for the code editor
+//!
+//! Ctrl+F opens find, Ctrl+H find and replace. Matches are highlighted in
+//! the editor; Enter / Shift+Enter step through them. Replacements go
+//! through the normal undo history.
+
+use nih_plug_egui::egui;
+
+use super::theme;
+
+/// Find bar state (lives in `EditorState`)
+#[derive(Default)]
+pub struct FindBar {
+    pub open: bool,
+    replace: bool,
+    query: String,
+    replacement: String,
+    case_sensitive: bool,
+    /// Index of the current match
+    current: usize,
+    /// Focus the query field on the next frame
+    focus_query: bool,
+}
+
+impl FindBar {
+    /// Open the bar, with the replace row when `replace` is set
+    pub fn open(&mut self, replace: bool) {
+        self.open = true;
+        self.replace = replace;
+        self.focus_query = true;
+    }
+
+    /// Query and case sensitivity for highlighting, while searching
+    pub fn search(&self) -> Option<(String, bool)> {
+        (self.open && !self.query.is_empty()).then(|| (self.query.clone(), self.case_sensitive))
+    }
+
+    /// Current match index (for the stronger highlight)
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Draw the bar; returns the new code after a replacement
+    pub fn show(&mut self, ui: &mut egui::Ui, code: &str) -> Option<String> {
+        let matches = find_matches(code, &self.query, self.case_sensitive);
+        if self.current >= matches.len() {
+            self.current = 0;
+        }
+        let mut new_code = None;
+
+        ui.horizontal(|ui| {
+            let query = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("find")
+                    .desired_width(140.0),
+            );
+            if std::mem::take(&mut self.focus_query) {
+                query.request_focus();
+            }
+            if query.changed() {
+                self.current = 0;
+            }
+
+            // Enter in the query field keeps focus there and steps forward
+            if query.lost_focus() {
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    let backward = ui.input(|i| i.modifiers.shift);
+                    self.step(matches.len(), backward);
+                    query.request_focus();
+                } else if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    self.open = false;
+                }
+            }
+
+            let count = if self.query.is_empty() {
+                String::new()
+            } else if matches.is_empty() {
+                "no matches".to_string()
+            } else {
+                format!("{}/{}", self.current + 1, matches.len())
+            };
+            ui.label(egui::RichText::new(count).color(theme::text_dim()).small());
+            if ui
+                .small_button("▲")
+                .on_hover_text("Previous (Shift+Enter)")
+                .clicked()
+            {
+                self.step(matches.len(), true);
+            }
+            if ui.small_button("▼").on_hover_text("Next (Enter)").clicked() {
+                self.step(matches.len(), false);
+            }
+            ui.toggle_value(&mut self.case_sensitive, "Aa")
+                .on_hover_text("Match case");
+            ui.toggle_value(&mut self.replace, "Replace");
+            if ui.small_button("✕").on_hover_text("Close (Esc)").clicked() {
+                self.open = false;
+            }
+        });
+
+        if self.replace {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.replacement)
+                        .hint_text("replace with")
+                        .desired_width(140.0),
+                );
+                if ui
+                    .add_enabled(!matches.is_empty(), egui::Button::new("Replace"))
+                    .clicked()
+                {
+                    let (start, end) = matches[self.current];
+                    let mut code = code.to_string();
+                    code.replace_range(start..end, &self.replacement);
+                    new_code = Some(code);
+                }
+                if ui
+                    .add_enabled(!matches.is_empty(), egui::Button::new("All"))
+                    .clicked()
+                {
+                    new_code = Some(replace_all(code, &matches, &self.replacement));
+                }
+            });
+        }
+        new_code
+    }
+
+    fn step(&mut self, count: usize, backward: bool) {
+        if count == 0 {
+            return;
+        }
+        self.current = if backward {
+            (self.current + count - 1) % count
+        } else {
+            (self.current + 1) % count
+        };
+    }
+}
+
+/// Byte ranges of all non-overlapping matches of `query` in `code`
+pub fn find_matches(code: &str, query: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // ASCII folding keeps byte offsets identical to the original
+    let (haystack, needle) = if case_sensitive {
+        (code.to_string(), query.to_string())
+    } else {
+        (code.to_ascii_lowercase(), query.to_ascii_lowercase())
+    };
+    haystack
+        .match_indices(&needle)
+        .map(|(start, found)| (start, start + found.len()))
+        .collect()
+}
+
+fn replace_all(code: &str, matches: &[(usize, usize)], replacement: &str) -> String {
+    let mut result = String::with_capacity(code.len());
+    let mut last = 0;
+    for &(start, end) in matches {
+        result.push_str(&code[last..start]);
+        result.push_str(replacement);
+        last = end;
+    }
+    result.push_str(&code[last..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches() {
+        let code = "out: ~input >> mul ~mod\n~mod: sin ~Rate >> mul ~MOD";
+        assert_eq!(find_matches(code, "~mod", true).len(), 2);
+        assert_eq!(find_matches(code, "~mod", false).len(), 3);
+        assert_eq!(find_matches(code, "", false), vec![]);
+        assert_eq!(find_matches("aaaa", "aa", true), vec![(0, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let code = "out: ~input >> mul ~mod\n~mod: sin 2.0";
+        let matches = find_matches(code, "~mod", true);
+        assert_eq!(
+            replace_all(code, &matches, "~lfo"),
+            "out: ~input >> mul ~lfo\n~lfo: sin 2.0"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Signal-flow diagram of the running code
+//!
+//! Chains are drawn as rows of stage boxes joined by arrows, with curves
+//! from each `~reference` to the stage that uses it. The diagram follows
+//! the applied code, so it updates when Update succeeds.
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::lang::graph::{parse_graph, Chain};
+
+const ROW_HEIGHT: f32 = 24.0;
+const ROW_GAP: f32 = 14.0;
+const ARROW_GAP: f32 = 18.0;
+const BOX_PADDING: f32 = 6.0;
+
+/// Cached graph of the last code shown (lives in `EditorState`)
+#[derive(Default)]
+pub struct FlowGraph {
+    code: String,
+    chains: Vec<Chain>,
+}
+
+impl FlowGraph {
+    pub fn show(&mut self, ui: &mut egui::Ui, code: &str) {
+        if code != self.code {
+            self.code = code.to_string();
+            self.chains = parse_graph(code);
+        }
+        if self.chains.is_empty() {
+            ui.label(egui::RichText::new("No chains to show").color(theme::text_dim()));
+            return;
+        }
+
+        egui::ScrollArea::horizontal()
+            .id_salt("flow_graph")
+            .show(ui, |ui| self.draw(ui));
+    }
+
+    fn draw(&self, ui: &mut egui::Ui) {
+        let font = egui::FontId::monospace(11.0);
+        let layout = |text: &str, color: egui::Color32| {
+            ui.painter()
+                .layout_no_wrap(text.to_string(), font.clone(), color)
+        };
+
+        // Lay out every row: chain label first, then its stages
+        let mut rows = Vec::with_capacity(self.chains.len());
+        let mut width: f32 = 0.0;
+        for chain in &self.chains {
+            let mut boxes = vec![layout(&chain.name, theme::accent())];
+            for stage in &chain.stages {
+                let color = if stage.refs.is_empty() {
+                    theme::text_bright()
+                } else {
+                    theme::text_normal()
+                };
+                boxes.push(layout(&stage.text, color));
+            }
+            let row_width: f32 = boxes
+                .iter()
+                .map(|galley| galley.size().x + BOX_PADDING * 2.0 + ARROW_GAP)
+                .sum();
+            width = width.max(row_width);
+            rows.push(boxes);
+        }
+        let height = rows.len() as f32 * (ROW_HEIGHT + ROW_GAP);
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+
+        // Box rects, indexed like `rows`
+        let mut rects: Vec<Vec<egui::Rect>> = Vec::with_capacity(rows.len());
+        for (row, boxes) in rows.iter().enumerate() {
+            let top = rect.top() + row as f32 * (ROW_HEIGHT + ROW_GAP);
+            let mut x = rect.left();
+            let mut row_rects = Vec::with_capacity(boxes.len());
+            for galley in boxes {
+                let box_rect = egui::Rect::from_min_size(
+                    egui::pos2(x, top),
+                    egui::vec2(galley.size().x + BOX_PADDING * 2.0, ROW_HEIGHT),
+                );
+                row_rects.push(box_rect);
+                x = box_rect.right() + ARROW_GAP;
+            }
+            rects.push(row_rects);
+        }
+
+        // Reference links behind the boxes
+        let link_stroke = egui::Stroke::new(1.5, theme::knob_indicator().gamma_multiply(0.7));
+        for (row, chain) in self.chains.iter().enumerate() {
+            for (index, stage) in chain.stages.iter().enumerate() {
+                for reference in &stage.refs {
+                    let Some(source) = self.chains.iter().position(|c| &c.name == reference) else {
+                        continue;
+                    };
+                    let from = rects[source][0];
+                    let to = rects[row][index + 1];
+                    let (start, end) = if source > row {
+                        (from.center_top(), to.center_bottom())
+                    } else {
+                        (from.center_bottom(), to.center_top())
+                    };
+                    let bend = (end.y - start.y) * 0.5;
+                    painter.add(egui::epaint::CubicBezierShape::from_points_stroke(
+                        [
+                            start,
+                            start + egui::vec2(0.0, bend),
+                            end - egui::vec2(0.0, bend),
+                            end,
+                        ],
+                        false,
+                        egui::Color32::TRANSPARENT,
+                        link_stroke,
+                    ));
+                }
+            }
+        }
+
+        // Boxes and `>>` arrows
+        let arrow_stroke = egui::Stroke::new(1.0, theme::text_dim());
+        for (boxes, row_rects) in rows.into_iter().zip(&rects) {
+            for (index, (galley, box_rect)) in boxes.into_iter().zip(row_rects).enumerate() {
+                let fill = if index == 0 {
+                    theme::bg_dark()
+                } else {
+                    theme::card_bg()
+                };
+                painter.rect_filled(*box_rect, 4.0, fill);
+                painter.galley(
+                    box_rect.left_center() + egui::vec2(BOX_PADDING, -galley.size().y / 2.0),
+                    galley,
+                    theme::text_normal(),
+                );
+                if index + 1 < row_rects.len() {
+                    let start = box_rect.right_center() + egui::vec2(3.0, 0.0);
+                    let end = row_rects[index + 1].left_center() - egui::vec2(3.0, 0.0);
+                    painter.arrow(start, end - start, arrow_stroke);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Goniometer (vector scope) and correlation meter
+//!
+//! Mono content draws a vertical line, wide content spreads sideways and
+//! out-of-phase content leans towards the horizontal axis.
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::telemetry::Telemetry;
+
+const SCOPE_SIZE: f32 = 120.0;
+
+/// Number of most recent samples plotted
+const SCOPE_POINTS: usize = 512;
+
+pub fn goniometer(ui: &mut egui::Ui, telemetry: &Telemetry) {
+    let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(SCOPE_SIZE), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, theme::bg_dark());
+
+    // Axes: M (vertical), S (horizontal), and the L/R diagonals
+    let grid = egui::Stroke::new(1.0, theme::knob_ring());
+    painter.line_segment([rect.center_top(), rect.center_bottom()], grid);
+    painter.line_segment([rect.left_center(), rect.right_center()], grid);
+    painter.line_segment([rect.left_top(), rect.right_bottom()], grid);
+    painter.line_segment([rect.right_top(), rect.left_bottom()], grid);
+
+    let left = telemetry.output_left_tap.snapshot();
+    let right = telemetry.output_right_tap.snapshot();
+    let start = left.len().saturating_sub(SCOPE_POINTS);
+    let radius = SCOPE_SIZE * 0.5;
+    for (&l, &r) in left[start..].iter().zip(&right[start..]) {
+        let (x, y) = scope_point(l, r);
+        let pos = rect.center() + egui::vec2(x, -y) * radius;
+        if rect.contains(pos) {
+            painter.circle_filled(pos, 0.8, theme::accent());
+        }
+    }
+
+    correlation_bar(ui, telemetry.correlation.load());
+    ui.ctx().request_repaint();
+}
+
+/// Horizontal -1..+1 bar with a marker at the current correlation
+fn correlation_bar(ui: &mut egui::Ui, correlation: f32) {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(SCOPE_SIZE, 8.0), egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, theme::knob_bg());
+    painter.line_segment(
+        [rect.center_top(), rect.center_bottom()],
+        egui::Stroke::new(1.0, theme::knob_ring()),
+    );
+
+    let x = rect.center().x + correlation * rect.width() * 0.5;
+    let color = if correlation < 0.0 {
+        theme::status_error()
+    } else {
+        theme::status_active()
+    };
+    painter.line_segment(
+        [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+        egui::Stroke::new(3.0, color),
+    );
+
+    response.on_hover_text(format!(
+        "Correlation {:+.2}\n+1 mono, 0 wide, below 0 cancels in mono",
+        correlation
+    ));
+}
+
+/// Rotate an L/R pair 45° so mid is vertical and side is horizontal
+fn scope_point(left: f32, right: f32) -> (f32, f32) {
+    let scale = std::f32::consts::FRAC_1_SQRT_2;
+    ((right - left) * scale, (left + right) * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_point_axes() {
+        let (x, y) = scope_point(0.5, 0.5);
+        assert!(x.abs() < 1e-6 && y > 0.0);
+
+        let (x, y) = scope_point(0.5, -0.5);
+        assert!(y.abs() < 1e-6 && x < 0.0);
+    }
+}
@@ -0,0 +1,95 @@
+//! Undo/redo history for the code buffer
+//!
+//! Separate from egui's per-widget undo so that whole-buffer replacements
+//! (recipes, reset, reverts) can be undone too.
+
+/// Maximum number of undo steps kept
+const MAX_HISTORY: usize = 100;
+
+/// Seconds without typing before an idle snapshot is taken
+pub const IDLE_SNAPSHOT_SECS: f64 = 1.0;
+
+/// Undo/redo stacks of code snapshots
+#[derive(Default)]
+pub struct CodeHistory {
+    undo_stack: Vec<String>,
+    redo_stack: Vec<String>,
+}
+
+impl CodeHistory {
+    /// Record `code` as an undo point (ignored if unchanged since the last one)
+    pub fn snapshot(&mut self, code: &str) {
+        if self.undo_stack.last().map(String::as_str) == Some(code) {
+            return;
+        }
+        self.undo_stack.push(code.to_string());
+        if self.undo_stack.len() > MAX_HISTORY {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Step back from `current`, returning the code to restore
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        // The newest snapshot may be the current text itself
+        while self.undo_stack.last().map(String::as_str) == Some(current) {
+            self.undo_stack.pop();
+        }
+        let previous = self.undo_stack.pop()?;
+        self.redo_stack.push(current.to_string());
+        Some(previous)
+    }
+
+    /// Step forward again after an undo
+    pub fn redo(&mut self, current: &str) -> Option<String> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push(current.to_string());
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_redo_roundtrip() {
+        let mut history = CodeHistory::default();
+        history.snapshot("a");
+        history.snapshot("b");
+
+        assert_eq!(history.undo("c").as_deref(), Some("b"));
+        assert_eq!(history.undo("b").as_deref(), Some("a"));
+        assert_eq!(history.undo("a"), None);
+
+        assert_eq!(history.redo("a").as_deref(), Some("b"));
+        assert_eq!(history.redo("b").as_deref(), Some("c"));
+        assert_eq!(history.redo("c"), None);
+    }
+
+    #[test]
+    fn test_undo_skips_snapshot_of_current_text() {
+        let mut history = CodeHistory::default();
+        history.snapshot("a");
+        history.snapshot("b");
+        assert_eq!(history.undo("b").as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_snapshot_clears_redo() {
+        let mut history = CodeHistory::default();
+        history.snapshot("a");
+        history.undo("b");
+        history.snapshot("x");
+        assert_eq!(history.redo("x"), None);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = CodeHistory::default();
+        for i in 0..(MAX_HISTORY + 10) {
+            history.snapshot(&i.to_string());
+        }
+        assert_eq!(history.undo_stack.len(), MAX_HISTORY);
+    }
+}
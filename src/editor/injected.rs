@@ -0,0 +1,61 @@
+//! Read-only preview of the code the engine actually receives
+//!
+//! Runs the editor buffer through the same preprocessing and parameter
+//! injection as the audio thread, using the current parameter values
+//! (live MIDI CC overrides aren't visible here).
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::engine::{preprocess, ParamInjector};
+use crate::params::GlicolVerbParams;
+
+pub fn injected_view(ui: &mut egui::Ui, params: &GlicolVerbParams, code: &str) {
+    let expanded = match preprocess(code, &params.snippets.read()) {
+        Ok(expanded) => expanded,
+        Err(message) => {
+            ui.colored_label(theme::status_error(), message);
+            return;
+        }
+    };
+    let injected = injector_for(params).inject(&expanded);
+
+    // Everything before the user's own lines was added on the way to the engine
+    let added_lines = injected
+        .lines()
+        .count()
+        .saturating_sub(code.lines().count());
+    egui::Frame::new()
+        .fill(theme::bg_dark())
+        .corner_radius(egui::CornerRadius::same(4))
+        .inner_margin(egui::Margin::same(6))
+        .show(ui, |ui| {
+            for (index, line) in injected.lines().enumerate() {
+                let color = if index < added_lines {
+                    theme::knob_indicator()
+                } else {
+                    theme::text_dim()
+                };
+                ui.label(egui::RichText::new(line).monospace().color(color));
+            }
+        });
+}
+
+fn injector_for(params: &GlicolVerbParams) -> ParamInjector {
+    ParamInjector {
+        knob1: params.glicol.knob1.modulated_plain_value(),
+        knob2: params.glicol.knob2.modulated_plain_value(),
+        knob3: params.glicol.knob3.modulated_plain_value(),
+        knob4: params.glicol.knob4.modulated_plain_value(),
+        knob5: params.glicol.knob5.modulated_plain_value(),
+        knob6: params.glicol.knob6.modulated_plain_value(),
+        knob7: params.glicol.knob7.modulated_plain_value(),
+        knob8: params.glicol.knob8.modulated_plain_value(),
+        drive: params.glicol.drive.modulated_plain_value(),
+        feedback: params.glicol.feedback.modulated_plain_value(),
+        mix: params.glicol.mix.modulated_plain_value(),
+        rate: params.glicol.rate.modulated_plain_value(),
+        expr: params.glicol.expr.modulated_plain_value(),
+        knob_names: params.knob_names.read().clone(),
+    }
+}
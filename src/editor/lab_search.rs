@@ -0,0 +1,93 @@
+//! Search box and category chips for the Effects Lab
+//!
+//! Filters recipes and building blocks by name, description and code.
+//! Every whitespace-separated term must appear somewhere (case-insensitive).
+
+use nih_plug_egui::egui;
+
+use super::theme;
+
+/// Chip for the built-in recipes
+pub const BUILTIN_RECIPES: &str = "Recipes";
+/// Chip for the user's own recipes
+pub const USER_RECIPES: &str = "My recipes";
+
+/// Effects Lab filter state (lives in `EditorState`)
+#[derive(Default)]
+pub struct LabSearch {
+    query: String,
+    /// Selected category chip, `None` shows everything
+    category: Option<&'static str>,
+}
+
+impl LabSearch {
+    /// Draw the search box and chips
+    pub fn show(&mut self, ui: &mut egui::Ui, categories: &[&'static str]) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text("🔍 search recipes and blocks")
+                    .desired_width(180.0),
+            );
+            if !self.query.is_empty() && ui.small_button("✕").on_hover_text("Clear").clicked() {
+                self.query.clear();
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.selectable_value(&mut self.category, None, "All");
+            for &category in categories {
+                ui.selectable_value(&mut self.category, Some(category), category);
+            }
+        });
+    }
+
+    /// Whether a filter narrows the lists
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty() || self.category.is_some()
+    }
+
+    /// Whether items of this category are shown
+    pub fn shows_category(&self, category: &str) -> bool {
+        self.category.is_none_or(|selected| selected == category)
+    }
+
+    /// Whether an item with these texts matches the query
+    pub fn matches(&self, texts: &[&str]) -> bool {
+        matches_query(&self.query, texts)
+    }
+}
+
+fn matches_query(query: &str, texts: &[&str]) -> bool {
+    let texts: Vec<String> = texts.iter().map(|text| text.to_lowercase()).collect();
+    query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .all(|term| texts.iter().any(|text| text.contains(&term)))
+}
+
+/// Label shown when the filter hides everything in a list
+pub fn no_matches(ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new("No matches")
+            .color(theme::text_dim())
+            .small(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_query() {
+        let texts = [
+            "Tremolo",
+            "Pulsing volume effect",
+            "out: ~input >> mul ~lfo",
+        ];
+        assert!(matches_query("", &texts));
+        assert!(matches_query("trem", &texts));
+        assert!(matches_query("VOLUME lfo", &texts));
+        assert!(!matches_query("volume delay", &texts));
+    }
+}
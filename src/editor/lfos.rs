@@ -0,0 +1,130 @@
+//! LFO controls and the modulation matrix editor
+//!
+//! Shape, sync and division are parameters set through the host like any
+//! other; the matrix itself is persisted state the audio thread reads each
+//! block.
+
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::modulation::{ModRoute, ModSource};
+use crate::params::GlicolVerbParams;
+
+/// Dropdown for an enum parameter
+pub fn enum_combo<T: Enum + PartialEq>(
+    ui: &mut egui::Ui,
+    setter: &ParamSetter,
+    param: &EnumParam<T>,
+    id_salt: impl std::hash::Hash,
+) {
+    let current = param.value().to_index();
+    egui::ComboBox::from_id_salt(id_salt)
+        .width(90.0)
+        .selected_text(T::variants()[current])
+        .show_ui(ui, |ui| {
+            for (index, name) in T::variants().iter().enumerate() {
+                if ui.selectable_label(index == current, *name).clicked() && index != current {
+                    setter.begin_set_parameter(param);
+                    setter.set_parameter(param, T::from_index(index));
+                    setter.end_set_parameter(param);
+                }
+            }
+        });
+}
+
+/// Shape, tempo sync and division of LFO `index` (0-based)
+pub fn lfo_controls(
+    ui: &mut egui::Ui,
+    params: &GlicolVerbParams,
+    setter: &ParamSetter,
+    index: usize,
+) {
+    let (shape, sync, division) = match index {
+        0 => (
+            &params.modulation.lfo1_shape,
+            &params.modulation.lfo1_sync,
+            &params.modulation.lfo1_division,
+        ),
+        _ => (
+            &params.modulation.lfo2_shape,
+            &params.modulation.lfo2_sync,
+            &params.modulation.lfo2_division,
+        ),
+    };
+    ui.horizontal(|ui| {
+        ui.add_sized([70.0, 18.0], egui::Label::new("Shape"));
+        enum_combo(ui, setter, shape, ("lfo_shape", index));
+    });
+    ui.horizontal(|ui| {
+        let mut synced = sync.value();
+        if ui.checkbox(&mut synced, "Sync").changed() {
+            setter.begin_set_parameter(sync);
+            setter.set_parameter(sync, synced);
+            setter.end_set_parameter(sync);
+        }
+        ui.add_enabled_ui(synced, |ui| {
+            enum_combo(ui, setter, division, ("lfo_division", index));
+        });
+    });
+}
+
+/// Every matrix route: source, target and signed amount
+pub fn mod_matrix(ui: &mut egui::Ui, params: &GlicolVerbParams) {
+    let mut matrix = params.mod_matrix.write();
+    if matrix.routes.is_empty() {
+        ui.label(
+            egui::RichText::new("No routes yet")
+                .color(theme::text_dim())
+                .small(),
+        );
+    }
+
+    let mut removed = None;
+    for (row, route) in matrix.routes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(("mod_route_source", row))
+                .width(70.0)
+                .selected_text(route.source.label())
+                .show_ui(ui, |ui| {
+                    for source in ModSource::ALL {
+                        ui.selectable_value(&mut route.source, source, source.label());
+                    }
+                });
+            // LFOs reach fewer parameters than the envelope
+            let targets = route.source.targets();
+            if !targets.contains(&route.target.as_str()) {
+                route.target = targets[0].to_string();
+            }
+            ui.label(egui::RichText::new("→").color(theme::text_dim()));
+            egui::ComboBox::from_id_salt(("mod_route_target", row))
+                .width(100.0)
+                .selected_text(route.target.as_str())
+                .show_ui(ui, |ui| {
+                    for &target in targets {
+                        ui.selectable_value(&mut route.target, target.to_string(), target);
+                    }
+                });
+            if ui.small_button("✕").on_hover_text("Remove route").clicked() {
+                removed = Some(row);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add_sized([70.0, 18.0], egui::Label::new("Amount"));
+            ui.add(egui::Slider::new(&mut route.amount, -1.0..=1.0).fixed_decimals(2));
+        });
+        ui.add_space(4.0);
+    }
+    if let Some(row) = removed {
+        matrix.routes.remove(row);
+    }
+
+    if ui.small_button("+ Add route").clicked() {
+        let source = ModSource::default();
+        matrix.routes.push(ModRoute {
+            source,
+            target: source.targets()[0].to_string(),
+            amount: 0.5,
+        });
+    }
+}
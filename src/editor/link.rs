@@ -0,0 +1,35 @@
+//! Link mode settings
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+
+use super::theme;
+use crate::link::{LinkHandle, LinkSettings, LINK_GROUPS};
+
+/// Link toggle, group and peer count, for the settings panel
+pub fn link_settings(ui: &mut egui::Ui, link: &LinkHandle, link_settings: &RwLock<LinkSettings>) {
+    let mut settings = *link_settings.read();
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut settings.enabled, "Link instances")
+            .on_hover_text("Instances in the same group share applied code and scene changes");
+        ui.add_enabled_ui(settings.enabled, |ui| {
+            for group in 0..LINK_GROUPS {
+                ui.selectable_value(&mut settings.group, group, format!("{}", group + 1));
+            }
+        });
+    });
+    if settings.enabled {
+        let peers = link.peer_count();
+        let text = match peers {
+            0 => "No other instances in this group".to_string(),
+            1 => "1 instance linked".to_string(),
+            n => format!("{} instances linked", n),
+        };
+        ui.label(egui::RichText::new(text).small().color(theme::text_dim()));
+    }
+
+    if settings != *link_settings.read() {
+        *link_settings.write() = settings;
+    }
+}
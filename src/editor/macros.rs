@@ -0,0 +1,99 @@
+//! Macro mapping editor
+//!
+//! Lists the targets one macro drives, each with its own range (in the
+//! target's units) and curve. Edits go straight to the persisted map, which
+//! the audio thread picks up on the next block.
+
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::modulation::{MacroCurve, MacroMapping, MACRO_TARGETS};
+use crate::params::GlicolVerbParams;
+
+/// Editor for the routes of macro `index`
+pub fn macro_mappings(ui: &mut egui::Ui, params: &GlicolVerbParams, index: usize) {
+    let targets: Vec<(String, ParamPtr)> = params
+        .param_map()
+        .into_iter()
+        .filter(|(id, _, _)| MACRO_TARGETS.contains(&id.as_str()))
+        .map(|(id, ptr, _)| (id, ptr))
+        .collect();
+    // SAFETY: the pointers come from `params`, which outlives the editor
+    let name = |id: &str| {
+        targets
+            .iter()
+            .find(|(target, _)| target == id)
+            .map(|(_, ptr)| unsafe { ptr.name() }.to_string())
+            .unwrap_or_else(|| id.to_string())
+    };
+    let display = |id: &str, normalized: f32| {
+        targets
+            .iter()
+            .find(|(target, _)| target == id)
+            .map(|(_, ptr)| unsafe { ptr.normalized_value_to_string(normalized, true) })
+            .unwrap_or_else(|| format!("{:.2}", normalized))
+    };
+
+    let mut map = params.macro_map.write();
+    let mappings = &mut map.macros[index];
+    if mappings.is_empty() {
+        ui.label(
+            egui::RichText::new("No targets yet")
+                .color(theme::text_dim())
+                .small(),
+        );
+    }
+
+    let mut removed = None;
+    for (row, mapping) in mappings.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(("macro_target", index, row))
+                .width(110.0)
+                .selected_text(name(&mapping.target))
+                .show_ui(ui, |ui| {
+                    for &id in MACRO_TARGETS {
+                        ui.selectable_value(&mut mapping.target, id.to_string(), name(id));
+                    }
+                });
+            egui::ComboBox::from_id_salt(("macro_curve", index, row))
+                .width(60.0)
+                .selected_text(mapping.curve.label())
+                .show_ui(ui, |ui| {
+                    for curve in MacroCurve::ALL {
+                        ui.selectable_value(&mut mapping.curve, curve, curve.label());
+                    }
+                });
+            if ui
+                .small_button("✕")
+                .on_hover_text("Remove target")
+                .clicked()
+            {
+                removed = Some(row);
+            }
+        });
+        let target = mapping.target.clone();
+        for (label, value) in [("From", &mut mapping.min), ("To", &mut mapping.max)] {
+            ui.horizontal(|ui| {
+                ui.add_sized([40.0, 18.0], egui::Label::new(label));
+                ui.add(
+                    egui::Slider::new(value, 0.0..=1.0)
+                        .custom_formatter(|v, _| display(&target, v as f32)),
+                );
+            });
+        }
+        ui.add_space(4.0);
+    }
+    if let Some(row) = removed {
+        mappings.remove(row);
+    }
+
+    if ui.small_button("+ Add target").clicked() {
+        // Start with the first target this macro doesn't drive yet
+        let target = MACRO_TARGETS
+            .iter()
+            .find(|id| !mappings.iter().any(|mapping| mapping.target == **id))
+            .unwrap_or(&MACRO_TARGETS[0]);
+        mappings.push(MacroMapping::new(target));
+    }
+}
@@ -0,0 +1,98 @@
+//! Peak/RMS level meters for the editor header
+//!
+//! Reads the lock-free telemetry published by the audio thread. Peaks are
+//! held on the GUI side and fall back at a fixed rate.
+
+use nih_plug::util;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::telemetry::{LevelMeter, Telemetry};
+
+/// Bottom of the meter scale
+const METER_FLOOR_DB: f32 = -60.0;
+
+/// How fast held peaks fall back
+const PEAK_FALL_DB_PER_SEC: f32 = 20.0;
+
+const METER_SIZE: egui::Vec2 = egui::vec2(70.0, 8.0);
+
+/// Held peak values for input, wet and output (lives in `EditorState`)
+#[derive(Default)]
+pub struct Meters {
+    peaks: [f32; 3],
+}
+
+impl Meters {
+    pub fn show(&mut self, ui: &mut egui::Ui, telemetry: &Telemetry) {
+        let dt = ui.input(|i| i.stable_dt).min(0.1);
+        let fall = util::db_to_gain(-PEAK_FALL_DB_PER_SEC * dt);
+        let meters: [(&str, &LevelMeter); 3] = [
+            ("IN", &telemetry.input),
+            ("WET", &telemetry.wet),
+            ("OUT", &telemetry.output),
+        ];
+
+        ui.horizontal(|ui| {
+            for ((label, meter), held) in meters.iter().zip(self.peaks.iter_mut()) {
+                *held = meter.peak.take().max(*held * fall);
+                level_bar(ui, label, *held, meter.rms.load());
+                ui.add_space(6.0);
+            }
+        });
+
+        // Keep the meters moving while the mouse is idle
+        ui.ctx().request_repaint();
+    }
+}
+
+/// One labelled horizontal bar: RMS filled, peak as a tick
+fn level_bar(ui: &mut egui::Ui, label: &str, peak: f32, rms: f32) {
+    ui.label(egui::RichText::new(label).color(theme::text_dim()).small());
+    let (rect, response) = ui.allocate_exact_size(METER_SIZE, egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, theme::knob_bg());
+
+    let mut rms_rect = rect;
+    rms_rect.set_width(rect.width() * meter_fraction(rms));
+    painter.rect_filled(rms_rect, 2.0, theme::accent_dim());
+
+    let peak_x = rect.left() + rect.width() * meter_fraction(peak);
+    let peak_color = if peak >= 1.0 {
+        theme::status_error()
+    } else {
+        theme::status_active()
+    };
+    painter.line_segment(
+        [
+            egui::pos2(peak_x, rect.top()),
+            egui::pos2(peak_x, rect.bottom()),
+        ],
+        egui::Stroke::new(2.0, peak_color),
+    );
+
+    response.on_hover_text(format!(
+        "Peak {:.1} dB\nRMS {:.1} dB",
+        util::gain_to_db(peak).max(METER_FLOOR_DB),
+        util::gain_to_db(rms).max(METER_FLOOR_DB)
+    ));
+}
+
+/// Position of a linear level on the dB meter scale (0.0-1.0)
+fn meter_fraction(gain: f32) -> f32 {
+    let db = util::gain_to_db(gain).clamp(METER_FLOOR_DB, 0.0);
+    (db - METER_FLOOR_DB) / -METER_FLOOR_DB
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_fraction() {
+        assert_eq!(meter_fraction(0.0), 0.0);
+        assert_eq!(meter_fraction(1.0), 1.0);
+        assert_eq!(meter_fraction(4.0), 1.0);
+        assert!((meter_fraction(util::db_to_gain(-30.0)) - 0.5).abs() < 0.001);
+    }
+}
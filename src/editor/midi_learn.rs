@@ -0,0 +1,170 @@
+//! MIDI learn UI
+//!
+//! Right-click a slider (or use the MIDI panel) to arm learning, then move a
+//! controller: the audio thread records the first CC it sees. Program
+//! changes pick scenes and factory presets; their programs are set here.
+
+use crossbeam_channel::Sender;
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+
+use super::theme;
+use crate::messages::CodeMessage;
+use crate::midi::{MidiMap, DEFAULT_PRESET_PROGRAM, MIDI_LEARN_TARGETS};
+use crate::preset::factory::FACTORY_PRESETS;
+
+/// Arm (or with `None`, cancel) MIDI learn on the audio thread
+fn set_learn_target(
+    sender: &Sender<CodeMessage>,
+    learning: &mut Option<String>,
+    target: Option<&str>,
+) {
+    let target = target.map(str::to_string);
+    if sender
+        .try_send(CodeMessage::MidiLearn(target.clone()))
+        .is_ok()
+    {
+        *learning = target;
+    }
+}
+
+/// Everything the learn menu items need to arm or clear a mapping
+pub struct LearnTarget<'a> {
+    pub sender: &'a Sender<CodeMessage>,
+    pub learning: &'a mut Option<String>,
+    pub map: &'a RwLock<MidiMap>,
+    pub param_id: &'a str,
+}
+
+/// "MIDI Learn" / "Clear MIDI" entries for an open context menu
+pub fn learn_items(ui: &mut egui::Ui, target: LearnTarget) {
+    if ui.button("MIDI Learn").clicked() {
+        set_learn_target(target.sender, target.learning, Some(target.param_id));
+        ui.close_menu();
+    }
+    let mapped = target.map.read().cc_for(target.param_id);
+    if let Some(cc) = mapped {
+        if ui.button(format!("Clear MIDI (CC {})", cc)).clicked() {
+            target.map.write().clear(target.param_id);
+            ui.close_menu();
+        }
+    }
+}
+
+/// Right-click menu for a MIDI-learnable control
+pub fn learn_menu(response: &egui::Response, target: LearnTarget) {
+    response.context_menu(|ui| learn_items(ui, target));
+}
+
+/// Overview of all learnable parameters and their CCs
+pub fn midi_section(
+    ui: &mut egui::Ui,
+    sender: &Sender<CodeMessage>,
+    learning: &mut Option<String>,
+    map: &RwLock<MidiMap>,
+) {
+    if let Some(target) = learning.clone() {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(format!("Move a controller for {}…", target))
+                    .color(theme::knob_indicator()),
+            );
+            if ui.small_button("Cancel").clicked() {
+                set_learn_target(sender, learning, None);
+            }
+        });
+        ui.add_space(4.0);
+    }
+
+    egui::Grid::new("midi_map").num_columns(3).show(ui, |ui| {
+        for &param_id in MIDI_LEARN_TARGETS {
+            ui.label(egui::RichText::new(param_id).color(theme::text_normal()));
+            let mapped = map.read().cc_for(param_id);
+            match mapped {
+                Some(cc) => ui.label(egui::RichText::new(format!("CC {}", cc)).monospace()),
+                None => ui.label(egui::RichText::new("—").color(theme::text_dim())),
+            };
+            ui.horizontal(|ui| {
+                if ui.small_button("Learn").clicked() {
+                    set_learn_target(sender, learning, Some(param_id));
+                }
+                if mapped.is_some() && ui.small_button("✕").clicked() {
+                    map.write().clear(param_id);
+                }
+            });
+            ui.end_row();
+        }
+    });
+
+    ui.add_space(6.0);
+    ui.label(egui::RichText::new("Program changes").color(theme::text_dim()));
+    egui::Grid::new("midi_programs")
+        .num_columns(2)
+        .show(ui, |ui| {
+            let mut programs = map.read().scene_programs;
+            let mut changed = false;
+            for (scene, program) in programs.iter_mut().enumerate() {
+                let mut enabled = program.is_some();
+                if ui
+                    .checkbox(&mut enabled, format!("Scene {}", scene + 1))
+                    .changed()
+                {
+                    *program = enabled.then_some(scene as u8);
+                    changed = true;
+                }
+                if let Some(program) = program {
+                    // Shown 1-based, as most controllers label them
+                    let mut number = *program + 1;
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut number)
+                                .range(1..=128)
+                                .prefix("PC "),
+                        )
+                        .changed()
+                    {
+                        *program = number - 1;
+                        changed = true;
+                    }
+                } else {
+                    ui.label(egui::RichText::new("—").color(theme::text_dim()));
+                }
+                ui.end_row();
+            }
+            if changed {
+                map.write().scene_programs = programs;
+            }
+
+            let mut first = map.read().preset_program;
+            let mut enabled = first.is_some();
+            let mut preset_changed = ui
+                .checkbox(&mut enabled, "Factory presets")
+                .on_hover_text("Programs from this one on load the factory presets in bank order")
+                .changed();
+            if preset_changed {
+                first = enabled.then_some(DEFAULT_PRESET_PROGRAM);
+            }
+            if let Some(program) = &mut first {
+                // Keep the whole bank within the 128 programs
+                let latest_start = 128 - FACTORY_PRESETS.len() as u8;
+                let mut number = *program + 1;
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut number)
+                            .range(1..=latest_start + 1)
+                            .prefix("PC "),
+                    )
+                    .changed()
+                {
+                    *program = number - 1;
+                    preset_changed = true;
+                }
+            } else {
+                ui.label(egui::RichText::new("—").color(theme::text_dim()));
+            }
+            ui.end_row();
+            if preset_changed {
+                map.write().preset_program = first;
+            }
+        });
+}
@@ -0,0 +1,2503 @@
+use crossbeam_channel::{Receiver, Sender};
+use nih_plug::prelude::*;
+use nih_plug_egui::{create_egui_editor, egui, widgets::ResizableWindow};
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::api::ApiServer;
+use crate::diagnostics::Level;
+use crate::dsp::eq::EqBands;
+use crate::engine::{preprocess, validate, KnobNames, KNOB_COUNT};
+use crate::lang::completion::word_at;
+use crate::lang::format::format_code;
+use crate::lang::mutate::{mutate, Rng};
+use crate::lang::nodes::{self, NodeInfo};
+use crate::lang::references::{reference_spans, undefined_references};
+use crate::link::LinkHandle;
+use crate::messages::{CodeMessage, CompileError, StatusMessage};
+use crate::params::{GlicolVerbParams, UserRecipe};
+use crate::preset::factory::FACTORY_PRESETS;
+use crate::preset::randomize::{is_randomizable, randomize};
+use crate::preset::Preset;
+use crate::recorder::Recorder;
+use crate::shortcuts::Action;
+use crate::state::PresetInfo;
+use crate::telemetry::Telemetry;
+use crate::tempo_link::TempoLink;
+
+mod ab_compare;
+mod api;
+mod autocomplete;
+mod cheat_sheet;
+mod diagnostics;
+mod eq_curve;
+mod external;
+mod file_drop;
+mod find;
+mod flow_graph;
+mod goniometer;
+mod history;
+mod injected;
+mod lab_search;
+mod lfos;
+mod link;
+mod macros;
+mod meters;
+mod midi_learn;
+mod module_strip;
+mod param_menu;
+mod placeholders;
+mod presets;
+mod recipes;
+mod recorder;
+mod scenes;
+mod settings;
+mod spectrum;
+mod status_bar;
+mod tempo_link;
+mod test_signal;
+mod theme;
+mod timeline;
+mod tuner;
+mod vim;
+
+use ab_compare::ab_buttons;
+use api::api_settings;
+use autocomplete::{char_to_byte, Autocomplete};
+use cheat_sheet::{pressed_actions, CheatSheet};
+use diagnostics::DiagnosticsPanel;
+use eq_curve::eq_curve;
+use external::ExternalEdit;
+use file_drop::{handle_drops, Dropped};
+use find::{find_matches, FindBar};
+use flow_graph::FlowGraph;
+use goniometer::goniometer;
+use history::{CodeHistory, IDLE_SNAPSHOT_SECS};
+use injected::injected_view;
+use lab_search::{no_matches, LabSearch, BUILTIN_RECIPES, USER_RECIPES};
+use lfos::{enum_combo, lfo_controls, mod_matrix};
+use link::link_settings;
+use macros::macro_mappings;
+use meters::Meters;
+use midi_learn::{learn_menu, midi_section, LearnTarget};
+use module_strip::module_strip;
+use param_menu::param_menu;
+use placeholders::{first_placeholder, select_placeholder_after};
+use presets::PresetPanel;
+use recipes::{ordered_builtins, RecipeManager};
+use recorder::record_button;
+use scenes::{step_scene, SceneTabs};
+use settings::{apply_view, settings_section, zoom_view};
+use spectrum::Spectrum;
+use status_bar::StatusBar;
+use tempo_link::tempo_link_settings;
+use test_signal::test_signal_controls;
+use timeline::{diff_view, Timeline};
+use tuner::Tuner;
+use vim::Vim;
+
+/// Smallest window size the resize corner allows
+const MIN_WINDOW_SIZE: egui::Vec2 = egui::vec2(640.0, 400.0);
+
+/// Below this content width the two columns are stacked vertically
+const STACK_COLUMNS_BELOW: f32 = 820.0;
+
+/// A recipe is a complete, educational effect with explanation
+struct Recipe<'a> {
+    icon: &'a str,
+    name: &'a str,
+    description: &'a str,
+    code: &'a str,
+    explanation: &'a str,
+}
+
+/// All built-in recipes
+const RECIPES: &[Recipe<'static>] = &[
+    Recipe {
+        icon: "🎸",
+        name: "Amp Sim",
+        description: "Drive + tone shaping",
+        code: "out: ~input >> mul ~drive >> lpf 3000.0 0.5",
+        explanation: "mul boosts signal (adds grit), lpf cuts harsh highs",
+    },
+    Recipe {
+        icon: "🌊",
+        name: "Tremolo",
+        description: "Pulsing volume effect",
+        code: "out: ~input >> mul ~lfo\n~lfo: sin ~rate >> mul 0.5 >> add 0.5",
+        explanation: "sin creates a wave, scaled to 0-1 so volume pulses smoothly",
+    },
+    Recipe {
+        icon: "🎚",
+        name: "Filter Sweep",
+        description: "Auto-wah effect",
+        code: "out: ~input >> lpf ~freq 0.7\n~freq: sin ~rate >> mul 2000.0 >> add 2500.0",
+        explanation: "Filter cutoff moves with a sine wave for sweeping wah sound",
+    },
+    Recipe {
+        icon: "🔊",
+        name: "Slapback",
+        description: "Quick doubling echo",
+        code: "out: ~input >> delayms 120.0 >> mul 0.6 >> add ~input",
+        explanation: "Short delay + dry signal = doubling effect (try 80-150ms)",
+    },
+    Recipe {
+        icon: "🏛",
+        name: "Plate Reverb",
+        description: "Classic plate space",
+        code: "out: ~input >> plate ~mix",
+        explanation: "Built-in plate algorithm. ~mix: 0=dry, 1=full reverb",
+    },
+    Recipe {
+        icon: "📼",
+        name: "Lo-Fi",
+        description: "Gritty retro tone",
+        code: "out: ~input >> mul 3.0 >> lpf 2000.0 0.5",
+        explanation: "Overdrive + aggressive filtering = vintage character",
+    },
+    Recipe {
+        icon: "🎵",
+        name: "Chorus",
+        description: "Shimmering doubled sound",
+        code: "out: ~input >> add ~chorus\n~chorus: ~input >> delayms ~mod >> mul 0.5\n~mod: sin ~rate >> mul 10.0 >> add 25.0",
+        explanation: "Modulated 15-35ms delay mixed with dry = lush doubling",
+    },
+    Recipe {
+        icon: "〰",
+        name: "Vibrato",
+        description: "Wobbly pitch modulation",
+        code: "out: ~input >> delayms ~mod\n~mod: sin ~rate >> mul 3.0 >> add 5.0",
+        explanation: "Delay time modulation creates pitch wobble (no dry mix)",
+    },
+    Recipe {
+        icon: "🔔",
+        name: "Ring Mod",
+        description: "Robotic/bell-like tones",
+        code: "out: ~input >> mul ~carrier\n~carrier: sin 200.0 >> mul 0.5 >> add 0.5",
+        explanation: "Multiplying by a carrier frequency creates metallic harmonics",
+    },
+    Recipe {
+        icon: "🌌",
+        name: "Ambient Wash",
+        description: "Atmospheric swells",
+        code: "out: ~input >> plate 0.6 >> delayms 400.0 >> mul 0.7 >> add ~input",
+        explanation: "Reverb into delay creates dreamy, evolving textures",
+    },
+    Recipe {
+        icon: "🌀",
+        name: "Phaser",
+        description: "Swirling phase sweep",
+        code: "out: ~dry >> add ~wet\n~dry: ~input >> mul 0.5\n~wet: ~input >> apfmsgain ~mod 0.7 >> mul 0.5\n~mod: sin ~rate >> mul 2.0 >> add 4.0",
+        explanation: "Allpass filter with modulated delay creates phase cancellation sweeps",
+    },
+    // Note: Bit Crusher using `meta` node crashes Glicol 0.13.5 - meta isn't fully implemented for ~input
+];
+
+/// A building block is a single node snippet users can insert
+///
+/// Arguments are `<name>` placeholders, selected in turn with Tab.
+struct BuildingBlock {
+    snippet: &'static str,
+    description: &'static str,
+}
+
+/// Building block categories
+struct BlockCategory {
+    name: &'static str,
+    blocks: &'static [BuildingBlock],
+}
+
+const BUILDING_BLOCKS: &[BlockCategory] = &[
+    BlockCategory {
+        name: "Filters",
+        blocks: &[
+            BuildingBlock {
+                snippet: "lpf <cutoff> <q>",
+                description: "Low-pass (cuts highs), cutoff in Hz, q 0.1-1",
+            },
+            BuildingBlock {
+                snippet: "hpf <cutoff> <q>",
+                description: "High-pass (cuts lows), cutoff in Hz, q 0.1-1",
+            },
+            BuildingBlock {
+                snippet: "onepole <cutoff>",
+                description: "Smooth simple filter, cutoff in Hz",
+            },
+        ],
+    },
+    BlockCategory {
+        name: "Modulation",
+        blocks: &[
+            BuildingBlock {
+                snippet: "sin ~rate",
+                description: "Sine LFO",
+            },
+            BuildingBlock {
+                snippet: "saw ~rate",
+                description: "Ramp LFO",
+            },
+            BuildingBlock {
+                snippet: "squ ~rate",
+                description: "Square LFO",
+            },
+        ],
+    },
+    BlockCategory {
+        name: "Time",
+        blocks: &[
+            BuildingBlock {
+                snippet: "delayms <ms>",
+                description: "Delay (ms)",
+            },
+            BuildingBlock {
+                snippet: "plate <mix>",
+                description: "Plate reverb, mix 0-1",
+            },
+        ],
+    },
+    BlockCategory {
+        name: "Gain",
+        blocks: &[
+            BuildingBlock {
+                snippet: "mul <gain>",
+                description: "Multiply/volume",
+            },
+            BuildingBlock {
+                snippet: "add ~input",
+                description: "Mix signals",
+            },
+        ],
+    },
+];
+
+/// Helper macro to create a standard egui slider for a nih-plug parameter
+/// This works around ParamSlider not responding to mouse events on macOS.
+/// Double-click resets to the default; right-click opens the value menu,
+/// which includes MIDI learn when a `LearnTarget` is passed.
+macro_rules! param_slider {
+    ($ui:expr, $setter:expr, $param:expr, $range:expr, $label:expr) => {
+        param_slider!($ui, $setter, $param, $range, $label, None)
+    };
+    ($ui:expr, $setter:expr, $param:expr, $range:expr, $label:expr, $learn:expr) => {{
+        let response = $ui
+            .horizontal(|ui| {
+                ui.add_sized([70.0, 18.0], egui::Label::new($label));
+                ui.add(
+                    egui::Slider::from_get_set($range, |new_value| match new_value {
+                        Some(v) => {
+                            let v = v as f32;
+                            $setter.begin_set_parameter($param);
+                            $setter.set_parameter($param, v);
+                            $setter.end_set_parameter($param);
+                            v as f64
+                        }
+                        None => $param.modulated_plain_value() as f64,
+                    })
+                    .show_value(true),
+                )
+            })
+            .inner;
+        param_menu(&response, $setter, $param, $learn);
+        response
+    }};
+}
+
+/// Custom painted knob widget with hardware styling (for future use)
+#[allow(dead_code)]
+fn knob_widget(
+    ui: &mut egui::Ui,
+    value: &mut f32,
+    range: std::ops::RangeInclusive<f32>,
+    label: &str,
+) -> egui::Response {
+    let size = egui::Vec2::splat(50.0);
+
+    ui.vertical(|ui| {
+        ui.set_width(size.x + 10.0);
+
+        // Allocate space for the knob
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::drag());
+
+        // Handle drag interaction
+        if response.dragged() {
+            let delta = response.drag_delta();
+            let range_size = range.end() - range.start();
+            // Vertical drag: up increases, down decreases
+            let sensitivity = range_size / 150.0;
+            *value = (*value - delta.y * sensitivity).clamp(*range.start(), *range.end());
+        }
+
+        // Draw the knob
+        if ui.is_rect_visible(rect) {
+            let painter = ui.painter();
+            let center = rect.center();
+            let radius = rect.width() / 2.0 - 3.0;
+
+            // Outer shadow/ring
+            painter.circle_filled(
+                center + egui::Vec2::new(1.0, 1.0),
+                radius + 2.0,
+                egui::Color32::from_rgb(15, 15, 18),
+            );
+
+            // Outer ring
+            painter.circle_filled(center, radius + 2.0, theme::knob_ring());
+
+            // Main knob body - layered for depth effect
+            painter.circle_filled(center, radius, theme::knob_bg());
+            painter.circle_filled(
+                center - egui::Vec2::new(1.0, 2.0),
+                radius - 2.0,
+                egui::Color32::from_rgb(52, 52, 58),
+            );
+            painter.circle_filled(center, radius - 3.0, egui::Color32::from_rgb(48, 48, 54));
+
+            // Inner circle highlight
+            painter.circle_stroke(
+                center,
+                radius - 6.0,
+                egui::Stroke::new(1.0, egui::Color32::from_rgb(58, 58, 64)),
+            );
+
+            // Value indicator line
+            let normalized = (*value - range.start()) / (range.end() - range.start());
+            // Angle goes from 225° (min) to -45° (max), clockwise
+            let angle = std::f32::consts::PI * 1.25 - normalized * std::f32::consts::PI * 1.5;
+            let indicator_start =
+                center + egui::Vec2::new(angle.cos(), -angle.sin()) * (radius * 0.25);
+            let indicator_end =
+                center + egui::Vec2::new(angle.cos(), -angle.sin()) * (radius * 0.7);
+
+            // Indicator glow
+            painter.line_segment(
+                [indicator_start, indicator_end],
+                egui::Stroke::new(4.0, egui::Color32::from_rgba_unmultiplied(220, 140, 80, 60)),
+            );
+            // Main indicator
+            painter.line_segment(
+                [indicator_start, indicator_end],
+                egui::Stroke::new(2.5, theme::knob_indicator()),
+            );
+        }
+
+        // Label below knob
+        ui.add_space(4.0);
+        ui.label(egui::RichText::new(label).color(theme::text_dim()).small());
+
+        response
+    })
+    .inner
+}
+
+/// Styled section with dark panel background
+fn styled_section<R>(
+    ui: &mut egui::Ui,
+    title: &str,
+    status: Option<bool>, // Some(true) = active, Some(false) = bypassed, None = no status
+    default_open: bool,
+    content: impl FnOnce(&mut egui::Ui) -> R,
+) -> egui::CollapsingResponse<R> {
+    // Build header text with status indicator
+    let header_text = match status {
+        Some(true) => format!("{}  ●", title),
+        Some(false) => format!("{}  ○", title),
+        None => title.to_string(),
+    };
+
+    let status_color = match status {
+        Some(true) => theme::status_active(),
+        Some(false) => theme::status_bypass(),
+        None => theme::text_bright(),
+    };
+
+    egui::Frame::new()
+        .fill(theme::bg_section())
+        .corner_radius(egui::CornerRadius::same(6))
+        .inner_margin(egui::Margin::same(10))
+        .outer_margin(egui::Margin::symmetric(0, 3))
+        .stroke(egui::Stroke::new(1.0, theme::section_stroke()))
+        .show(ui, |ui| {
+            egui::CollapsingHeader::new(
+                egui::RichText::new(header_text)
+                    .color(status_color)
+                    .strong(),
+            )
+            .default_open(default_open)
+            .show(ui, content)
+        })
+        .inner
+}
+
+/// Render a compact recipe button with hover tooltip
+fn recipe_chip(ui: &mut egui::Ui, recipe: &Recipe, state: &mut EditorState) -> egui::Response {
+    let button_text = format!("{} {}", recipe.icon, recipe.name);
+    let tooltip = format!(
+        "{}\n\nCode:\n{}\n\n💡 {}",
+        recipe.description, recipe.code, recipe.explanation
+    );
+
+    let button = egui::Button::new(
+        egui::RichText::new(&button_text)
+            .color(theme::text_bright())
+            .size(12.0),
+    )
+    .fill(theme::card_bg())
+    .corner_radius(egui::CornerRadius::same(4));
+
+    let response = ui
+        .add(button)
+        .on_hover_text(format!("{}\n\nHold to preview", tooltip));
+
+    // Holding previews the recipe; releasing returns to the user's code
+    if response.is_pointer_button_down_on() {
+        let held = ui.input(|i| {
+            i.pointer
+                .press_start_time()
+                .map_or(0.0, |start| i.time - start)
+        });
+        if state.audition.is_none() {
+            if held >= AUDITION_HOLD_SECS {
+                let code = recipe.code.to_string();
+                if state
+                    .code_sender
+                    .try_send(CodeMessage::Audition(Some(code)))
+                    .is_ok()
+                {
+                    state.audition = Some(response.id);
+                }
+            } else {
+                // Keep checking the hold time while the button is down
+                ui.ctx().request_repaint();
+            }
+        }
+        if state.audition == Some(response.id) {
+            state.status_message = format!("Previewing {}, release to return", recipe.name);
+            state.status_is_error = false;
+        }
+    } else if state.audition == Some(response.id) {
+        let _ = state.code_sender.try_send(CodeMessage::Audition(None));
+        state.audition = None;
+        state.status_message.clear();
+    } else if response.clicked() {
+        replace_code(state, recipe.code.to_string());
+        send_code_update_from_buffer(state);
+    }
+    response
+}
+
+/// How long a recipe chip must be held before it previews
+const AUDITION_HOLD_SECS: f64 = 0.35;
+
+/// Documentation card for a Glicol node (hover tooltips)
+fn node_doc_card(ui: &mut egui::Ui, node: &NodeInfo) {
+    ui.set_max_width(280.0);
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(node.name)
+                .monospace()
+                .strong()
+                .color(theme::accent()),
+        );
+        ui.label(
+            egui::RichText::new(node.args)
+                .monospace()
+                .color(theme::text_normal()),
+        );
+    });
+    ui.label(egui::RichText::new(node.description).color(theme::text_bright()));
+    ui.label(
+        egui::RichText::new(node.ranges)
+            .color(theme::text_dim())
+            .small(),
+    );
+    ui.add_space(4.0);
+    ui.code(node.example);
+}
+
+/// Show the reference card for the node under the mouse in the code editor
+fn code_hover_docs(ui: &egui::Ui, output: &egui::text_edit::TextEditOutput, code: &str) {
+    if !output.response.hovered() {
+        return;
+    }
+    let Some(pointer) = ui.input(|i| i.pointer.hover_pos()) else {
+        return;
+    };
+
+    let cursor = output.galley.cursor_from_pos(pointer - output.galley_pos);
+    let index = cursor.ccursor.index;
+    // The nearest cursor may sit just after the word's last character
+    let word = word_at(code, index).or_else(|| word_at(code, index.checked_sub(1)?));
+    if let Some(node) = word.as_deref().and_then(nodes::find) {
+        output
+            .response
+            .clone()
+            .on_hover_ui_at_pointer(|ui| node_doc_card(ui, node));
+    }
+}
+
+/// Render the building blocks section with categories
+fn building_blocks_section(ui: &mut egui::Ui, state: &mut EditorState) {
+    let mut any_shown = false;
+    for category in BUILDING_BLOCKS {
+        let blocks: Vec<&BuildingBlock> = category
+            .blocks
+            .iter()
+            .filter(|block| {
+                state.lab_search.shows_category(category.name)
+                    && state
+                        .lab_search
+                        .matches(&[block.snippet, block.description, category.name])
+            })
+            .collect();
+        if blocks.is_empty() {
+            continue;
+        }
+        any_shown = true;
+
+        ui.add_space(4.0);
+        ui.label(
+            egui::RichText::new(category.name)
+                .color(theme::accent())
+                .small()
+                .strong(),
+        );
+        ui.horizontal_wrapped(|ui| {
+            for block in blocks {
+                let button =
+                    egui::Button::new(egui::RichText::new(block.snippet).monospace().size(10.0));
+                let response = ui.add(button);
+                // Show the node's reference card when we have one
+                let node = block
+                    .snippet
+                    .split_whitespace()
+                    .next()
+                    .and_then(nodes::find);
+                let response = match node {
+                    Some(node) => response.on_hover_ui(|ui| {
+                        ui.label(
+                            egui::RichText::new(block.description).color(theme::text_normal()),
+                        );
+                        ui.separator();
+                        node_doc_card(ui, node);
+                    }),
+                    None => response.on_hover_text(block.description),
+                };
+                if response.clicked() {
+                    state.history.snapshot(&state.code_buffer);
+                    // Placeholders are searched from where the block lands
+                    let inserted_at = if state.code_buffer.trim().is_empty() {
+                        0
+                    } else {
+                        state.code_buffer.chars().count()
+                    };
+                    // Append to code with >> prefix if code isn't empty
+                    if state.code_buffer.trim().is_empty() {
+                        state.code_buffer = format!("out: ~input >> {}", block.snippet);
+                    } else if state.code_buffer.ends_with('\n') || state.code_buffer.ends_with(' ')
+                    {
+                        state.code_buffer.push_str(&format!(">> {}", block.snippet));
+                    } else {
+                        state
+                            .code_buffer
+                            .push_str(&format!(" >> {}", block.snippet));
+                    }
+                    select_placeholder_after(
+                        ui.ctx(),
+                        &state.code_buffer,
+                        egui::Id::new(CODE_EDITOR_ID),
+                        inserted_at,
+                    );
+                }
+            }
+        });
+    }
+    if !any_shown {
+        no_matches(ui);
+    }
+}
+
+/// Render the user snippet library (save current code, insert `#include`, delete)
+/// Rename the mappable knobs; a named knob is also injected as `~name`
+fn knob_names_section(ui: &mut egui::Ui, knob_names: &RwLock<KnobNames>) {
+    let mut names = knob_names.write();
+    egui::Grid::new("knob_names_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for index in 0..KNOB_COUNT {
+                let generic = format!("knob{}", index + 1);
+                ui.label(
+                    egui::RichText::new(&generic)
+                        .monospace()
+                        .color(theme::text_dim()),
+                );
+                let mut name = names.0.get(index).cloned().unwrap_or_default();
+                let edit = egui::TextEdit::singleline(&mut name)
+                    .hint_text(&generic)
+                    .desired_width(110.0);
+                if ui.add(edit).changed() {
+                    names.set(index, &name);
+                }
+                ui.end_row();
+            }
+        });
+}
+
+fn snippets_section(ui: &mut egui::Ui, state: &mut EditorState) {
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut state.snippet_name)
+                .hint_text("snippet name")
+                .desired_width(120.0),
+        );
+        let name = state.snippet_name.trim().to_string();
+        let can_save = !name.is_empty() && !name.contains('"');
+        if ui
+            .add_enabled(can_save, egui::Button::new("Save code as snippet"))
+            .clicked()
+        {
+            state
+                .snippets
+                .write()
+                .insert(name, state.code_buffer.clone());
+            state.snippet_name.clear();
+        }
+    });
+
+    let names: Vec<String> = state.snippets.read().keys().cloned().collect();
+    if names.is_empty() {
+        ui.label(
+            egui::RichText::new("No snippets yet")
+                .color(theme::text_dim())
+                .small(),
+        );
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for name in names {
+            let button = egui::Button::new(egui::RichText::new(&name).monospace().size(10.0));
+            if ui
+                .add(button)
+                .on_hover_text(format!("Insert #include \"{}\"", name))
+                .clicked()
+            {
+                let code = format!("#include \"{}\"\n{}", name, state.code_buffer);
+                replace_code(state, code);
+            }
+            if ui
+                .small_button("✕")
+                .on_hover_text("Delete snippet")
+                .clicked()
+            {
+                state.snippets.write().remove(&name);
+            }
+        }
+    });
+}
+
+/// Widget ID of the code editor
+const CODE_EDITOR_ID: &str = "code_editor";
+
+/// Width of the code editor's left gutter (error marker + line numbers)
+const GUTTER_WIDTH: f32 = 40.0;
+
+/// Part of the gutter reserved for the error marker, left of the numbers
+const MARKER_WIDTH: f32 = 14.0;
+
+/// What to highlight in the code editor besides the error line
+struct CodeHighlights<'a> {
+    /// Find bar query and case sensitivity
+    search: Option<&'a (String, bool)>,
+    /// Selected find match
+    current_match: usize,
+    /// Char index of the cursor (for reference highlighting)
+    cursor: Option<usize>,
+    /// References nothing defines
+    undefined: &'a [String],
+}
+
+impl CodeHighlights<'_> {
+    /// Byte ranges and colors, highest priority first
+    fn ranges(&self, text: &str) -> Vec<(usize, usize, egui::Color32)> {
+        let mut ranges = Vec::new();
+        if let Some((query, case_sensitive)) = self.search {
+            let matches = find_matches(text, query, *case_sensitive);
+            let match_bg = theme::knob_indicator().gamma_multiply(0.3);
+            let current_bg = theme::knob_indicator().gamma_multiply(0.7);
+            for (index, (start, end)) in matches.into_iter().enumerate() {
+                let color = if index == self.current_match {
+                    current_bg
+                } else {
+                    match_bg
+                };
+                ranges.push((start, end, color));
+            }
+        }
+
+        let spans = reference_spans(text);
+        let undefined_bg = theme::status_error().gamma_multiply(0.4);
+        for span in spans
+            .iter()
+            .filter(|span| self.undefined.contains(&span.name))
+        {
+            ranges.push((span.start, span.end, undefined_bg));
+        }
+
+        // Every occurrence of the reference under the cursor
+        let cursor = self.cursor.map(|cursor| char_to_byte(text, cursor));
+        let under_cursor = cursor.and_then(|cursor| {
+            spans
+                .iter()
+                .find(|span| span.start <= cursor && cursor <= span.end)
+        });
+        if let Some(selected) = under_cursor {
+            let usage_bg = theme::accent().gamma_multiply(0.2);
+            let definition_bg = theme::accent().gamma_multiply(0.45);
+            for span in spans.iter().filter(|span| span.name == selected.name) {
+                let color = if span.definition {
+                    definition_bg
+                } else {
+                    usage_bg
+                };
+                ranges.push((span.start, span.end, color));
+            }
+        }
+        ranges
+    }
+}
+
+/// Lay out code in monospace, highlighting `error_line` (1-based), find
+/// matches and references
+fn code_layout_job(
+    ui: &egui::Ui,
+    text: &str,
+    wrap_width: f32,
+    error_line: Option<usize>,
+    highlights: &CodeHighlights,
+) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let color = ui.visuals().text_color();
+    let error_bg = theme::status_error().gamma_multiply(0.25);
+    let ranges = highlights.ranges(text);
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut line_start = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let line_bg = if error_line == Some(index + 1) {
+            error_bg
+        } else {
+            egui::Color32::TRANSPARENT
+        };
+        let line_end = line_start + line.len();
+
+        // Split the line wherever a highlight starts or ends
+        let mut position = line_start;
+        while position < line_end {
+            let next_start = ranges
+                .iter()
+                .map(|(start, _, _)| *start)
+                .filter(|start| *start > position)
+                .min()
+                .unwrap_or(line_end);
+            let hit = ranges
+                .iter()
+                .find(|(start, end, _)| *start <= position && position < *end);
+            let (end, background) = match hit {
+                Some(&(_, end, background)) => (end.min(next_start), background),
+                None => (next_start, line_bg),
+            };
+            let end = end.min(line_end);
+            job.append(
+                &text[position..end],
+                0.0,
+                egui::TextFormat {
+                    font_id: font_id.clone(),
+                    color,
+                    background,
+                    ..Default::default()
+                },
+            );
+            position = end;
+        }
+        line_start = line_end;
+    }
+    job.wrap.max_width = wrap_width;
+    job
+}
+
+/// Char index where each line of the code starts
+fn line_starts(code: &str) -> impl Iterator<Item = usize> + '_ {
+    std::iter::once(0).chain(
+        code.chars()
+            .enumerate()
+            .filter(|(_, c)| *c == '\n')
+            .map(|(index, _)| index + 1),
+    )
+}
+
+/// Paint line numbers in the gutter, right-aligned next to each line's
+/// first row (wrapped rows stay unnumbered)
+fn line_number_gutter(
+    ui: &egui::Ui,
+    output: &egui::text_edit::TextEditOutput,
+    code: &str,
+    error_line: Option<usize>,
+) {
+    let font_id =
+        egui::FontId::monospace(egui::TextStyle::Monospace.resolve(ui.style()).size * 0.85);
+    let painter = ui.painter();
+    for (index, start) in line_starts(code).enumerate() {
+        let row = output
+            .galley
+            .pos_from_ccursor(egui::text::CCursor::new(start));
+        let color = if error_line == Some(index + 1) {
+            theme::status_error()
+        } else {
+            theme::text_dim()
+        };
+        painter.text(
+            egui::pos2(
+                output.galley_pos.x - 4.0,
+                output.galley_pos.y + row.center().y,
+            ),
+            egui::Align2::RIGHT_CENTER,
+            (index + 1).to_string(),
+            font_id.clone(),
+            color,
+        );
+    }
+}
+
+/// Paint an error marker in the gutter next to the failing line, with the
+/// message as its tooltip
+fn error_gutter_marker(
+    ui: &mut egui::Ui,
+    output: &egui::text_edit::TextEditOutput,
+    code: &str,
+    error: &CompileError,
+) {
+    // Unlocated errors are pinned to the first line
+    let line = error.line.unwrap_or(1);
+    let char_index = line_starts(code)
+        .nth(line - 1)
+        .unwrap_or_else(|| code.chars().count());
+
+    let cursor_rect = output
+        .galley
+        .pos_from_ccursor(egui::text::CCursor::new(char_index));
+    let row_center_y = output.galley_pos.y + cursor_rect.center().y;
+    let marker_rect = egui::Rect::from_center_size(
+        egui::pos2(
+            output.galley_pos.x - GUTTER_WIDTH + MARKER_WIDTH / 2.0,
+            row_center_y,
+        ),
+        egui::Vec2::splat(MARKER_WIDTH - 2.0),
+    );
+
+    ui.painter().text(
+        marker_rect.center(),
+        egui::Align2::CENTER_CENTER,
+        "●",
+        egui::FontId::monospace(10.0),
+        theme::status_error(),
+    );
+
+    let tooltip = match (error.line, error.column) {
+        (Some(line), Some(column)) => {
+            format!("Line {}, column {}\n{}", line, column, error.message)
+        }
+        (Some(line), None) => format!("Line {}\n{}", line, error.message),
+        _ => error.message.clone(),
+    };
+    ui.interact(
+        marker_rect,
+        egui::Id::new("code_error_marker"),
+        egui::Sense::hover(),
+    )
+    .on_hover_text(tooltip);
+}
+
+/// Create the plugin editor GUI
+pub fn create(
+    params: Arc<GlicolVerbParams>,
+    code_sender: Sender<CodeMessage>,
+    status_receiver: Receiver<StatusMessage>,
+    telemetry: Arc<Telemetry>,
+    recorder: Arc<Recorder>,
+    link: Arc<LinkHandle>,
+    api: Arc<ApiServer>,
+    tempo_link: Arc<TempoLink>,
+) -> Option<Box<dyn Editor>> {
+    // Get initial code from params
+    let initial_code = params.code.read().clone();
+
+    create_egui_editor(
+        params.editor_state.clone(),
+        EditorState {
+            code_sender,
+            status_receiver,
+            code_buffer: initial_code.clone(),
+            last_synced_code: initial_code,
+            snippets: params.snippets.clone(),
+            knob_names: params.knob_names.clone(),
+            snippet_name: String::new(),
+            panic_revert: false,
+            mutate_amount: 0.25,
+            mutate_swap: false,
+            status_message: String::new(),
+            status_is_error: false,
+            last_error: None,
+            autocomplete: Autocomplete::default(),
+            history: CodeHistory::default(),
+            timeline: Timeline::default(),
+            preset_panel: PresetPanel::default(),
+            diagnostics_panel: DiagnosticsPanel::default(),
+            recipes: RecipeManager::default(),
+            lab_search: LabSearch::default(),
+            meters: Meters::default(),
+            midi_learning: None,
+            audition: None,
+            spectrum: Spectrum::default(),
+            vim: Vim::default(),
+            external: ExternalEdit::default(),
+            focus_mode: false,
+            show_diff: false,
+            show_injected: false,
+            show_graph: false,
+            find: FindBar::default(),
+            flow_graph: FlowGraph::default(),
+            cheat_sheet: CheatSheet::default(),
+            status_bar: StatusBar::default(),
+            tuner: Tuner::default(),
+            scenes: SceneTabs::new(&params),
+            link,
+            last_edit_time: None,
+            // Initialize EQ state from params
+            eq_low_freq: params.eq.low_freq.modulated_plain_value(),
+            eq_low_gain: params.eq.low_gain.modulated_plain_value(),
+            eq_mid_freq: params.eq.mid_freq.modulated_plain_value(),
+            eq_mid_gain: params.eq.mid_gain.modulated_plain_value(),
+            eq_mid_q: params.eq.mid_q.modulated_plain_value(),
+            eq_high_freq: params.eq.high_freq.modulated_plain_value(),
+            eq_high_gain: params.eq.high_gain.modulated_plain_value(),
+        },
+        |_, _| {},
+        move |egui_ctx, setter, state| {
+            // Theme and zoom can change from the settings panel or a state restore
+            theme::apply(egui_ctx, &params.theme.read());
+            // Configurable shortcuts (none fire while one is being rebound)
+            if !state.cheat_sheet.capture(egui_ctx, &params.shortcuts) {
+                for action in pressed_actions(egui_ctx, &params.shortcuts.read()) {
+                    run_action(action, state, &params, setter);
+                }
+            }
+            apply_view(egui_ctx, &params.view, state.focus_mode);
+
+            // Sync code_buffer with params if state was restored externally
+            let current_params_code = params.code.read().clone();
+            if current_params_code != state.last_synced_code {
+                replace_code(state, current_params_code.clone());
+                state.last_synced_code = current_params_code;
+            }
+
+            // Audio-thread events, logged even while the panel is closed
+            telemetry.diagnostics.drain();
+
+            // Compile results from the audio thread
+            while let Ok(status) = state.status_receiver.try_recv() {
+                match status {
+                    StatusMessage::Success => {
+                        timeline::record_version(&params.code_versions, &params.code.read());
+                        state.last_error = None;
+                        if state.status_is_error {
+                            state.status_message = "Code updated!".to_string();
+                            state.status_is_error = false;
+                        }
+                    }
+                    StatusMessage::Error(error) => {
+                        state.status_message = match error.line {
+                            Some(line) => format!("Error on line {}", line),
+                            None => "Error: Glicol rejected the code".to_string(),
+                        };
+                        state.status_is_error = true;
+                        telemetry
+                            .diagnostics
+                            .log(Level::Warn, format!("Compile error: {}", error.message));
+                        state.last_error = Some(error);
+                    }
+                    StatusMessage::FactoryPreset { index } => {
+                        // The audio thread already runs the preset's code,
+                        // which reaches the buffer through `params.code`
+                        let preset = FACTORY_PRESETS[index].to_preset(&params);
+                        preset.apply_params(&params, setter);
+                        params.module_chain.write().preset = Some(PresetInfo::now(&preset.name));
+                        sync_eq_state(state, &params);
+                        state.status_message = format!("Loaded {}", preset.name);
+                        state.status_is_error = false;
+                    }
+                    StatusMessage::MidiLearned { param_id, cc } => {
+                        params.midi_map.write().learn(&param_id, cc);
+                        state.midi_learning = None;
+                        state.status_message = format!("Mapped CC {} to {}", cc, param_id);
+                        state.status_is_error = false;
+                    }
+                    StatusMessage::BufferUnderrun => {}
+                }
+            }
+
+            // Undo/redo for the whole code buffer (taken before the text edit sees the keys)
+            let (redo_pressed, undo_pressed) = egui_ctx.input_mut(|i| {
+                let redo = i.consume_key(
+                    egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                    egui::Key::Z,
+                ) || i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+                let undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                (redo, undo)
+            });
+            if undo_pressed {
+                if let Some(code) = state.history.undo(&state.code_buffer) {
+                    state.code_buffer = code;
+                }
+            } else if redo_pressed {
+                if let Some(code) = state.history.redo(&state.code_buffer) {
+                    state.code_buffer = code;
+                }
+            }
+
+            // Scene switched (tab click or host automation); linked
+            // instances switch along with this one
+            if let Some(code) = state.scenes.sync(&params, &state.code_buffer) {
+                state.link.publish(state.scenes.shown(), &code);
+                replace_code(state, code);
+            }
+
+            // Saves from the external editor apply like an Update click
+            if let Some(code) = state.external.poll(egui_ctx) {
+                replace_code(state, code);
+                send_code_update_from_buffer(state);
+            }
+
+            match handle_drops(egui_ctx) {
+                Some(Ok(Dropped::Code(code))) => {
+                    replace_code(state, code);
+                    state.status_message = "Loaded dropped code, press Update to apply".to_string();
+                    state.status_is_error = false;
+                }
+                Some(Ok(Dropped::Preset(preset))) => {
+                    state.status_message = format!("Loaded preset '{}'", preset.name);
+                    state.status_is_error = false;
+                    load_preset(state, &params, setter, &preset);
+                }
+                Some(Err(error)) => {
+                    state.status_message = error;
+                    state.status_is_error = true;
+                }
+                None => {}
+            }
+
+            // Side panels must be added before the central panel
+            if let Some(code) =
+                state
+                    .timeline
+                    .show(egui_ctx, &params.code_versions, &state.code_buffer)
+            {
+                replace_code(state, code);
+                send_code_update_from_buffer(state);
+            }
+
+            state
+                .status_bar
+                .show(egui_ctx, &telemetry, state.last_error.as_ref());
+
+            // Resize corner; the chosen size is persisted in `EguiState`
+            ResizableWindow::new("glicol-verb-window")
+                .min_size(MIN_WINDOW_SIZE)
+                .show(egui_ctx, params.editor_state.as_ref(), |ui| {
+                    // Styled header
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.heading(
+                            egui::RichText::new("GlicolVerb")
+                                .color(theme::accent())
+                                .strong(),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.toggle_value(&mut state.cheat_sheet.open, "?")
+                                .on_hover_text("Keyboard shortcuts");
+                            ui.toggle_value(&mut state.timeline.open, "History")
+                                .on_hover_text("Show every applied code version");
+                            ui.toggle_value(&mut state.focus_mode, "Focus")
+                                .on_hover_text("Full-screen code with a larger font (F11)");
+                            let mut tuner_open = state.tuner.open;
+                            if ui
+                                .toggle_value(&mut tuner_open, "Tuner")
+                                .on_hover_text("Full-window tuner on the input")
+                                .changed()
+                            {
+                                if tuner_open {
+                                    state.tuner.open = true;
+                                } else {
+                                    state.tuner.close(&state.code_sender);
+                                }
+                            }
+                            if let Some((message, is_error)) = record_button(
+                                ui,
+                                &recorder,
+                                &params.recorder,
+                                telemetry.sample_rate.load(),
+                            ) {
+                                state.status_message = message;
+                                state.status_is_error = is_error;
+                            }
+                            ui.add_space(12.0);
+                            if let Some(snapshot) = ab_buttons(ui, &params, &state.code_buffer) {
+                                load_preset(state, &params, setter, &snapshot);
+                            }
+                            ui.add_space(12.0);
+                            state.meters.show(ui, &telemetry);
+                        });
+                    });
+                    ui.add_space(8.0);
+
+                    egui::ScrollArea::vertical()
+                        .auto_shrink(false)
+                        .show(ui, |ui| {
+                            if state.tuner.open {
+                                telemetry.taps_enabled.store(true, Ordering::Relaxed);
+                                state.tuner.show(ui, &telemetry, &state.code_sender);
+                                return;
+                            }
+                            if !state.focus_mode {
+                                module_strip(ui, &params, setter);
+                                ui.add_space(6.0);
+                            }
+
+                            // Two columns side by side, or stacked when the window is narrow
+                            let available_width = ui.available_width();
+                            let stacked = available_width < STACK_COLUMNS_BELOW;
+                            let (left_width, right_width, columns_layout) = if stacked || state.focus_mode {
+                                (
+                                    available_width - 8.0,
+                                    available_width - 8.0,
+                                    egui::Layout::top_down(egui::Align::Min),
+                                )
+                            } else {
+                                let left_width = (available_width * 0.25).clamp(220.0, 320.0);
+                                (
+                                    left_width,
+                                    available_width - left_width - 40.0, // spacing + right margin
+                                    egui::Layout::left_to_right(egui::Align::Min),
+                                )
+                            };
+
+                            ui.with_layout(columns_layout, |ui| {
+                                if !state.focus_mode {
+                                    // === LEFT: Controls (fixed width) ===
+                                    ui.vertical(|ui| {
+                                        ui.set_width(left_width);
+                                        egui::Frame::new()
+                                            .fill(theme::bg_section())
+                                            .corner_radius(egui::CornerRadius::same(6))
+                                            .inner_margin(egui::Margin::same(10))
+                                            .show(ui, |ui| {
+                                                // === GLICOL PARAMETERS ===
+                                                ui.label(
+                                                    egui::RichText::new("GLICOL").color(theme::accent()).strong(),
+                                                );
+                                                ui.label(
+                                                    egui::RichText::new("Use in code")
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                );
+                                                ui.add_space(4.0);
+                                                // Right-click any of these for value entry and MIDI learn
+                                                let names = state.knob_names.read().clone();
+                                                let glicol_sliders = [
+                                                    (&params.glicol.drive, 1.0..=10.0, "~drive".to_string(), "drive"),
+                                                    (&params.glicol.rate, 0.1..=20.0, "~rate".to_string(), "rate"),
+                                                    (&params.glicol.mix, 0.0..=1.0, "~mix".to_string(), "mix"),
+                                                    (&params.glicol.feedback, 0.0..=0.95, "~feedback".to_string(), "feedback"),
+                                                    (&params.glicol.knob1, 0.0..=1.0, names.label(0), "knob1"),
+                                                    (&params.glicol.knob2, 0.0..=1.0, names.label(1), "knob2"),
+                                                    (&params.glicol.knob3, 0.0..=1.0, names.label(2), "knob3"),
+                                                    (&params.glicol.knob4, 0.0..=1.0, names.label(3), "knob4"),
+                                                    (&params.glicol.knob5, 0.0..=1.0, names.label(4), "knob5"),
+                                                    (&params.glicol.knob6, 0.0..=1.0, names.label(5), "knob6"),
+                                                    (&params.glicol.knob7, 0.0..=1.0, names.label(6), "knob7"),
+                                                    (&params.glicol.knob8, 0.0..=1.0, names.label(7), "knob8"),
+                                                    (&params.glicol.expr, 0.0..=1.0, "~expr".to_string(), "expr"),
+                                                ];
+                                                for (param, range, label, param_id) in glicol_sliders {
+                                                    let learn = LearnTarget {
+                                                        sender: &state.code_sender,
+                                                        learning: &mut state.midi_learning,
+                                                        map: &params.midi_map,
+                                                        param_id,
+                                                    };
+                                                    param_slider!(ui, setter, param, range, label.as_str(), Some(learn));
+                                                }
+
+                                                egui::CollapsingHeader::new(
+                                                    egui::RichText::new("Knob names").color(theme::text_dim()).small(),
+                                                )
+                                                .id_salt("knob_names")
+                                                .default_open(false)
+                                                .show(ui, |ui| knob_names_section(ui, &state.knob_names));
+
+                                                ui.add_space(12.0);
+                                                ui.separator();
+                                                ui.add_space(8.0);
+
+                                                // === CORE ===
+                                                ui.label(
+                                                    egui::RichText::new("CORE")
+                                                        .color(theme::text_normal())
+                                                        .strong(),
+                                                );
+                                                ui.add_space(4.0);
+                                                param_slider!(ui, setter, &params.core.dry_wet, 0.0..=1.0, "Dry/Wet");
+                                                param_slider!(ui, setter, &params.core.width, 0.0..=2.0, "Width");
+                                                let morph_ready = {
+                                                    let snapshots = params.ab_snapshots.read();
+                                                    snapshots.a.is_some() && snapshots.b.is_some()
+                                                };
+                                                param_slider!(ui, setter, &params.core.morph, 0.0..=1.0, "Morph").on_hover_text(if morph_ready {
+                                                    "Blend parameters and code toward the other A/B snapshot"
+                                                } else {
+                                                    "Store both A and B snapshots to morph between them"
+                                                });
+                                                let mut latency_match = params.core.dry_latency_match.value();
+                                                if ui
+                                                    .checkbox(&mut latency_match, "Align dry with wet")
+                                                    .on_hover_text(format!(
+                                                        "Delay the dry signal by the wet latency ({} samples) so low mix settings don't comb filter",
+                                                        telemetry.latency_samples.load(Ordering::Relaxed)
+                                                    ))
+                                                    .changed()
+                                                {
+                                                    setter.begin_set_parameter(&params.core.dry_latency_match);
+                                                    setter.set_parameter(&params.core.dry_latency_match, latency_match);
+                                                    setter.end_set_parameter(&params.core.dry_latency_match);
+                                                }
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Pad"));
+                                                    enum_combo(ui, setter, &params.core.input_pad, "input_pad");
+                                                    let mut inverted = params.core.phase_invert.value();
+                                                    if ui
+                                                        .checkbox(&mut inverted, "Ø")
+                                                        .on_hover_text("Invert input polarity")
+                                                        .changed()
+                                                    {
+                                                        setter.begin_set_parameter(&params.core.phase_invert);
+                                                        setter.set_parameter(&params.core.phase_invert, inverted);
+                                                        setter.end_set_parameter(&params.core.phase_invert);
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Input"));
+                                                    enum_combo(ui, setter, &params.core.input_source, "input_source");
+                                                })
+                                                .response
+                                                .on_hover_text("Left, Right or Sum feed both channels from one source; Stereo keeps them apart");
+                                                if let Some((message, is_error)) =
+                                                    test_signal_controls(ui, setter, &params, &state.code_sender)
+                                                {
+                                                    state.status_message = message;
+                                                    state.status_is_error = is_error;
+                                                }
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Output"));
+                                                    enum_combo(ui, setter, &params.core.output_routing, "output_routing");
+                                                })
+                                                .response
+                                                .on_hover_text("Wet Only for parallel routing in the host; Difference plays what the patch adds");
+                                                let mut auto_gain = params.core.auto_gain.value();
+                                                if ui
+                                                    .checkbox(&mut auto_gain, "Auto gain")
+                                                    .on_hover_text(format!(
+                                                        "Trim the output to the input's loudness so patch changes don't jump in level (now {:+.1} dB)",
+                                                        telemetry.auto_gain_db.load()
+                                                    ))
+                                                    .changed()
+                                                {
+                                                    setter.begin_set_parameter(&params.core.auto_gain);
+                                                    setter.set_parameter(&params.core.auto_gain, auto_gain);
+                                                    setter.end_set_parameter(&params.core.auto_gain);
+                                                }
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Oversample"));
+                                                    enum_combo(ui, setter, &params.core.oversampling, "oversampling");
+                                                })
+                                                .response
+                                                .on_hover_text(format!(
+                                                    "Run Glicol at a higher rate so distortion aliases less. Costs CPU and adds latency (now {} samples); changing it restarts the engine",
+                                                    telemetry.latency_samples.load(Ordering::Relaxed)
+                                                ));
+
+                                                ui.add_space(12.0);
+                                                ui.separator();
+                                                ui.add_space(8.0);
+
+                                                // === MACROS ===
+                                                ui.label(
+                                                    egui::RichText::new("MACROS")
+                                                        .color(theme::text_normal())
+                                                        .strong(),
+                                                );
+                                                ui.label(
+                                                    egui::RichText::new("One knob, many targets")
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                );
+                                                ui.add_space(4.0);
+                                                let macro_sliders = [(&params.modulation.macro1, "Macro 1", "macro1"), (&params.modulation.macro2, "Macro 2", "macro2")];
+                                                for (index, (param, label, param_id)) in macro_sliders.into_iter().enumerate() {
+                                                    let learn = LearnTarget {
+                                                        sender: &state.code_sender,
+                                                        learning: &mut state.midi_learning,
+                                                        map: &params.midi_map,
+                                                        param_id,
+                                                    };
+                                                    param_slider!(ui, setter, param, 0.0..=1.0, label, Some(learn));
+                                                    let target_count = params.macro_map.read().macros[index].len();
+                                                    egui::CollapsingHeader::new(
+                                                        egui::RichText::new(format!("{} targets ({})", label, target_count))
+                                                            .color(theme::text_dim())
+                                                            .small(),
+                                                    )
+                                                    .id_salt(("macro_targets", index))
+                                                    .default_open(false)
+                                                    .show(ui, |ui| macro_mappings(ui, params, index));
+                                                }
+
+                                                ui.add_space(12.0);
+                                                ui.separator();
+                                                ui.add_space(8.0);
+
+                                                // === MODULATION ===
+                                                ui.label(
+                                                    egui::RichText::new("MODULATION")
+                                                        .color(theme::text_normal())
+                                                        .strong(),
+                                                );
+                                                ui.add_space(4.0);
+                                                let lfo_sliders = [
+                                                    (&params.modulation.lfo1_rate, &params.modulation.lfo1_depth, "LFO 1"),
+                                                    (&params.modulation.lfo2_rate, &params.modulation.lfo2_depth, "LFO 2"),
+                                                ];
+                                                for (index, (rate, depth, name)) in lfo_sliders.into_iter().enumerate() {
+                                                    ui.label(egui::RichText::new(name).color(theme::text_dim()).small());
+                                                    param_slider!(ui, setter, rate, 0.01..=20.0, "Rate");
+                                                    param_slider!(ui, setter, depth, 0.0..=1.0, "Depth");
+                                                    lfo_controls(ui, params, setter, index);
+                                                    ui.add_space(4.0);
+                                                }
+                                                ui.label(egui::RichText::new("Envelope").color(theme::text_dim()).small());
+                                                param_slider!(ui, setter, &params.modulation.env_attack, 0.1..=100.0, "Attack");
+                                                param_slider!(ui, setter, &params.modulation.env_release, 10.0..=2000.0, "Release");
+                                                param_slider!(ui, setter, &params.modulation.env_sensitivity, 0.0..=30.0, "Sensitivity");
+                                                ui.add_space(4.0);
+                                                let route_count = params.mod_matrix.read().routes.len();
+                                                egui::CollapsingHeader::new(
+                                                    egui::RichText::new(format!("Modulation matrix ({})", route_count))
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                )
+                                                .id_salt("mod_matrix")
+                                                .default_open(false)
+                                                .show(ui, |ui| mod_matrix(ui, params));
+
+                                                ui.add_space(12.0);
+                                                ui.separator();
+                                                ui.add_space(8.0);
+
+                                                // === EQ ===
+                                                ui.horizontal(|ui| {
+                                                    ui.label(
+                                                        egui::RichText::new("EQ")
+                                                            .color(theme::text_normal())
+                                                            .strong(),
+                                                    );
+                                                    let bypass_text = if params.eq.bypass.value() {
+                                                        "○"
+                                                    } else {
+                                                        "●"
+                                                    };
+                                                    let bypass_color = if params.eq.bypass.value() {
+                                                        theme::status_bypass()
+                                                    } else {
+                                                        theme::status_active()
+                                                    };
+                                                    let bypass_button = ui
+                                                        .add(egui::Button::new(
+                                                            egui::RichText::new(bypass_text).color(bypass_color),
+                                                        ))
+                                                        .on_hover_text("Toggle EQ bypass (right-click for MIDI learn)");
+                                                    if bypass_button.clicked() {
+                                                        let new_val = !params.eq.bypass.value();
+                                                        setter.begin_set_parameter(&params.eq.bypass);
+                                                        setter.set_parameter(&params.eq.bypass, new_val);
+                                                        setter.end_set_parameter(&params.eq.bypass);
+                                                    }
+                                                    learn_menu(
+                                                        &bypass_button,
+                                                        LearnTarget {
+                                                            sender: &state.code_sender,
+                                                            learning: &mut state.midi_learning,
+                                                            map: &params.midi_map,
+                                                            param_id: "eq_bypass",
+                                                        },
+                                                    );
+                                                    if ui
+                                                        .add(egui::Button::new(
+                                                            egui::RichText::new("Reset")
+                                                                .color(theme::text_dim())
+                                                                .small(),
+                                                        ))
+                                                        .on_hover_text("Reset EQ to flat")
+                                                        .clicked()
+                                                    {
+                                                        // Reset EQ state to defaults
+                                                        state.eq_low_freq = 200.0;
+                                                        state.eq_low_gain = 0.0;
+                                                        state.eq_mid_freq = 1000.0;
+                                                        state.eq_mid_gain = 0.0;
+                                                        state.eq_mid_q = 1.0;
+                                                        state.eq_high_freq = 4000.0;
+                                                        state.eq_high_gain = 0.0;
+
+                                                        // Sync to params
+                                                        setter.begin_set_parameter(&params.eq.low_freq);
+                                                        setter.set_parameter(&params.eq.low_freq, 200.0);
+                                                        setter.end_set_parameter(&params.eq.low_freq);
+
+                                                        setter.begin_set_parameter(&params.eq.low_gain);
+                                                        setter.set_parameter(&params.eq.low_gain, 0.0);
+                                                        setter.end_set_parameter(&params.eq.low_gain);
+
+                                                        setter.begin_set_parameter(&params.eq.mid_freq);
+                                                        setter.set_parameter(&params.eq.mid_freq, 1000.0);
+                                                        setter.end_set_parameter(&params.eq.mid_freq);
+
+                                                        setter.begin_set_parameter(&params.eq.mid_gain);
+                                                        setter.set_parameter(&params.eq.mid_gain, 0.0);
+                                                        setter.end_set_parameter(&params.eq.mid_gain);
+
+                                                        setter.begin_set_parameter(&params.eq.mid_q);
+                                                        setter.set_parameter(&params.eq.mid_q, 1.0);
+                                                        setter.end_set_parameter(&params.eq.mid_q);
+
+                                                        setter.begin_set_parameter(&params.eq.high_freq);
+                                                        setter.set_parameter(&params.eq.high_freq, 4000.0);
+                                                        setter.end_set_parameter(&params.eq.high_freq);
+
+                                                        setter.begin_set_parameter(&params.eq.high_gain);
+                                                        setter.set_parameter(&params.eq.high_gain, 0.0);
+                                                        setter.end_set_parameter(&params.eq.high_gain);
+                                                    }
+                                                });
+                                                ui.add_space(4.0);
+
+                                                // Response curve with draggable band handles
+                                                let mut bands = eq_bands(state, &params);
+                                                let sample_rate = telemetry.sample_rate.load();
+                                                let sample_rate = if sample_rate > 0.0 { sample_rate } else { 44100.0 };
+                                                eq_curve(ui, &params, setter, &mut bands, sample_rate);
+                                                set_eq_bands(state, &bands);
+                                                ui.add_space(4.0);
+
+                                                // Helper to format frequency
+                                                let fmt_freq = |v: f32| -> String {
+                                                    if v >= 1000.0 {
+                                                        format!("{:.1}k", v / 1000.0)
+                                                    } else {
+                                                        format!("{:.0}", v)
+                                                    }
+                                                };
+
+                                                // Low shelf
+                                                eq_band_header(ui, setter, &params.eq.low_enabled, "Low");
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Freq"));
+                                                    let old_val = state.eq_low_freq;
+                                                    let slider =
+                                                        egui::Slider::new(&mut state.eq_low_freq, 20.0..=500.0)
+                                                            .custom_formatter(|v, _| fmt_freq(v as f32));
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_low_freq != old_val {
+                                                        setter.begin_set_parameter(&params.eq.low_freq);
+                                                        setter
+                                                            .set_parameter(&params.eq.low_freq, state.eq_low_freq);
+                                                        setter.end_set_parameter(&params.eq.low_freq);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.low_freq, None) {
+                                                        state.eq_low_freq = params.eq.low_freq.value();
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Gain"));
+                                                    let old_val = state.eq_low_gain;
+                                                    let slider =
+                                                        egui::Slider::new(&mut state.eq_low_gain, -12.0..=12.0)
+                                                            .custom_formatter(|v, _| format!("{:+.1}", v));
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_low_gain != old_val {
+                                                        setter.begin_set_parameter(&params.eq.low_gain);
+                                                        setter
+                                                            .set_parameter(&params.eq.low_gain, state.eq_low_gain);
+                                                        setter.end_set_parameter(&params.eq.low_gain);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.low_gain, None) {
+                                                        state.eq_low_gain = params.eq.low_gain.value();
+                                                    }
+                                                });
+
+                                                ui.add_space(4.0);
+
+                                                // Mid peak
+                                                eq_band_header(ui, setter, &params.eq.mid_enabled, "Mid");
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Freq"));
+                                                    let old_val = state.eq_mid_freq;
+                                                    let slider =
+                                                        egui::Slider::new(&mut state.eq_mid_freq, 200.0..=8000.0)
+                                                            .custom_formatter(|v, _| fmt_freq(v as f32));
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_mid_freq != old_val {
+                                                        setter.begin_set_parameter(&params.eq.mid_freq);
+                                                        setter
+                                                            .set_parameter(&params.eq.mid_freq, state.eq_mid_freq);
+                                                        setter.end_set_parameter(&params.eq.mid_freq);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.mid_freq, None) {
+                                                        state.eq_mid_freq = params.eq.mid_freq.value();
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Gain"));
+                                                    let old_val = state.eq_mid_gain;
+                                                    let slider =
+                                                        egui::Slider::new(&mut state.eq_mid_gain, -12.0..=12.0)
+                                                            .custom_formatter(|v, _| format!("{:+.1}", v));
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_mid_gain != old_val {
+                                                        setter.begin_set_parameter(&params.eq.mid_gain);
+                                                        setter
+                                                            .set_parameter(&params.eq.mid_gain, state.eq_mid_gain);
+                                                        setter.end_set_parameter(&params.eq.mid_gain);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.mid_gain, None) {
+                                                        state.eq_mid_gain = params.eq.mid_gain.value();
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Q"));
+                                                    let old_val = state.eq_mid_q;
+                                                    let slider = egui::Slider::new(&mut state.eq_mid_q, 0.5..=4.0);
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_mid_q != old_val {
+                                                        setter.begin_set_parameter(&params.eq.mid_q);
+                                                        setter.set_parameter(&params.eq.mid_q, state.eq_mid_q);
+                                                        setter.end_set_parameter(&params.eq.mid_q);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.mid_q, None) {
+                                                        state.eq_mid_q = params.eq.mid_q.value();
+                                                    }
+                                                });
+
+                                                ui.add_space(4.0);
+
+                                                // High shelf
+                                                eq_band_header(ui, setter, &params.eq.high_enabled, "High");
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Freq"));
+                                                    let old_val = state.eq_high_freq;
+                                                    let slider = egui::Slider::new(
+                                                        &mut state.eq_high_freq,
+                                                        2000.0..=20000.0,
+                                                    )
+                                                    .custom_formatter(|v, _| fmt_freq(v as f32));
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_high_freq != old_val {
+                                                        setter.begin_set_parameter(&params.eq.high_freq);
+                                                        setter.set_parameter(
+                                                            &params.eq.high_freq,
+                                                            state.eq_high_freq,
+                                                        );
+                                                        setter.end_set_parameter(&params.eq.high_freq);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.high_freq, None) {
+                                                        state.eq_high_freq = params.eq.high_freq.value();
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.add_sized([70.0, 18.0], egui::Label::new("Gain"));
+                                                    let old_val = state.eq_high_gain;
+                                                    let slider =
+                                                        egui::Slider::new(&mut state.eq_high_gain, -12.0..=12.0)
+                                                            .custom_formatter(|v, _| format!("{:+.1}", v));
+                                                    let response = ui.add(slider);
+                                                    if response.changed() && state.eq_high_gain != old_val {
+                                                        setter.begin_set_parameter(&params.eq.high_gain);
+                                                        setter.set_parameter(
+                                                            &params.eq.high_gain,
+                                                            state.eq_high_gain,
+                                                        );
+                                                        setter.end_set_parameter(&params.eq.high_gain);
+                                                    }
+                                                    if param_menu(&response, setter, &params.eq.high_gain, None) {
+                                                        state.eq_high_gain = params.eq.high_gain.value();
+                                                    }
+                                                });
+                                            });
+                                    });
+
+                                    ui.add_space(8.0);
+                                }
+
+                                // === RIGHT: Code Editor + Effects Lab (takes remaining space) ===
+                                ui.vertical(|ui| {
+                                    ui.set_width(right_width);
+                                    // Glicol editor panel with accent styling
+                                    egui::Frame::new()
+                                        .fill(theme::bg_section())
+                                        .corner_radius(egui::CornerRadius::same(6))
+                                        .inner_margin(egui::Margin::same(12))
+                                        .stroke(egui::Stroke::new(1.0, theme::accent_dim()))
+                                        .show(ui, |ui| {
+                                            // Running code differs from the buffer until Update succeeds
+                                            let running_code = params.code.read().clone();
+                                            let unapplied = running_code != state.code_buffer;
+
+                                            // Header
+                                            ui.horizontal(|ui| {
+                                                ui.label(
+                                                    egui::RichText::new("Glicol Code")
+                                                        .color(theme::accent())
+                                                        .strong()
+                                                        .size(16.0),
+                                                );
+                                                ui.with_layout(
+                                                    egui::Layout::right_to_left(egui::Align::Center),
+                                                    |ui| {
+                                                        let update = if unapplied {
+                                                            egui::Button::new(egui::RichText::new("Update ●").color(theme::knob_indicator()))
+                                                        } else {
+                                                            egui::Button::new("Update")
+                                                        };
+                                                        let update_hint = if unapplied {
+                                                            "Unapplied changes: the running code differs from the editor (Ctrl+Enter)"
+                                                        } else {
+                                                            "The editor matches the running code"
+                                                        };
+                                                        if ui.add(update).on_hover_text(update_hint).clicked() {
+                                                            send_code_update_from_buffer(state);
+                                                        }
+                                                        if unapplied {
+                                                            ui.toggle_value(&mut state.show_diff, "Diff")
+                                                                .on_hover_text("Compare the editor with the running code");
+                                                        }
+                                                        ui.toggle_value(&mut state.show_injected, "Injected")
+                                                            .on_hover_text("Show the code as sent to the engine, with parameter lines");
+                                                        ui.toggle_value(&mut state.show_graph, "Graph")
+                                                            .on_hover_text("Show how the running chains and ~references connect");
+                                                        if ui
+                                                            .add(egui::Button::new(
+                                                                egui::RichText::new("Panic")
+                                                                    .color(theme::status_error())
+                                                                    .strong(),
+                                                            ))
+                                                            .on_hover_text(
+                                                                "Fade the effect to silence and clear buffers (Ctrl+.)",
+                                                            )
+                                                            .clicked()
+                                                        {
+                                                            send_panic(state);
+                                                        }
+                                                        ui.checkbox(&mut state.panic_revert, "")
+                                                            .on_hover_text("Panic also reverts code to passthrough");
+                                                        let mut safe_mode = params.safe_mode.value();
+                                                        if ui
+                                                            .toggle_value(&mut safe_mode, "Safe")
+                                                            .on_hover_text("Safe mode: clamp feedback and drive, and limit the output")
+                                                            .changed()
+                                                        {
+                                                            setter.begin_set_parameter(&params.safe_mode);
+                                                            setter.set_parameter(&params.safe_mode, safe_mode);
+                                                            setter.end_set_parameter(&params.safe_mode);
+                                                        }
+                                                        if ui
+                                                            .button("Reset")
+                                                            .on_hover_text("Reset to passthrough (no effects)")
+                                                            .clicked()
+                                                        {
+                                                            replace_code(state, "out: ~input".to_string());
+                                                            send_code_update_from_buffer(state);
+                                                        }
+                                                        if ui
+                                                            .button("Format")
+                                                            .on_hover_text("Tidy whitespace and wrap long chains (Ctrl+Shift+F)")
+                                                            .clicked()
+                                                        {
+                                                            format_buffer(state);
+                                                        }
+                                                        let mutate_button = ui
+                                                            .button("🎲 Mutate")
+                                                            .on_hover_text("Randomly vary the numbers in the code (Ctrl+Z undoes, right-click for options)");
+                                                        mutate_button.context_menu(|ui| {
+                                                            ui.add(
+                                                                egui::Slider::new(&mut state.mutate_amount, 0.05..=1.0)
+                                                                    .text("amount")
+                                                                    .custom_formatter(|value, _| format!("±{:.0}%", value * 100.0)),
+                                                            );
+                                                            ui.checkbox(&mut state.mutate_swap, "Swap compatible nodes");
+                                                        });
+                                                        if mutate_button.clicked() {
+                                                            mutate_code(state);
+                                                        }
+                                                        let randomize_button = ui
+                                                            .button("🎲 Randomize")
+                                                            .on_hover_text("Randomize the unlocked parameters (right-click to lock some)");
+                                                        randomize_button.context_menu(|ui| param_locks_menu(ui, params));
+                                                        if randomize_button.clicked() {
+                                                            randomize(params, setter, &mut Rng::new(time_seed()));
+                                                        }
+                                                        if state.external.is_active() {
+                                                            if ui
+                                                                .button("Stop external")
+                                                                .on_hover_text("Stop applying saves from the external editor")
+                                                                .clicked()
+                                                            {
+                                                                state.external.close();
+                                                            }
+                                                        } else if ui
+                                                            .button("External…")
+                                                            .on_hover_text("Edit in $EDITOR (or the default app); saves are applied live")
+                                                            .clicked()
+                                                        {
+                                                            if let Err(error) = state.external.open(&state.code_buffer) {
+                                                                state.status_message = error;
+                                                                state.status_is_error = true;
+                                                            }
+                                                        }
+                                                        // Status display
+                                                        if state.status_is_error {
+                                                            ui.colored_label(
+                                                                theme::status_error(),
+                                                                &state.status_message,
+                                                            );
+                                                        } else if !state.status_message.is_empty() {
+                                                            ui.colored_label(
+                                                                theme::status_active(),
+                                                                &state.status_message,
+                                                            );
+                                                        }
+                                                    },
+                                                );
+                                            });
+
+                                            ui.add_space(8.0);
+                                            state.scenes.show(ui, &params, setter);
+                                            ui.add_space(4.0);
+                                            if state.find.open {
+                                                if let Some(code) = state.find.show(ui, &state.code_buffer) {
+                                                    replace_code(state, code);
+                                                }
+                                                ui.add_space(4.0);
+                                            }
+
+                                            // Code editor - compact but functional
+                                            let editor_id = egui::Id::new(CODE_EDITOR_ID);
+                                            let force_complete = state.autocomplete.handle_keys(
+                                                ui,
+                                                &mut state.code_buffer,
+                                                editor_id,
+                                            );
+                                            placeholders::handle_tab(ui, &state.code_buffer, editor_id);
+                                            let vim_mode = params.view.read().vim_mode;
+                                            if vim_mode
+                                                && state.vim.handle_keys(ui, &mut state.code_buffer, editor_id)
+                                            {
+                                                state.last_edit_time = Some(ui.input(|i| i.time));
+                                            }
+                                            let error_line =
+                                                state.last_error.as_ref().and_then(|error| error.line);
+                                            let search = state.find.search();
+                                            let knob_names = state.knob_names.read().clone();
+                                            let undefined: Vec<String> = preprocess(&state.code_buffer, &state.snippets.read())
+                                                .map(|expanded| {
+                                                    undefined_references(&expanded, &knob_names.aliases())
+                                                        .into_iter()
+                                                        .map(|span| span.name)
+                                                        .collect()
+                                                })
+                                                .unwrap_or_default();
+                                            let highlights = CodeHighlights {
+                                                search: search.as_ref(),
+                                                current_match: state.find.current(),
+                                                cursor: egui::TextEdit::load_state(ui.ctx(), editor_id)
+                                                    .and_then(|text_state| text_state.cursor.char_range())
+                                                    .map(|range| range.primary.index),
+                                                undefined: &undefined,
+                                            };
+                                            let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                                                let job = code_layout_job(ui, text, wrap_width, error_line, &highlights);
+                                                ui.fonts(|fonts| fonts.layout_job(job))
+                                            };
+                                            // Focus mode gives the code the whole window height
+                                            let code_rows = if state.focus_mode {
+                                                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                                                ((ui.ctx().screen_rect().height() - 160.0) / row_height).max(5.0) as usize
+                                            } else {
+                                                5
+                                            };
+                                            let output = egui::TextEdit::multiline(&mut state.code_buffer)
+                                                .font(egui::TextStyle::Monospace)
+                                                .desired_width(f32::INFINITY)
+                                                .desired_rows(code_rows)
+                                                .margin(egui::Margin {
+                                                    left: GUTTER_WIDTH as i8,
+                                                    right: 4,
+                                                    top: 2,
+                                                    bottom: 2,
+                                                })
+                                                .layouter(&mut layouter)
+                                                .id(editor_id)
+                                                .show(ui);
+                                            state.autocomplete.update(
+                                                &output,
+                                                &state.code_buffer,
+                                                force_complete,
+                                                &knob_names.aliases(),
+                                            );
+                                            state.autocomplete.show(
+                                                ui.ctx(),
+                                                &mut state.code_buffer,
+                                                editor_id,
+                                            );
+                                            line_number_gutter(ui, &output, &state.code_buffer, error_line);
+                                            if let Some(error) = &state.last_error {
+                                                error_gutter_marker(ui, &output, &state.code_buffer, error);
+                                            }
+                                            code_hover_docs(ui, &output, &state.code_buffer);
+                                            if vim_mode {
+                                                ui.label(
+                                                    egui::RichText::new(state.vim.mode_label())
+                                                        .monospace()
+                                                        .small()
+                                                        .color(theme::text_dim()),
+                                                );
+                                            }
+                                            if unapplied && state.show_diff {
+                                                ui.label(
+                                                    egui::RichText::new("Running → editor")
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                );
+                                                diff_view(ui, &running_code, &state.code_buffer);
+                                            }
+                                            if state.show_injected {
+                                                injected_view(ui, &params, &state.code_buffer);
+                                            }
+                                            if state.show_graph {
+                                                state.flow_graph.show(ui, &running_code);
+                                            }
+
+                                            // Snapshot for undo once typing pauses
+                                            let now = ui.input(|i| i.time);
+                                            if output.response.changed() {
+                                                state.last_edit_time = Some(now);
+                                            } else if state
+                                                .last_edit_time
+                                                .is_some_and(|t| now - t > IDLE_SNAPSHOT_SECS)
+                                            {
+                                                state.history.snapshot(&state.code_buffer);
+                                                state.last_edit_time = None;
+                                            }
+                                            let response = output.response;
+
+                                            if response.clicked() {
+                                                response.request_focus();
+                                            }
+
+                                            // Last error stays visible until the next successful compile
+                                            if let Some(error) = &state.last_error {
+                                                ui.label(
+                                                    egui::RichText::new(&error.message)
+                                                        .color(theme::status_error())
+                                                        .monospace()
+                                                        .small(),
+                                                );
+                                            }
+
+                                            ui.add_space(8.0);
+
+                                            // Available variables reference
+                                            ui.horizontal_wrapped(|ui| {
+                                                ui.label(
+                                                    egui::RichText::new("Variables:")
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                );
+                                                ui.code("~input");
+                                                ui.code("~drive");
+                                                ui.code("~rate");
+                                                ui.code("~mix");
+                                                ui.code("~feedback");
+                                            });
+                                        });
+
+                                    // The audio thread only feeds the taps while one of these is open
+                                    let mut taps_visible = false;
+                                    if !state.focus_mode {
+                                        ui.add_space(8.0);
+
+                                        // === SPECTRUM / STEREO IMAGE ===
+                                        styled_section(ui, "Spectrum", None, false, |ui| {
+                                            taps_visible = true;
+                                            state.spectrum.show(ui, &telemetry);
+                                        });
+                                        styled_section(ui, "Stereo Image", None, false, |ui| {
+                                            taps_visible = true;
+                                            goniometer(ui, &telemetry);
+                                        });
+
+                                        ui.add_space(8.0);
+
+                                        // === PRESETS ===
+                                        let mut loaded = None;
+                                        styled_section(ui, "Presets", None, false, |ui| {
+                                            loaded = state.preset_panel.show(ui, &params, &state.code_buffer);
+                                        });
+                                        if let Some(preset) = loaded {
+                                            load_preset(state, &params, setter, &preset);
+                                        }
+
+                                        ui.add_space(8.0);
+
+                                        // === MIDI ===
+                                        styled_section(ui, "MIDI", None, false, |ui| {
+                                            midi_section(
+                                                ui,
+                                                &state.code_sender,
+                                                &mut state.midi_learning,
+                                                &params.midi_map,
+                                            );
+                                        });
+
+                                        ui.add_space(8.0);
+
+                                        // === SETTINGS ===
+                                        styled_section(ui, "Settings", None, false, |ui| {
+                                            settings_section(ui, &params);
+                                            ui.separator();
+                                            link_settings(ui, &state.link, &params.link);
+                                            ui.separator();
+                                            if tempo_link.is_available() {
+                                                tempo_link_settings(ui, &tempo_link);
+                                                ui.separator();
+                                            }
+                                            if let Some((message, is_error)) =
+                                                api_settings(ui, &api, &params.api)
+                                            {
+                                                state.status_message = message;
+                                                state.status_is_error = is_error;
+                                            }
+                                        });
+
+                                        ui.add_space(8.0);
+
+                                        // === DIAGNOSTICS ===
+                                        styled_section(ui, "Diagnostics", None, false, |ui| {
+                                            state.diagnostics_panel.show(ui, &telemetry.diagnostics);
+                                        });
+
+                                        ui.add_space(8.0);
+
+                                        // === EFFECTS LAB ===
+                                        styled_section(ui, "Effects Lab", None, true, |ui| {
+                                            ui.add_space(4.0);
+
+                                            // Recipes section - compact chips
+                                            ui.horizontal(|ui| {
+                                                ui.label(
+                                                    egui::RichText::new("RECIPES")
+                                                        .color(theme::text_normal())
+                                                        .small()
+                                                        .strong(),
+                                                );
+                                                ui.label(
+                                                    egui::RichText::new("(click to load, hold to preview)")
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                );
+                                                ui.toggle_value(&mut state.recipes.form_open, "＋ Save")
+                                                    .on_hover_text("Save the current code as a recipe");
+                                                ui.toggle_value(&mut state.recipes.arranging, "Arrange")
+                                                    .on_hover_text("Reorder or hide built-in recipes");
+                                            });
+                                            ui.add_space(4.0);
+
+                                            let mut chips = vec![BUILTIN_RECIPES, USER_RECIPES];
+                                            chips.extend(BUILDING_BLOCKS.iter().map(|category| category.name));
+                                            state.lab_search.show(ui, &chips);
+                                            ui.add_space(4.0);
+
+                                            if state.recipes.form_open {
+                                                state.recipes.show_form(
+                                                    ui,
+                                                    &params.user_recipes,
+                                                    &state.code_buffer,
+                                                );
+                                                ui.add_space(4.0);
+                                            }
+                                            let recipe_names: Vec<&str> =
+                                                RECIPES.iter().map(|recipe| recipe.name).collect();
+                                            if state.recipes.arranging {
+                                                state.recipes.show_arrange(
+                                                    ui,
+                                                    &params.recipe_layout,
+                                                    &recipe_names,
+                                                );
+                                                ui.add_space(4.0);
+                                            }
+
+                                            // Recipe chips in a wrapping grid, user recipes last
+                                            let search = &state.lab_search;
+                                            let order: Vec<usize> =
+                                                ordered_builtins(&params.recipe_layout.read(), &recipe_names, false)
+                                                    .into_iter()
+                                                    .filter(|&index| {
+                                                        let recipe = &RECIPES[index];
+                                                        search.shows_category(BUILTIN_RECIPES)
+                                                            && search.matches(&[
+                                                                recipe.name,
+                                                                recipe.description,
+                                                                recipe.code,
+                                                                recipe.explanation,
+                                                            ])
+                                                    })
+                                                    .collect();
+                                            let user_recipes: Vec<(usize, UserRecipe)> = params
+                                                .user_recipes
+                                                .read()
+                                                .iter()
+                                                .cloned()
+                                                .enumerate()
+                                                .filter(|(_, recipe)| {
+                                                    search.shows_category(USER_RECIPES)
+                                                        && search.matches(&[
+                                                            recipe.name.as_str(),
+                                                            recipe.description.as_str(),
+                                                            recipe.code.as_str(),
+                                                        ])
+                                                })
+                                                .collect();
+                                            if order.is_empty() && user_recipes.is_empty() && state.lab_search.is_active() {
+                                                no_matches(ui);
+                                            }
+                                            ui.horizontal_wrapped(|ui| {
+                                                for index in order {
+                                                    recipe_chip(ui, &RECIPES[index], state);
+                                                }
+                                                for (index, user_recipe) in &user_recipes {
+                                                    let recipe = Recipe {
+                                                        icon: &user_recipe.icon,
+                                                        name: &user_recipe.name,
+                                                        description: &user_recipe.description,
+                                                        code: &user_recipe.code,
+                                                        explanation: "Your recipe (right-click to delete)",
+                                                    };
+                                                    recipe_chip(ui, &recipe, state).context_menu(|ui| {
+                                                        if ui.button("Delete recipe").clicked() {
+                                                            params.user_recipes.write().remove(*index);
+                                                            ui.close_menu();
+                                                        }
+                                                    });
+                                                }
+                                            });
+
+                                            ui.add_space(8.0);
+
+                                            // Building blocks (expanded by default)
+                                            egui::CollapsingHeader::new(
+                                                egui::RichText::new("BUILDING BLOCKS")
+                                                    .color(theme::text_normal())
+                                                    .small()
+                                                    .strong(),
+                                            )
+                                            .default_open(true)
+                                            // Searching shows the matching blocks even when collapsed
+                                            .open(state.lab_search.is_active().then_some(true))
+                                            .show(ui, |ui| {
+                                                ui.label(
+                                                    egui::RichText::new("Click to append to your code")
+                                                        .color(theme::text_dim())
+                                                        .small(),
+                                                );
+                                                building_blocks_section(ui, state);
+                                            });
+
+                                            // User snippets for #include
+                                            egui::CollapsingHeader::new(
+                                                egui::RichText::new("SNIPPETS")
+                                                    .color(theme::text_normal())
+                                                    .small()
+                                                    .strong(),
+                                            )
+                                            .default_open(false)
+                                            .show(ui, |ui| {
+                                                ui.label(
+                                                    egui::RichText::new(
+                                                        "Use #include \"name\" and #define name value ($name)",
+                                                    )
+                                                    .color(theme::text_dim())
+                                                    .small(),
+                                                );
+                                                snippets_section(ui, state);
+                                            });
+
+                                            ui.add_space(4.0);
+                                        });
+                                    }
+                                    telemetry.taps_enabled.store(taps_visible, Ordering::Relaxed);
+                                });
+                            });
+                        });
+                });
+
+            // Overlays
+            state.cheat_sheet.show(egui_ctx, &params.shortcuts);
+        },
+    )
+}
+
+/// Editor state (not persisted)
+struct EditorState {
+    code_sender: Sender<CodeMessage>,
+    status_receiver: Receiver<StatusMessage>,
+    code_buffer: String,      // Local copy for editing
+    last_synced_code: String, // Track what we last synced from params
+    // Snippet library, shared with params for #include
+    snippets: Arc<RwLock<BTreeMap<String, String>>>,
+    // Custom knob names, shared with params for injection
+    knob_names: Arc<RwLock<KnobNames>>,
+    snippet_name: String,
+    panic_revert: bool, // Panic also reverts code to passthrough
+    mutate_amount: f32, // Maximum relative change per mutation
+    mutate_swap: bool,  // Mutation may swap compatible nodes
+    status_message: String,
+    status_is_error: bool,
+    last_error: Option<CompileError>, // Kept until the next successful compile
+    autocomplete: Autocomplete,
+    history: CodeHistory,
+    timeline: Timeline,
+    preset_panel: PresetPanel,
+    diagnostics_panel: DiagnosticsPanel,
+    recipes: RecipeManager,
+    lab_search: LabSearch,
+    meters: Meters,
+    midi_learning: Option<String>, // Parameter armed for MIDI learn
+    audition: Option<egui::Id>,    // Recipe chip being held for a preview
+    spectrum: Spectrum,
+    vim: Vim,
+    external: ExternalEdit,
+    scenes: SceneTabs,
+    /// Link mode membership, for publishing applied code and scene changes
+    link: Arc<LinkHandle>,
+    cheat_sheet: CheatSheet,
+    status_bar: StatusBar,
+    tuner: Tuner,
+    show_diff: bool,     // Show unapplied changes under the editor
+    show_injected: bool, // Show the code as sent to the engine
+    show_graph: bool,    // Show the signal-flow diagram
+    flow_graph: FlowGraph,
+    find: FindBar,
+    focus_mode: bool,            // Distraction-free code view
+    last_edit_time: Option<f64>, // For idle undo snapshots
+    // EQ state - stored locally for immediate UI updates
+    eq_low_freq: f32,
+    eq_low_gain: f32,
+    eq_mid_freq: f32,
+    eq_mid_gain: f32,
+    eq_mid_q: f32,
+    eq_high_freq: f32,
+    eq_high_gain: f32,
+}
+
+/// Refresh the local EQ slider state after params changed underneath the GUI
+fn sync_eq_state(state: &mut EditorState, params: &GlicolVerbParams) {
+    state.eq_low_freq = params.eq.low_freq.modulated_plain_value();
+    state.eq_low_gain = params.eq.low_gain.modulated_plain_value();
+    state.eq_mid_freq = params.eq.mid_freq.modulated_plain_value();
+    state.eq_mid_gain = params.eq.mid_gain.modulated_plain_value();
+    state.eq_mid_q = params.eq.mid_q.modulated_plain_value();
+    state.eq_high_freq = params.eq.high_freq.modulated_plain_value();
+    state.eq_high_gain = params.eq.high_gain.modulated_plain_value();
+}
+
+/// Current EQ band settings from the local slider state
+fn eq_bands(state: &EditorState, params: &GlicolVerbParams) -> EqBands {
+    EqBands {
+        low_freq: state.eq_low_freq,
+        low_gain: state.eq_low_gain,
+        mid_freq: state.eq_mid_freq,
+        mid_gain: state.eq_mid_gain,
+        mid_q: state.eq_mid_q,
+        high_freq: state.eq_high_freq,
+        high_gain: state.eq_high_gain,
+        low_enabled: params.eq.low_enabled.value(),
+        mid_enabled: params.eq.mid_enabled.value(),
+        high_enabled: params.eq.high_enabled.value(),
+    }
+}
+
+/// Band name with a small power button for its enable parameter
+fn eq_band_header(ui: &mut egui::Ui, setter: &ParamSetter, param: &BoolParam, name: &str) {
+    ui.horizontal(|ui| {
+        let enabled = param.value();
+        let (text, color) = if enabled {
+            ("●", theme::status_active())
+        } else {
+            ("○", theme::status_bypass())
+        };
+        let button = egui::Button::new(egui::RichText::new(text).color(color).small()).small();
+        if ui
+            .add(button)
+            .on_hover_text(format!("Switch the {} band on or off", name.to_lowercase()))
+            .clicked()
+        {
+            setter.begin_set_parameter(param);
+            setter.set_parameter(param, !enabled);
+            setter.end_set_parameter(param);
+        }
+        let label_color = if enabled {
+            theme::text_dim()
+        } else {
+            theme::status_bypass()
+        };
+        ui.label(egui::RichText::new(name).color(label_color).small());
+    });
+}
+
+/// Copy band settings changed on the EQ curve back into the slider state
+fn set_eq_bands(state: &mut EditorState, bands: &EqBands) {
+    state.eq_low_freq = bands.low_freq;
+    state.eq_low_gain = bands.low_gain;
+    state.eq_mid_freq = bands.mid_freq;
+    state.eq_mid_gain = bands.mid_gain;
+    state.eq_mid_q = bands.mid_q;
+    state.eq_high_freq = bands.high_freq;
+    state.eq_high_gain = bands.high_gain;
+}
+
+/// Load a preset: set all its parameters, then apply its code
+fn load_preset(
+    state: &mut EditorState,
+    params: &GlicolVerbParams,
+    setter: &ParamSetter,
+    preset: &Preset,
+) {
+    preset.apply_params(params, setter);
+    // A/B snapshots are captured unnamed and aren't presets in their own right
+    if !preset.name.is_empty() {
+        params.module_chain.write().preset = Some(PresetInfo::now(&preset.name));
+    }
+    sync_eq_state(state, params);
+    let differing = preset.merge_snippets(&mut params.snippets.write());
+    if !differing.is_empty() {
+        state.status_message = format!(
+            "Kept your own version of snippet {}",
+            differing
+                .iter()
+                .map(|name| format!("\"{}\"", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        state.status_is_error = false;
+    }
+    replace_code(state, preset.code.clone());
+    send_code_update_from_buffer(state);
+}
+
+/// Replace the whole code buffer, keeping the old code undoable
+fn replace_code(state: &mut EditorState, code: String) {
+    state.history.snapshot(&state.code_buffer);
+    state.code_buffer = code;
+}
+
+/// Reformat the editor code (undoable, not applied)
+fn format_buffer(state: &mut EditorState) {
+    let formatted = format_code(&state.code_buffer);
+    if formatted != state.code_buffer {
+        replace_code(state, formatted);
+    }
+}
+
+/// Seed for the random tools
+fn time_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(1, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Vary the code's numbers (undoable like any other code replacement)
+fn mutate_code(state: &mut EditorState) {
+    let code = mutate(
+        &state.code_buffer,
+        state.mutate_amount as f64,
+        state.mutate_swap,
+        &mut Rng::new(time_seed()),
+    );
+    replace_code(state, code);
+    send_code_update_from_buffer(state);
+}
+
+/// Lock toggles for the randomize button's menu
+fn param_locks_menu(ui: &mut egui::Ui, params: &GlicolVerbParams) {
+    ui.label(
+        egui::RichText::new("Locked parameters keep their value")
+            .color(theme::text_dim())
+            .small(),
+    );
+    egui::ScrollArea::vertical()
+        .max_height(240.0)
+        .show(ui, |ui| {
+            let mut locks = params.param_locks.write();
+            for (id, ptr, _) in params.param_map() {
+                if !is_randomizable(&id) {
+                    continue;
+                }
+                let mut locked = locks.is_locked(&id);
+                // SAFETY: the pointers come from `params`, which outlives the editor
+                let name = unsafe { ptr.name() };
+                if ui.checkbox(&mut locked, name).changed() {
+                    locks.set_locked(&id, locked);
+                }
+            }
+        });
+}
+
+/// Run a command bound to a keyboard shortcut
+fn run_action(
+    action: Action,
+    state: &mut EditorState,
+    params: &GlicolVerbParams,
+    setter: &ParamSetter,
+) {
+    match action {
+        Action::Apply => send_code_update_from_buffer(state),
+        Action::Panic => send_panic(state),
+        Action::ToggleEqBypass => toggle_param(setter, &params.eq.bypass),
+        Action::ToggleDelayBypass => toggle_param(setter, &params.delay.bypass),
+        Action::NextScene => step_scene(params, setter, 1),
+        Action::PreviousScene => step_scene(params, setter, -1),
+        Action::ZoomIn => zoom_view(&params.view, Some(1.0)),
+        Action::ZoomOut => zoom_view(&params.view, Some(-1.0)),
+        Action::ZoomReset => zoom_view(&params.view, None),
+        Action::FocusMode => state.focus_mode = !state.focus_mode,
+        Action::CheatSheet => state.cheat_sheet.open = !state.cheat_sheet.open,
+        Action::Find => state.find.open(false),
+        Action::Replace => state.find.open(true),
+        Action::Format => format_buffer(state),
+    }
+}
+
+fn toggle_param(setter: &ParamSetter, param: &BoolParam) {
+    setter.begin_set_parameter(param);
+    setter.set_parameter(param, !param.value());
+    setter.end_set_parameter(param);
+}
+
+/// Send a panic request to the audio thread
+fn send_panic(state: &mut EditorState) {
+    let revert_code = state.panic_revert;
+    match state
+        .code_sender
+        .try_send(CodeMessage::Panic { revert_code })
+    {
+        Ok(_) => {
+            if revert_code {
+                replace_code(state, "out: ~input".to_string());
+            }
+            state.status_message = "Panic: output hushed".to_string();
+            state.status_is_error = false;
+        }
+        Err(_) => {
+            state.status_message = "Error: Message queue full".to_string();
+            state.status_is_error = true;
+        }
+    }
+}
+
+/// Send code update to the audio thread
+fn send_code_update_from_buffer(state: &mut EditorState) {
+    state.history.snapshot(&state.code_buffer);
+
+    if let Some(placeholder) = first_placeholder(&state.code_buffer) {
+        state.status_message = format!("Fill in {} first (Tab jumps to it)", placeholder);
+        state.status_is_error = true;
+        return;
+    }
+
+    // Expand #include/#define first so validation sees the real code
+    let expanded = match preprocess(&state.code_buffer, &state.snippets.read()) {
+        Ok(expanded) => expanded,
+        Err(error) => {
+            state.status_message = format!("Error: {}", error);
+            state.status_is_error = true;
+            return;
+        }
+    };
+
+    // Catch typos in references before the engine does
+    let knob_names = state.knob_names.read().clone();
+    if let Some(undefined) = undefined_references(&expanded, &knob_names.aliases()).first() {
+        // Point at the usage in the editor (includes may hide it)
+        let line = reference_spans(&state.code_buffer)
+            .into_iter()
+            .find(|span| span.name == undefined.name && !span.definition)
+            .map(|span| span.line);
+        let message = format!("{} is used but never defined", undefined.name);
+        state.status_message = format!("Error: {}", message);
+        state.status_is_error = true;
+        state.last_error = Some(CompileError {
+            message,
+            line,
+            column: None,
+        });
+        return;
+    }
+
+    // Validate the code
+    match validate(&expanded) {
+        Err(error) => {
+            state.status_message = format!("Error: {}", error);
+            state.status_is_error = true;
+        }
+        Ok(warning) => {
+            // Send to audio thread
+            match state
+                .code_sender
+                .try_send(CodeMessage::UpdateCode(state.code_buffer.clone()))
+            {
+                Ok(_) => {
+                    state.link.publish(state.scenes.shown(), &state.code_buffer);
+                    if let Some(warn_msg) = warning {
+                        state.status_message = warn_msg;
+                        state.status_is_error = false; // Warning, not error
+                    } else {
+                        state.status_message = "Code updated!".to_string();
+                        state.status_is_error = false;
+                    }
+                }
+                Err(_) => {
+                    state.status_message = "Error: Message queue full".to_string();
+                    state.status_is_error = true;
+                }
+            }
+        }
+    }
+}
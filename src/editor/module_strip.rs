@@ -0,0 +1,93 @@
+//! Pedalboard strip: the signal path as draggable module cards
+//!
+//! Drag a card by its handle to reorder EQ, Glicol and delay; the order is
+//! persisted and picked up by the audio thread on the next block. Cards
+//! with a bypass parameter get a power toggle.
+
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::dsp::ModuleKind;
+use crate::params::GlicolVerbParams;
+
+pub fn module_strip(ui: &mut egui::Ui, params: &GlicolVerbParams, setter: &ParamSetter) {
+    let order = params.module_chain.read().order;
+    let mut moved = None;
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("IN").color(theme::text_dim()).small());
+        for (index, &kind) in order.0.iter().enumerate() {
+            ui.label(egui::RichText::new("→").color(theme::text_dim()));
+            let card = egui::Frame::new()
+                .fill(theme::card_bg())
+                .corner_radius(egui::CornerRadius::same(4))
+                .inner_margin(egui::Margin::symmetric(6, 3))
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.dnd_drag_source(egui::Id::new(("module_card", index)), index, |ui| {
+                            ui.label(
+                                egui::RichText::new(format!("⠿ {}", kind.label()))
+                                    .color(theme::text_bright()),
+                            );
+                        })
+                        .response
+                        .on_hover_text("Drag to reorder");
+                        if let Some(bypass) = bypass_param(params, kind) {
+                            power_toggle(ui, setter, bypass);
+                        }
+                    });
+                })
+                .response;
+
+            // Highlight the card under a dragged one
+            if card.dnd_hover_payload::<usize>().is_some() {
+                ui.painter().rect_stroke(
+                    card.rect,
+                    4.0,
+                    egui::Stroke::new(1.5, theme::accent()),
+                    egui::StrokeKind::Outside,
+                );
+            }
+            if let Some(from) = card.dnd_release_payload::<usize>() {
+                moved = Some((*from, index));
+            }
+        }
+        ui.label(
+            egui::RichText::new("→ OUT")
+                .color(theme::text_dim())
+                .small(),
+        );
+    });
+
+    if let Some((from, to)) = moved {
+        params.module_chain.write().order.move_module(from, to);
+    }
+}
+
+fn bypass_param(params: &GlicolVerbParams, kind: ModuleKind) -> Option<&BoolParam> {
+    match kind {
+        ModuleKind::Eq => Some(&params.eq.bypass),
+        ModuleKind::Delay => Some(&params.delay.bypass),
+        ModuleKind::Glicol => None,
+    }
+}
+
+fn power_toggle(ui: &mut egui::Ui, setter: &ParamSetter, bypass: &BoolParam) {
+    let active = !bypass.value();
+    let color = if active {
+        theme::status_active()
+    } else {
+        theme::status_bypass()
+    };
+    let hint = if active { "Bypass" } else { "Enable" };
+    if ui
+        .small_button(egui::RichText::new("⏻").color(color))
+        .on_hover_text(hint)
+        .clicked()
+    {
+        setter.begin_set_parameter(bypass);
+        setter.set_parameter(bypass, active);
+        setter.end_set_parameter(bypass);
+    }
+}
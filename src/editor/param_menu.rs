@@ -0,0 +1,79 @@
+//! Reset and value entry for parameter sliders
+//!
+//! Double-click a slider to return it to its default. Right-click to type an
+//! exact value, reset, or (for learnable parameters) arm MIDI learn.
+
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+
+use super::midi_learn::{learn_items, LearnTarget};
+
+/// Attach the double-click reset and context menu to a slider's response
+///
+/// Returns `true` when the parameter was changed from here, so callers that
+/// mirror the value locally know to refresh it.
+pub fn param_menu<P: Param>(
+    response: &egui::Response,
+    setter: &ParamSetter,
+    param: &P,
+    learn: Option<LearnTarget>,
+) -> bool {
+    let mut changed = false;
+
+    if response.double_clicked() {
+        reset(setter, param);
+        changed = true;
+    }
+
+    response.context_menu(|ui| {
+        // Keep the typed text only while the field is being edited, so the
+        // menu always opens on the current value
+        let edit_id = response.id.with("value_entry");
+        let editing = ui.memory(|m| m.has_focus(edit_id));
+        let mut text = if editing {
+            ui.data_mut(|d| d.get_temp::<String>(edit_id))
+                .unwrap_or_else(|| param.to_string())
+        } else {
+            param.to_string()
+        };
+
+        let entry = ui.add(
+            egui::TextEdit::singleline(&mut text)
+                .id(edit_id)
+                .desired_width(90.0)
+                .hint_text("Enter value"),
+        );
+        if entry.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            if let Some(normalized) = param.string_to_normalized_value(&text) {
+                setter.begin_set_parameter(param);
+                setter.set_parameter_normalized(param, normalized);
+                setter.end_set_parameter(param);
+                changed = true;
+                ui.close_menu();
+            }
+            ui.data_mut(|d| d.remove::<String>(edit_id));
+        } else {
+            ui.data_mut(|d| d.insert_temp(edit_id, text));
+        }
+
+        let default = param.normalized_value_to_string(param.default_normalized_value(), true);
+        if ui.button(format!("Reset to {}", default)).clicked() {
+            reset(setter, param);
+            changed = true;
+            ui.close_menu();
+        }
+
+        if let Some(target) = learn {
+            ui.separator();
+            learn_items(ui, target);
+        }
+    });
+
+    changed
+}
+
+fn reset<P: Param>(setter: &ParamSetter, param: &P) {
+    setter.begin_set_parameter(param);
+    setter.set_parameter(param, param.default_plain_value());
+    setter.end_set_parameter(param);
+}
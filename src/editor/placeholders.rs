@@ -0,0 +1,133 @@
+//! Placeholder arguments and tab-stops
+//!
+//! Building blocks insert arguments as `<name>` placeholders. The first one
+//! is selected after inserting, and Tab / Shift+Tab jump between the
+//! placeholders left in the code so typing replaces them one by one.
+
+use nih_plug_egui::egui;
+
+/// Char ranges (start, end) of all `<name>` placeholders in the code
+pub fn placeholder_ranges(code: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        if chars[index] == '<' {
+            let name_len = chars[index + 1..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .count();
+            let end = index + 1 + name_len;
+            if name_len > 0 && chars.get(end) == Some(&'>') {
+                ranges.push((index, end + 1));
+                index = end + 1;
+                continue;
+            }
+        }
+        index += 1;
+    }
+    ranges
+}
+
+/// Name of the first placeholder left in the code
+pub fn first_placeholder(code: &str) -> Option<String> {
+    let (start, end) = *placeholder_ranges(code).first()?;
+    Some(code.chars().skip(start).take(end - start).collect())
+}
+
+/// Select the first placeholder at or after `from` (a char index)
+pub fn select_placeholder_after(ctx: &egui::Context, code: &str, editor_id: egui::Id, from: usize) {
+    let ranges = placeholder_ranges(code);
+    if let Some(&range) = ranges.iter().find(|(start, _)| *start >= from) {
+        select(ctx, editor_id, range);
+    }
+}
+
+/// Tab / Shift+Tab cycle through placeholders while any are left
+///
+/// Runs before the text edit so Tab isn't inserted into the code.
+pub fn handle_tab(ui: &mut egui::Ui, code: &str, editor_id: egui::Id) {
+    if !ui.memory(|m| m.has_focus(editor_id)) {
+        return;
+    }
+    let ranges = placeholder_ranges(code);
+    if ranges.is_empty() {
+        return;
+    }
+
+    let (forward, backward) = ui.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Tab),
+            i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab),
+        )
+    });
+    if !forward && !backward {
+        return;
+    }
+
+    let ctx = ui.ctx().clone();
+    let cursor = egui::TextEdit::load_state(&ctx, editor_id)
+        .and_then(|state| state.cursor.char_range())
+        .map_or(0, |range| range.primary.index.min(range.secondary.index));
+    if let Some(range) = next_placeholder(&ranges, cursor, forward) {
+        select(&ctx, editor_id, range);
+    }
+}
+
+/// The placeholder after (or before) the cursor, wrapping around
+fn next_placeholder(
+    ranges: &[(usize, usize)],
+    cursor: usize,
+    forward: bool,
+) -> Option<(usize, usize)> {
+    if forward {
+        ranges
+            .iter()
+            .find(|(start, _)| *start > cursor)
+            .or(ranges.first())
+            .copied()
+    } else {
+        ranges
+            .iter()
+            .rev()
+            .find(|(start, _)| *start < cursor)
+            .or(ranges.last())
+            .copied()
+    }
+}
+
+fn select(ctx: &egui::Context, editor_id: egui::Id, (start, end): (usize, usize)) {
+    if let Some(mut text_state) = egui::TextEdit::load_state(ctx, editor_id) {
+        text_state
+            .cursor
+            .set_char_range(Some(egui::text::CCursorRange::two(
+                egui::text::CCursor::new(start),
+                egui::text::CCursor::new(end),
+            )));
+        text_state.store(ctx, editor_id);
+    }
+    ctx.memory_mut(|m| m.request_focus(editor_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_ranges() {
+        let code = "out: ~input >> lpf <cutoff> <q> >> mul 0.5 < 3 <>";
+        assert_eq!(placeholder_ranges(code), vec![(19, 27), (28, 31)]);
+        assert_eq!(first_placeholder(code).as_deref(), Some("<cutoff>"));
+        assert_eq!(first_placeholder("out: ~input"), None);
+    }
+
+    #[test]
+    fn test_next_placeholder_wraps() {
+        let ranges = [(5, 10), (12, 15)];
+        assert_eq!(next_placeholder(&ranges, 0, true), Some((5, 10)));
+        assert_eq!(next_placeholder(&ranges, 5, true), Some((12, 15)));
+        assert_eq!(next_placeholder(&ranges, 12, true), Some((5, 10)));
+        assert_eq!(next_placeholder(&ranges, 12, false), Some((5, 10)));
+        assert_eq!(next_placeholder(&ranges, 5, false), Some((12, 15)));
+    }
+}
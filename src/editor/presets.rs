@@ -0,0 +1,257 @@
+//! User preset browser
+//!
+//! Browses the factory bank by category, saves the editor code plus all
+//! parameter values under a name, and lists saved presets with
+//! load/overwrite/delete actions. Presets can be exported to and imported
+//! from `.glicolpreset` files or copied and pasted as patch strings.
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::params::GlicolVerbParams;
+use crate::preset::factory::{CATEGORIES, FACTORY_PRESETS};
+use crate::preset::{file, share, Preset};
+
+/// Preset panel state (lives in `EditorState`)
+#[derive(Default)]
+pub struct PresetPanel {
+    /// Name for the next save
+    name: String,
+    /// Selected factory category (index into `CATEGORIES`)
+    category: usize,
+    /// Result of the last import/export (message, is_error)
+    file_status: Option<(String, bool)>,
+    /// Patch string pasted by the user
+    patch_text: String,
+}
+
+impl PresetPanel {
+    /// Draw the panel; returns a preset the user chose to load
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        params: &GlicolVerbParams,
+        code_buffer: &str,
+    ) -> Option<Preset> {
+        let current = params.module_chain.read().preset.clone();
+        if let Some(current) = current {
+            ui.label(
+                egui::RichText::new(format!("Loaded: {}", current.name))
+                    .color(theme::text_dim())
+                    .small(),
+            );
+        }
+        let mut load = self.factory_bank(ui, params);
+        ui.separator();
+
+        // Save current state under a new (or existing) name
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name)
+                    .hint_text("preset name")
+                    .desired_width(140.0),
+            );
+            let name = self.name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save"))
+                .on_hover_text("Save code and all parameters")
+                .clicked()
+            {
+                save_preset(params, &name, code_buffer);
+                self.name.clear();
+            }
+        });
+
+        // Share patches as files
+        ui.horizontal(|ui| {
+            if ui
+                .button("Export…")
+                .on_hover_text("Save code and parameters to a .glicolpreset file")
+                .clicked()
+            {
+                self.export(params, code_buffer);
+            }
+            if ui
+                .button("Import…")
+                .on_hover_text("Load a .glicolpreset file")
+                .clicked()
+            {
+                if let Some(preset) = self.import() {
+                    load = Some(preset);
+                }
+            }
+        });
+
+        // Share patches as text
+        ui.horizontal(|ui| {
+            if ui
+                .button("Copy patch")
+                .on_hover_text("Copy code and parameters as a patch string")
+                .clicked()
+            {
+                let preset = Preset::capture(self.save_name(), code_buffer, params);
+                ui.ctx().copy_text(share::encode(&preset));
+                self.file_status = Some(("Patch string copied".to_string(), false));
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.patch_text)
+                    .hint_text("paste a patch string")
+                    .desired_width(140.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.patch_text.trim().is_empty(),
+                    egui::Button::new("Paste patch"),
+                )
+                .clicked()
+            {
+                match share::decode(&self.patch_text) {
+                    Ok(preset) => {
+                        self.file_status = Some((format!("Loaded patch '{}'", preset.name), false));
+                        self.patch_text.clear();
+                        load = Some(preset);
+                    }
+                    Err(e) => self.file_status = Some((e, true)),
+                }
+            }
+        });
+        if let Some((message, is_error)) = &self.file_status {
+            let color = if *is_error {
+                theme::status_error()
+            } else {
+                theme::text_dim()
+            };
+            ui.label(egui::RichText::new(message).color(color).small());
+        }
+        ui.add_space(4.0);
+
+        let names: Vec<String> = params
+            .user_presets
+            .read()
+            .iter()
+            .map(|preset| preset.name.clone())
+            .collect();
+        if names.is_empty() {
+            ui.label(
+                egui::RichText::new("No presets saved yet")
+                    .color(theme::text_dim())
+                    .small(),
+            );
+            return load;
+        }
+
+        for (index, name) in names.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .add(egui::Button::new(
+                        egui::RichText::new(name).color(theme::text_bright()),
+                    ))
+                    .on_hover_text("Load preset")
+                    .clicked()
+                {
+                    load = params.user_presets.read().get(index).cloned();
+                }
+                if ui
+                    .small_button("Overwrite")
+                    .on_hover_text("Replace with the current code and parameters")
+                    .clicked()
+                {
+                    save_preset(params, name, code_buffer);
+                }
+                if ui
+                    .small_button("✕")
+                    .on_hover_text("Delete preset")
+                    .clicked()
+                {
+                    params.user_presets.write().remove(index);
+                }
+            });
+        }
+        load
+    }
+
+    /// Name typed for the next save, or "Untitled"
+    fn save_name(&self) -> &str {
+        if self.name.trim().is_empty() {
+            "Untitled"
+        } else {
+            self.name.trim()
+        }
+    }
+
+    /// Ask for a path and write the current patch to it
+    fn export(&mut self, params: &GlicolVerbParams, code_buffer: &str) {
+        let name = self.save_name().to_string();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("GlicolVerb preset", &[file::EXTENSION])
+            .set_file_name(format!("{}.{}", name, file::EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+
+        let preset = Preset::capture(&name, code_buffer, params);
+        self.file_status = Some(match file::save(&preset, &path) {
+            Ok(()) => (format!("Exported '{}'", name), false),
+            Err(e) => (e, true),
+        });
+    }
+
+    /// Ask for a preset file and parse it
+    fn import(&mut self) -> Option<Preset> {
+        let path = rfd::FileDialog::new()
+            .add_filter("GlicolVerb preset", &[file::EXTENSION, "json"])
+            .pick_file()?;
+
+        match file::load(&path) {
+            Ok(preset) => {
+                self.file_status = Some((format!("Imported '{}'", preset.name), false));
+                Some(preset)
+            }
+            Err(e) => {
+                self.file_status = Some((e, true));
+                None
+            }
+        }
+    }
+
+    /// Category tabs plus the factory presets of the selected category
+    fn factory_bank(&mut self, ui: &mut egui::Ui, params: &GlicolVerbParams) -> Option<Preset> {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Factory").color(theme::text_dim()));
+            for (index, category) in CATEGORIES.iter().enumerate() {
+                ui.selectable_value(&mut self.category, index, *category);
+            }
+        });
+
+        let mut load = None;
+        ui.horizontal_wrapped(|ui| {
+            for preset in FACTORY_PRESETS
+                .iter()
+                .filter(|preset| preset.category == CATEGORIES[self.category])
+            {
+                if ui
+                    .button(egui::RichText::new(preset.name).color(theme::text_bright()))
+                    .on_hover_text(preset.code)
+                    .clicked()
+                {
+                    load = Some(preset.to_preset(params));
+                }
+            }
+        });
+        load
+    }
+}
+
+/// Capture the current state into the user preset list, replacing any
+/// preset with the same name
+fn save_preset(params: &GlicolVerbParams, name: &str, code_buffer: &str) {
+    // Save what's in the editor, even if it hasn't been applied yet
+    let preset = Preset::capture(name, code_buffer, params);
+
+    let mut presets = params.user_presets.write();
+    match presets.iter_mut().find(|existing| existing.name == name) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+}
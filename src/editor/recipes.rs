@@ -0,0 +1,168 @@
+//! Custom recipes and built-in recipe arrangement
+//!
+//! "Save as recipe" promotes the current code to a user recipe shown next to
+//! the built-ins. Arrange mode reorders and hides built-ins. Both are
+//! persisted in the plugin state.
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+
+use super::theme;
+use crate::params::{RecipeLayout, UserRecipe};
+
+/// Recipe management state (lives in `EditorState`)
+pub struct RecipeManager {
+    pub form_open: bool,
+    pub arranging: bool,
+    icon: String,
+    name: String,
+    description: String,
+}
+
+impl Default for RecipeManager {
+    fn default() -> Self {
+        Self {
+            form_open: false,
+            arranging: false,
+            icon: "⭐".to_string(),
+            name: String::new(),
+            description: String::new(),
+        }
+    }
+}
+
+impl RecipeManager {
+    /// Form for saving the current code as a recipe
+    pub fn show_form(&mut self, ui: &mut egui::Ui, recipes: &RwLock<Vec<UserRecipe>>, code: &str) {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.icon).desired_width(24.0));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name)
+                    .hint_text("name")
+                    .desired_width(100.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut self.description)
+                    .hint_text("description")
+                    .desired_width(160.0),
+            );
+
+            let name = self.name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save"))
+                .on_hover_text("Save the current code as a recipe")
+                .clicked()
+            {
+                let recipe = UserRecipe {
+                    icon: self.icon.trim().to_string(),
+                    name: name.clone(),
+                    description: self.description.trim().to_string(),
+                    code: code.to_string(),
+                };
+                let mut recipes = recipes.write();
+                match recipes.iter_mut().find(|existing| existing.name == name) {
+                    Some(existing) => *existing = recipe,
+                    None => recipes.push(recipe),
+                }
+                self.name.clear();
+                self.description.clear();
+                self.form_open = false;
+            }
+        });
+    }
+
+    /// List of built-ins with visibility toggles and move buttons
+    pub fn show_arrange(&self, ui: &mut egui::Ui, layout: &RwLock<RecipeLayout>, names: &[&str]) {
+        let order = ordered_builtins(&layout.read(), names, true);
+        let mut new_order = None;
+
+        for (position, &index) in order.iter().enumerate() {
+            let name = names[index];
+            ui.horizontal(|ui| {
+                let mut visible = !layout.read().hidden.iter().any(|hidden| hidden == name);
+                if ui
+                    .checkbox(&mut visible, "")
+                    .on_hover_text("Show recipe")
+                    .changed()
+                {
+                    let mut layout = layout.write();
+                    if visible {
+                        layout.hidden.retain(|hidden| hidden != name);
+                    } else {
+                        layout.hidden.push(name.to_string());
+                    }
+                }
+                if ui
+                    .add_enabled(position > 0, egui::Button::new("⬆").small())
+                    .clicked()
+                {
+                    let mut moved = order.clone();
+                    moved.swap(position, position - 1);
+                    new_order = Some(moved);
+                }
+                if ui
+                    .add_enabled(position + 1 < order.len(), egui::Button::new("⬇").small())
+                    .clicked()
+                {
+                    let mut moved = order.clone();
+                    moved.swap(position, position + 1);
+                    new_order = Some(moved);
+                }
+                ui.label(egui::RichText::new(name).color(theme::text_bright()));
+            });
+        }
+
+        if let Some(moved) = new_order {
+            layout.write().order = moved
+                .iter()
+                .map(|&index| names[index].to_string())
+                .collect();
+        }
+        if ui
+            .small_button("Restore defaults")
+            .on_hover_text("Original order, nothing hidden")
+            .clicked()
+        {
+            *layout.write() = RecipeLayout::default();
+        }
+    }
+}
+
+/// Indices into `names` in display order, optionally skipping hidden ones
+pub fn ordered_builtins(layout: &RecipeLayout, names: &[&str], include_hidden: bool) -> Vec<usize> {
+    let ordered = layout
+        .order
+        .iter()
+        .filter_map(|name| names.iter().position(|known| *known == name.as_str()));
+    let unordered =
+        (0..names.len()).filter(|&index| !layout.order.iter().any(|name| name == names[index]));
+
+    ordered
+        .chain(unordered)
+        .filter(|&index| include_hidden || !layout.hidden.iter().any(|name| name == names[index]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: &[&str] = &["A", "B", "C", "D"];
+
+    #[test]
+    fn test_default_layout_keeps_builtin_order() {
+        let layout = RecipeLayout::default();
+        assert_eq!(ordered_builtins(&layout, NAMES, false), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_order_and_hidden() {
+        let layout = RecipeLayout {
+            order: vec!["C".to_string(), "Gone".to_string(), "A".to_string()],
+            hidden: vec!["A".to_string()],
+        };
+        // Unknown names are skipped, unlisted built-ins come last
+        assert_eq!(ordered_builtins(&layout, NAMES, true), vec![2, 0, 1, 3]);
+        assert_eq!(ordered_builtins(&layout, NAMES, false), vec![2, 1, 3]);
+    }
+}
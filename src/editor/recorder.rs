@@ -0,0 +1,98 @@
+//! Record button and recorder settings
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+use std::path::PathBuf;
+
+use super::theme;
+use crate::recorder::{Recorder, RecorderSettings};
+
+/// Header button starting and stopping a recording
+///
+/// Returns a status line for the editor and whether it is an error.
+pub fn record_button(
+    ui: &mut egui::Ui,
+    recorder: &Recorder,
+    settings: &RwLock<RecorderSettings>,
+    sample_rate: f32,
+) -> Option<(String, bool)> {
+    if recorder.is_recording() {
+        let elapsed = recorder.elapsed() as u32;
+        let text = egui::RichText::new(format!("■ {}:{:02}", elapsed / 60, elapsed % 60))
+            .color(theme::status_error());
+        let dropped = recorder.dropped();
+        let hover = if dropped > 0 {
+            format!(
+                "Stop recording ({} frames dropped, the disk fell behind)",
+                dropped
+            )
+        } else {
+            "Stop recording".to_string()
+        };
+        // Keep the clock moving
+        ui.ctx()
+            .request_repaint_after(std::time::Duration::from_millis(250));
+        if !ui.button(text).on_hover_text(hover).clicked() {
+            return None;
+        }
+        return Some(match recorder.stop() {
+            Ok(()) => ("Recording saved".to_string(), false),
+            Err(e) => (e, true),
+        });
+    }
+
+    if !ui
+        .button("● Rec")
+        .on_hover_text("Record the output to a WAV file")
+        .clicked()
+    {
+        return None;
+    }
+    if sample_rate <= 0.0 {
+        return Some(("Nothing to record until audio is running".to_string(), true));
+    }
+    let mut directory = settings.read().directory.clone();
+    if directory.is_empty() {
+        directory = pick_directory()?.display().to_string();
+        settings.write().directory.clone_from(&directory);
+    }
+    let include_input = settings.read().include_input;
+    Some(
+        match recorder.start(&PathBuf::from(directory), sample_rate, include_input) {
+            Ok(path) => (format!("Recording to {}", path.display()), false),
+            Err(e) => (e, true),
+        },
+    )
+}
+
+/// Recording folder and input capture, for the settings panel
+pub fn recorder_settings(ui: &mut egui::Ui, recorder: &RwLock<RecorderSettings>) {
+    let mut settings = recorder.read().clone();
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Recordings").color(theme::text_normal()));
+        let folder = if settings.directory.is_empty() {
+            "Not set".to_string()
+        } else {
+            settings.directory.clone()
+        };
+        ui.label(egui::RichText::new(folder).small().color(theme::text_dim()));
+        if ui.small_button("Choose…").clicked() {
+            if let Some(path) = pick_directory() {
+                settings.directory = path.display().to_string();
+            }
+        }
+    });
+    ui.checkbox(&mut settings.include_input, "Record the input too")
+        .on_hover_text("Writes the plugin's input to a second file next to the output");
+
+    if settings != *recorder.read() {
+        *recorder.write() = settings;
+    }
+}
+
+fn pick_directory() -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .set_title("Folder for recordings")
+        .pick_folder()
+}
@@ -0,0 +1,105 @@
+//! Scene tabs above the code editor
+//!
+//! Each tab is a named code slot. Clicking a tab sets the automatable
+//! `scene` parameter; the audio thread then loads that scene's code. The
+//! editor buffer follows the parameter, so host automation switches the
+//! visible code too.
+
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::params::{GlicolVerbParams, SceneSlot, SCENE_COUNT};
+
+/// Scene tab state (lives in `EditorState`)
+pub struct SceneTabs {
+    /// Scene whose code is in the editor buffer
+    shown: usize,
+    /// Scene being renamed and its new name
+    renaming: Option<(usize, String)>,
+}
+
+impl SceneTabs {
+    pub fn new(params: &GlicolVerbParams) -> Self {
+        Self {
+            shown: params.scene.value().to_index(),
+            renaming: None,
+        }
+    }
+
+    /// Scene whose code is in the editor buffer
+    pub fn shown(&self) -> usize {
+        self.shown
+    }
+
+    /// Follow the `scene` parameter
+    ///
+    /// Keeps the (possibly unapplied) buffer in the scene being left and
+    /// returns the code to show for the new scene.
+    pub fn sync(&mut self, params: &GlicolVerbParams, code_buffer: &str) -> Option<String> {
+        let scene = params.scene.value().to_index();
+        if scene == self.shown {
+            return None;
+        }
+
+        let mut scenes = params.scenes.write();
+        if let Some(left) = scenes.get_mut(self.shown) {
+            left.code = code_buffer.to_string();
+        }
+        self.shown = scene;
+        scenes.get(scene).map(|scene| scene.code.clone())
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, params: &GlicolVerbParams, setter: &ParamSetter) {
+        ui.horizontal(|ui| {
+            for index in 0..SCENE_COUNT {
+                if let Some((renaming, name)) = &mut self.renaming {
+                    if *renaming == index {
+                        let response = ui.add(egui::TextEdit::singleline(name).desired_width(80.0));
+                        if response.lost_focus() {
+                            let name = name.trim().to_string();
+                            if !name.is_empty() {
+                                if let Some(scene) = params.scenes.write().get_mut(index) {
+                                    scene.name = name;
+                                }
+                            }
+                            self.renaming = None;
+                        } else {
+                            response.request_focus();
+                        }
+                        continue;
+                    }
+                }
+
+                let name = params.scenes.read().get(index).map_or_else(
+                    || format!("Scene {}", index + 1),
+                    |scene| scene.name.clone(),
+                );
+                let label = egui::RichText::new(&name).color(if index == self.shown {
+                    theme::text_bright()
+                } else {
+                    theme::text_dim()
+                });
+                let response = ui
+                    .selectable_label(index == self.shown, label)
+                    .on_hover_text("Switch scene (double-click to rename)");
+                if response.double_clicked() {
+                    self.renaming = Some((index, name));
+                } else if response.clicked() && index != self.shown {
+                    setter.begin_set_parameter(&params.scene);
+                    setter.set_parameter(&params.scene, SceneSlot::from_index(index));
+                    setter.end_set_parameter(&params.scene);
+                }
+            }
+        });
+    }
+}
+
+/// Switch to the scene `offset` tabs away (wrapping)
+pub fn step_scene(params: &GlicolVerbParams, setter: &ParamSetter, offset: isize) {
+    let current = params.scene.value().to_index() as isize;
+    let next = (current + offset).rem_euclid(SCENE_COUNT as isize) as usize;
+    setter.begin_set_parameter(&params.scene);
+    setter.set_parameter(&params.scene, SceneSlot::from_index(next));
+    setter.end_set_parameter(&params.scene);
+}
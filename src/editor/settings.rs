@@ -0,0 +1,158 @@
+//! Editor settings panel (theme, colors, zoom, code font size, key bindings,
+//! recording folder)
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+
+use super::recorder::recorder_settings;
+use super::theme;
+use crate::params::{GlicolVerbParams, ThemeKind, ThemeSettings, ViewSettings};
+
+const MIN_ZOOM: f32 = 0.75;
+const MAX_ZOOM: f32 = 2.0;
+const ZOOM_STEP: f32 = 0.1;
+const MIN_CODE_FONT_SIZE: f32 = 9.0;
+const MAX_CODE_FONT_SIZE: f32 = 28.0;
+/// Code font enlargement in focus mode
+const FOCUS_FONT_SCALE: f32 = 1.5;
+
+pub fn settings_section(ui: &mut egui::Ui, params: &GlicolVerbParams) {
+    theme_settings(ui, &params.theme);
+    ui.separator();
+    view_settings(ui, &params.view);
+    ui.separator();
+    recorder_settings(ui, &params.recorder);
+}
+
+fn theme_settings(ui: &mut egui::Ui, theme_settings: &RwLock<ThemeSettings>) {
+    let mut settings = theme_settings.read().clone();
+    // Theme colors without the user's overrides, shown until a color is picked
+    let palette = theme::Palette::from_settings(&ThemeSettings {
+        kind: settings.kind,
+        ..Default::default()
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Theme").color(theme::text_normal()));
+        ui.selectable_value(&mut settings.kind, ThemeKind::Dark, "Dark");
+        ui.selectable_value(&mut settings.kind, ThemeKind::Light, "Light");
+        ui.selectable_value(&mut settings.kind, ThemeKind::HighContrast, "High contrast");
+    });
+
+    color_row(ui, "Accent", &mut settings.accent, palette.accent());
+    color_row(
+        ui,
+        "Highlight",
+        &mut settings.highlight,
+        palette.knob_indicator(),
+    );
+
+    if settings != *theme_settings.read() {
+        *theme_settings.write() = settings;
+    }
+}
+
+/// Color picker for an optional override, with a reset to the theme color
+fn color_row(
+    ui: &mut egui::Ui,
+    label: &str,
+    color: &mut Option<[u8; 3]>,
+    theme_default: egui::Color32,
+) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new(label).color(theme::text_normal()));
+        let mut rgb = color.unwrap_or([theme_default.r(), theme_default.g(), theme_default.b()]);
+        if ui.color_edit_button_srgb(&mut rgb).changed() {
+            *color = Some(rgb);
+        }
+        if color.is_some() && ui.small_button("Reset").clicked() {
+            *color = None;
+        }
+    });
+}
+
+fn view_settings(ui: &mut egui::Ui, view: &RwLock<ViewSettings>) {
+    let mut settings = view.read().clone();
+
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Zoom").color(theme::text_normal()));
+        ui.add(
+            egui::Slider::new(&mut settings.zoom, MIN_ZOOM..=MAX_ZOOM)
+                .step_by(ZOOM_STEP as f64)
+                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+        )
+        .on_hover_text("Zoom shortcuts are listed in the cheat sheet (?)");
+    });
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("Code font").color(theme::text_normal()));
+        ui.add(
+            egui::Slider::new(
+                &mut settings.code_font_size,
+                MIN_CODE_FONT_SIZE..=MAX_CODE_FONT_SIZE,
+            )
+            .step_by(1.0)
+            .suffix(" pt"),
+        );
+    });
+    ui.checkbox(&mut settings.vim_mode, "Vim keys in the code editor")
+        .on_hover_text("Modal editing: Esc for normal mode, i to insert");
+
+    if settings != *view.read() {
+        *view.write() = settings;
+    }
+}
+
+/// Step the zoom by +1/-1 steps, or reset it with `None`
+pub fn zoom_view(view: &RwLock<ViewSettings>, direction: Option<f32>) {
+    let mut view = view.write();
+    view.zoom = match direction {
+        Some(direction) => step_zoom(view.zoom, direction),
+        None => ViewSettings::default().zoom,
+    };
+}
+
+/// Apply zoom and code font size
+///
+/// egui's own keyboard zoom is disabled so the persisted value (and the
+/// configurable zoom shortcuts) stay in charge. Focus mode enlarges the
+/// code font further.
+pub fn apply_view(ctx: &egui::Context, view: &RwLock<ViewSettings>, focus_mode: bool) {
+    ctx.options_mut(|options| options.zoom_with_keyboard = false);
+
+    let view = view.read().clone();
+    if ctx.zoom_factor() != view.zoom {
+        ctx.set_zoom_factor(view.zoom);
+    }
+    let code_font_size = if focus_mode {
+        view.code_font_size * FOCUS_FONT_SCALE
+    } else {
+        view.code_font_size
+    };
+    let code_font = egui::FontId::monospace(code_font_size);
+    if ctx.style().text_styles.get(&egui::TextStyle::Monospace) != Some(&code_font) {
+        ctx.style_mut(|style| {
+            style
+                .text_styles
+                .insert(egui::TextStyle::Monospace, code_font);
+        });
+    }
+}
+
+/// Next zoom step in `direction` (+1/-1), snapped to the step grid
+fn step_zoom(zoom: f32, direction: f32) -> f32 {
+    let steps = (zoom / ZOOM_STEP).round() + direction;
+    (steps * ZOOM_STEP).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_zoom_snaps_and_clamps() {
+        assert!((step_zoom(1.0, 1.0) - 1.1).abs() < 1e-5);
+        assert!((step_zoom(1.04, -1.0) - 0.9).abs() < 1e-5);
+        assert_eq!(step_zoom(MAX_ZOOM, 1.0), MAX_ZOOM);
+        assert_eq!(step_zoom(MIN_ZOOM, -1.0), MIN_ZOOM);
+    }
+}
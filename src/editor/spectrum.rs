@@ -0,0 +1,134 @@
+//! Spectrum analyzer panel
+//!
+//! FFTs the output tap (and optionally the input tap) published by the audio
+//! thread and draws them on a log-frequency / dB grid.
+
+use nih_plug::util;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::dsp::fft::magnitude_spectrum;
+use crate::telemetry::Telemetry;
+
+const MIN_FREQ: f32 = 20.0;
+const MAX_FREQ: f32 = 20000.0;
+const FLOOR_DB: f32 = -90.0;
+
+/// Falloff of the displayed curve (per frame, in dB)
+const FALL_DB_PER_FRAME: f32 = 1.5;
+
+const PLOT_HEIGHT: f32 = 120.0;
+
+/// Analyzer state (lives in `EditorState`)
+#[derive(Default)]
+pub struct Spectrum {
+    show_input: bool,
+    /// Displayed dB values per plot column, with falloff applied
+    output_curve: Vec<f32>,
+    input_curve: Vec<f32>,
+}
+
+impl Spectrum {
+    pub fn show(&mut self, ui: &mut egui::Ui, telemetry: &Telemetry) {
+        ui.checkbox(&mut self.show_input, "Overlay input");
+
+        let width = ui.available_width();
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(width, PLOT_HEIGHT), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, theme::bg_dark());
+        draw_grid(&painter, rect);
+
+        let sample_rate = telemetry.sample_rate.load().max(1.0);
+        let columns = rect.width().max(1.0) as usize;
+
+        let output: Vec<f32> = telemetry
+            .output_left_tap
+            .snapshot()
+            .iter()
+            .zip(telemetry.output_right_tap.snapshot())
+            .map(|(left, right)| (left + right) * 0.5)
+            .collect();
+        update_curve(&mut self.output_curve, &output, sample_rate, columns);
+        if self.show_input {
+            let input = telemetry.input_tap.snapshot();
+            update_curve(&mut self.input_curve, &input, sample_rate, columns);
+            draw_curve(&painter, rect, &self.input_curve, theme::text_dim());
+        }
+        draw_curve(&painter, rect, &self.output_curve, theme::accent());
+
+        ui.ctx().request_repaint();
+    }
+}
+
+/// Recompute one curve from tap samples, letting it fall back smoothly
+fn update_curve(curve: &mut Vec<f32>, samples: &[f32], sample_rate: f32, columns: usize) {
+    let spectrum = magnitude_spectrum(samples);
+    let bin_hz = sample_rate / (spectrum.len() * 2) as f32;
+    curve.resize(columns, FLOOR_DB);
+
+    for (column, value) in curve.iter_mut().enumerate() {
+        // Bins covered by this column; take the loudest
+        let low = column_to_freq(column as f32 / columns as f32) / bin_hz;
+        let high = column_to_freq((column + 1) as f32 / columns as f32) / bin_hz;
+        let first = (low.floor() as usize).min(spectrum.len() - 1);
+        let last = (high.ceil() as usize).clamp(first + 1, spectrum.len());
+        let magnitude = spectrum[first..last].iter().fold(0.0f32, |a, &b| a.max(b));
+
+        let db = util::gain_to_db(magnitude).max(FLOOR_DB);
+        *value = db.max(*value - FALL_DB_PER_FRAME);
+    }
+}
+
+/// Map 0.0-1.0 across the plot to a frequency on a log scale
+fn column_to_freq(t: f32) -> f32 {
+    MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(t)
+}
+
+/// Inverse of `column_to_freq`
+fn freq_to_column(freq: f32) -> f32 {
+    (freq / MIN_FREQ).ln() / (MAX_FREQ / MIN_FREQ).ln()
+}
+
+fn draw_grid(painter: &egui::Painter, rect: egui::Rect) {
+    let stroke = egui::Stroke::new(1.0, theme::knob_ring());
+    for freq in [100.0, 1000.0, 10000.0] {
+        let x = rect.left() + rect.width() * freq_to_column(freq);
+        painter.line_segment(
+            [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+            stroke,
+        );
+    }
+    for db in [-24.0, -48.0, -72.0] {
+        let y = db_to_y(rect, db);
+        painter.line_segment(
+            [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+            stroke,
+        );
+    }
+}
+
+fn draw_curve(painter: &egui::Painter, rect: egui::Rect, curve: &[f32], color: egui::Color32) {
+    let points: Vec<egui::Pos2> = curve
+        .iter()
+        .enumerate()
+        .map(|(column, &db)| egui::pos2(rect.left() + column as f32, db_to_y(rect, db)))
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
+
+fn db_to_y(rect: egui::Rect, db: f32) -> f32 {
+    rect.top() + rect.height() * (db / FLOOR_DB).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_mapping_roundtrip() {
+        assert!((column_to_freq(0.0) - MIN_FREQ).abs() < 0.01);
+        assert!((column_to_freq(1.0) - MAX_FREQ).abs() < 1.0);
+        assert!((column_to_freq(freq_to_column(1000.0)) - 1000.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,140 @@
+//! Status strip along the bottom of the editor
+//!
+//! Compile status, bridge underruns, processing load, process memory, host
+//! tempo and the block bridge latency. All but the memory are read from
+//! `Telemetry`.
+
+use nih_plug_egui::egui;
+use std::sync::atomic::Ordering;
+
+use super::theme;
+use crate::memory;
+use crate::messages::CompileError;
+use crate::telemetry::Telemetry;
+
+/// How long the underrun counter stays highlighted after it moves
+const UNDERRUN_HIGHLIGHT_SECS: f64 = 2.0;
+
+/// Load above which the CPU readout turns red
+const CPU_WARNING: f32 = 0.7;
+
+/// How often the process memory is read
+const MEMORY_INTERVAL_SECS: f64 = 1.0;
+
+/// Status bar state (lives in `EditorState`)
+#[derive(Default)]
+pub struct StatusBar {
+    last_underruns: u32,
+    last_underrun_time: Option<f64>,
+    memory: Option<u64>,
+    last_memory_time: Option<f64>,
+}
+
+impl StatusBar {
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        telemetry: &Telemetry,
+        last_error: Option<&CompileError>,
+    ) {
+        let now = ctx.input(|i| i.time);
+        let underruns = telemetry.underruns.load(Ordering::Relaxed);
+        if underruns != self.last_underruns {
+            self.last_underruns = underruns;
+            self.last_underrun_time = Some(now);
+        }
+        if self
+            .last_memory_time
+            .map_or(true, |time| now - time >= MEMORY_INTERVAL_SECS)
+        {
+            self.last_memory_time = Some(now);
+            self.memory = memory::resident_bytes();
+        }
+        let recent_underrun = self
+            .last_underrun_time
+            .is_some_and(|time| now - time < UNDERRUN_HIGHLIGHT_SECS);
+
+        egui::TopBottomPanel::bottom("status_bar")
+            .frame(
+                egui::Frame::new()
+                    .fill(theme::bg_dark())
+                    .inner_margin(egui::Margin::symmetric(8, 3)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    match last_error {
+                        Some(error) => {
+                            let text = match error.line {
+                                Some(line) => format!("● Compile error (line {})", line),
+                                None => "● Compile error".to_string(),
+                            };
+                            ui.label(small(&text, theme::status_error()))
+                                .on_hover_text(&error.message);
+                        }
+                        None => {
+                            ui.label(small("● Engine OK", theme::status_active()));
+                        }
+                    }
+                    ui.separator();
+
+                    let underrun_color = if recent_underrun {
+                        theme::status_error()
+                    } else {
+                        theme::text_dim()
+                    };
+                    ui.label(small(&format!("Underruns {}", underruns), underrun_color))
+                        .on_hover_text("Output samples Glicol couldn't deliver in time");
+                    ui.separator();
+
+                    let cpu_load = telemetry.cpu_load.load();
+                    let cpu_color = if cpu_load > CPU_WARNING {
+                        theme::status_error()
+                    } else {
+                        theme::text_dim()
+                    };
+                    ui.label(small(&format!("CPU {:.0}%", cpu_load * 100.0), cpu_color))
+                        .on_hover_text("Time spent processing, as a share of the buffer length");
+                    ui.separator();
+
+                    if let Some(bytes) = self.memory {
+                        let megabytes = bytes as f64 / (1024.0 * 1024.0);
+                        ui.label(small(
+                            &format!("Mem {:.0} MB", megabytes),
+                            theme::text_dim(),
+                        ))
+                        .on_hover_text(
+                            "Resident memory of the whole host process, plugin included",
+                        );
+                        ui.separator();
+                    }
+
+                    let tempo = telemetry.tempo.load();
+                    let tempo_text = if tempo > 0.0 {
+                        format!("{:.1} BPM", tempo)
+                    } else {
+                        "— BPM".to_string()
+                    };
+                    ui.label(small(&tempo_text, theme::text_dim()));
+                    ui.separator();
+
+                    let latency = telemetry.latency_samples.load(Ordering::Relaxed);
+                    let sample_rate = telemetry.sample_rate.load();
+                    let latency_ms = if sample_rate > 0.0 {
+                        latency as f32 / sample_rate * 1000.0
+                    } else {
+                        0.0
+                    };
+                    ui.label(small(
+                        &format!("Latency {} smp ({:.1} ms)", latency, latency_ms),
+                        theme::text_dim(),
+                    ))
+                    .on_hover_text("Latency reported to the host (mostly the Glicol block bridge)");
+                });
+            });
+        ctx.request_repaint_after(std::time::Duration::from_millis(250));
+    }
+}
+
+fn small(text: &str, color: egui::Color32) -> egui::RichText {
+    egui::RichText::new(text).small().color(color)
+}
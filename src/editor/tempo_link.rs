@@ -0,0 +1,41 @@
+//! Ableton Link settings of the standalone app
+
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::tempo_link::TempoLink;
+
+/// Link toggle, session tempo and peer count, for the settings panel
+pub fn tempo_link_settings(ui: &mut egui::Ui, link: &TempoLink) {
+    let mut enabled = link.is_enabled();
+    ui.horizontal(|ui| {
+        if ui
+            .checkbox(&mut enabled, "Ableton Link")
+            .on_hover_text("Follow the tempo of Link-enabled apps on the network")
+            .changed()
+        {
+            link.set_enabled(enabled);
+        }
+        if let Some(tempo) = link.tempo() {
+            let mut bpm = tempo;
+            let response = ui.add(
+                egui::DragValue::new(&mut bpm)
+                    .range(20.0..=999.0)
+                    .speed(0.1)
+                    .fixed_decimals(1)
+                    .suffix(" BPM"),
+            );
+            if response.changed() {
+                link.set_tempo(bpm);
+            }
+        }
+    });
+    if enabled {
+        let text = match link.peers() {
+            0 => "No other Link apps".to_string(),
+            1 => "1 Link app connected".to_string(),
+            n => format!("{} Link apps connected", n),
+        };
+        ui.label(egui::RichText::new(text).small().color(theme::text_dim()));
+    }
+}
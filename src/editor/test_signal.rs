@@ -0,0 +1,92 @@
+//! Test signal controls: source, mix mode, level and the sample to play
+
+use crossbeam_channel::Sender;
+use nih_plug::prelude::*;
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+use std::path::Path;
+
+use super::lfos::enum_combo;
+use super::theme;
+use crate::messages::{CodeMessage, TestSample};
+use crate::params::{GlicolVerbParams, TestSource};
+
+/// Rows for the CORE section
+///
+/// Returns a status line for the editor and whether it is an error.
+pub fn test_signal_controls(
+    ui: &mut egui::Ui,
+    setter: &ParamSetter,
+    params: &GlicolVerbParams,
+    code_sender: &Sender<CodeMessage>,
+) -> Option<(String, bool)> {
+    ui.horizontal(|ui| {
+        ui.add_sized([70.0, 18.0], egui::Label::new("Test"));
+        enum_combo(ui, setter, &params.core.test_signal, "test_signal");
+        enum_combo(ui, setter, &params.core.test_signal_mix, "test_signal_mix");
+    })
+    .response
+    .on_hover_text(
+        "Audition patches without an instrument: Replace plays only the test signal, Mix adds it to the input",
+    );
+
+    let source = params.core.test_signal.value();
+    if source == TestSource::Off {
+        return None;
+    }
+
+    ui.horizontal(|ui| {
+        ui.add_sized([70.0, 18.0], egui::Label::new("Test level"));
+        let level = &params.core.test_signal_level;
+        ui.add(
+            egui::Slider::from_get_set(-40.0..=0.0, |new_value| match new_value {
+                Some(db) => {
+                    setter.begin_set_parameter(level);
+                    setter.set_parameter(level, util::db_to_gain(db as f32));
+                    setter.end_set_parameter(level);
+                    db
+                }
+                None => util::gain_to_db(level.modulated_plain_value()) as f64,
+            })
+            .suffix(" dB"),
+        );
+    });
+
+    if source != TestSource::Sample {
+        return None;
+    }
+    let mut status = None;
+    ui.horizontal(|ui| {
+        ui.add_sized([70.0, 18.0], egui::Label::new(""));
+        let path = params.test_sample.read().clone();
+        let name = Path::new(&path)
+            .file_name()
+            .map_or("No sample".to_string(), |name| {
+                name.to_string_lossy().into_owned()
+            });
+        ui.label(egui::RichText::new(name).small().color(theme::text_dim()))
+            .on_hover_text(&path);
+        if ui.small_button("Load…").clicked() {
+            status = load_sample(&params.test_sample, code_sender);
+        }
+    });
+    status
+}
+
+/// Pick a WAV, decode it here and hand it to the audio thread
+fn load_sample(
+    sample_path: &RwLock<String>,
+    code_sender: &Sender<CodeMessage>,
+) -> Option<(String, bool)> {
+    let path = rfd::FileDialog::new()
+        .add_filter("WAV", &["wav"])
+        .pick_file()?;
+    Some(match TestSample::load(&path) {
+        Ok(sample) => {
+            let _ = code_sender.try_send(CodeMessage::TestSample(Some(sample)));
+            *sample_path.write() = path.display().to_string();
+            (format!("Loaded {}", path.display()), false)
+        }
+        Err(error) => (error, true),
+    })
+}
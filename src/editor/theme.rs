@@ -0,0 +1,256 @@
+//! Color themes
+//!
+//! The active palette is chosen per frame from the persisted
+//! `ThemeSettings`; widgets read it through the accessor functions below
+//! (e.g. `theme::accent()`).
+
+use nih_plug_egui::egui::{self, Color32};
+use std::cell::Cell;
+
+use crate::params::{ThemeKind, ThemeSettings};
+
+/// Every color the editor paints with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    dark_mode: bool,
+
+    // Background colors
+    bg_dark: Color32,
+    bg_panel: Color32,
+    bg_section: Color32,
+    section_stroke: Color32,
+    window_stroke: Color32,
+
+    // Widget fills
+    widget_inactive: Color32,
+    widget_weak: Color32,
+    widget_hovered: Color32,
+    widget_active: Color32,
+
+    // Accent colors
+    accent: Color32,
+    accent_dim: Color32,
+
+    // Knob colors
+    knob_bg: Color32,
+    knob_ring: Color32,
+    knob_indicator: Color32,
+
+    // Text colors
+    text_dim: Color32,
+    text_normal: Color32,
+    text_bright: Color32,
+
+    // Status colors
+    status_active: Color32,
+    status_bypass: Color32,
+    status_error: Color32,
+
+    // Recipe card colors
+    card_bg: Color32,
+}
+
+/// Dark hardware theme (the original look)
+const DARK: Palette = Palette {
+    dark_mode: true,
+    bg_dark: Color32::from_rgb(25, 25, 28),
+    bg_panel: Color32::from_rgb(38, 38, 42),
+    bg_section: Color32::from_rgb(32, 32, 36),
+    section_stroke: Color32::from_rgb(45, 45, 50),
+    window_stroke: Color32::from_rgb(50, 50, 55),
+    widget_inactive: Color32::from_rgb(48, 48, 52),
+    widget_weak: Color32::from_rgb(42, 42, 46),
+    widget_hovered: Color32::from_rgb(58, 58, 64),
+    widget_active: Color32::from_rgb(68, 68, 75),
+    accent: Color32::from_rgb(100, 140, 180),
+    accent_dim: Color32::from_rgb(70, 100, 130),
+    knob_bg: Color32::from_rgb(45, 45, 50),
+    knob_ring: Color32::from_rgb(55, 55, 60),
+    knob_indicator: Color32::from_rgb(220, 140, 80), // Warm orange
+    text_dim: Color32::from_rgb(130, 130, 135),
+    text_normal: Color32::from_rgb(180, 180, 185),
+    text_bright: Color32::from_rgb(220, 220, 225),
+    status_active: Color32::from_rgb(80, 180, 120),
+    status_bypass: Color32::from_rgb(120, 120, 125),
+    status_error: Color32::from_rgb(220, 100, 100),
+    card_bg: Color32::from_rgb(42, 42, 46),
+};
+
+/// Light theme for bright stages
+const LIGHT: Palette = Palette {
+    dark_mode: false,
+    bg_dark: Color32::from_rgb(250, 250, 252),
+    bg_panel: Color32::from_rgb(232, 232, 236),
+    bg_section: Color32::from_rgb(242, 242, 245),
+    section_stroke: Color32::from_rgb(205, 205, 210),
+    window_stroke: Color32::from_rgb(190, 190, 195),
+    widget_inactive: Color32::from_rgb(222, 222, 228),
+    widget_weak: Color32::from_rgb(228, 228, 232),
+    widget_hovered: Color32::from_rgb(208, 208, 216),
+    widget_active: Color32::from_rgb(192, 192, 200),
+    accent: Color32::from_rgb(40, 90, 150),
+    accent_dim: Color32::from_rgb(150, 180, 210),
+    knob_bg: Color32::from_rgb(220, 220, 225),
+    knob_ring: Color32::from_rgb(196, 196, 202),
+    knob_indicator: Color32::from_rgb(200, 100, 30),
+    text_dim: Color32::from_rgb(105, 105, 112),
+    text_normal: Color32::from_rgb(55, 55, 60),
+    text_bright: Color32::from_rgb(20, 20, 25),
+    status_active: Color32::from_rgb(30, 140, 70),
+    status_bypass: Color32::from_rgb(140, 140, 145),
+    status_error: Color32::from_rgb(190, 40, 40),
+    card_bg: Color32::from_rgb(228, 228, 232),
+};
+
+/// Maximum contrast for projectors and poor screens
+const HIGH_CONTRAST: Palette = Palette {
+    dark_mode: true,
+    bg_dark: Color32::from_rgb(0, 0, 0),
+    bg_panel: Color32::from_rgb(0, 0, 0),
+    bg_section: Color32::from_rgb(12, 12, 12),
+    section_stroke: Color32::from_rgb(200, 200, 200),
+    window_stroke: Color32::from_rgb(255, 255, 255),
+    widget_inactive: Color32::from_rgb(24, 24, 24),
+    widget_weak: Color32::from_rgb(16, 16, 16),
+    widget_hovered: Color32::from_rgb(56, 56, 56),
+    widget_active: Color32::from_rgb(88, 88, 88),
+    accent: Color32::from_rgb(255, 210, 0),
+    accent_dim: Color32::from_rgb(150, 120, 0),
+    knob_bg: Color32::from_rgb(30, 30, 30),
+    knob_ring: Color32::from_rgb(200, 200, 200),
+    knob_indicator: Color32::from_rgb(0, 255, 255),
+    text_dim: Color32::from_rgb(210, 210, 210),
+    text_normal: Color32::from_rgb(240, 240, 240),
+    text_bright: Color32::from_rgb(255, 255, 255),
+    status_active: Color32::from_rgb(0, 255, 100),
+    status_bypass: Color32::from_rgb(170, 170, 170),
+    status_error: Color32::from_rgb(255, 80, 80),
+    card_bg: Color32::from_rgb(16, 16, 16),
+};
+
+thread_local! {
+    /// Palette of the editor currently being drawn (GUI thread only)
+    static CURRENT: Cell<Palette> = const { Cell::new(DARK) };
+}
+
+impl Palette {
+    /// Built-in palette with the user's accent overrides applied
+    pub fn from_settings(settings: &ThemeSettings) -> Self {
+        let mut palette = match settings.kind {
+            ThemeKind::Dark => DARK,
+            ThemeKind::Light => LIGHT,
+            ThemeKind::HighContrast => HIGH_CONTRAST,
+        };
+        if let Some([r, g, b]) = settings.accent {
+            palette.accent = Color32::from_rgb(r, g, b);
+            palette.accent_dim = palette.accent.gamma_multiply(0.7);
+        }
+        if let Some([r, g, b]) = settings.highlight {
+            palette.knob_indicator = Color32::from_rgb(r, g, b);
+        }
+        palette
+    }
+
+    pub fn accent(&self) -> Color32 {
+        self.accent
+    }
+
+    pub fn knob_indicator(&self) -> Color32 {
+        self.knob_indicator
+    }
+
+    fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark_mode {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+
+        // Background colors
+        visuals.window_fill = self.bg_dark;
+        visuals.panel_fill = self.bg_panel;
+        visuals.faint_bg_color = self.bg_section;
+        visuals.extreme_bg_color = self.bg_dark;
+
+        // Widget colors
+        visuals.widgets.inactive.bg_fill = self.widget_inactive;
+        visuals.widgets.inactive.fg_stroke = egui::Stroke::new(1.0, self.text_dim);
+        visuals.widgets.inactive.weak_bg_fill = self.widget_weak;
+
+        visuals.widgets.hovered.bg_fill = self.widget_hovered;
+        visuals.widgets.hovered.fg_stroke = egui::Stroke::new(1.0, self.text_normal);
+
+        visuals.widgets.active.bg_fill = self.widget_active;
+        visuals.widgets.active.fg_stroke = egui::Stroke::new(1.0, self.text_bright);
+
+        visuals.widgets.noninteractive.bg_fill = self.bg_section;
+        visuals.widgets.noninteractive.fg_stroke = egui::Stroke::new(1.0, self.text_dim);
+
+        // Selection colors
+        visuals.selection.bg_fill = self.accent_dim;
+        visuals.selection.stroke = egui::Stroke::new(1.0, self.accent);
+
+        // Other visual tweaks
+        visuals.window_stroke = egui::Stroke::new(1.0, self.window_stroke);
+
+        visuals
+    }
+}
+
+/// Make the configured theme current for this frame
+pub fn apply(ctx: &egui::Context, settings: &ThemeSettings) {
+    let palette = Palette::from_settings(settings);
+    CURRENT.with(|current| current.set(palette));
+
+    let visuals = palette.visuals();
+    if ctx.style().visuals != visuals {
+        ctx.set_visuals(visuals);
+    }
+}
+
+macro_rules! palette_colors {
+    ($($name:ident),* $(,)?) => {
+        $(
+            pub fn $name() -> Color32 {
+                CURRENT.with(|current| current.get().$name)
+            }
+        )*
+    };
+}
+
+palette_colors!(
+    bg_dark,
+    bg_panel,
+    bg_section,
+    section_stroke,
+    accent,
+    accent_dim,
+    knob_bg,
+    knob_ring,
+    knob_indicator,
+    text_dim,
+    text_normal,
+    text_bright,
+    status_active,
+    status_bypass,
+    status_error,
+    card_bg,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accent_override() {
+        let settings = ThemeSettings {
+            kind: ThemeKind::Light,
+            accent: Some([10, 20, 30]),
+            highlight: None,
+        };
+        let palette = Palette::from_settings(&settings);
+        assert_eq!(palette.accent, Color32::from_rgb(10, 20, 30));
+        assert_eq!(palette.knob_indicator, LIGHT.knob_indicator);
+        assert!(!palette.dark_mode);
+    }
+}
@@ -0,0 +1,177 @@
+//! Code version timeline
+//!
+//! Side panel listing every successfully applied code version, newest
+//! first, with a diff against the editor buffer and one-click revert.
+
+use nih_plug_egui::egui;
+use parking_lot::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::theme;
+use crate::lang::diff::{diff_lines, DiffLine};
+use crate::params::CodeVersion;
+
+/// Oldest versions are dropped beyond this count
+const MAX_VERSIONS: usize = 200;
+
+/// Timeline panel state (lives in `EditorState`)
+#[derive(Default)]
+pub struct Timeline {
+    pub open: bool,
+    /// Index into the versions list
+    selected: Option<usize>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Record a successfully applied code version (skipped if unchanged)
+pub fn record_version(versions: &RwLock<Vec<CodeVersion>>, code: &str) {
+    let mut versions = versions.write();
+    if versions.last().is_some_and(|v| v.code == code) {
+        return;
+    }
+    versions.push(CodeVersion {
+        timestamp: unix_now(),
+        code: code.to_string(),
+    });
+    if versions.len() > MAX_VERSIONS {
+        let excess = versions.len() - MAX_VERSIONS;
+        versions.drain(..excess);
+    }
+}
+
+/// Human-readable age like "5 min ago"
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86_399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} d ago", seconds / 86_400),
+    }
+}
+
+impl Timeline {
+    /// Draw the panel; returns the code to revert to when requested
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        versions: &RwLock<Vec<CodeVersion>>,
+        current: &str,
+    ) -> Option<String> {
+        if !self.open {
+            return None;
+        }
+
+        let versions = versions.read();
+        let now = unix_now();
+        let mut revert = None;
+
+        egui::SidePanel::right("version_timeline")
+            .resizable(true)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("HISTORY")
+                            .color(theme::accent())
+                            .strong(),
+                    );
+                    ui.label(
+                        egui::RichText::new(format!("{} versions", versions.len()))
+                            .color(theme::text_dim())
+                            .small(),
+                    );
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .id_salt("timeline_list")
+                    .max_height(ui.available_height() * 0.5)
+                    .show(ui, |ui| {
+                        for (index, version) in versions.iter().enumerate().rev() {
+                            let first_line = version.code.lines().next().unwrap_or("");
+                            let text = format!(
+                                "{}  {}",
+                                format_age(now.saturating_sub(version.timestamp)),
+                                first_line
+                            );
+                            let label = egui::RichText::new(text).monospace().size(11.0);
+                            if ui
+                                .selectable_label(self.selected == Some(index), label)
+                                .clicked()
+                            {
+                                self.selected = Some(index);
+                            }
+                        }
+                    });
+
+                let Some(version) = self.selected.and_then(|i| versions.get(i)) else {
+                    return;
+                };
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("Diff vs editor")
+                            .color(theme::text_normal())
+                            .small(),
+                    );
+                    if ui
+                        .button("Revert")
+                        .on_hover_text("Load and apply this version")
+                        .clicked()
+                    {
+                        revert = Some(version.code.clone());
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .id_salt("timeline_diff")
+                    .show(ui, |ui| diff_view(ui, &version.code, current));
+            });
+
+        revert
+    }
+}
+
+/// Render a line diff from `old` to `new` (- removed, + added)
+pub fn diff_view(ui: &mut egui::Ui, old: &str, new: &str) {
+    for line in diff_lines(old, new) {
+        let (prefix, text, color) = match line {
+            DiffLine::Same(text) => (" ", text, theme::text_dim()),
+            DiffLine::Added(text) => ("+", text, theme::status_active()),
+            DiffLine::Removed(text) => ("-", text, theme::status_error()),
+        };
+        ui.label(
+            egui::RichText::new(format!("{} {}", prefix, text))
+                .monospace()
+                .size(11.0)
+                .color(color),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(300), "5 min ago");
+        assert_eq!(format_age(7200), "2 h ago");
+        assert_eq!(format_age(172_800), "2 d ago");
+    }
+
+    #[test]
+    fn test_record_version_skips_duplicates() {
+        let versions = RwLock::new(Vec::new());
+        record_version(&versions, "out: ~input");
+        record_version(&versions, "out: ~input");
+        record_version(&versions, "out: ~input >> mul 0.5");
+        assert_eq!(versions.read().len(), 2);
+    }
+}
@@ -0,0 +1,156 @@
+//! Full-window tuner page
+//!
+//! Detects the pitch of the input tap (after input gain, before the EQ)
+//! and shows the note with a cents needle or a strobe. The output can be
+//! muted while tuning.
+
+use crossbeam_channel::Sender;
+use nih_plug_egui::egui;
+
+use super::theme;
+use crate::dsp::pitch::{detect_pitch, nearest_note};
+use crate::messages::CodeMessage;
+use crate::telemetry::Telemetry;
+
+/// Within this many cents the note counts as in tune
+const IN_TUNE_CENTS: f32 = 3.0;
+
+/// Smoothing of the displayed frequency (0 = frozen, 1 = raw)
+const SMOOTHING: f32 = 0.3;
+
+/// Tuner page state (lives in `EditorState`)
+#[derive(Default)]
+pub struct Tuner {
+    pub open: bool,
+    strobe: bool,
+    mute: bool,
+    /// Displayed frequency, held while the input is silent
+    freq: Option<f32>,
+    strobe_phase: f32,
+}
+
+impl Tuner {
+    /// Close the page, unmuting the output if the tuner muted it
+    pub fn close(&mut self, sender: &Sender<CodeMessage>) {
+        self.open = false;
+        if self.mute {
+            self.mute = false;
+            let _ = sender.try_send(CodeMessage::TunerMute(false));
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, telemetry: &Telemetry, sender: &Sender<CodeMessage>) {
+        let sample_rate = telemetry.sample_rate.load().max(1.0);
+        if let Some(detected) = detect_pitch(&telemetry.input_tap.snapshot(), sample_rate) {
+            self.freq = Some(match self.freq {
+                // Jumps to a new note aren't smoothed
+                Some(freq) if (detected / freq).log2().abs() < 1.0 / 24.0 => {
+                    freq + (detected - freq) * SMOOTHING
+                }
+                _ => detected,
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut self.strobe, "Strobe");
+            if ui.checkbox(&mut self.mute, "Mute output").changed() {
+                let _ = sender.try_send(CodeMessage::TunerMute(self.mute));
+            }
+            if ui.button("Close tuner").clicked() {
+                self.close(sender);
+            }
+        });
+        ui.add_space(12.0);
+
+        let Some(freq) = self.freq else {
+            ui.vertical_centered(|ui| {
+                ui.label(
+                    egui::RichText::new("Play a note")
+                        .size(32.0)
+                        .color(theme::text_dim()),
+                );
+            });
+            ui.ctx().request_repaint();
+            return;
+        };
+
+        let note = nearest_note(freq);
+        let color = if note.cents.abs() <= IN_TUNE_CENTS {
+            theme::status_active()
+        } else {
+            theme::knob_indicator()
+        };
+        ui.vertical_centered(|ui| {
+            ui.label(
+                egui::RichText::new(format!("{}{}", note.name, note.octave))
+                    .size(96.0)
+                    .strong()
+                    .color(color),
+            );
+            ui.label(
+                egui::RichText::new(format!("{:+.1} cents   {:.2} Hz", note.cents, freq))
+                    .size(18.0)
+                    .color(theme::text_normal()),
+            );
+        });
+        ui.add_space(16.0);
+
+        if self.strobe {
+            self.draw_strobe(ui, note.cents);
+        } else {
+            draw_needle(ui, note.cents, color);
+        }
+        ui.ctx().request_repaint();
+    }
+
+    /// Stripes that drift right when sharp, left when flat, and stand still in tune
+    fn draw_strobe(&mut self, ui: &mut egui::Ui, cents: f32) {
+        let dt = ui.input(|i| i.stable_dt).min(0.1);
+        self.strobe_phase = (self.strobe_phase + cents * dt * 2.0).rem_euclid(1.0);
+
+        let width = ui.available_width();
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 60.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, theme::bg_dark());
+        let stripe = 24.0;
+        let offset = self.strobe_phase * stripe * 2.0;
+        let mut x = rect.left() - stripe * 2.0 + offset;
+        while x < rect.right() {
+            let stripe_rect = egui::Rect::from_min_size(
+                egui::pos2(x, rect.top()),
+                egui::vec2(stripe, rect.height()),
+            );
+            painter.rect_filled(stripe_rect, 0.0, theme::accent());
+            x += stripe * 2.0;
+        }
+    }
+}
+
+/// Horizontal -50..+50 cents scale with a needle
+fn draw_needle(ui: &mut egui::Ui, cents: f32, color: egui::Color32) {
+    let width = ui.available_width();
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 60.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, theme::bg_dark());
+
+    for tick in (-50..=50).step_by(10) {
+        let x = rect.center().x + tick as f32 / 50.0 * rect.width() * 0.45;
+        let height = if tick == 0 { 0.5 } else { 0.25 };
+        painter.line_segment(
+            [
+                egui::pos2(x, rect.bottom()),
+                egui::pos2(x, rect.bottom() - rect.height() * height),
+            ],
+            egui::Stroke::new(1.0, theme::knob_ring()),
+        );
+    }
+
+    let x = rect.center().x + cents.clamp(-50.0, 50.0) / 50.0 * rect.width() * 0.45;
+    painter.line_segment(
+        [
+            egui::pos2(x, rect.top() + 4.0),
+            egui::pos2(x, rect.bottom()),
+        ],
+        egui::Stroke::new(4.0, color),
+    );
+}
@@ -0,0 +1,423 @@
+//! Optional vim-style modal editing for the code editor
+//!
+//! Supports normal/insert/visual modes, `hjkl`, `0`/`$`, `w`/`b`,
+//! `i`/`a`/`I`/`A`/`o`/`O`, `x`, `dd`, `yy`, `p`/`P` and visual `d`/`x`/`y`.
+//! Keys are taken from the input queue before the text edit sees them, so
+//! normal mode never types into the buffer.
+
+use nih_plug_egui::egui;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VimMode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+}
+
+/// Escape, as delivered to `Vim::key`
+const ESCAPE: char = '\x1b';
+
+/// Modal editing state (lives in `EditorState`)
+#[derive(Default)]
+pub struct Vim {
+    pub mode: VimMode,
+    /// First key of a two-key command (`d` or `y`)
+    pending: Option<char>,
+    /// Yanked or deleted text
+    register: String,
+    /// Whether the register holds whole lines
+    linewise: bool,
+    /// Char index of the cursor
+    cursor: usize,
+    /// Visual mode start
+    anchor: usize,
+    /// Range last written to the text edit, to notice mouse moves
+    shown: Option<(usize, usize)>,
+}
+
+impl Vim {
+    pub fn mode_label(&self) -> &'static str {
+        match self.mode {
+            VimMode::Normal => "-- NORMAL --",
+            VimMode::Insert => "-- INSERT --",
+            VimMode::Visual => "-- VISUAL --",
+        }
+    }
+
+    /// Take vim keys from the input queue before the text edit runs
+    ///
+    /// Returns true when a command changed the code.
+    pub fn handle_keys(
+        &mut self,
+        ui: &mut egui::Ui,
+        code: &mut String,
+        editor_id: egui::Id,
+    ) -> bool {
+        if !ui.memory(|m| m.has_focus(editor_id)) {
+            return false;
+        }
+        let ctx = ui.ctx().clone();
+        let Some(mut text_state) = egui::TextEdit::load_state(&ctx, editor_id) else {
+            return false;
+        };
+
+        let range = text_state
+            .cursor
+            .char_range()
+            .map(|range| (range.secondary.index, range.primary.index));
+        if range.is_some() && range != self.shown {
+            // Moved with the mouse (or typed in insert mode)
+            self.cursor = range.map_or(0, |(_, primary)| primary);
+            if self.mode == VimMode::Visual {
+                self.mode = VimMode::Normal;
+            }
+        }
+
+        let keys = if self.mode == VimMode::Insert {
+            if !ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+                self.shown = range;
+                return false;
+            }
+            vec![ESCAPE]
+        } else {
+            ui.input_mut(|i| take_normal_keys(&mut i.events))
+        };
+        if keys.is_empty() && self.shown.is_some() {
+            return false;
+        }
+
+        let mut text: Vec<char> = code.chars().collect();
+        let before = text.clone();
+        for key in keys {
+            self.key(key, &mut text);
+        }
+        let changed = text != before;
+        if changed {
+            *code = text.iter().collect();
+        }
+
+        let shown = self.display_range(&text);
+        text_state
+            .cursor
+            .set_char_range(Some(egui::text::CCursorRange::two(
+                egui::text::CCursor::new(shown.0),
+                egui::text::CCursor::new(shown.1),
+            )));
+        text_state.store(&ctx, editor_id);
+        self.shown = Some(shown);
+        changed
+    }
+
+    /// Selection shown for the current mode as (secondary, primary)
+    ///
+    /// Normal mode highlights the character under the cursor as a block.
+    fn display_range(&self, text: &[char]) -> (usize, usize) {
+        match self.mode {
+            VimMode::Insert => (self.cursor, self.cursor),
+            VimMode::Normal if self.cursor < line_end(text, self.cursor) => {
+                (self.cursor + 1, self.cursor)
+            }
+            VimMode::Normal => (self.cursor, self.cursor),
+            VimMode::Visual if self.cursor >= self.anchor => {
+                (self.anchor, (self.cursor + 1).min(text.len()))
+            }
+            VimMode::Visual => ((self.anchor + 1).min(text.len()), self.cursor),
+        }
+    }
+
+    /// Apply one key in the current mode
+    fn key(&mut self, key: char, text: &mut Vec<char>) {
+        self.cursor = self.cursor.min(text.len());
+        let pos = self.cursor;
+        let start = line_start(text, pos);
+        let end = line_end(text, pos);
+
+        if key == ESCAPE {
+            if self.mode == VimMode::Insert && pos > start {
+                self.cursor -= 1;
+            }
+            self.mode = VimMode::Normal;
+            self.pending = None;
+            return;
+        }
+        if self.mode == VimMode::Insert {
+            return;
+        }
+
+        if let Some(first) = self.pending.take() {
+            match (first, key) {
+                ('d', 'd') => self.delete_line(text),
+                ('y', 'y') => {
+                    self.register = text[start..end].iter().collect::<String>() + "\n";
+                    self.linewise = true;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.mode == VimMode::Visual {
+            let (from, to) = (
+                self.anchor.min(pos),
+                (self.anchor.max(pos) + 1).min(text.len()),
+            );
+            match key {
+                'd' | 'x' | 'y' => {
+                    self.register = text[from..to].iter().collect();
+                    self.linewise = false;
+                    if key != 'y' {
+                        text.drain(from..to);
+                    }
+                    self.cursor = from;
+                    self.mode = VimMode::Normal;
+                    return;
+                }
+                'v' => {
+                    self.mode = VimMode::Normal;
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        match key {
+            'h' => self.cursor = pos.saturating_sub(1).max(start),
+            'l' => self.cursor = (pos + 1).min(end.saturating_sub(1).max(start)),
+            'j' if end < text.len() => self.cursor = column_in_line(text, end + 1, pos - start),
+            'k' if start > 0 => {
+                let previous = line_start(text, start - 1);
+                self.cursor = column_in_line(text, previous, pos - start);
+            }
+            '0' => self.cursor = start,
+            '$' => self.cursor = end.saturating_sub(1).max(start),
+            'w' => self.cursor = next_word(text, pos),
+            'b' => self.cursor = previous_word(text, pos),
+            'v' => {
+                self.anchor = pos;
+                self.mode = VimMode::Visual;
+            }
+            'd' | 'y' if self.mode == VimMode::Normal => self.pending = Some(key),
+            'x' if pos < end => {
+                self.register = text.remove(pos).to_string();
+                self.linewise = false;
+                let end = line_end(text, pos);
+                self.cursor = pos.min(end.saturating_sub(1).max(start));
+            }
+            'p' | 'P' if !self.register.is_empty() => self.put(text, key == 'p'),
+            'i' => self.mode = VimMode::Insert,
+            'a' => {
+                self.cursor = (pos + 1).min(end);
+                self.mode = VimMode::Insert;
+            }
+            'I' => {
+                self.cursor = start;
+                self.mode = VimMode::Insert;
+            }
+            'A' => {
+                self.cursor = end;
+                self.mode = VimMode::Insert;
+            }
+            'o' => {
+                text.insert(end, '\n');
+                self.cursor = end + 1;
+                self.mode = VimMode::Insert;
+            }
+            'O' => {
+                text.insert(start, '\n');
+                self.cursor = start;
+                self.mode = VimMode::Insert;
+            }
+            _ => {}
+        }
+    }
+
+    fn delete_line(&mut self, text: &mut Vec<char>) {
+        let start = line_start(text, self.cursor);
+        let end = line_end(text, self.cursor);
+        self.register = text[start..end].iter().collect::<String>() + "\n";
+        self.linewise = true;
+
+        if end < text.len() {
+            text.drain(start..=end);
+        } else if start > 0 {
+            // Last line: take the newline before it instead
+            text.drain(start - 1..end);
+        } else {
+            text.drain(start..end);
+        }
+        self.cursor = line_start(text, start.min(text.len()));
+    }
+
+    /// Paste after (`p`) or before (`P`) the cursor
+    fn put(&mut self, text: &mut Vec<char>, after: bool) {
+        let pos = self.cursor;
+        let register: Vec<char> = self.register.chars().collect();
+        if self.linewise {
+            let end = line_end(text, pos);
+            let at = if !after {
+                line_start(text, pos)
+            } else if end < text.len() {
+                end + 1
+            } else {
+                // No line below yet: start one
+                text.push('\n');
+                text.len()
+            };
+            let mut lines = register;
+            if at == text.len() {
+                lines.pop();
+            }
+            text.splice(at..at, lines);
+            self.cursor = at;
+        } else {
+            let at = if after {
+                (pos + 1).min(line_end(text, pos))
+            } else {
+                pos
+            };
+            let count = register.len();
+            text.splice(at..at, register);
+            self.cursor = at + count - 1;
+        }
+    }
+}
+
+/// Remove normal-mode keys from the input queue, returning them as chars
+///
+/// Unmodified editing keys (Backspace, Enter, ...) and paste/cut are
+/// dropped so they can't change the buffer outside insert mode.
+fn take_normal_keys(events: &mut Vec<egui::Event>) -> Vec<char> {
+    let mut keys = Vec::new();
+    events.retain(|event| match event {
+        egui::Event::Text(text) => {
+            keys.extend(text.chars());
+            false
+        }
+        // Shortcuts like Ctrl+Enter still reach the editor
+        egui::Event::Key { modifiers, .. } if modifiers.command || modifiers.ctrl => true,
+        egui::Event::Key { key, pressed, .. } => {
+            if *key == egui::Key::Escape && *pressed {
+                keys.push(ESCAPE);
+            }
+            !matches!(
+                key,
+                egui::Key::Escape
+                    | egui::Key::Backspace
+                    | egui::Key::Delete
+                    | egui::Key::Enter
+                    | egui::Key::Tab
+            )
+        }
+        egui::Event::Paste(_) | egui::Event::Cut => false,
+        _ => true,
+    });
+    keys
+}
+
+fn line_start(text: &[char], pos: usize) -> usize {
+    text[..pos.min(text.len())]
+        .iter()
+        .rposition(|&c| c == '\n')
+        .map_or(0, |newline| newline + 1)
+}
+
+/// Index of the line's `\n` (or the end of the text)
+fn line_end(text: &[char], pos: usize) -> usize {
+    text[pos.min(text.len())..]
+        .iter()
+        .position(|&c| c == '\n')
+        .map_or(text.len(), |offset| pos + offset)
+}
+
+/// Position at `column` in the line starting at `start`, clamped to its last char
+fn column_in_line(text: &[char], start: usize, column: usize) -> usize {
+    let end = line_end(text, start);
+    (start + column).min(end.saturating_sub(1).max(start))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '~' || c == '.'
+}
+
+fn next_word(text: &[char], pos: usize) -> usize {
+    let mut pos = pos;
+    if pos < text.len() && is_word_char(text[pos]) {
+        while pos < text.len() && is_word_char(text[pos]) {
+            pos += 1;
+        }
+    } else if pos < text.len() {
+        pos += 1;
+    }
+    while pos < text.len() && text[pos].is_whitespace() {
+        pos += 1;
+    }
+    pos.min(text.len().saturating_sub(1))
+}
+
+fn previous_word(text: &[char], pos: usize) -> usize {
+    let mut pos = pos;
+    while pos > 0 && text[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    if pos > 0 && is_word_char(text[pos - 1]) {
+        while pos > 0 && is_word_char(text[pos - 1]) {
+            pos -= 1;
+        }
+    } else {
+        pos = pos.saturating_sub(1);
+    }
+    pos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(vim: &mut Vim, text: &mut Vec<char>, keys: &str) {
+        for key in keys.chars() {
+            vim.key(key, text);
+        }
+    }
+
+    #[test]
+    fn test_motions() {
+        let mut text: Vec<char> = "out: ~input\n~a: sin 2".chars().collect();
+        let mut vim = Vim::default();
+        run(&mut vim, &mut text, "w");
+        assert_eq!(vim.cursor, 3);
+        run(&mut vim, &mut text, "w");
+        assert_eq!(vim.cursor, 5);
+        run(&mut vim, &mut text, "j$");
+        assert_eq!(text[vim.cursor], '2');
+        run(&mut vim, &mut text, "0k");
+        assert_eq!(vim.cursor, 0);
+    }
+
+    #[test]
+    fn test_dd_and_put() {
+        let mut text: Vec<char> = "one\ntwo\nthree".chars().collect();
+        let mut vim = Vim::default();
+        run(&mut vim, &mut text, "ddp");
+        assert_eq!(text.iter().collect::<String>(), "two\none\nthree");
+        run(&mut vim, &mut text, "jdd");
+        assert_eq!(text.iter().collect::<String>(), "two\none");
+        run(&mut vim, &mut text, "kyyjp");
+        assert_eq!(text.iter().collect::<String>(), "two\none\ntwo");
+    }
+
+    #[test]
+    fn test_visual_delete_and_insert() {
+        let mut text: Vec<char> = "mul 0.5".chars().collect();
+        let mut vim = Vim::default();
+        run(&mut vim, &mut text, "vlld");
+        assert_eq!(text.iter().collect::<String>(), " 0.5");
+        assert_eq!(vim.mode, VimMode::Normal);
+
+        run(&mut vim, &mut text, "i");
+        assert_eq!(vim.mode, VimMode::Insert);
+        run(&mut vim, &mut text, "x\x1b");
+        assert_eq!(text.iter().collect::<String>(), " 0.5");
+        assert_eq!(vim.mode, VimMode::Normal);
+    }
+}
@@ -0,0 +1,184 @@
+//! Autocomplete for Glicol code
+//!
+//! Suggests node names for plain words and references for `~words`:
+//! `~input`, the injectable params, and any `~name:` defined in the buffer.
+
+use super::nodes::NODES;
+use crate::engine::PARAM_NAMES;
+
+/// What a completion inserts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompletionKind {
+    Node,
+    Reference,
+}
+
+/// A single completion suggestion
+#[derive(Clone, Debug, PartialEq)]
+pub struct Completion {
+    /// Text inserted in place of the typed prefix
+    pub label: String,
+    /// Short hint shown next to the label
+    pub detail: String,
+    pub kind: CompletionKind,
+}
+
+/// Find the word being typed at `cursor` (a char index)
+///
+/// Returns the char index where the word starts and the word itself.
+/// A leading `~` is part of the word.
+pub fn word_before_cursor(code: &str, cursor: usize) -> (usize, String) {
+    let chars: Vec<char> = code.chars().take(cursor).collect();
+    let mut start = chars.len();
+    while start > 0 && is_word_char(chars[start - 1]) {
+        start -= 1;
+    }
+    if start > 0 && chars[start - 1] == '~' {
+        start -= 1;
+    }
+    (start, chars[start..].iter().collect())
+}
+
+/// Find the word (node name or `~reference`) under `index` (a char index)
+pub fn word_at(code: &str, index: usize) -> Option<String> {
+    let chars: Vec<char> = code.chars().collect();
+    if !chars
+        .get(index)
+        .is_some_and(|&c| is_word_char(c) || c == '~')
+    {
+        return None;
+    }
+
+    let mut start = index;
+    while start > 0 && is_word_char(chars[start - 1]) {
+        start -= 1;
+    }
+    if start > 0 && chars[start - 1] == '~' {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < chars.len() && is_word_char(chars[end]) {
+        end += 1;
+    }
+    Some(chars[start..end].iter().collect())
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// All `~name` references defined in the code (`~name: ...` chains)
+pub fn defined_references(code: &str) -> Vec<&str> {
+    code.lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let name = line.strip_prefix('~')?;
+            let end = name.find(|c: char| !is_word_char(c))?;
+            name[end..]
+                .trim_start()
+                .starts_with(':')
+                .then_some(&line[..end + 1])
+        })
+        .filter(|name| name.len() > 1)
+        .collect()
+}
+
+/// Suggestions for the typed `prefix`, best matches first
+///
+/// `aliases` are the user's knob names, offered like the built-in params.
+pub fn complete(code: &str, prefix: &str, aliases: &[&str]) -> Vec<Completion> {
+    if let Some(name) = prefix.strip_prefix('~') {
+        let mut candidates: Vec<(String, &str)> = vec![("~input".to_string(), "live audio")];
+        for param in PARAM_NAMES {
+            candidates.push((format!("~{}", param), "plugin param"));
+        }
+        for alias in aliases {
+            candidates.push((format!("~{}", alias), "named knob"));
+        }
+        for reference in defined_references(code) {
+            if !candidates.iter().any(|(label, _)| label == reference) {
+                candidates.push((reference.to_string(), "reference"));
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|(label, _)| label[1..].starts_with(name) && label[1..] != *name)
+            .map(|(label, detail)| Completion {
+                label,
+                detail: detail.to_string(),
+                kind: CompletionKind::Reference,
+            })
+            .collect()
+    } else if prefix.is_empty() {
+        Vec::new()
+    } else {
+        NODES
+            .iter()
+            .filter(|node| node.name.starts_with(prefix) && node.name != prefix)
+            .map(|node| Completion {
+                label: node.name.to_string(),
+                detail: node.args.to_string(),
+                kind: CompletionKind::Node,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_before_cursor() {
+        let code = "out: ~input >> lp";
+        let (start, word) = word_before_cursor(code, code.chars().count());
+        assert_eq!(word, "lp");
+        assert_eq!(start, 15);
+
+        let (start, word) = word_before_cursor("out: ~in", 8);
+        assert_eq!(word, "~in");
+        assert_eq!(start, 5);
+    }
+
+    #[test]
+    fn test_word_at() {
+        let code = "out: ~input >> lpf 800.0 0.7";
+        assert_eq!(word_at(code, 16).as_deref(), Some("lpf"));
+        assert_eq!(word_at(code, 7).as_deref(), Some("~input"));
+        assert_eq!(word_at(code, 4), None);
+    }
+
+    #[test]
+    fn test_defined_references() {
+        let code = "out: ~input >> mul ~lfo\n~lfo: sin 2.0\n  ~mod : saw 1.0";
+        assert_eq!(defined_references(code), vec!["~lfo", "~mod"]);
+    }
+
+    #[test]
+    fn test_complete_node_names() {
+        let labels: Vec<String> = complete("", "de", &[])
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert_eq!(labels, vec!["delayms", "delayn"]);
+    }
+
+    #[test]
+    fn test_complete_references() {
+        let code = "out: ~input >> mul ~wobble\n~wobble: sin 2.0";
+        let labels: Vec<String> = complete(code, "~w", &[])
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert_eq!(labels, vec!["~wobble"]);
+
+        let labels: Vec<String> = complete(code, "~", &["shimmer"])
+            .into_iter()
+            .map(|c| c.label)
+            .collect();
+        assert!(labels.contains(&"~input".to_string()));
+        assert!(labels.contains(&"~drive".to_string()));
+        assert!(labels.contains(&"~shimmer".to_string()));
+    }
+}
@@ -0,0 +1,88 @@
+//! Line-based diff between two versions of Glicol code
+
+/// One line of a diff
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffLine<'a> {
+    /// Present in both versions
+    Same(&'a str),
+    /// Only in the new version
+    Added(&'a str),
+    /// Only in the old version
+    Removed(&'a str),
+}
+
+/// Diff `old` against `new` line by line (longest common subsequence)
+///
+/// Patches are a handful of lines, so the quadratic table is fine.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    result.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_code() {
+        let code = "out: ~input\n~lfo: sin 2.0";
+        assert!(diff_lines(code, code)
+            .iter()
+            .all(|line| matches!(line, DiffLine::Same(_))));
+    }
+
+    #[test]
+    fn test_changed_line() {
+        let diff = diff_lines("out: ~input >> mul 0.5", "out: ~input >> mul 0.8");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Removed("out: ~input >> mul 0.5"),
+                DiffLine::Added("out: ~input >> mul 0.8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_added_reference() {
+        let diff = diff_lines("out: ~input", "out: ~input\n~lfo: sin 2.0");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("out: ~input"),
+                DiffLine::Added("~lfo: sin 2.0")
+            ]
+        );
+    }
+}
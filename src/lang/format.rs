@@ -0,0 +1,202 @@
+//! Code formatter
+//!
+//! Normalizes whitespace inside stages, puts one space around `>>`, and
+//! wraps chains that are too long (or carry comments) one stage per line,
+//! with the `>>` continuations aligned under the first stage. Directives
+//! and comment lines are trimmed; anything inside backticks (e.g. `meta`
+//! scripts) is left exactly as written.
+
+/// Chains longer than this are wrapped
+const MAX_LINE: usize = 72;
+
+/// One stage of a chain and the comment that followed it
+struct Stage {
+    text: String,
+    comment: Option<String>,
+}
+
+/// A `name: a >> b` statement being collected
+struct Statement {
+    name: String,
+    stages: Vec<Stage>,
+}
+
+impl Statement {
+    fn push_stages(&mut self, body: &str, comment: Option<String>) {
+        for part in body.split(">>") {
+            let text = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                self.stages.push(Stage {
+                    text,
+                    comment: None,
+                });
+            }
+        }
+        if let Some(comment) = comment {
+            match self.stages.last_mut() {
+                Some(stage) => stage.comment = Some(comment),
+                None => self.stages.push(Stage {
+                    text: String::new(),
+                    comment: Some(comment),
+                }),
+            }
+        }
+    }
+
+    fn render(&self, out: &mut Vec<String>) {
+        let head = format!("{}: ", self.name);
+        let single = self
+            .stages
+            .iter()
+            .map(|stage| stage.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" >> ");
+        let inner_comments = self.stages[..self.stages.len().saturating_sub(1)]
+            .iter()
+            .any(|stage| stage.comment.is_some());
+        let last_comment = self.stages.last().and_then(|stage| stage.comment.as_ref());
+
+        if !inner_comments && head.len() + single.len() <= MAX_LINE {
+            let mut line = format!("{}{}", head, single).trim_end().to_string();
+            if let Some(comment) = last_comment {
+                line = format!("{} {}", line, comment);
+            }
+            out.push(line);
+            return;
+        }
+
+        // `>> ` ends where the first stage starts
+        let indent = " ".repeat(head.len().saturating_sub(3));
+        for (index, stage) in self.stages.iter().enumerate() {
+            let mut line = if index == 0 {
+                format!("{}{}", head, stage.text)
+            } else {
+                format!("{}>> {}", indent, stage.text)
+            };
+            line = line.trim_end().to_string();
+            if let Some(comment) = &stage.comment {
+                line = format!("{} {}", line, comment);
+            }
+            out.push(line);
+        }
+    }
+}
+
+/// Split off a trailing `// comment`
+fn split_comment(line: &str) -> (&str, Option<String>) {
+    match line.find("//") {
+        Some(index) => (&line[..index], Some(line[index..].trim().to_string())),
+        None => (line, None),
+    }
+}
+
+/// Format Glicol code
+pub fn format_code(code: &str) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut current: Option<Statement> = None;
+    let mut in_backticks = false;
+
+    let flush = |current: &mut Option<Statement>, out: &mut Vec<String>| {
+        if let Some(statement) = current.take() {
+            statement.render(out);
+        }
+    };
+
+    for line in code.lines() {
+        if in_backticks || line.contains('`') {
+            flush(&mut current, &mut out);
+            out.push(line.trim_end().to_string());
+            in_backticks ^= line.matches('`').count() % 2 == 1;
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            flush(&mut current, &mut out);
+            // Collapse runs of blank lines
+            if out.last().is_some_and(|last| !last.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+        if trimmed.starts_with('#') || trimmed.starts_with("//") {
+            flush(&mut current, &mut out);
+            out.push(trimmed.to_string());
+            continue;
+        }
+
+        let (body, comment) = split_comment(trimmed);
+        if let Some(rest) = body.trim_start().strip_prefix(">>") {
+            if let Some(statement) = current.as_mut() {
+                statement.push_stages(rest, comment);
+                continue;
+            }
+        }
+        match body.split_once(':') {
+            Some((name, rest)) if !name.trim().is_empty() && !name.contains(">>") => {
+                flush(&mut current, &mut out);
+                let mut statement = Statement {
+                    name: name.trim().to_string(),
+                    stages: Vec::new(),
+                };
+                statement.push_stages(rest, comment);
+                current = Some(statement);
+            }
+            // Not a chain, keep as written
+            _ => {
+                flush(&mut current, &mut out);
+                out.push(trimmed.to_string());
+            }
+        }
+    }
+    flush(&mut current, &mut out);
+
+    while out.last().is_some_and(|last| last.is_empty()) {
+        out.pop();
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalizes_whitespace() {
+        let code = "  out:~input>>lpf  1000.0   0.7>> mul ~drive  \n\n\n~drive:  sig 0.5";
+        assert_eq!(
+            format_code(code),
+            "out: ~input >> lpf 1000.0 0.7 >> mul ~drive\n\n~drive: sig 0.5"
+        );
+    }
+
+    #[test]
+    fn test_wraps_long_chains_and_comments() {
+        let code = "out: ~input >> lpf 1000.0 0.7 // tone\n >> plate 0.3";
+        assert_eq!(
+            format_code(code),
+            "out: ~input\n  >> lpf 1000.0 0.7 // tone\n  >> plate 0.3"
+        );
+
+        let long = format!("~fx: ~input >> {}", vec!["mul 0.5"; 12].join(" >> "));
+        let formatted = format_code(&long);
+        assert_eq!(formatted.lines().count(), 13);
+        assert!(formatted
+            .lines()
+            .skip(1)
+            .all(|line| line.starts_with("  >> ")));
+    }
+
+    #[test]
+    fn test_keeps_directives_and_backticks() {
+        let code = "#define x 1.0\nout: meta `\n  output = input;\n  `\n// note";
+        assert_eq!(format_code(code), code);
+    }
+
+    #[test]
+    fn test_idempotent() {
+        let code = "out: ~input >> lpf ~f 0.7 // cut\n  >> plate 0.3\n~f: sin 0.5 >> mul 500.0 >> add 800.0";
+        let once = format_code(code);
+        assert_eq!(format_code(&once), once);
+    }
+}
@@ -0,0 +1,85 @@
+//! Signal-flow graph parsed from Glicol code
+//!
+//! Each `name: a >> b >> c` line is a chain of stages. Stages mention other
+//! chains through `~references`, which become the links of the graph.
+
+/// One stage of a chain, e.g. `lpf ~cutoff 0.7`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stage {
+    pub text: String,
+    /// `~references` used by this stage
+    pub refs: Vec<String>,
+}
+
+/// A named chain of stages
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chain {
+    /// `out` or `~name`
+    pub name: String,
+    pub stages: Vec<Stage>,
+}
+
+/// Parse the chains of the code, in order of appearance
+///
+/// Lines starting with `>>` continue the previous chain. Directives and
+/// comments are skipped.
+pub fn parse_graph(code: &str) -> Vec<Chain> {
+    let mut chains: Vec<Chain> = Vec::new();
+    for line in code.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let body = if let Some(rest) = line.strip_prefix(">>") {
+            match chains.last_mut() {
+                Some(_) => rest,
+                None => continue,
+            }
+        } else {
+            let Some((name, rest)) = line.split_once(':') else {
+                continue;
+            };
+            chains.push(Chain {
+                name: name.trim().to_string(),
+                stages: Vec::new(),
+            });
+            rest
+        };
+
+        let Some(chain) = chains.last_mut() else {
+            continue;
+        };
+        for stage in body.split(">>").map(str::trim).filter(|s| !s.is_empty()) {
+            chain.stages.push(Stage {
+                text: stage.to_string(),
+                refs: stage
+                    .split_whitespace()
+                    .filter(|word| word.starts_with('~') && word.len() > 1)
+                    .map(str::to_string)
+                    .collect(),
+            });
+        }
+    }
+    chains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_graph() {
+        let code = "#define x 1.0\nout: ~input >> mul ~lfo // tremolo\n  >> plate 0.3\n~lfo: sin ~rate >> mul 0.5";
+        let chains = parse_graph(code);
+        assert_eq!(chains.len(), 2);
+
+        assert_eq!(chains[0].name, "out");
+        let texts: Vec<&str> = chains[0].stages.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["~input", "mul ~lfo", "plate 0.3"]);
+        assert_eq!(chains[0].stages[1].refs, ["~lfo"]);
+
+        assert_eq!(chains[1].name, "~lfo");
+        assert_eq!(chains[1].stages[0].refs, ["~rate"]);
+    }
+}
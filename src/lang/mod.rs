@@ -0,0 +1,13 @@
+//! Glicol language tooling for the editor
+//!
+//! Static helpers that work on the code text only (no engine involved):
+//! the node reference table, autocomplete, hover documentation, diffs,
+//! formatting, mutation, reference analysis and the signal-flow graph.
+
+pub mod completion;
+pub mod diff;
+pub mod format;
+pub mod graph;
+pub mod mutate;
+pub mod nodes;
+pub mod references;
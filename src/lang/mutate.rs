@@ -0,0 +1,213 @@
+//! Patch mutation for idea generation
+//!
+//! Perturbs numeric literals by a relative amount, clamped to a sensible
+//! range for the node argument they belong to, and optionally swaps nodes
+//! for a compatible one (e.g. `sin` for `tri`). Comments, `#` directives,
+//! quoted strings and sequencer nodes are left alone.
+
+/// Nodes whose arguments are notes or patterns rather than amounts
+const SKIP_NODES: &[&str] = &["seq", "choose", "meta", "speed"];
+
+/// Nodes that can replace each other without changing the arguments
+const SWAP_GROUPS: &[&[&str]] = &[
+    &["lpf", "hpf"],
+    &["sin", "saw", "squ", "tri"],
+    &["sawsynth", "squsynth", "trisynth"],
+];
+
+/// Chance that a swappable node is swapped
+const SWAP_CHANCE: f64 = 0.3;
+
+/// Sensible range for a node argument
+fn arg_range(node: &str, arg: usize) -> Option<(f64, f64)> {
+    match (node, arg) {
+        ("lpf" | "hpf" | "onepole", 0) => Some((20.0, 18000.0)),
+        ("lpf" | "hpf", 1) => Some((0.1, 1.0)),
+        ("sin" | "saw" | "squ" | "tri", 0) => Some((0.05, 2000.0)),
+        ("delayms", 0) => Some((1.0, 2000.0)),
+        ("apfmsgain", 0) => Some((0.1, 50.0)),
+        ("apfmsgain", 1) => Some((0.0, 0.99)),
+        ("plate", 0) => Some((0.0, 1.0)),
+        ("mul", 0) => Some((0.0, 4.0)),
+        _ => None,
+    }
+}
+
+/// Small xorshift generator, good enough for musical randomness
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Zero would get stuck
+        Self(seed | 1)
+    }
+
+    /// Uniform in 0..1
+    pub fn unit(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in -1..1
+    fn signed(&mut self) -> f64 {
+        self.unit() * 2.0 - 1.0
+    }
+}
+
+/// Mutate the code; `amount` is the maximum relative change (0.25 = ±25%)
+pub fn mutate(code: &str, amount: f64, swap_nodes: bool, rng: &mut Rng) -> String {
+    code.split('\n')
+        .map(|line| mutate_line(line, amount, swap_nodes, rng))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn mutate_line(line: &str, amount: f64, swap_nodes: bool, rng: &mut Rng) -> String {
+    if line.trim_start().starts_with('#') {
+        return line.to_string();
+    }
+    let (body, comment) = match line.find("//") {
+        Some(index) => line.split_at(index),
+        None => (line, ""),
+    };
+
+    let mut result = String::with_capacity(line.len());
+    let mut node: Option<&str> = None;
+    let mut arg = 0;
+    let mut in_string = false;
+    let mut rest = body;
+    while !rest.is_empty() {
+        // Copy whitespace through unchanged
+        let word_start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        result.push_str(&rest[..word_start]);
+        rest = &rest[word_start..];
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        rest = &rest[word_end..];
+
+        if in_string || word.starts_with('"') {
+            in_string = word.matches('"').count() % 2 == if in_string { 0 } else { 1 };
+            result.push_str(word);
+        } else if word == ">>" || word.ends_with(':') {
+            node = None;
+            result.push_str(word);
+        } else if let Some(value) = parse_number(word) {
+            match node {
+                Some(name) if !SKIP_NODES.contains(&name) => {
+                    let range = arg_range(name, arg);
+                    let mutated = perturb(value, amount, range, rng);
+                    result.push_str(&format_like(mutated, word));
+                }
+                _ => result.push_str(word),
+            }
+            arg += 1;
+        } else if node.is_none() && !word.starts_with('~') {
+            node = Some(word);
+            arg = 0;
+            let swapped = if swap_nodes { swap(word, rng) } else { None };
+            result.push_str(swapped.unwrap_or(word));
+        } else {
+            // `~reference` arguments still take an argument slot
+            arg += 1;
+            result.push_str(word);
+        }
+    }
+    result.push_str(comment);
+    result
+}
+
+fn parse_number(word: &str) -> Option<f64> {
+    let first = word.chars().next()?;
+    if first.is_ascii_digit() || first == '-' || first == '.' {
+        word.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn perturb(value: f64, amount: f64, range: Option<(f64, f64)>, rng: &mut Rng) -> f64 {
+    let mutated = if value == 0.0 {
+        // Relative changes can't move zero; nudge within the range instead
+        range.map_or(0.0, |(min, max)| min + (max - min) * amount * rng.unit())
+    } else {
+        value * (1.0 + amount * rng.signed())
+    };
+    match range {
+        Some((min, max)) => mutated.clamp(min, max),
+        // Without a known range, at least keep the sign
+        None if value >= 0.0 => mutated.max(0.0),
+        None => mutated.min(0.0),
+    }
+}
+
+/// Format like the original literal: integers stay integers, floats keep
+/// their precision (at least two decimals for small values)
+fn format_like(value: f64, original: &str) -> String {
+    let Some(dot) = original.find('.') else {
+        return format!("{}", value.round() as i64);
+    };
+    let mut decimals = (original.len() - dot - 1).max(1);
+    if value.abs() < 10.0 {
+        decimals = decimals.max(2);
+    }
+    format!("{:.*}", decimals, value)
+}
+
+fn swap(node: &str, rng: &mut Rng) -> Option<&'static str> {
+    let group = SWAP_GROUPS.iter().find(|group| group.contains(&node))?;
+    if rng.unit() >= SWAP_CHANCE {
+        return None;
+    }
+    let others: Vec<&'static str> = group.iter().copied().filter(|&n| n != node).collect();
+    let index = ((rng.unit() * others.len() as f64) as usize).min(others.len() - 1);
+    Some(others[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutate_keeps_structure_and_ranges() {
+        let code = "// lpf 1000.0\nout: ~input >> lpf 1000.0 0.7 >> mul ~drive\n#define x 3.0\n~seq: seq 60 _ 62";
+        for seed in 1..50 {
+            let mutated = mutate(code, 0.5, false, &mut Rng::new(seed));
+            let lines: Vec<&str> = mutated.lines().collect();
+            assert_eq!(lines[0], "// lpf 1000.0");
+            assert_eq!(lines[2], "#define x 3.0");
+            assert_eq!(lines[3], "~seq: seq 60 _ 62");
+
+            let words: Vec<&str> = lines[1].split_whitespace().collect();
+            assert_eq!(words[..4], ["out:", "~input", ">>", "lpf"]);
+            let cutoff: f64 = words[4].parse().unwrap();
+            let q: f64 = words[5].parse().unwrap();
+            assert!((500.0..=1500.0).contains(&cutoff));
+            assert!((0.1..=1.0).contains(&q));
+            assert_eq!(words[6..], [">>", "mul", "~drive"]);
+        }
+    }
+
+    #[test]
+    fn test_swap_stays_in_group() {
+        let mut swapped = false;
+        for seed in 1..50 {
+            let mutated = mutate("~lfo: sin 2.0 >> plate 0.5", 0.0, true, &mut Rng::new(seed));
+            let words: Vec<&str> = mutated.split_whitespace().collect();
+            assert!(["sin", "saw", "squ", "tri"].contains(&words[1]));
+            assert_eq!(words[4], "plate");
+            swapped |= words[1] != "sin";
+        }
+        assert!(swapped);
+    }
+
+    #[test]
+    fn test_format_like() {
+        assert_eq!(format_like(1234.567, "1000.0"), "1234.6");
+        assert_eq!(format_like(0.734, "0.7"), "0.73");
+        assert_eq!(format_like(130.4, "120"), "130");
+    }
+}
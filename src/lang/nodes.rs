@@ -0,0 +1,290 @@
+//! Built-in Glicol node reference
+//!
+//! A small table of the nodes available in Glicol 0.13, used for
+//! autocomplete and hover documentation. See `vendor/glicol/js/src/glicol-api.json` for the
+//! upstream reference.
+
+/// Reference entry for one Glicol node
+pub struct NodeInfo {
+    pub name: &'static str,
+    /// Argument list, e.g. `cutoff q`
+    pub args: &'static str,
+    pub description: &'static str,
+    /// Useful argument ranges
+    pub ranges: &'static str,
+    /// One-line usage example
+    pub example: &'static str,
+}
+
+/// All known Glicol nodes
+pub const NODES: &[NodeInfo] = &[
+    // Oscillators
+    NodeInfo {
+        name: "sin",
+        args: "freq",
+        description: "Sine oscillator",
+        ranges: "freq: 0.01-20000 Hz (or ~ref)",
+        example: "~lfo: sin 2.0 >> mul 0.5 >> add 0.5",
+    },
+    NodeInfo {
+        name: "saw",
+        args: "freq",
+        description: "Sawtooth oscillator",
+        ranges: "freq: 0.01-20000 Hz (or ~ref)",
+        example: "~ramp: saw ~rate",
+    },
+    NodeInfo {
+        name: "squ",
+        args: "freq",
+        description: "Square oscillator",
+        ranges: "freq: 0.01-20000 Hz (or ~ref)",
+        example: "~gate: squ 4.0 >> mul 0.5 >> add 0.5",
+    },
+    NodeInfo {
+        name: "tri",
+        args: "freq",
+        description: "Triangle oscillator",
+        ranges: "freq: 0.01-20000 Hz (or ~ref)",
+        example: "~lfo: tri 0.5",
+    },
+    NodeInfo {
+        name: "noiz",
+        args: "seed",
+        description: "White noise",
+        ranges: "seed: any integer",
+        example: "out: noiz 42 >> mul 0.1",
+    },
+    NodeInfo {
+        name: "imp",
+        args: "freq",
+        description: "Impulse train",
+        ranges: "freq: 0.1-100 Hz",
+        example: "~clock: imp 2.0",
+    },
+    NodeInfo {
+        name: "sig",
+        args: "value",
+        description: "Constant signal",
+        ranges: "value: any number",
+        example: "~level: sig 0.5",
+    },
+    // Filters
+    NodeInfo {
+        name: "lpf",
+        args: "cutoff q",
+        description: "Resonant low-pass filter",
+        ranges: "cutoff: 20-20000 Hz (or ~ref), q: 0.1-20",
+        example: "out: ~input >> lpf 1000.0 0.7",
+    },
+    NodeInfo {
+        name: "hpf",
+        args: "cutoff q",
+        description: "Resonant high-pass filter",
+        ranges: "cutoff: 20-20000 Hz (or ~ref), q: 0.1-20",
+        example: "out: ~input >> hpf 200.0 0.7",
+    },
+    NodeInfo {
+        name: "onepole",
+        args: "coefficient",
+        description: "One-pole smoothing filter",
+        ranges: "coefficient: 0.0-0.99 (higher = darker)",
+        example: "out: ~input >> onepole 0.5",
+    },
+    NodeInfo {
+        name: "apfmsgain",
+        args: "delay_ms gain",
+        description: "Allpass filter with delay in ms",
+        ranges: "delay_ms: 0.1-100 (or ~ref), gain: 0.0-0.99",
+        example: "~wet: ~input >> apfmsgain 4.0 0.7",
+    },
+    // Effects
+    NodeInfo {
+        name: "plate",
+        args: "mix",
+        description: "Plate reverb",
+        ranges: "mix: 0.0 (dry) - 1.0 (wet)",
+        example: "out: ~input >> plate 0.5",
+    },
+    NodeInfo {
+        name: "delayms",
+        args: "time_ms",
+        description: "Delay in milliseconds",
+        ranges: "time_ms: 0-5000 (or ~ref)",
+        example: "out: ~input >> delayms 250.0",
+    },
+    NodeInfo {
+        name: "delayn",
+        args: "samples",
+        description: "Delay in samples",
+        ranges: "samples: 0-88200",
+        example: "out: ~input >> delayn 441",
+    },
+    // Operators
+    NodeInfo {
+        name: "mul",
+        args: "factor",
+        description: "Multiply (volume, ring mod)",
+        ranges: "factor: any number (or ~ref)",
+        example: "out: ~input >> mul 0.5",
+    },
+    NodeInfo {
+        name: "add",
+        args: "value",
+        description: "Add a value or signal",
+        ranges: "value: any number (or ~ref)",
+        example: "out: ~input >> add ~echo",
+    },
+    // Envelopes
+    NodeInfo {
+        name: "envperc",
+        args: "attack decay",
+        description: "Percussive envelope",
+        ranges: "attack: seconds, decay: seconds",
+        example: "out: sin 440 >> mul ~env\n~env: imp 1.0 >> envperc 0.01 0.2",
+    },
+    NodeInfo {
+        name: "adsr",
+        args: "attack decay sustain release",
+        description: "ADSR envelope",
+        ranges: "attack/decay/release: seconds, sustain: 0.0-1.0",
+        example: "~env: ~gate >> adsr 0.01 0.1 0.7 0.3",
+    },
+    // Sequencing
+    NodeInfo {
+        name: "seq",
+        args: "pattern",
+        description: "Note sequencer",
+        ranges: "pattern: MIDI notes, _ = rest",
+        example: "~notes: speed 2.0 >> seq 60 _60 72 _",
+    },
+    NodeInfo {
+        name: "speed",
+        args: "factor",
+        description: "Sequencer speed",
+        ranges: "factor: 0.25-16",
+        example: "~notes: speed 4.0 >> seq 60 67",
+    },
+    NodeInfo {
+        name: "choose",
+        args: "values...",
+        description: "Random choice per trigger",
+        ranges: "values: numbers to pick from",
+        example: "~pitch: choose 60 64 67",
+    },
+    // Synths
+    NodeInfo {
+        name: "sawsynth",
+        args: "attack decay",
+        description: "Sawtooth synth voice",
+        ranges: "attack: seconds, decay: seconds",
+        example: "out: ~notes >> sawsynth 0.01 0.1",
+    },
+    NodeInfo {
+        name: "squsynth",
+        args: "attack decay",
+        description: "Square synth voice",
+        ranges: "attack: seconds, decay: seconds",
+        example: "out: ~notes >> squsynth 0.01 0.1",
+    },
+    NodeInfo {
+        name: "trisynth",
+        args: "attack decay",
+        description: "Triangle synth voice",
+        ranges: "attack: seconds, decay: seconds",
+        example: "out: ~notes >> trisynth 0.01 0.1",
+    },
+    // Drums
+    NodeInfo {
+        name: "bd",
+        args: "decay",
+        description: "Bass drum",
+        ranges: "decay: 0.05-1.0 seconds",
+        example: "out: ~beat >> bd 0.3",
+    },
+    NodeInfo {
+        name: "sn",
+        args: "decay",
+        description: "Snare drum",
+        ranges: "decay: 0.05-1.0 seconds",
+        example: "out: ~beat >> sn 0.15",
+    },
+    NodeInfo {
+        name: "hh",
+        args: "decay",
+        description: "Hi-hat",
+        ranges: "decay: 0.01-0.5 seconds",
+        example: "out: ~beat >> hh 0.05",
+    },
+    // Sampling
+    NodeInfo {
+        name: "sp",
+        args: "\\sample",
+        description: "Sample player",
+        ranges: "\\sample: name of a loaded sample",
+        example: "out: ~beat >> sp \\808",
+    },
+    NodeInfo {
+        name: "sampler",
+        args: "\\sample",
+        description: "Triggered sampler",
+        ranges: "\\sample: name of a loaded sample",
+        example: "out: ~trig >> sampler \\808",
+    },
+    // Utility
+    NodeInfo {
+        name: "pan",
+        args: "position",
+        description: "Stereo panner",
+        ranges: "position: -1.0 (left) - 1.0 (right)",
+        example: "out: ~input >> pan 0.3",
+    },
+    NodeInfo {
+        name: "balance",
+        args: "~left ~right mix",
+        description: "Crossfade two signals",
+        ranges: "mix: 0.0 (left ref) - 1.0 (right ref)",
+        example: "out: balance ~dry ~wet 0.5",
+    },
+    NodeInfo {
+        name: "mix",
+        args: "~refs...",
+        description: "Sum several references",
+        ranges: "~refs: any number of references",
+        example: "out: mix ~a ~b",
+    },
+    // Scripting
+    NodeInfo {
+        name: "meta",
+        args: "`script`",
+        description: "Rhai script node",
+        ranges: "script: Rhai code returning `output`",
+        example: "out: ~input >> meta `output = input; output`",
+    },
+];
+
+/// Look up a node by exact name
+pub fn find(name: &str) -> Option<&'static NodeInfo> {
+    NODES.iter().find(|node| node.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_node() {
+        assert_eq!(find("lpf").map(|node| node.args), Some("cutoff q"));
+        assert!(find("tanh").is_none());
+    }
+
+    #[test]
+    fn test_examples_use_their_node() {
+        for node in NODES {
+            assert!(
+                node.example.contains(node.name),
+                "example for {} doesn't use it",
+                node.name
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! `~reference` analysis
+//!
+//! Finds every definition (`~name:` at the start of a line) and usage of a
+//! reference, so the editor can highlight all occurrences of the one under
+//! the cursor and flag usages that nothing defines.
+
+use crate::engine::PARAM_NAMES;
+
+/// One occurrence of a `~reference` in the code
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferenceSpan {
+    /// Including the `~`
+    pub name: String,
+    /// Byte range in the code
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line
+    pub line: usize,
+    /// `~name:` rather than a usage
+    pub definition: bool,
+}
+
+/// All references in the code, skipping comments and `#` directives
+pub fn reference_spans(code: &str) -> Vec<ReferenceSpan> {
+    let mut spans = Vec::new();
+    let mut line_start = 0;
+    for (index, line) in code.split_inclusive('\n').enumerate() {
+        let body = line.split("//").next().unwrap_or("");
+        if !body.trim_start().starts_with('#') {
+            let first_word = body.len() - body.trim_start().len();
+            let mut chars = body.char_indices().peekable();
+            while let Some((offset, c)) = chars.next() {
+                if c != '~' {
+                    continue;
+                }
+                let mut end = offset + 1;
+                while let Some(&(next, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = next + c.len_utf8();
+                    chars.next();
+                }
+                if end == offset + 1 {
+                    continue;
+                }
+                spans.push(ReferenceSpan {
+                    name: body[offset..end].to_string(),
+                    start: line_start + offset,
+                    end: line_start + end,
+                    line: index + 1,
+                    definition: offset == first_word && body[end..].trim_start().starts_with(':'),
+                });
+            }
+        }
+        line_start += line.len();
+    }
+    spans
+}
+
+/// Usages that are neither defined in the code nor provided by the plugin
+/// (`~input`, the injected parameters and the knob names in `aliases`)
+pub fn undefined_references(code: &str, aliases: &[&str]) -> Vec<ReferenceSpan> {
+    let spans = reference_spans(code);
+    spans
+        .iter()
+        .filter(|span| !span.definition && !is_builtin(&span.name, aliases))
+        .filter(|span| {
+            !spans
+                .iter()
+                .any(|other| other.definition && other.name == span.name)
+        })
+        .cloned()
+        .collect()
+}
+
+fn is_builtin(name: &str, aliases: &[&str]) -> bool {
+    let name = &name[1..];
+    name == "input" || PARAM_NAMES.contains(&name) || aliases.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_spans() {
+        let code = "out: ~input >> mul ~lfo // ~ignored\n  ~lfo : sin ~rate";
+        let spans = reference_spans(code);
+        let names: Vec<(&str, bool)> = spans
+            .iter()
+            .map(|span| (span.name.as_str(), span.definition))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("~input", false),
+                ("~lfo", false),
+                ("~lfo", true),
+                ("~rate", false)
+            ]
+        );
+        assert_eq!(&code[spans[2].start..spans[2].end], "~lfo");
+        assert_eq!(spans[2].line, 2);
+    }
+
+    #[test]
+    fn test_undefined_references() {
+        let code = "#define x ~nope\nout: ~input >> mul ~drive >> add ~wet >> mul ~typo\n~wet: ~input >> plate 0.5";
+        let undefined = undefined_references(code, &[]);
+        assert_eq!(undefined.len(), 1);
+        assert_eq!(undefined[0].name, "~typo");
+        assert_eq!(undefined[0].line, 2);
+        assert!(undefined_references(code, &["typo"]).is_empty());
+    }
+}
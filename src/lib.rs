@@ -1,23 +1,122 @@
 use crossbeam_channel::{bounded, Receiver, Sender};
 use nih_plug::prelude::*;
+use std::collections::BTreeMap;
 use std::num::NonZeroU32;
+use std::ops::Range;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-mod dsp;
+use glicol_verb_core::{dsp, engine};
+
+mod api;
+mod diagnostics;
+#[cfg(feature = "editor")]
 mod editor;
-mod engine;
+#[cfg(feature = "editor")]
+mod lang;
+mod link;
+mod memory;
 mod messages;
+mod midi;
+mod modulation;
 mod params;
+mod preset;
+mod recorder;
+mod shortcuts;
+mod state;
+mod telemetry;
+mod tempo_link;
 
+use api::ApiServer;
+use diagnostics::Event;
+use dsp::auto_gain::AutoGain;
 use dsp::delay::Delay;
+use dsp::envelope::EnvelopeFollower;
 use dsp::eq::Eq;
-use dsp::{DspModule, StereoSample};
-use engine::{BufferBridge, GlicolWrapper, ParamInjector};
-use messages::CodeMessage;
-use params::GlicolVerbParams;
+use dsp::hush::Hush;
+use dsp::latency::LatencyDelay;
+use dsp::limiter::Limiter;
+use dsp::tail::{TailEstimator, TailState};
+use dsp::test_signal::{TestSample, TestSignal};
+use dsp::{DspModule, ModuleKind, ModuleOrder, StereoSample};
+use engine::{
+    preprocess, preprocess_mapped, write_message, BufferBridge, EngineOversampler, GlicolWrapper,
+    ParamInjector, SourceMap, GLICOL_BLOCK_SIZE,
+};
+use link::LinkHandle;
+use messages::{
+    CodeMessage, EngineRequest, EngineSet, MorphEngine, MorphRequest, StatusMessage, Task,
+};
+use midi::MidiOverrides;
+use modulation::{Lfo, ModTargets, LFO_COUNT, SOURCE_COUNT};
+use params::{GlicolVerbParams, InputSource, OutputRouting, TestMix};
+use preset::factory::FACTORY_PRESETS;
+use preset::AbSlot;
+use recorder::Recorder;
+use telemetry::{CorrelationAccumulator, CpuMeter, MeterAccumulator, Telemetry};
+use tempo_link::{LinkSession, TempoLink};
+
+/// Longest stretch of a host buffer processed in one go. Bigger buffers
+/// are split, keeping the scratch buffers small and the bridge's 2048-sample
+/// rings from overflowing.
+const MAX_BUFFER_SIZE: usize = 1024;
+
+/// Tempo without a host transport or Link session
+const FALLBACK_BPM: f32 = 120.0;
+
+/// Time constant of the expression pedal mod source
+const EXPR_SMOOTHING_MS: f32 = 80.0;
+
+/// Safe mode ceilings for feedback-like values and drive
+const SAFE_DELAY_FEEDBACK: f32 = 0.7;
+const SAFE_FEEDBACK: f32 = 0.7;
+const SAFE_DRIVE: f32 = 4.0;
+
+/// Code a panic reverts to
+const PASSTHROUGH_CODE: &str = "out: ~input";
 
-/// Maximum buffer size we expect from DAWs (most use 64-2048)
-const MAX_BUFFER_SIZE: usize = 4096;
+/// Code length the audio thread copies without allocating. The code
+/// strings it writes (running, pending, persisted and scene code) are
+/// reserved to this off the audio thread; see `copy_code()`. Applying code
+/// still allocates (see `apply_code()`), this only covers committing it.
+const CODE_CAPACITY: usize = 16 * 1024;
+
+/// Port names shared by the layouts, which all carry a stereo sidechain
+const SIDECHAIN_PORT_NAMES: PortNames = PortNames {
+    aux_inputs: &["Sidechain"],
+    ..PortNames::const_default()
+};
+
+/// Per-sample values of the smoothed core parameters, filled once per
+/// buffer so the processing passes don't step each smoother per sample
+struct Ramps {
+    input_gain: [f32; MAX_BUFFER_SIZE],
+    test_level: [f32; MAX_BUFFER_SIZE],
+    output_gain: [f32; MAX_BUFFER_SIZE],
+    dry_wet: [f32; MAX_BUFFER_SIZE],
+    width: [f32; MAX_BUFFER_SIZE],
+}
+
+impl Default for Ramps {
+    fn default() -> Self {
+        Self {
+            input_gain: [0.0; MAX_BUFFER_SIZE],
+            test_level: [0.0; MAX_BUFFER_SIZE],
+            output_gain: [0.0; MAX_BUFFER_SIZE],
+            dry_wet: [0.0; MAX_BUFFER_SIZE],
+            width: [0.0; MAX_BUFFER_SIZE],
+        }
+    }
+}
+
+/// A scene change waiting for the Glicol block grid
+#[derive(Clone, Copy)]
+struct SceneSwitch {
+    scene: usize,
+    /// Input samples queued before the change, which the old code still
+    /// has to process
+    samples_before: usize,
+}
 
 /// GlicolVerb - Live coding guitar pedal VST
 pub struct GlicolVerb {
@@ -26,51 +125,329 @@ pub struct GlicolVerb {
     /// Glicol audio engine
     engine: GlicolWrapper,
 
+    /// Second engine running the inactive A/B snapshot's code while morphing
+    morph_engine: GlicolWrapper,
+
+    /// Raw code loaded in `morph_engine` (or being built for it), and the
+    /// injected code it runs
+    morph_code: String,
+    morph_running_code: String,
+    /// Morph engines built on the background thread (the sender goes to the
+    /// task executor), whether a build is underway, and whether the engine
+    /// hasn't run since it was built
+    morph_receiver: Receiver<MorphEngine>,
+    morph_sender: Sender<MorphEngine>,
+    building_morph: bool,
+    morph_fresh: bool,
+
+    /// Morph amount for this block (0.0 without a snapshot to morph to),
+    /// and the crossfade position reached at the end of the last block
+    morph_target: f32,
+    morph_mix: f32,
+
+    /// Oversampling around each engine (factor 1 passes straight through)
+    oversampler: EngineOversampler,
+    morph_oversampler: EngineOversampler,
+
     /// Buffer bridge for DAW <-> Glicol block size conversion
     buffer_bridge: BufferBridge,
 
-    /// EQ module (pre-Glicol)
+    /// EQ module (pre-Glicol by default)
     eq: Eq,
 
-    /// Delay module (post-Glicol)
+    /// Delay module (post-Glicol by default)
     delay: Delay,
 
+    /// Current order of EQ, Glicol and delay
+    module_order: ModuleOrder,
+
+    /// Panic fade on the wet path
+    hush: Hush,
+
+    /// Last seen value of the panic param (for edge detection)
+    panic_param_active: bool,
+
+    /// Output limiter, engaged by safe mode
+    limiter: Limiter,
+
+    /// Last seen value of the safe mode param (for edge detection)
+    safe_mode_active: bool,
+
+    /// Loudness match between input and output
+    auto_gain: AutoGain,
+
+    /// Decides the process status from the module tails and levels
+    tail: TailEstimator,
+
+    /// Stand-in for the instrument, fed in ahead of the input gain
+    test_signal: TestSignal,
+
+    /// Revert code to passthrough once the panic fade completes
+    revert_on_silence: bool,
+
+    /// Output muted by the tuner, and the ramped gain following it
+    tuner_mute: bool,
+    mute_gain: f32,
+
+    /// Crossfade position of the soft bypass (1.0 = only the input is heard)
+    bypass_mix: f32,
+
+    /// Host feeds a single input channel
+    mono_input: bool,
+    /// Host takes a single output channel
+    mono_output: bool,
+
     /// Receiver for code updates from GUI
     code_receiver: Receiver<CodeMessage>,
 
     /// Sender for code updates (given to GUI)
+    #[cfg(feature = "editor")]
     code_sender: Option<Sender<CodeMessage>>,
 
+    /// Sender for compile results (audio -> GUI)
+    status_sender: Sender<StatusMessage>,
+
+    /// Receiver for compile results (given to GUI)
+    #[cfg(feature = "editor")]
+    status_receiver: Option<Receiver<StatusMessage>>,
+
+    /// Engines built on the background thread for a new oversampling
+    /// factor (the sender goes to the task executor), and whether a build
+    /// is underway
+    engine_receiver: Receiver<EngineSet>,
+    engine_sender: Sender<EngineSet>,
+    building_engines: bool,
+
     /// Raw user code (before param injection)
     user_code: String,
 
+    /// Scene whose code is loaded (index into `params.scenes`)
+    active_scene: usize,
+
+    /// Scene change applied at the next Glicol block that starts after it
+    pending_scene: Option<SceneSwitch>,
+
+    /// Code sent to the engine but not yet confirmed to compile, valid
+    /// while `code_pending` is set
+    pending_code: String,
+    code_pending: bool,
+
+    /// Code waiting for the editor to let go of the snippets, valid while
+    /// `code_deferred` is set
+    deferred_code: String,
+    code_deferred: bool,
+
+    /// Scene whose committed code still has to be saved (into its slot and
+    /// the persisted code); see `save_code()`
+    unsaved_scene: Option<usize>,
+
+    /// User code line of each line of the last code sent to the engine
+    source_map: SourceMap,
+
+    /// Injected code last sent to the engine, and the number of code
+    /// updates so far, for rebuilding the engines off the audio thread
+    running_code: String,
+    code_generation: u64,
+
     /// Parameter injector for ~knob1, ~drive, etc.
     param_injector: ParamInjector,
 
+    /// Injected chains of the running code, updated every Glicol block:
+    /// name, index into `ParamInjector::values()` and the last value sent
+    live_params: Vec<(String, usize, f32)>,
+
+    /// Injected chains of `pending_code`, taken over once it compiles
+    pending_live_params: Option<Vec<(String, usize, f32)>>,
+
+    /// Injected chains of the code in `morph_engine`, updated every block
+    /// while it runs
+    morph_live_params: Vec<(String, usize, f32)>,
+
+    /// Reused buffer for the per-block Glicol messages
+    live_messages: String,
+
+    /// Live MIDI CC values for mapped parameters
+    midi_overrides: MidiOverrides,
+
+    /// Parameter waiting for a CC in MIDI learn mode
+    midi_learn_target: Option<String>,
+
+    /// Target values driven by the macros and LFOs this block
+    mod_targets: ModTargets,
+
+    /// Built-in LFOs, advanced once per block
+    lfos: [Lfo; LFO_COUNT],
+
+    /// Input level follower, a modulation matrix source
+    envelope: EnvelopeFollower,
+
+    /// Smoothed expression pedal, a modulation matrix source
+    expr_level: f32,
+
+    /// Sidechain level follower (same settings as `envelope`), and its
+    /// scaled level as of the last block: a matrix source and `~sidechain`
+    sidechain_envelope: EnvelopeFollower,
+    sidechain_level: f32,
+
     /// Sample rate from DAW
     sample_rate: f32,
 
-    /// Pre-allocated buffer for dry samples (avoids allocation in process())
-    dry_buffer: [f32; MAX_BUFFER_SIZE],
+    /// Levels and other readouts for the editor
+    telemetry: Arc<Telemetry>,
+
+    /// Output recorder, started and stopped from the editor
+    recorder: Arc<Recorder>,
+    /// Local HTTP API, started here for sessions that have it enabled
+    api: Arc<ApiServer>,
+
+    /// Membership in link mode, shared with the editor and the API which
+    /// publish. Held here so the instance stays linked without them.
+    #[cfg_attr(not(feature = "editor"), allow(dead_code))]
+    link: Arc<LinkHandle>,
+    /// Ableton Link session of the standalone app, and the audio thread's
+    /// copy of its state
+    tempo_link: Arc<TempoLink>,
+    link_session: LinkSession,
+    /// Interleaved frames for the recorder, filled while writing the output
+    record_buffer: [f32; MAX_BUFFER_SIZE * 4],
+
+    /// Level meters for input (after gain), Glicol output and final output
+    input_meter: MeterAccumulator,
+    wet_meter: MeterAccumulator,
+    output_meter: MeterAccumulator,
+    correlation: CorrelationAccumulator,
+    cpu_meter: CpuMeter,
+
+    /// Pre-allocated buffers for the dry and wet signals (avoids
+    /// allocation in process())
+    dry_buffer: [StereoSample; MAX_BUFFER_SIZE],
+    wet_buffer: [StereoSample; MAX_BUFFER_SIZE],
+    /// Smoothed parameter values for the current buffer
+    ramps: Ramps,
+    /// Delays the dry signal to line up with the wet one
+    dry_delay: LatencyDelay,
+    /// Delays the raw input by the reported latency for the soft bypass
+    bypass_delay: LatencyDelay,
+    /// Latency last reported to the host
+    reported_latency: u32,
+
+    /// Diagnostics state: Glicol's output channel count, whether the last
+    /// buffer overloaded, and the underrun total already reported
+    engine_channels: Option<usize>,
+    overloaded: bool,
+    reported_underruns: u32,
+    samples_since_underrun_report: u32,
 }
 
 impl Default for GlicolVerb {
     fn default() -> Self {
         // Bounded channel for code updates (capacity 4 is plenty)
         let (code_sender, code_receiver) = bounded(4);
+        let (status_sender, status_receiver) = bounded(16);
+        // Without the editor nothing reads them, so sending just fails
+        #[cfg(not(feature = "editor"))]
+        drop(status_receiver);
+        // One build is underway at a time
+        let (engine_sender, engine_receiver) = bounded(1);
+        let (morph_sender, morph_receiver) = bounded(1);
+        let params = Arc::new(GlicolVerbParams::default());
+        // Linked instances send to this instance's code queue
+        let link = Arc::new(LinkHandle::join(params.link.clone(), code_sender.clone()));
+        let telemetry = Arc::new(Telemetry::default());
+        let api = Arc::new(ApiServer::new(
+            params.clone(),
+            telemetry.clone(),
+            code_sender.clone(),
+            link.clone(),
+        ));
 
         Self {
-            params: Arc::new(GlicolVerbParams::default()),
+            params,
             engine: GlicolWrapper::new(44100.0),
+            morph_engine: GlicolWrapper::new(44100.0),
+            morph_code: String::with_capacity(CODE_CAPACITY),
+            morph_running_code: String::new(),
+            morph_receiver,
+            morph_sender,
+            building_morph: false,
+            morph_fresh: true,
+            morph_target: 0.0,
+            morph_mix: 0.0,
+            oversampler: EngineOversampler::new(),
+            morph_oversampler: EngineOversampler::new(),
             buffer_bridge: BufferBridge::new(),
             eq: Eq::new(44100.0),
             delay: Delay::new(44100.0),
+            module_order: ModuleOrder::default(),
+            hush: Hush::new(44100.0),
+            panic_param_active: false,
+            limiter: Limiter::new(44100.0),
+            safe_mode_active: false,
+            auto_gain: AutoGain::new(44100.0),
+            tail: TailEstimator::new(44100.0),
+            test_signal: TestSignal::new(44100.0),
+            revert_on_silence: false,
+            tuner_mute: false,
+            mute_gain: 1.0,
+            bypass_mix: 0.0,
+            mono_input: true,
+            mono_output: false,
             code_receiver,
+            #[cfg(feature = "editor")]
             code_sender: Some(code_sender),
-            user_code: "out: ~input".to_string(),
+            status_sender,
+            #[cfg(feature = "editor")]
+            status_receiver: Some(status_receiver),
+            engine_receiver,
+            engine_sender,
+            building_engines: false,
+            user_code: String::with_capacity(CODE_CAPACITY) + PASSTHROUGH_CODE,
+            active_scene: 0,
+            pending_scene: None,
+            pending_code: String::with_capacity(CODE_CAPACITY),
+            code_pending: false,
+            deferred_code: String::with_capacity(CODE_CAPACITY),
+            code_deferred: false,
+            unsaved_scene: None,
+            source_map: SourceMap::default(),
+            running_code: String::new(),
+            code_generation: 0,
             param_injector: ParamInjector::new(),
+            live_params: Vec::new(),
+            pending_live_params: None,
+            morph_live_params: Vec::new(),
+            live_messages: String::with_capacity(1024),
+            midi_overrides: MidiOverrides::default(),
+            midi_learn_target: None,
+            mod_targets: ModTargets::default(),
+            lfos: Default::default(),
+            envelope: EnvelopeFollower::new(44100.0),
+            sidechain_envelope: EnvelopeFollower::new(44100.0),
+            sidechain_level: 0.0,
+            expr_level: 0.0,
             sample_rate: 44100.0,
-            dry_buffer: [0.0; MAX_BUFFER_SIZE],
+            telemetry,
+            recorder: Arc::new(Recorder::default()),
+            api,
+            link,
+            tempo_link: Arc::new(TempoLink::default()),
+            link_session: LinkSession::default(),
+            record_buffer: [0.0; MAX_BUFFER_SIZE * 4],
+            input_meter: MeterAccumulator::new(44100.0),
+            wet_meter: MeterAccumulator::new(44100.0),
+            output_meter: MeterAccumulator::new(44100.0),
+            correlation: CorrelationAccumulator::new(44100.0),
+            cpu_meter: CpuMeter::new(),
+            dry_buffer: [StereoSample::default(); MAX_BUFFER_SIZE],
+            wet_buffer: [StereoSample::default(); MAX_BUFFER_SIZE],
+            ramps: Ramps::default(),
+            dry_delay: LatencyDelay::new(),
+            bypass_delay: LatencyDelay::new(),
+            reported_latency: 0,
+            engine_channels: None,
+            overloaded: false,
+            reported_underruns: 0,
+            samples_since_underrun_report: 0,
         }
     }
 }
@@ -78,35 +455,935 @@ impl Default for GlicolVerb {
 impl GlicolVerb {
     /// Update param_injector with current parameter values
     fn update_param_injector(&mut self) {
-        self.param_injector.knob1 = self.params.knob1.value();
-        self.param_injector.knob2 = self.params.knob2.value();
-        self.param_injector.knob3 = self.params.knob3.value();
-        self.param_injector.knob4 = self.params.knob4.value();
-        self.param_injector.drive = self.params.drive.value();
-        self.param_injector.feedback = self.params.feedback.value();
-        self.param_injector.mix = self.params.mix.value();
-        self.param_injector.rate = self.params.rate.value();
+        let params = &self.params;
+        let overrides = &mut self.midi_overrides;
+        let modulation = &self.mod_targets;
+        // MIDI overrides first, then macros and LFOs on top
+        let mut value = |param: &FloatParam, param_id: &str| {
+            modulation.float(param, param_id, overrides.smoothed(params, param, param_id))
+        };
+        self.param_injector.knob1 = value(&params.glicol.knob1, "knob1");
+        self.param_injector.knob2 = value(&params.glicol.knob2, "knob2");
+        self.param_injector.knob3 = value(&params.glicol.knob3, "knob3");
+        self.param_injector.knob4 = value(&params.glicol.knob4, "knob4");
+        self.param_injector.knob5 = value(&params.glicol.knob5, "knob5");
+        self.param_injector.knob6 = value(&params.glicol.knob6, "knob6");
+        self.param_injector.knob7 = value(&params.glicol.knob7, "knob7");
+        self.param_injector.knob8 = value(&params.glicol.knob8, "knob8");
+        self.param_injector.drive = value(&params.glicol.drive, "drive");
+        self.param_injector.feedback = value(&params.glicol.feedback, "feedback");
+        if params.safe_mode.value() {
+            self.param_injector.drive = self.param_injector.drive.min(SAFE_DRIVE);
+            self.param_injector.feedback = self.param_injector.feedback.min(SAFE_FEEDBACK);
+        }
+        self.param_injector.mix = value(&params.glicol.mix, "mix");
+        self.param_injector.rate = value(&params.glicol.rate, "rate");
+        self.param_injector.expr = value(&params.glicol.expr, "expr");
+        self.param_injector.sidechain = self.sidechain_level;
+    }
+
+    /// Advance the injected parameters' smoothers by one Glicol block and
+    /// send the chains whose value changed to the running code
+    ///
+    /// Runs before every block, so automation reaches the engine at block
+    /// rate instead of only when code is applied.
+    fn send_live_params(&mut self) {
+        for param in self.params.glicol.injected() {
+            param.smoothed.next_step(GLICOL_BLOCK_SIZE as u32);
+        }
+        self.update_param_injector();
+
+        let values = self.param_injector.values();
+        self.live_messages.clear();
+        write_live_messages(&mut self.live_messages, &mut self.live_params, &values);
+        if !self.live_messages.is_empty() {
+            util::permit_alloc(|| self.engine.send_msg(&self.live_messages));
+        }
+
+        // The other snapshot's code gets the same (morphed) values
+        if self.morph_target > 0.0 || self.morph_mix > 0.0 {
+            self.live_messages.clear();
+            write_live_messages(
+                &mut self.live_messages,
+                &mut self.morph_live_params,
+                &values,
+            );
+            if !self.live_messages.is_empty() {
+                util::permit_alloc(|| self.morph_engine.send_msg(&self.live_messages));
+            }
+        }
+    }
+
+    /// Preprocess, inject params and hot-swap user code into the engine
+    ///
+    /// On error the old code keeps running.
+    /// Parse errors surface a block later via `handle_compile_result()`.
+    /// While the editor holds the snippets the code waits for the next
+    /// block (see `apply_deferred_code()`); errors then arrive as a status.
+    fn apply_code(&mut self, user_code: &str) -> Result<(), String> {
+        let params = self.params.clone();
+        let Some(snippets) = params.snippets.try_read() else {
+            copy_code(&mut self.deferred_code, user_code);
+            self.code_deferred = true;
+            return Ok(());
+        };
+        // Newer code replaces any that was waiting
+        self.code_deferred = false;
+        self.send_code(user_code, &snippets)
+    }
+
+    /// Apply code that waited for the snippets lock, once it's free
+    fn apply_deferred_code(&mut self) {
+        if !self.code_deferred {
+            return;
+        }
+        let params = self.params.clone();
+        let Some(snippets) = params.snippets.try_read() else {
+            return;
+        };
+        self.code_deferred = false;
+        let code = std::mem::take(&mut self.deferred_code);
+        if let Err(message) = self.send_code(&code, &snippets) {
+            self.code_pending = false;
+            self.send_error(message);
+        }
+        self.deferred_code = code;
+    }
+
+    /// `apply_code()` with the snippets in hand
+    fn send_code(
+        &mut self,
+        user_code: &str,
+        snippets: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        util::permit_alloc(|| {
+            let (expanded, source_map) = preprocess_mapped(user_code, snippets)?;
+
+            // Capture current param values for injection
+            self.update_param_injector();
+            if let Some(names) = self.params.knob_names.try_read() {
+                self.param_injector.knob_names.clone_from(&names);
+            }
+            let injected_code = self.param_injector.inject(&expanded);
+            self.pending_live_params = Some(live_params(&self.param_injector, &expanded));
+            self.source_map = source_map.with_header(
+                injected_code
+                    .lines()
+                    .count()
+                    .saturating_sub(expanded.lines().count()),
+            );
+            let result = self.engine.update_code(&injected_code);
+            self.running_code = injected_code;
+            self.code_generation += 1;
+            result
+        })
+    }
+
+    /// Follow the oversampling factor
+    ///
+    /// Glicol builds its nodes for the sample rate it compiles at, so both
+    /// engines are rebuilt at the new rate on the background thread, with
+    /// the running code compiled in, and swapped in once they're ready.
+    /// Code waiting on its compile result is seen through first.
+    fn update_oversampling(&mut self, context: &mut impl ProcessContext<Self>) {
+        let factor = self.params.core.oversampling.value().factor();
+        if !self.building_engines && !self.code_pending && factor != self.oversampler.factor() {
+            self.building_engines = true;
+            // Comes back with the engines
+            let code = std::mem::take(&mut self.running_code);
+            context.execute_background(Task::BuildEngines(EngineRequest {
+                factor,
+                rate: self.sample_rate * factor as f32,
+                generation: self.code_generation,
+                code,
+            }));
+        }
+
+        let Ok(mut set) = self.engine_receiver.try_recv() else {
+            return;
+        };
+        self.building_engines = false;
+        let up_to_date = set.request.generation == self.code_generation;
+        if up_to_date {
+            std::mem::swap(&mut self.running_code, &mut set.request.code);
+        }
+        // Stale if the factor or the sample rate changed again meanwhile;
+        // the next block asks for new ones
+        if set.request.factor == factor && set.request.rate == self.sample_rate * factor as f32 {
+            self.oversampler.set_factor(factor);
+            self.morph_oversampler.set_factor(factor);
+            std::mem::swap(&mut self.engine, &mut set.engine);
+            std::mem::swap(&mut self.morph_engine, &mut set.morph_engine);
+            // Rebuilt for the new rate by `update_morph()`
+            self.morph_code.clear();
+            self.morph_fresh = true;
+            self.engine_channels = None;
+            if up_to_date {
+                // Compiled on the background thread already
+                let _ = self.engine.take_compile_result();
+            } else {
+                // Code applied while building; its result commits as usual
+                let _ = util::permit_alloc(|| self.engine.update_code(&self.running_code));
+            }
+        }
+        context.execute_background(Task::DropEngines(set));
+    }
+
+    /// Commit or discard pending code once the engine reports the compile outcome
+    fn handle_compile_result(&mut self) {
+        let Some(result) = self.engine.take_compile_result() else {
+            return;
+        };
+
+        let status = match result {
+            Ok(()) => {
+                if let Some(live_params) = self.pending_live_params.take() {
+                    util::permit_alloc(|| self.live_params = live_params);
+                }
+                if std::mem::take(&mut self.code_pending) {
+                    copy_code(&mut self.user_code, &self.pending_code);
+                    self.unsaved_scene = Some(self.active_scene);
+                    self.save_code();
+                }
+                // A working patch ends a panic, unless the panic param
+                // still holds it
+                if !self.panic_param_active {
+                    self.hush.release();
+                }
+                StatusMessage::Success
+            }
+            Err(mut error) => {
+                // Map the engine's line back to the user's code
+                self.source_map.locate(&mut error);
+                self.code_pending = false;
+                util::permit_alloc(|| self.pending_live_params = None);
+                StatusMessage::Error(error)
+            }
+        };
+        let _ = self.status_sender.try_send(status);
+    }
+
+    /// Swap in code for a preview, or back to the committed code
+    ///
+    /// The preview never becomes `pending_code`, so it isn't persisted. Like
+    /// scene switches this is a hot swap, not a crossfade.
+    #[cfg(feature = "editor")]
+    fn audition(&mut self, code: Option<String>) {
+        util::permit_alloc(|| {
+            let code = code.unwrap_or_else(|| self.user_code.clone());
+            self.code_pending = false;
+            let _ = self.apply_code(&code);
+        });
+    }
+
+    /// Load the code stored for `scene`
+    ///
+    /// Switching is a hot swap like any code update (no crossfade). Returns
+    /// false if the GUI holds the scene lock, so the caller can retry.
+    fn switch_scene(&mut self, scene: usize) -> bool {
+        util::permit_alloc(|| {
+            let Some(scenes) = self.params.scenes.try_read() else {
+                return false;
+            };
+            let code = scenes.get(scene).map(|scene| scene.code.clone());
+            drop(scenes);
+
+            self.active_scene = scene;
+            if let Some(code) = code {
+                if self.apply_code(&code).is_ok() {
+                    self.set_pending_code(&code);
+                }
+            }
+            true
+        })
+    }
+
+    /// Take over a linked instance's code for `scene`
+    ///
+    /// The code goes into this instance's scene slot; it runs right away
+    /// if that scene is active, otherwise the scene is selected like a
+    /// program change would and loads it.
+    fn follow_link(&mut self, scene: usize, code: String) {
+        util::permit_alloc(|| {
+            if let Some(mut scenes) = self.params.scenes.try_write() {
+                if let Some(slot) = scenes.get_mut(scene) {
+                    slot.code.clone_from(&code);
+                }
+            }
+        });
+        let upcoming = self
+            .pending_scene
+            .map_or(self.active_scene, |switch| switch.scene);
+        if scene == upcoming {
+            if self.apply_code(&code).is_ok() {
+                self.set_pending_code(&code);
+            }
+        } else {
+            self.midi_overrides.select_scene(&self.params, scene);
+        }
+        util::permit_alloc(|| drop(code));
+    }
+
+    /// Copy committed code into the active scene's slot and the persisted
+    /// code, once the GUI isn't holding either lock (retried every block)
+    fn save_code(&mut self) {
+        let Some(scene) = self.unsaved_scene else {
+            return;
+        };
+        let (Some(mut scenes), Some(mut code)) =
+            (self.params.scenes.try_write(), self.params.code.try_write())
+        else {
+            return;
+        };
+        // The running code belongs to the active scene
+        if let Some(slot) = scenes.get_mut(scene) {
+            copy_code(&mut slot.code, &self.user_code);
+        }
+        copy_code(&mut code, &self.user_code);
+        self.unsaved_scene = None;
+    }
+
+    /// Report code that failed before reaching the engine
+    fn send_error(&self, message: String) {
+        util::permit_alloc(|| {
+            let _ = self
+                .status_sender
+                .try_send(StatusMessage::Error(messages::CompileError {
+                    message,
+                    line: None,
+                    column: None,
+                }));
+        });
+    }
+
+    /// Hold `code` until the engine reports whether it compiled
+    fn set_pending_code(&mut self, code: &str) {
+        copy_code(&mut self.pending_code, code);
+        self.code_pending = true;
+    }
+
+    /// Switch scenes or load a factory preset on a program change
+    ///
+    /// A factory preset's code runs right away; its parameter values are
+    /// set by the editor, since a plugin can't set its own parameters.
+    fn handle_program(&mut self, program: u8) {
+        let params = self.params.clone();
+        let Some(map) = params.midi_map.try_read() else {
+            return;
+        };
+        self.midi_overrides.handle_program(&map, &params, program);
+        let Some(index) = map.preset_for_program(program) else {
+            return;
+        };
+        let code = FACTORY_PRESETS[index].code;
+        if self.apply_code(code).is_ok() {
+            self.set_pending_code(code);
+        }
+        let _ = self
+            .status_sender
+            .try_send(StatusMessage::FactoryPreset { index });
+    }
+
+    /// Record a MIDI learn mapping or apply a CC to its mapped parameters
+    fn handle_midi_cc(&mut self, cc: u8, value: f32) {
+        // The editor writes the mapping; the map is its to lock
+        if let Some(param_id) = self.midi_learn_target.take() {
+            let learned = StatusMessage::MidiLearned { param_id, cc };
+            if let Err(unsent) = self.status_sender.try_send(learned) {
+                util::permit_alloc(|| drop(unsent));
+            }
+        }
+
+        // Never block the audio thread on the GUI editing the map
+        if let Some(map) = self.params.midi_map.try_read() {
+            self.midi_overrides.handle_cc(&map, &self.params, cc, value);
+        }
+    }
+
+    /// Run the audio passes over `range` of the host buffer, at most
+    /// `MAX_BUFFER_SIZE` samples, and return its input and output peaks
+    fn process_chunk(
+        &mut self,
+        buffer: &mut Buffer,
+        sidechain: Option<&[&mut [f32]]>,
+        range: Range<usize>,
+    ) -> (f32, f32) {
+        let order = self.module_order;
+        let (pre_glicol, post_glicol) = order.split();
+
+        let num_samples = range.len();
+        let num_channels = buffer.channels();
+
+        // Only feed the analyzer taps while the editor shows them
+        let taps_enabled = self.telemetry.taps_enabled.load(Ordering::Relaxed);
+
+        // Step 1: Push all input samples to the buffer bridge (through the
+        // pre-Glicol modules)
+        let stereo_input = num_channels >= 2 && !self.mono_input;
+        let input_source = if stereo_input {
+            self.params.core.input_source.value()
+        } else {
+            InputSource::Left
+        };
+        // Pad and polarity sit at the very front, ahead of the input gain
+        let mut front_gain = self.params.core.input_pad.value().gain();
+        if self.params.core.phase_invert.value() {
+            front_gain = -front_gain;
+        }
+        let input_gains = &mut self.ramps.input_gain[..num_samples];
+        self.params
+            .core
+            .input_gain
+            .smoothed
+            .next_block(input_gains, num_samples);
+        let test_source = self.params.core.test_signal.value().kind();
+        let test_mix = self.params.core.test_signal_mix.value();
+        let test_levels = &mut self.ramps.test_level[..num_samples];
+        self.params
+            .core
+            .test_signal_level
+            .smoothed
+            .next_block(test_levels, num_samples);
+        let dry = &mut self.dry_buffer[..num_samples];
+
+        // Pass 1a: bring in the test signal where the instrument enters,
+        // pick the source channels and apply the gain
+        let input = buffer.as_slice_immutable();
+        let left = &input[0][range.clone()];
+        let right = if stereo_input {
+            &input[1][range.clone()]
+        } else {
+            left
+        };
+        for ((((sample, &left), &right), &gain), &test_level) in dry
+            .iter_mut()
+            .zip(left)
+            .zip(right)
+            .zip(&*input_gains)
+            .zip(&*test_levels)
+        {
+            let (left, right) = match test_source {
+                Some(kind) => {
+                    let test = self.test_signal.next(kind) * test_level;
+                    match test_mix {
+                        TestMix::Replace => (test, test),
+                        TestMix::Mix => (left + test, right + test),
+                    }
+                }
+                None => (left, right),
+            };
+            let gain = gain * front_gain;
+            *sample = match input_source {
+                InputSource::Left => StereoSample::from_mono(left * gain),
+                InputSource::Right => StereoSample::from_mono(right * gain),
+                InputSource::Sum => StereoSample::from_mono((left + right) * 0.5 * gain),
+                InputSource::Stereo => StereoSample::new(left * gain, right * gain),
+            };
+        }
+
+        // Pass 1b: meters, envelope followers and the analyzer tap
+        let mut input_peak: f32 = 0.0;
+        for (i, sample) in dry.iter().enumerate() {
+            input_peak = input_peak.max(sample.left.abs()).max(sample.right.abs());
+            let input_mono = sample.to_mono();
+            self.input_meter.add(sample.left, sample.right);
+            self.envelope.process(input_mono);
+            if let Some(channels) = sidechain {
+                let sum: f32 = channels
+                    .iter()
+                    .map(|channel| channel.get(range.start + i).copied().unwrap_or(0.0))
+                    .sum();
+                self.sidechain_envelope
+                    .process(sum / channels.len().max(1) as f32);
+            }
+            if taps_enabled {
+                self.telemetry.input_tap.push(input_mono);
+            }
+        }
+
+        // Pass 1c: pre-Glicol modules, then on to the bridge
+        for &kind in pre_glicol {
+            Self::process_module(&mut self.eq, &mut self.delay, kind, dry);
+        }
+        // Only a stereo source keeps the channels apart
+        if input_source != InputSource::Stereo {
+            for sample in dry.iter_mut() {
+                *sample = StereoSample::from_mono(sample.to_mono());
+            }
+        }
+        for sample in dry.iter() {
+            self.buffer_bridge.push_input(sample.left, sample.right);
+        }
+
+        // Step 2: Process all available Glicol blocks
+        while self.buffer_bridge.has_block() {
+            // Glicol swaps code at block boundaries: switch scenes on the
+            // first block that starts at or after the change, never earlier
+            if let Some(switch) = self.pending_scene {
+                if switch.samples_before == 0 && self.switch_scene(switch.scene) {
+                    self.pending_scene = None;
+                }
+            }
+
+            self.send_live_params();
+            let (input_left, input_right) = self.buffer_bridge.pop_input_block();
+            let mut block_left = [0.0; GLICOL_BLOCK_SIZE];
+            let mut block_right = [0.0; GLICOL_BLOCK_SIZE];
+            // Glicol allocates its input list on every block
+            util::permit_alloc(|| {
+                let (left, right) =
+                    self.oversampler
+                        .process(&mut self.engine, input_left, input_right);
+                block_left.copy_from_slice(left);
+                block_right.copy_from_slice(right);
+            });
+            let channels = self.engine.output_channels();
+            if self.engine_channels != Some(channels) {
+                self.engine_channels = Some(channels);
+                self.telemetry
+                    .diagnostics
+                    .report(Event::EngineChannels { count: channels });
+            }
+
+            // Crossfade toward the other snapshot's code, ramped over the block
+            if self.morph_target > 0.0 || self.morph_mix > 0.0 {
+                self.morph_fresh = false;
+                let mut other_left = [0.0; GLICOL_BLOCK_SIZE];
+                let mut other_right = [0.0; GLICOL_BLOCK_SIZE];
+                util::permit_alloc(|| {
+                    let (left, right) = self.morph_oversampler.process(
+                        &mut self.morph_engine,
+                        input_left,
+                        input_right,
+                    );
+                    other_left.copy_from_slice(left);
+                    other_right.copy_from_slice(right);
+                });
+                let step = (self.morph_target - self.morph_mix) / GLICOL_BLOCK_SIZE as f32;
+                #[allow(clippy::needless_range_loop)] // Index needed for multi-slice access
+                for i in 0..GLICOL_BLOCK_SIZE {
+                    self.morph_mix += step;
+                    block_left[i] += (other_left[i] - block_left[i]) * self.morph_mix;
+                    block_right[i] += (other_right[i] - block_right[i]) * self.morph_mix;
+                }
+                self.morph_mix = self.morph_target;
+            }
+
+            self.buffer_bridge.push_output(&block_left, &block_right);
+
+            if let Some(switch) = &mut self.pending_scene {
+                switch.samples_before = switch.samples_before.saturating_sub(GLICOL_BLOCK_SIZE);
+            }
+        }
+        self.handle_compile_result();
+
+        // Step 3: Pop output samples and write to DAW buffer
+        let output_slices = buffer.as_slice();
+        // 10 ms mute ramp
+        let mute_target = if self.tuner_mute { 0.0 } else { 1.0 };
+        let mute_step = 1.0 / (0.01 * self.sample_rate);
+        // 20 ms bypass crossfade. Everything keeps running underneath, so
+        // tails carry on and re-engaging picks up the live state.
+        let bypass_target = if self.params.bypass.value() { 1.0 } else { 0.0 };
+        let bypass_step = 1.0 / (0.02 * self.sample_rate);
+        let routing = self.params.core.output_routing.value();
+        // The line keeps running while off so switching it on is seamless
+        let match_latency = self.params.core.dry_latency_match.value();
+        let auto_gain = self.params.core.auto_gain.value();
+
+        let ramps = &mut self.ramps;
+        let core = &self.params.core;
+        core.output_gain
+            .smoothed
+            .next_block(&mut ramps.output_gain[..num_samples], num_samples);
+        core.dry_wet
+            .smoothed
+            .next_block(&mut ramps.dry_wet[..num_samples], num_samples);
+        core.width
+            .smoothed
+            .next_block(&mut ramps.width[..num_samples], num_samples);
+        let wet = &mut self.wet_buffer[..num_samples];
+        let dry = &mut self.dry_buffer[..num_samples];
+
+        // Pass 3a: Glicol's output (silence on underrun) through the
+        // post-Glicol modules, the hush fade and the width
+        for sample in wet.iter_mut() {
+            let (left, right) = self.buffer_bridge.pop_output();
+            self.wet_meter.add(left, right);
+            *sample = StereoSample::new(left, right);
+        }
+        for &kind in post_glicol {
+            Self::process_module(&mut self.eq, &mut self.delay, kind, wet);
+        }
+        self.hush.process_block_with_bypass(wet);
+        for (sample, &width) in wet.iter_mut().zip(&ramps.width[..num_samples]) {
+            *sample = sample.with_width(width);
+        }
+
+        // Pass 3b: line the dry signal up with the wet one
+        for sample in dry.iter_mut() {
+            let aligned = self.dry_delay.process(*sample);
+            if match_latency {
+                *sample = aligned;
+            }
+        }
+
+        // Pass 3c: route (normally the dry/wet mix) into the wet buffer and
+        // apply the output gain. Auto gain is always metering, so engaging
+        // it starts from a settled trim.
+        for (((sample, &dry), &dry_wet), &output_gain) in wet
+            .iter_mut()
+            .zip(&*dry)
+            .zip(&ramps.dry_wet[..num_samples])
+            .zip(&ramps.output_gain[..num_samples])
+        {
+            let routed = match routing {
+                OutputRouting::Normal => dry.mix(*sample, dry_wet),
+                OutputRouting::WetOnly => *sample,
+                OutputRouting::DryOnly => dry,
+                OutputRouting::Difference => {
+                    StereoSample::new(sample.left - dry.left, sample.right - dry.right)
+                }
+            };
+            let trim = self.auto_gain.process(dry.to_mono(), routed.to_mono());
+            let output_gain = if auto_gain {
+                output_gain * trim
+            } else {
+                output_gain
+            };
+            *sample = StereoSample::new(routed.left * output_gain, routed.right * output_gain);
+        }
+        self.limiter.process_block_with_bypass(wet);
+
+        // Pass 3d: bypass, mute and write to the host (and the recorder)
+        let record_channels = self.recorder.channels();
+        let mut output_peak: f32 = 0.0;
+        #[allow(clippy::needless_range_loop)] // Index needed for multi-slice access
+        for i in 0..num_samples {
+            let (out_left, out_right) = (wet[i].left, wet[i].right);
+
+            // The untouched input is still in the buffer, since this sample
+            // hasn't been written yet
+            let in_left = output_slices[0][range.start + i];
+            let in_right = if num_channels >= 2 && !self.mono_input {
+                output_slices[1][range.start + i]
+            } else {
+                in_left
+            };
+            // Runs while processing too, so engaging bypass doesn't jump
+            // ahead by the reported latency
+            let bypassed = self
+                .bypass_delay
+                .process(StereoSample::new(in_left, in_right));
+
+            // Soft bypass towards the input
+            self.bypass_mix += (bypass_target - self.bypass_mix).clamp(-bypass_step, bypass_step);
+            let (out_left, out_right) = if self.bypass_mix > 0.0 {
+                let processed = 1.0 - self.bypass_mix;
+                (
+                    out_left * processed + bypassed.left * self.bypass_mix,
+                    out_right * processed + bypassed.right * self.bypass_mix,
+                )
+            } else {
+                (out_left, out_right)
+            };
+            // Meter what the host actually gets
+            let (out_left, out_right) = if self.mono_output {
+                let mono = StereoSample::new(out_left, out_right).to_mono();
+                (mono, mono)
+            } else {
+                (out_left, out_right)
+            };
+
+            output_peak = output_peak.max(out_left.abs()).max(out_right.abs());
+            self.output_meter.add(out_left, out_right);
+            self.correlation.add(out_left, out_right);
+            if taps_enabled {
+                self.telemetry.output_left_tap.push(out_left);
+                self.telemetry.output_right_tap.push(out_right);
+            }
+
+            // Write to output
+            self.mute_gain += (mute_target - self.mute_gain).clamp(-mute_step, mute_step);
+            let (out_left, out_right) = (out_left * self.mute_gain, out_right * self.mute_gain);
+            output_slices[0][range.start + i] = out_left;
+            if num_channels >= 2 {
+                output_slices[1][range.start + i] = out_right;
+            }
+
+            if record_channels > 0 {
+                let frame = &mut self.record_buffer[i * record_channels..][..record_channels];
+                frame[0] = out_left;
+                frame[1] = out_right;
+                if record_channels == 4 {
+                    frame[2] = in_left;
+                    frame[3] = in_right;
+                }
+            }
+        }
+        if record_channels > 0 {
+            self.recorder.push(
+                &self.record_buffer[..num_samples * record_channels],
+                record_channels,
+            );
+        }
+
+        (input_peak, output_peak)
+    }
+
+    /// Run one module of the order over a block (Glicol itself is handled
+    /// by the buffer bridge). Takes the modules rather than `self` so the
+    /// block can be one of the plugin's own buffers.
+    fn process_module(
+        eq: &mut Eq,
+        delay: &mut Delay,
+        kind: ModuleKind,
+        block: &mut [StereoSample],
+    ) {
+        match kind {
+            ModuleKind::Eq => eq.process_block_with_bypass(block),
+            ModuleKind::Delay => delay.process_block_with_bypass(block),
+            ModuleKind::Glicol => {}
+        }
+    }
+
+    fn module_latency(&self, kind: ModuleKind) -> usize {
+        match kind {
+            ModuleKind::Eq => self.eq.latency(),
+            ModuleKind::Delay => self.delay.latency(),
+            ModuleKind::Glicol => BufferBridge::LATENCY + self.oversampler.latency(),
+        }
+    }
+
+    /// Add up the latency of the wet path and set the dry and bypass lines
+    /// to match
+    ///
+    /// All latency accounting lives here. The dry signal is taken after the
+    /// pre-Glicol modules and shares the limiter, so it only makes up Glicol
+    /// and what follows it; the bypass line delays the raw input by the
+    /// whole reported latency.
+    fn update_latency(&mut self) -> u32 {
+        let (pre_glicol, post_glicol) = self.module_order.split();
+        let pre: usize = pre_glicol
+            .iter()
+            .map(|&kind| self.module_latency(kind))
+            .sum();
+        let wet_only: usize = self.module_latency(ModuleKind::Glicol)
+            + post_glicol
+                .iter()
+                .map(|&kind| self.module_latency(kind))
+                .sum::<usize>();
+        self.dry_delay.set_delay(wet_only);
+
+        let total = pre + wet_only + self.limiter.latency();
+        self.bypass_delay.set_delay(total);
+        let total = total as u32;
+        self.telemetry
+            .latency_samples
+            .store(total, Ordering::Relaxed);
+        total
+    }
+
+    /// Recompute macro-driven targets from the macro params
+    fn update_macro_targets(&mut self) {
+        let positions = [
+            self.midi_overrides
+                .float(&self.params, &self.params.modulation.macro1, "macro1"),
+            self.midi_overrides
+                .float(&self.params, &self.params.modulation.macro2, "macro2"),
+        ];
+        // Never block the audio thread on the GUI editing the routes
+        if let Some(map) = self.params.macro_map.try_read() {
+            self.mod_targets.update_macros(&map, positions);
+        }
+    }
+
+    /// Morph parameters and code toward the inactive A/B snapshot
+    ///
+    /// The live state is always one end of the morph, so A/B switching
+    /// behaves as before with the morph at zero.
+    fn update_morph(&mut self, context: &mut impl ProcessContext<Self>) {
+        let morph = self.params.core.morph.value();
+        let params = self.params.clone();
+        // Keep last block's morph while the GUI holds the snapshots
+        let Some(snapshots) = params.ab_snapshots.try_read() else {
+            return;
+        };
+        let other = match snapshots.active {
+            AbSlot::A => snapshots.b.as_ref(),
+            AbSlot::B => snapshots.a.as_ref(),
+        };
+        self.mod_targets
+            .update_morph(other.map(|snapshot| &snapshot.params), morph);
+        self.morph_target = if other.is_some() { morph } else { 0.0 };
+        let idle = self.morph_target == 0.0 && self.morph_mix == 0.0;
+        self.receive_morph_engine(context, idle);
+        if self.building_morph {
+            return;
+        }
+
+        let rate = self.sample_rate * self.oversampler.factor() as f32;
+        let new_code = other
+            .map(|snapshot| snapshot.code.as_str())
+            .filter(|&code| code != self.morph_code);
+        if let Some(code) = new_code {
+            // Built ahead of the morph, so moving it never waits on a compile
+            let Some(snippets) = params.snippets.try_read() else {
+                return;
+            };
+            copy_code(&mut self.morph_code, code);
+            util::permit_alloc(|| {
+                // Code that doesn't preprocess isn't retried until it changes
+                if let Ok(expanded) = preprocess(code, &snippets) {
+                    let injected_code = self.param_injector.inject(&expanded);
+                    self.morph_live_params = live_params(&self.param_injector, &expanded);
+                    self.building_morph = true;
+                    context.execute_background(Task::BuildMorphEngine(MorphRequest {
+                        rate,
+                        code: injected_code,
+                        reset: false,
+                    }));
+                }
+            });
+        } else if idle && !self.morph_fresh {
+            // Glicol can't clear its nodes' state, so a fresh engine stands
+            // in for the next morph instead of last time's delay and reverb
+            // tails. The running code comes back with it.
+            self.building_morph = true;
+            context.execute_background(Task::BuildMorphEngine(MorphRequest {
+                rate,
+                code: std::mem::take(&mut self.morph_running_code),
+                reset: true,
+            }));
+        }
+    }
+
+    /// Swap in a morph engine built on the background thread
+    ///
+    /// A reset engine is turned away if the morph started again meanwhile,
+    /// and any engine built for another sample rate.
+    fn receive_morph_engine(&mut self, context: &mut impl ProcessContext<Self>, idle: bool) {
+        let Ok(mut built) = self.morph_receiver.try_recv() else {
+            return;
+        };
+        self.building_morph = false;
+        let rate = self.sample_rate * self.oversampler.factor() as f32;
+        let accepted = built.request.rate == rate && (idle || !built.request.reset);
+        if accepted {
+            std::mem::swap(&mut self.morph_engine, &mut built.engine);
+            self.morph_oversampler.reset();
+            self.morph_fresh = true;
+        }
+        if accepted || built.request.reset {
+            // A reset borrowed the running code
+            std::mem::swap(&mut self.morph_running_code, &mut built.request.code);
+        }
+        context.execute_background(Task::DropMorphEngine(built));
+    }
+
+    /// Advance the LFOs over this block and recompute the matrix offsets
+    ///
+    /// The envelope is followed sample by sample on the input, so it
+    /// modulates with the level of the previous block.
+    fn update_mod_matrix(&mut self, num_samples: usize, bpm: f32) {
+        let params = &self.params;
+        let settings = [
+            (
+                &params.modulation.lfo1_rate,
+                &params.modulation.lfo1_shape,
+                &params.modulation.lfo1_depth,
+                &params.modulation.lfo1_sync,
+                &params.modulation.lfo1_division,
+            ),
+            (
+                &params.modulation.lfo2_rate,
+                &params.modulation.lfo2_shape,
+                &params.modulation.lfo2_depth,
+                &params.modulation.lfo2_sync,
+                &params.modulation.lfo2_division,
+            ),
+        ];
+        let seconds = num_samples as f32 / self.sample_rate;
+        let mut outputs = [0.0; SOURCE_COUNT];
+        for ((lfo, output), (rate, shape, depth, sync, division)) in
+            self.lfos.iter_mut().zip(outputs.iter_mut()).zip(settings)
+        {
+            *output = lfo.value(shape.value()) * depth.value();
+            let rate_hz = if sync.value() {
+                division.value().rate_hz(bpm)
+            } else {
+                rate.value()
+            };
+            lfo.advance(rate_hz, seconds);
+        }
+        let sensitivity = util::db_to_gain(params.modulation.env_sensitivity.value());
+        outputs[LFO_COUNT] = (self.envelope.level() * sensitivity).min(1.0);
+        // A learned CC overrides the pedal parameter and bypasses its
+        // smoother, so the source output gets its own block-rate smoothing
+        let expr_target = self.midi_overrides.normalized(params, "expr");
+        let expr_coeff = (-seconds / (EXPR_SMOOTHING_MS * 0.001)).exp();
+        self.expr_level = expr_target + (self.expr_level - expr_target) * expr_coeff;
+        outputs[LFO_COUNT + 1] = self.expr_level;
+        self.sidechain_level = (self.sidechain_envelope.level() * sensitivity).min(1.0);
+        outputs[LFO_COUNT + 2] = self.sidechain_level;
+        for envelope in [&mut self.envelope, &mut self.sidechain_envelope] {
+            envelope.set_times(
+                params.modulation.env_attack.value(),
+                params.modulation.env_release.value(),
+            );
+        }
+
+        if let Some(matrix) = params.mod_matrix.try_read() {
+            self.mod_targets.update_matrix(&matrix, outputs);
+        }
     }
 
     /// Update delay module with current parameter values
-    fn update_delay_params(&mut self) {
-        self.delay.set_bypassed(self.params.delay_bypass.value());
-        self.delay.set_time_ms(self.params.delay_time.value());
-        self.delay.set_feedback(self.params.delay_feedback.value());
-        self.delay.set_mix(self.params.delay_mix.value());
-        self.delay.set_highcut(self.params.delay_highcut.value());
+    fn update_delay_params(&mut self, bpm: f32) {
+        let params = &self.params;
+        let modulation = &self.mod_targets;
+        let bypassed = self.midi_overrides.bool(params, "delay_bypass");
+        self.delay.set_bypassed(bypassed);
+        let time_ms = if params.delay.sync.value() {
+            1000.0 / params.delay.division.value().rate_hz(bpm)
+        } else {
+            modulation.value(&params.delay.time, "delay_time")
+        };
+        self.delay.set_time_ms(time_ms);
+        let mut feedback = modulation.value(&params.delay.feedback, "delay_feedback");
+        if params.safe_mode.value() {
+            feedback = feedback.min(SAFE_DELAY_FEEDBACK);
+        }
+        self.delay.set_feedback(feedback);
+        self.delay
+            .set_mix(modulation.value(&params.delay.mix, "delay_mix"));
+        self.delay
+            .set_highcut(modulation.value(&params.delay.highcut, "delay_highcut"));
     }
 
     /// Update EQ module with current parameter values
     fn update_eq_params(&mut self) {
-        self.eq.set_bypassed(self.params.eq_bypass.value());
-        self.eq.set_low_freq(self.params.eq_low_freq.value());
-        self.eq.set_low_gain(self.params.eq_low_gain.value());
-        self.eq.set_mid_freq(self.params.eq_mid_freq.value());
-        self.eq.set_mid_gain(self.params.eq_mid_gain.value());
-        self.eq.set_mid_q(self.params.eq_mid_q.value());
-        self.eq.set_high_freq(self.params.eq_high_freq.value());
-        self.eq.set_high_gain(self.params.eq_high_gain.value());
+        let params = &self.params;
+        let modulation = &self.mod_targets;
+        let bypassed = self.midi_overrides.bool(params, "eq_bypass");
+        self.eq.set_bypassed(bypassed);
+        self.eq.set_low_enabled(params.eq.low_enabled.value());
+        self.eq.set_mid_enabled(params.eq.mid_enabled.value());
+        self.eq.set_high_enabled(params.eq.high_enabled.value());
+        self.eq
+            .set_low_freq(modulation.value(&params.eq.low_freq, "eq_low_freq"));
+        self.eq
+            .set_low_gain(modulation.value(&params.eq.low_gain, "eq_low_gain"));
+        self.eq
+            .set_mid_freq(modulation.value(&params.eq.mid_freq, "eq_mid_freq"));
+        self.eq
+            .set_mid_gain(modulation.value(&params.eq.mid_gain, "eq_mid_gain"));
+        self.eq
+            .set_mid_q(modulation.value(&params.eq.mid_q, "eq_mid_q"));
+        self.eq
+            .set_high_freq(modulation.value(&params.eq.high_freq, "eq_high_freq"));
+        self.eq
+            .set_high_gain(modulation.value(&params.eq.high_gain, "eq_high_gain"));
     }
 }
 
@@ -122,58 +1399,167 @@ impl Plugin for GlicolVerb {
         AudioIOLayout {
             main_input_channels: NonZeroU32::new(1),
             main_output_channels: NonZeroU32::new(2),
+            aux_input_ports: &[new_nonzero_u32(2)],
+            names: SIDECHAIN_PORT_NAMES,
             ..AudioIOLayout::const_default()
         },
         // Stereo input/output as fallback
         AudioIOLayout {
             main_input_channels: NonZeroU32::new(2),
             main_output_channels: NonZeroU32::new(2),
+            aux_input_ports: &[new_nonzero_u32(2)],
+            names: SIDECHAIN_PORT_NAMES,
+            ..AudioIOLayout::const_default()
+        },
+        // Mono input/output (mono FX slots); the stereo result is downmixed
+        AudioIOLayout {
+            main_input_channels: NonZeroU32::new(1),
+            main_output_channels: NonZeroU32::new(1),
+            aux_input_ports: &[new_nonzero_u32(2)],
+            names: SIDECHAIN_PORT_NAMES,
             ..AudioIOLayout::const_default()
         },
     ];
 
-    const MIDI_INPUT: MidiConfig = MidiConfig::None;
+    // `Basic` would drop CCs and program changes
+    const MIDI_INPUT: MidiConfig = MidiConfig::MidiCCs;
     const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
 
     const SAMPLE_ACCURATE_AUTOMATION: bool = true;
 
     type SysExMessage = ();
-    type BackgroundTask = ();
+    type BackgroundTask = Task;
 
     fn params(&self) -> Arc<dyn Params> {
         self.params.clone()
     }
 
+    fn task_executor(&mut self) -> TaskExecutor<Self> {
+        let engine_sender = self.engine_sender.clone();
+        let morph_sender = self.morph_sender.clone();
+        Box::new(move |task| match task {
+            Task::BuildEngines(request) => {
+                let _ = engine_sender.try_send(build_engines(request));
+            }
+            Task::DropEngines(set) => drop(set),
+            Task::BuildMorphEngine(request) => {
+                let engine = compiled_engine(request.rate, &request.code);
+                let _ = morph_sender.try_send(MorphEngine { request, engine });
+            }
+            Task::DropMorphEngine(morph) => drop(morph),
+        })
+    }
+
+    #[cfg(feature = "editor")]
     fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
         // Take the code sender to give to the editor
         let code_sender = self.code_sender.take()?;
-        editor::create(self.params.clone(), code_sender)
+        let status_receiver = self.status_receiver.take()?;
+        editor::create(
+            self.params.clone(),
+            code_sender,
+            status_receiver,
+            self.telemetry.clone(),
+            self.recorder.clone(),
+            self.link.clone(),
+            self.api.clone(),
+            self.tempo_link.clone(),
+        )
+    }
+
+    /// Headless build: hosts get no editor
+    #[cfg(not(feature = "editor"))]
+    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+        None
+    }
+
+    fn filter_state(state: &mut PluginState) {
+        let saved = state::migrate_fields(&mut state.fields);
+        if saved < state::SESSION_STATE_VERSION {
+            nih_log!(
+                "Migrated session state from version {} to {}",
+                saved,
+                state::SESSION_STATE_VERSION
+            );
+        }
     }
 
     fn initialize(
         &mut self,
-        _audio_io_layout: &AudioIOLayout,
+        audio_io_layout: &AudioIOLayout,
         buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
+        context: &mut impl InitContext<Self>,
     ) -> bool {
         self.sample_rate = buffer_config.sample_rate;
+        self.mono_input = audio_io_layout.main_input_channels.map(NonZeroU32::get) == Some(1);
+        self.mono_output = audio_io_layout.main_output_channels.map(NonZeroU32::get) == Some(1);
 
-        // Configure engine for DAW sample rate
-        self.engine.set_sample_rate(buffer_config.sample_rate);
+        // Configure engine for DAW sample rate (times the oversampling)
+        let factor = self.params.core.oversampling.value().factor();
+        self.oversampler.set_factor(factor);
+        self.morph_oversampler.set_factor(factor);
+        let engine_rate = buffer_config.sample_rate * factor as f32;
+        self.engine.set_sample_rate(engine_rate);
+        self.morph_engine.set_sample_rate(engine_rate);
 
         // Configure DSP modules
         self.eq.set_sample_rate(buffer_config.sample_rate);
         self.update_eq_params();
         self.delay.set_sample_rate(buffer_config.sample_rate);
-        self.update_delay_params();
+        self.update_delay_params(FALLBACK_BPM);
+        self.hush.set_sample_rate(buffer_config.sample_rate);
+        self.limiter.set_sample_rate(buffer_config.sample_rate);
+        self.auto_gain.set_sample_rate(buffer_config.sample_rate);
+        self.tail.set_sample_rate(buffer_config.sample_rate);
+        self.safe_mode_active = self.params.safe_mode.value();
+        self.limiter.set_bypassed(!self.safe_mode_active);
+        self.envelope.set_sample_rate(buffer_config.sample_rate);
+        self.sidechain_envelope
+            .set_sample_rate(buffer_config.sample_rate);
+        self.input_meter.set_sample_rate(buffer_config.sample_rate);
+        self.wet_meter.set_sample_rate(buffer_config.sample_rate);
+        self.output_meter.set_sample_rate(buffer_config.sample_rate);
+        self.correlation.set_sample_rate(buffer_config.sample_rate);
+        self.telemetry.sample_rate.store(buffer_config.sample_rate);
 
-        // Initialize with code from params (for state restoration)
-        self.user_code = self.params.code.read().clone();
+        // Test signal loop, and the session's sample if it isn't loaded yet
+        self.test_signal.set_sample_rate(buffer_config.sample_rate);
+        let sample_path = self.params.test_sample.read().clone();
+        if !sample_path.is_empty() && !self.test_signal.has_sample() {
+            match TestSample::load(std::path::Path::new(&sample_path)) {
+                Ok(sample) => {
+                    self.test_signal.set_sample(Some(sample));
+                }
+                Err(error) => nih_log!("{}", error),
+            }
+        }
 
-        // Inject current param values and update engine
-        self.update_param_injector();
-        let injected_code = self.param_injector.inject(&self.user_code);
-        let _ = self.engine.update_code(&injected_code);
+        if self.params.api.read().enabled {
+            if let Err(error) = self.api.start() {
+                nih_log!("{}", error);
+            }
+        }
+        // Without a host transport, the standalone app takes the Link tempo
+        if context.plugin_api() == PluginApi::Standalone {
+            self.tempo_link.start();
+        }
+
+        self.module_order = self.params.module_chain.read().order;
+        self.reported_latency = self.update_latency();
+        context.set_latency_samples(self.reported_latency);
+
+        // Initialize with code from params (for state restoration)
+        self.active_scene = self.params.scene.value().to_index();
+        self.pending_scene = None;
+        self.unsaved_scene = None;
+        // Reserve the code strings the audio thread writes
+        for scene in self.params.scenes.write().iter_mut() {
+            reserve_code(&mut scene.code);
+        }
+        reserve_code(&mut self.params.code.write());
+        copy_code(&mut self.user_code, &self.params.code.read());
+        let user_code = self.user_code.clone();
+        let _ = self.apply_code(&user_code);
 
         true
     }
@@ -181,148 +1567,241 @@ impl Plugin for GlicolVerb {
     fn reset(&mut self) {
         // Clear buffers on transport stop/start
         self.buffer_bridge.clear();
+        if let Some(switch) = &mut self.pending_scene {
+            switch.samples_before = 0;
+        }
         self.engine.reset();
+        self.morph_engine.reset();
+        self.oversampler.reset();
+        self.morph_oversampler.reset();
+        self.morph_mix = 0.0;
         self.eq.reset();
         self.delay.reset();
+        self.hush.reset();
+        self.limiter.reset();
+        self.auto_gain.reset();
+        self.tail.reset();
+        self.test_signal.reset();
+        for lfo in &mut self.lfos {
+            lfo.reset();
+        }
+        self.envelope.reset();
+        self.sidechain_envelope.reset();
+        self.sidechain_level = 0.0;
+        self.dry_delay.reset();
+        self.bypass_delay.reset();
+        self.input_meter.reset();
+        self.wet_meter.reset();
+        self.output_meter.reset();
+        self.correlation.reset();
+        // Start where the bypass switch is instead of fading in
+        self.bypass_mix = if self.params.bypass.value() { 1.0 } else { 0.0 };
     }
 
     fn process(
         &mut self,
         buffer: &mut Buffer,
-        _aux: &mut AuxiliaryBuffers,
-        _context: &mut impl ProcessContext<Self>,
+        aux: &mut AuxiliaryBuffers,
+        context: &mut impl ProcessContext<Self>,
     ) -> ProcessStatus {
+        let process_start = std::time::Instant::now();
+
         // Check for new code from GUI
         while let Ok(msg) = self.code_receiver.try_recv() {
             match msg {
                 CodeMessage::UpdateCode(new_code) => {
-                    // Capture current param values for injection
-                    self.update_param_injector();
-
-                    // Inject param definitions and try to update the engine
-                    let injected_code = self.param_injector.inject(&new_code);
-                    if self.engine.update_code(&injected_code).is_ok() {
-                        self.user_code = new_code.clone();
-                        // Update persisted code for state saving
-                        *self.params.code.write() = new_code;
+                    match self.apply_code(&new_code) {
+                        // Committed once the engine confirms it compiled
+                        Ok(()) => self.set_pending_code(&new_code),
+                        Err(message) => self.send_error(message),
                     }
                     // On error, old code keeps running
+                    util::permit_alloc(|| drop(new_code));
+                }
+                #[cfg(feature = "editor")]
+                CodeMessage::Panic { revert_code } => {
+                    self.hush.trigger();
+                    self.revert_on_silence = revert_code;
+                }
+                #[cfg(feature = "editor")]
+                CodeMessage::MidiLearn(target) => {
+                    // Dropping a previous target frees its string
+                    util::permit_alloc(|| self.midi_learn_target = target);
+                }
+                #[cfg(feature = "editor")]
+                CodeMessage::TunerMute(mute) => self.tuner_mute = mute,
+                #[cfg(feature = "editor")]
+                CodeMessage::Audition(code) => self.audition(code),
+                CodeMessage::Link { scene, code } => self.follow_link(scene, code),
+                #[cfg(feature = "editor")]
+                CodeMessage::TestSample(sample) => {
+                    let old = self.test_signal.set_sample(sample);
+                    util::permit_alloc(|| drop(old));
                 }
             }
         }
+        self.apply_deferred_code();
 
-        // Update DSP module parameters
-        self.update_eq_params();
-        self.update_delay_params();
-
-        // Collect input samples and dry signal for mixing
-        let num_samples = buffer.samples();
-        let num_channels = buffer.channels();
-
-        // Ensure we don't exceed our pre-allocated buffer
-        let num_samples = num_samples.min(MAX_BUFFER_SIZE);
+        while let Some(event) = context.next_event() {
+            match event {
+                NoteEvent::MidiCC { cc, value, .. } => self.handle_midi_cc(cc, value),
+                NoteEvent::MidiProgramChange { program, .. } => self.handle_program(program),
+                _ => {}
+            }
+        }
 
-        // Step 1: Push all input samples to the buffer bridge (through EQ)
-        for i in 0..num_samples {
-            let input_gain = self.params.input_gain.smoothed.next();
+        // Scene param changed (automation, the editor's tabs or a program
+        // change). Automation splits the buffer at the change, so everything
+        // already queued for Glicol came before it.
+        let scene = self.midi_overrides.scene(&self.params);
+        let upcoming = self
+            .pending_scene
+            .map_or(self.active_scene, |switch| switch.scene);
+        if scene != upcoming {
+            self.pending_scene = (scene != self.active_scene).then(|| SceneSwitch {
+                scene,
+                samples_before: self.buffer_bridge.input_pending(),
+            });
+        }
 
-            // Get mono input (average if stereo)
-            let input_sample = if num_channels >= 2 {
-                let left = buffer.as_slice()[0][i];
-                let right = buffer.as_slice()[1][i];
-                (left + right) * 0.5
+        // Panic param: hush while on, release when turned off
+        let panic = self.params.panic.value();
+        if panic != self.panic_param_active {
+            self.panic_param_active = panic;
+            if panic {
+                self.hush.trigger();
             } else {
-                buffer.as_slice()[0][i]
-            };
-
-            let input_with_gain = input_sample * input_gain;
-
-            // Process through EQ (mono expanded to stereo, take left channel)
-            let eq_input = StereoSample::new(input_with_gain, input_with_gain);
-            let eq_output = self.eq.process_with_bypass(eq_input);
-            let eq_mono = eq_output.left; // EQ is stereo-linked, so left == right
-
-            self.dry_buffer[i] = eq_mono;
-
-            self.buffer_bridge.push_input(eq_mono);
+                self.hush.release();
+            }
         }
 
-        // Step 2: Process all available Glicol blocks
-        let mut blocks_processed = 0;
-        while self.buffer_bridge.has_block() {
-            let input_block = self.buffer_bridge.pop_input_block();
-            let (left, right) = self.engine.process(input_block);
-            self.buffer_bridge.push_output(left, right);
-            blocks_processed += 1;
+        // Safe mode clamps injected values, so re-inject when it flips
+        let safe_mode = self.params.safe_mode.value();
+        if safe_mode != self.safe_mode_active {
+            self.safe_mode_active = safe_mode;
+            self.limiter.set_bypassed(!safe_mode);
+            util::permit_alloc(|| {
+                let user_code = self.user_code.clone();
+                let _ = self.apply_code(&user_code);
+            });
         }
 
-        // Debug: log every ~1 second (assuming 44100 Hz, ~344 calls at 128 samples)
-        static DEBUG_COUNTER: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
-        let count = DEBUG_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        if count.is_multiple_of(344) {
-            let input_max = self.dry_buffer[..num_samples]
-                .iter()
-                .map(|x| x.abs())
-                .fold(0.0f32, f32::max);
-            let output_avail = self.buffer_bridge.output_available();
-            eprintln!(
-                "[GlicolVerb] samples={}, blocks={}, input_max={:.4}, output_avail={}, code='{}'",
-                num_samples,
-                blocks_processed,
-                input_max,
-                output_avail,
-                &self.user_code[..self.user_code.len().min(30)]
-            );
+        // Update DSP module parameters
+        self.update_macro_targets();
+        self.update_morph(context);
+        // The Link session's tempo (standalone) or the host's
+        let tempo = self
+            .tempo_link
+            .audio_tempo(&mut self.link_session)
+            .or(context.transport().tempo);
+        let bpm = tempo.map_or(FALLBACK_BPM, |tempo| tempo as f32);
+        self.engine.set_bpm(bpm);
+        self.morph_engine.set_bpm(bpm);
+        self.update_mod_matrix(buffer.samples(), bpm);
+        self.update_eq_params();
+        self.update_delay_params(bpm);
+        if let Some(chain) = self.params.module_chain.try_read() {
+            self.module_order = chain.order;
         }
+        self.update_oversampling(context);
+        let latency = self.update_latency();
+        if latency != self.reported_latency {
+            // The delay lines' contents belong to the old alignment
+            self.dry_delay.reset();
+            self.bypass_delay.reset();
+            self.reported_latency = latency;
+            context.set_latency_samples(latency);
+        }
+        // Glicol's own tails (reverb nodes) are followed by the output level
+        self.tail
+            .set_decay(self.eq.tail() + self.delay.tail() + latency as usize);
 
-        // Step 3: Pop output samples and write to DAW buffer
-        let output_slices = buffer.as_slice();
-        let mut wet_max: f32 = 0.0;
-        let mut out_max: f32 = 0.0;
-
-        #[allow(clippy::needless_range_loop)] // Index needed for multi-slice access
-        for i in 0..num_samples {
-            let output_gain = self.params.output_gain.smoothed.next();
-            let dry_wet = self.params.dry_wet.smoothed.next();
-
-            // Get wet sample from Glicol output (may be 0 if buffer underrun)
-            let (wet_left, wet_right) = self.buffer_bridge.pop_output();
-
-            // Process through delay module (post-Glicol)
-            let glicol_out = StereoSample::new(wet_left, wet_right);
-            let delayed = self.delay.process_with_bypass(glicol_out);
+        // Hosts without sidechain routing leave the aux bus silent
+        let sidechain = aux.inputs.first().map(|buffer| buffer.as_slice_immutable());
 
-            let dry = self.dry_buffer[i];
+        // Longer host buffers (offline renders with big blocks) are split
+        // into chunks that fit the scratch buffers and the bridge
+        let num_samples = buffer.samples();
+        let (mut input_peak, mut output_peak) = (0.0_f32, 0.0_f32);
+        let mut chunk_start = 0;
+        while chunk_start < num_samples {
+            let chunk_end = (chunk_start + MAX_BUFFER_SIZE).min(num_samples);
+            let (chunk_input, chunk_output) =
+                self.process_chunk(buffer, sidechain, chunk_start..chunk_end);
+            input_peak = input_peak.max(chunk_input);
+            output_peak = output_peak.max(chunk_output);
+            chunk_start = chunk_end;
+        }
+        // Committed code the GUI kept locked last time
+        self.save_code();
 
-            wet_max = wet_max.max(delayed.left.abs()).max(delayed.right.abs());
+        self.input_meter.publish(&self.telemetry.input);
+        self.wet_meter.publish(&self.telemetry.wet);
+        self.output_meter.publish(&self.telemetry.output);
+        self.telemetry
+            .correlation
+            .store(self.correlation.correlation());
+        self.telemetry
+            .underruns
+            .store(self.buffer_bridge.underrun_count(), Ordering::Relaxed);
+        self.telemetry.auto_gain_db.store(self.auto_gain.trim_db());
+        self.telemetry.tempo.store(tempo.unwrap_or(0.0) as f32);
 
-            // Mix dry/wet and apply output gain
-            let out_left = (dry * (1.0 - dry_wet) + delayed.left * dry_wet) * output_gain;
-            let out_right = (dry * (1.0 - dry_wet) + delayed.right * dry_wet) * output_gain;
+        // Panic fade finished: flush everything that could still be ringing
+        if self.hush.take_silenced() {
+            self.buffer_bridge.clear();
+            self.engine.reset();
+            self.morph_engine.reset();
+            self.delay.reset();
 
-            out_max = out_max.max(out_left.abs()).max(out_right.abs());
+            // Committed like any code update, including the active scene's
+            // slot; the compile result releases the hush
+            if std::mem::take(&mut self.revert_on_silence)
+                && self.apply_code(PASSTHROUGH_CODE).is_ok()
+            {
+                self.set_pending_code(PASSTHROUGH_CODE);
+            }
+        }
 
-            // Write to output
-            output_slices[0][i] = out_left;
-            if num_channels >= 2 {
-                output_slices[1][i] = out_right;
+        let buffer_secs = num_samples as f32 / self.sample_rate;
+        let cpu_load = self
+            .cpu_meter
+            .update(process_start.elapsed().as_secs_f32(), buffer_secs);
+        self.telemetry.cpu_load.store(cpu_load);
+        if (cpu_load > 1.0) != self.overloaded {
+            self.overloaded = cpu_load > 1.0;
+            if self.overloaded {
+                self.telemetry
+                    .diagnostics
+                    .report(Event::Overload { load: cpu_load });
             }
         }
 
-        // Log output levels
-        if count % 344 == 1 {
-            eprintln!(
-                "[GlicolVerb] wet_max={:.4}, out_max={:.4}, dry_wet={:.2}",
-                wet_max,
-                out_max,
-                self.params.dry_wet.value()
-            );
+        // Underruns: at most one report a second while they keep happening
+        self.samples_since_underrun_report = self
+            .samples_since_underrun_report
+            .saturating_add(num_samples as u32);
+        let underruns = self.buffer_bridge.underrun_count();
+        if underruns != self.reported_underruns
+            && self.samples_since_underrun_report as f32 >= self.sample_rate
+        {
+            self.telemetry
+                .diagnostics
+                .report(Event::Underrun { total: underruns });
+            self.reported_underruns = underruns;
+            self.samples_since_underrun_report = 0;
         }
 
-        ProcessStatus::Normal
+        // Keep the host from suspending the plugin while echoes ring out
+        match self.tail.update(input_peak, output_peak, num_samples) {
+            TailState::Silent => ProcessStatus::Normal,
+            TailState::Decaying(samples) => ProcessStatus::Tail(samples),
+            TailState::Generating => ProcessStatus::KeepAlive,
+        }
     }
 }
 
+#[cfg(feature = "vst3")]
 impl Vst3Plugin for GlicolVerb {
     const VST3_CLASS_ID: [u8; 16] = *b"GlicolVerb__0001";
     const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = &[
@@ -330,21 +1809,97 @@ impl Vst3Plugin for GlicolVerb {
         Vst3SubCategory::Distortion,
         Vst3SubCategory::Filter,
         Vst3SubCategory::Delay,
+        Vst3SubCategory::Reverb,
     ];
 }
 
+#[cfg(feature = "clap")]
 impl ClapPlugin for GlicolVerb {
     const CLAP_ID: &'static str = "com.glicol-verb.glicol-verb";
     const CLAP_DESCRIPTION: Option<&'static str> = Some("Live-coding guitar effects pedal");
-    const CLAP_MANUAL_URL: Option<&'static str> = None;
+    const CLAP_MANUAL_URL: Option<&'static str> = Some(Self::URL);
     const CLAP_SUPPORT_URL: Option<&'static str> = None;
     const CLAP_FEATURES: &'static [ClapFeature] = &[
         ClapFeature::AudioEffect,
+        ClapFeature::Stereo,
+        ClapFeature::Mono,
         ClapFeature::Filter,
         ClapFeature::Distortion,
         ClapFeature::Delay,
+        ClapFeature::Reverb,
     ];
 }
 
+/// Injected chains referenced by `expanded` code, with the values injected
+/// into it
+fn live_params(injector: &ParamInjector, expanded: &str) -> Vec<(String, usize, f32)> {
+    let values = injector.values();
+    injector
+        .references(expanded)
+        .into_iter()
+        .map(|(name, index)| (name, index, values[index]))
+        .collect()
+}
+
+/// Append a message for each chain in `live_params` whose value changed,
+/// and note the value as sent
+fn write_live_messages(
+    messages: &mut String,
+    live_params: &mut [(String, usize, f32)],
+    values: &[f32],
+) {
+    for (name, index, sent) in live_params {
+        let value = values[*index];
+        if value != *sent {
+            write_message(messages, name, value);
+            *sent = value;
+        }
+    }
+}
+
+/// Build both engines for `request` (background thread)
+fn build_engines(request: EngineRequest) -> EngineSet {
+    EngineSet {
+        engine: compiled_engine(request.rate, &request.code),
+        morph_engine: GlicolWrapper::new(request.rate),
+        request,
+    }
+}
+
+/// A new engine at `rate` running `code` (background thread)
+///
+/// Glicol compiles on the next block it processes, so the engine runs one
+/// silent block to have the code compiled before it reaches the audio
+/// thread. The compile result is left for the audio thread to take.
+fn compiled_engine(rate: f32, code: &str) -> GlicolWrapper {
+    let mut engine = GlicolWrapper::new(rate);
+    if !code.is_empty() {
+        let _ = engine.update_code(code);
+        let silence = [0.0; GLICOL_BLOCK_SIZE];
+        engine.process(&silence, &silence);
+        engine.reset();
+    }
+    engine
+}
+
+/// Grow a code string to `CODE_CAPACITY`, off the audio thread
+fn reserve_code(code: &mut String) {
+    code.reserve(CODE_CAPACITY.saturating_sub(code.len()));
+}
+
+/// Overwrite `target` with `code`, in place when it fits
+///
+/// Only code longer than the target's capacity (`CODE_CAPACITY`, or less
+/// if the GUI replaced the string) allocates on the audio thread.
+fn copy_code(target: &mut String, code: &str) {
+    if code.len() > target.capacity() {
+        util::permit_alloc(|| target.reserve(code.len()));
+    }
+    target.clear();
+    target.push_str(code);
+}
+
+#[cfg(feature = "vst3")]
 nih_export_vst3!(GlicolVerb);
+#[cfg(feature = "clap")]
 nih_export_clap!(GlicolVerb);
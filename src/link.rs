@@ -0,0 +1,323 @@
+//! Link mode: instances sharing code and scene
+//!
+//! Instances that opt in and pick the same link group pass applied code
+//! and scene changes to each other, whether they run in one host process
+//! or in separate ones (hosts that sandbox each plugin). Every instance
+//! listens on a loopback port and lists it, with its settings, in a
+//! registry directory under the temp dir; publishing sends the code to
+//! each listed peer in the same group. The listener thread forwards what
+//! arrives into its own audio thread's message queue, so a linked instance
+//! follows even while its editor is closed.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::messages::CodeMessage;
+
+/// Number of link groups offered in the editor
+#[cfg(feature = "editor")]
+pub const LINK_GROUPS: u8 = 4;
+
+/// First line of every message, so stray connections are ignored
+const MAGIC: &[u8] = b"glicol-verb-link 1\n";
+
+/// Largest message accepted (code is small)
+const MAX_MESSAGE: u64 = 256 * 1024;
+
+/// Longest a peer may take to accept or send a message
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the idle listener checks whether it should stop
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often the registry entry is rewritten even if the settings didn't
+/// change (a peer may have removed it after a failed send)
+const REGISTER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Link options (persisted)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkSettings {
+    pub enabled: bool,
+    /// Only instances in the same group follow each other
+    pub group: u8,
+}
+
+/// What one instance sends another
+#[derive(Serialize, Deserialize)]
+struct LinkMessage {
+    group: u8,
+    scene: usize,
+    code: String,
+}
+
+/// An instance's membership, left when dropped
+pub struct LinkHandle {
+    settings: Arc<RwLock<LinkSettings>>,
+    /// Port this instance listens on, `None` if it couldn't listen (it can
+    /// still publish)
+    port: Option<u16>,
+    stop: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl LinkHandle {
+    pub fn join(settings: Arc<RwLock<LinkSettings>>, sender: Sender<CodeMessage>) -> Self {
+        let mut handle = Self {
+            settings,
+            port: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            listener: None,
+        };
+        let Ok(listener) = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) else {
+            return handle;
+        };
+        let Ok(port) = listener.local_addr().map(|addr| addr.port()) else {
+            return handle;
+        };
+        if listener.set_nonblocking(true).is_err() {
+            return handle;
+        }
+        let entry = registry_dir().join(port.to_string());
+        // Listed before returning, so peers see this instance right away
+        register(&entry, *handle.settings.read());
+
+        let listen = Listener {
+            listener,
+            entry,
+            settings: handle.settings.clone(),
+            sender,
+        };
+        let stop = handle.stop.clone();
+        handle.listener = thread::Builder::new()
+            .name("glicolverb-link".to_string())
+            .spawn(move || listen.run(&stop))
+            .ok();
+        if handle.listener.is_some() {
+            handle.port = Some(port);
+        } else {
+            let _ = std::fs::remove_file(registry_dir().join(port.to_string()));
+        }
+        handle
+    }
+
+    /// Send code running in `scene` to the linked instances (editor and API
+    /// threads)
+    ///
+    /// Used both when code is applied and when the scene changes.
+    pub fn publish(&self, scene: usize, code: &str) {
+        let settings = *self.settings.read();
+        if !settings.enabled {
+            return;
+        }
+        let Ok(message) = serde_json::to_vec(&LinkMessage {
+            group: settings.group,
+            scene,
+            code: code.to_string(),
+        }) else {
+            return;
+        };
+        for port in self.peers() {
+            if let Err(e) = send(port, &message) {
+                // Nothing listens there any more: the instance crashed
+                if e.kind() == ErrorKind::ConnectionRefused {
+                    let _ = std::fs::remove_file(registry_dir().join(port.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Number of other instances following this one
+    #[cfg(feature = "editor")]
+    pub fn peer_count(&self) -> usize {
+        self.peers().len()
+    }
+
+    /// Ports of the other enabled instances in this instance's group
+    fn peers(&self) -> Vec<u16> {
+        let settings = *self.settings.read();
+        if !settings.enabled {
+            return Vec::new();
+        }
+        let Ok(entries) = std::fs::read_dir(registry_dir()) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let port = entry.file_name().to_str()?.parse::<u16>().ok()?;
+                let listed = std::fs::read(entry.path()).ok()?;
+                let peer_settings: LinkSettings = serde_json::from_slice(&listed).ok()?;
+                (Some(port) != self.port && peer_settings == settings).then_some(port)
+            })
+            .collect()
+    }
+}
+
+impl Drop for LinkHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+/// Registry of listening instances: one file per port holding that
+/// instance's settings (per user, since the temp dir may be shared)
+fn registry_dir() -> PathBuf {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let user: String = user.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    std::env::temp_dir().join(format!("glicol-verb-link-{}", user))
+}
+
+/// List this instance's port with its current settings
+fn register(entry: &Path, settings: LinkSettings) {
+    if let Some(dir) = entry.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_vec(&settings) {
+        let _ = std::fs::write(entry, json);
+    }
+}
+
+/// Deliver one message to the instance listening on `port`
+fn send(port: u16, message: &[u8]) -> std::io::Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    stream.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+    stream.write_all(MAGIC)?;
+    stream.write_all(message)
+}
+
+/// Listener thread state
+struct Listener {
+    listener: TcpListener,
+    /// This instance's registry file
+    entry: PathBuf,
+    settings: Arc<RwLock<LinkSettings>>,
+    sender: Sender<CodeMessage>,
+}
+
+impl Listener {
+    /// Accept messages until stopped, keeping the registry entry current,
+    /// then unlist this instance
+    fn run(self, stop: &AtomicBool) {
+        let mut registered = *self.settings.read();
+        let mut last_register = Instant::now();
+        while !stop.load(Ordering::Acquire) {
+            let settings = *self.settings.read();
+            if settings != registered || last_register.elapsed() >= REGISTER_INTERVAL {
+                register(&self.entry, settings);
+                registered = settings;
+                last_register = Instant::now();
+            }
+            match self.listener.accept() {
+                Ok((stream, _)) => self.receive(stream),
+                // Nothing waiting (or a failed accept): look again shortly
+                Err(_) => thread::sleep(ACCEPT_INTERVAL),
+            }
+        }
+        let _ = std::fs::remove_file(&self.entry);
+    }
+
+    /// Pass a peer's code on to the audio thread if this instance still
+    /// follows that group
+    fn receive(&self, mut stream: TcpStream) {
+        let Some(message) = read_message(&mut stream) else {
+            return;
+        };
+        let settings = *self.settings.read();
+        if settings.enabled && settings.group == message.group {
+            let _ = self.sender.try_send(CodeMessage::Link {
+                scene: message.scene,
+                code: message.code,
+            });
+        }
+    }
+}
+
+fn read_message(stream: &mut TcpStream) -> Option<LinkMessage> {
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(READ_TIMEOUT)).ok()?;
+    let mut bytes = Vec::new();
+    stream.take(MAX_MESSAGE).read_to_end(&mut bytes).ok()?;
+    serde_json::from_slice(bytes.strip_prefix(MAGIC)?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::{bounded, Receiver};
+
+    // Tests share the registry with every other instance on the machine,
+    // so each uses its own groups
+
+    const DELIVERY: Duration = Duration::from_secs(2);
+
+    fn member(group: u8, enabled: bool) -> (LinkHandle, Receiver<CodeMessage>) {
+        let (sender, receiver) = bounded(4);
+        let settings = Arc::new(RwLock::new(LinkSettings { enabled, group }));
+        (LinkHandle::join(settings, sender), receiver)
+    }
+
+    #[test]
+    fn test_group_members_follow() {
+        let (a, a_rx) = member(100, true);
+        let (_b, b_rx) = member(100, true);
+        let (_other, other_rx) = member(101, true);
+        let (_off, off_rx) = member(100, false);
+
+        a.publish(2, "out: ~input");
+        assert!(matches!(
+            b_rx.recv_timeout(DELIVERY),
+            Ok(CodeMessage::Link { scene: 2, code }) if code == "out: ~input"
+        ));
+        assert!(a_rx.try_recv().is_err());
+        assert!(other_rx.try_recv().is_err());
+        assert!(off_rx.try_recv().is_err());
+        #[cfg(feature = "editor")]
+        assert_eq!(a.peer_count(), 1);
+    }
+
+    #[test]
+    fn test_disabled_instance_publishes_nothing() {
+        let (a, _a_rx) = member(110, false);
+        let (_b, b_rx) = member(110, true);
+        a.publish(0, "out: ~input");
+        assert!(b_rx.recv_timeout(Duration::from_millis(300)).is_err());
+    }
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn test_dropped_member_leaves() {
+        let (a, _a_rx) = member(120, true);
+        let (b, _b_rx) = member(120, true);
+        assert_eq!(a.peer_count(), 1);
+        drop(b);
+        assert_eq!(a.peer_count(), 0);
+    }
+
+    #[test]
+    fn test_stray_connections_are_ignored() {
+        let (a, a_rx) = member(130, true);
+        let port = a.port.unwrap();
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\n\r\n{\"group\":130,\"scene\":0,\"code\":\"\"}")
+            .unwrap();
+        drop(stream);
+        assert!(a_rx.recv_timeout(Duration::from_millis(300)).is_err());
+    }
+}
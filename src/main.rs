@@ -0,0 +1,8 @@
+//! Standalone app: `cargo run --release --features standalone`
+
+use glicol_verb::GlicolVerb;
+use nih_plug::prelude::*;
+
+fn main() {
+    nih_export_standalone::<GlicolVerb>();
+}
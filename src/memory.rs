@@ -0,0 +1,115 @@
+//! Process memory readout
+//!
+//! Resident memory of the process the plugin runs in. The plugin's own
+//! share can't be told apart from the host's, so this is a trend to watch
+//! while a patch runs rather than its exact cost. Read by the editor and
+//! the HTTP API, never on the audio thread.
+
+/// Resident set size of this process in bytes
+#[cfg(target_os = "linux")]
+pub fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+/// Resident set size of this process in bytes
+#[cfg(target_os = "macos")]
+pub fn resident_bytes() -> Option<u64> {
+    // `mach_task_basic_info` from <mach/task_info.h> (only read in part)
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Default)]
+    struct MachTaskBasicInfo {
+        virtual_size: u64,
+        resident_size: u64,
+        resident_size_max: u64,
+        user_time: [i32; 2],
+        system_time: [i32; 2],
+        policy: i32,
+        suspend_count: i32,
+    }
+    const MACH_TASK_BASIC_INFO: u32 = 20;
+    extern "C" {
+        static mach_task_self_: u32;
+        fn task_info(task: u32, flavor: u32, info: *mut MachTaskBasicInfo, count: *mut u32) -> i32;
+    }
+
+    let mut info = MachTaskBasicInfo::default();
+    // Counted in 32-bit words
+    let mut count = (std::mem::size_of::<MachTaskBasicInfo>() / 4) as u32;
+    // SAFETY: `info` is a `mach_task_basic_info` and `count` its size
+    let result = unsafe { task_info(mach_task_self_, MACH_TASK_BASIC_INFO, &mut info, &mut count) };
+    (result == 0).then_some(info.resident_size)
+}
+
+/// Working set of this process in bytes
+#[cfg(windows)]
+pub fn resident_bytes() -> Option<u64> {
+    use std::ffi::c_void;
+
+    // `PROCESS_MEMORY_COUNTERS` from <psapi.h> (only read in part)
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Default)]
+    struct ProcessMemoryCounters {
+        cb: u32,
+        page_fault_count: u32,
+        peak_working_set_size: usize,
+        working_set_size: usize,
+        quota_peak_paged_pool_usage: usize,
+        quota_paged_pool_usage: usize,
+        quota_peak_non_paged_pool_usage: usize,
+        quota_non_paged_pool_usage: usize,
+        pagefile_usage: usize,
+        peak_pagefile_usage: usize,
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetCurrentProcess() -> *mut c_void;
+        fn K32GetProcessMemoryInfo(
+            process: *mut c_void,
+            counters: *mut ProcessMemoryCounters,
+            cb: u32,
+        ) -> i32;
+    }
+
+    let mut counters = ProcessMemoryCounters {
+        cb: std::mem::size_of::<ProcessMemoryCounters>() as u32,
+        ..Default::default()
+    };
+    // SAFETY: `counters` is a `PROCESS_MEMORY_COUNTERS` of the size in `cb`
+    let ok = unsafe { K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) };
+    (ok != 0).then_some(counters.working_set_size as u64)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn resident_bytes() -> Option<u64> {
+    None
+}
+
+/// `VmRSS` from `/proc/self/status`, in bytes
+#[cfg(any(target_os = "linux", test))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib = line["VmRSS:".len()..].trim().strip_suffix("kB")?.trim();
+    kib.parse::<u64>().ok().map(|kib| kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tbitwig\nVmPeak:\t  900000 kB\nVmRSS:\t  412345 kB\nThreads:\t40\n";
+        assert_eq!(parse_vm_rss(status), Some(412345 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tbitwig\n"), None);
+    }
+
+    #[test]
+    fn test_reads_own_memory() {
+        if cfg!(any(target_os = "linux", target_os = "macos", windows)) {
+            assert!(resident_bytes().is_some_and(|bytes| bytes > 0));
+        }
+    }
+}
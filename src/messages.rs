@@ -3,17 +3,95 @@
 pub enum CodeMessage {
     /// Update the Glicol code
     UpdateCode(String),
+    /// Fade the wet signal to silence and clear buffers
+    /// (optionally reverting the code to `out: ~input`)
+    #[cfg(feature = "editor")]
+    Panic { revert_code: bool },
+    /// Map the next incoming CC to this parameter ID (`None` cancels)
+    #[cfg(feature = "editor")]
+    MidiLearn(Option<String>),
+    /// Silence the output while the tuner is open
+    #[cfg(feature = "editor")]
+    TunerMute(bool),
+    /// Run this code temporarily without committing it (`None` returns to
+    /// the committed code)
+    #[cfg(feature = "editor")]
+    Audition(Option<String>),
+    /// Audio for the "Sample" test signal (`None` unloads it)
+    #[cfg(feature = "editor")]
+    TestSample(Option<TestSample>),
+    /// Code a linked instance runs in `scene`
+    Link { scene: usize, code: String },
 }
 
 /// Messages from Audio to GUI thread (status updates)
-/// TODO: Implement Audio→GUI feedback channel
-#[allow(dead_code)]
+///
+/// Without the editor they are sent but never read.
+#[cfg_attr(not(feature = "editor"), allow(dead_code))]
 #[derive(Debug, Clone)]
 pub enum StatusMessage {
     /// Code update was successful
     Success,
-    /// Code update failed with error message
-    Error(String),
+    /// Code update failed
+    Error(CompileError),
+    /// MIDI learn caught a CC for a parameter (the editor stores it in the
+    /// map)
+    MidiLearned { param_id: String, cc: u8 },
+    /// A program change loaded the code of `FACTORY_PRESETS[index]` (the
+    /// editor sets its parameters, which the audio thread can't)
+    FactoryPreset { index: usize },
     /// Buffer underrun occurred
+    #[allow(dead_code)]
     BufferUnderrun,
 }
+
+/// Work the audio thread hands to nih-plug's background thread
+#[allow(clippy::large_enum_variant)] // Boxing would allocate on the audio thread
+pub enum Task {
+    /// Build both engines for a new oversampling factor
+    BuildEngines(EngineRequest),
+    /// Free engines (and code) the audio thread let go of
+    DropEngines(EngineSet),
+    /// Build the engine for the other A/B snapshot's code
+    BuildMorphEngine(MorphRequest),
+    /// Free a morph engine (and code) the audio thread let go of
+    DropMorphEngine(MorphEngine),
+}
+
+/// Engines to build, with the injected code to compile into them
+pub struct EngineRequest {
+    pub factor: usize,
+    /// Engine sample rate (the host rate times `factor`)
+    pub rate: f32,
+    /// Code updates the audio thread had made when it sent `code`
+    pub generation: u64,
+    pub code: String,
+}
+
+/// Engines built off the audio thread, sent back for it to swap in
+pub struct EngineSet {
+    pub request: EngineRequest,
+    pub engine: GlicolWrapper,
+    pub morph_engine: GlicolWrapper,
+}
+
+/// Morph engine to build, with the injected code to compile into it
+pub struct MorphRequest {
+    /// Engine sample rate (the host rate times the oversampling factor)
+    pub rate: f32,
+    pub code: String,
+    /// A fresh copy of the running code, only wanted while the morph is
+    /// at zero
+    pub reset: bool,
+}
+
+/// Morph engine built off the audio thread
+pub struct MorphEngine {
+    pub request: MorphRequest,
+    pub engine: GlicolWrapper,
+}
+
+#[cfg(feature = "editor")]
+pub use crate::dsp::test_signal::TestSample;
+pub use crate::engine::CompileError;
+use crate::engine::GlicolWrapper;
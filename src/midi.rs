@@ -0,0 +1,323 @@
+//! MIDI CC mapping (MIDI learn) and program changes
+//!
+//! The map is persisted with the plugin state and read on the audio thread.
+//! NIH-plug has no way to set parameters from `process()`, so incoming CCs
+//! become overrides that take precedence over the parameter value until the
+//! host or GUI moves that parameter again. Program changes pick scenes the
+//! same way, overriding the `scene` parameter, and a range of programs
+//! loads the factory presets.
+
+use nih_plug::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::params::{GlicolVerbParams, SCENE_COUNT};
+use crate::preset::factory::FACTORY_PRESETS;
+
+/// Parameters that can be driven by a MIDI CC
+pub const MIDI_LEARN_TARGETS: &[&str] = &[
+    "knob1",
+    "knob2",
+    "knob3",
+    "knob4",
+    "knob5",
+    "knob6",
+    "knob7",
+    "knob8",
+    "expr",
+    "drive",
+    "rate",
+    "mix",
+    "feedback",
+    "delay_bypass",
+    "eq_bypass",
+    "macro1",
+    "macro2",
+];
+
+/// One CC -> parameter assignment
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MidiMapping {
+    pub cc: u8,
+    pub param_id: String,
+}
+
+/// All CC assignments, and the program changes that select scenes and
+/// factory presets
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MidiMap {
+    pub mappings: Vec<MidiMapping>,
+    /// Program number (0-based, as sent) per scene; `None` ignores it
+    #[serde(default = "default_scene_programs")]
+    pub scene_programs: [Option<u8>; SCENE_COUNT],
+    /// Program loading the first factory preset, the rest following in
+    /// bank order; `None` ignores them
+    #[serde(default = "default_preset_program")]
+    pub preset_program: Option<u8>,
+}
+
+impl Default for MidiMap {
+    fn default() -> Self {
+        Self {
+            mappings: Vec::new(),
+            scene_programs: default_scene_programs(),
+            preset_program: default_preset_program(),
+        }
+    }
+}
+
+/// Programs 1-4 (0-3 on the wire) pick scenes 1-4
+fn default_scene_programs() -> [Option<u8>; SCENE_COUNT] {
+    std::array::from_fn(|scene| Some(scene as u8))
+}
+
+/// Program (0-based) loading the first factory preset unless the map says
+/// otherwise: 17 on controllers
+pub const DEFAULT_PRESET_PROGRAM: u8 = 16;
+
+fn default_preset_program() -> Option<u8> {
+    Some(DEFAULT_PRESET_PROGRAM)
+}
+
+impl MidiMap {
+    /// Assign `cc` to `param_id`, replacing the parameter's previous CC
+    #[cfg(feature = "editor")]
+    pub fn learn(&mut self, param_id: &str, cc: u8) {
+        self.clear(param_id);
+        self.mappings.push(MidiMapping {
+            cc,
+            param_id: param_id.to_string(),
+        });
+    }
+
+    #[cfg(feature = "editor")]
+    pub fn clear(&mut self, param_id: &str) {
+        self.mappings.retain(|mapping| mapping.param_id != param_id);
+    }
+
+    /// CC assigned to a parameter, if any
+    #[cfg(feature = "editor")]
+    pub fn cc_for(&self, param_id: &str) -> Option<u8> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.param_id == param_id)
+            .map(|mapping| mapping.cc)
+    }
+
+    /// Scene selected by a program change, if any
+    pub fn scene_for_program(&self, program: u8) -> Option<usize> {
+        self.scene_programs
+            .iter()
+            .position(|&assigned| assigned == Some(program))
+    }
+
+    /// Factory preset loaded by a program change, if any (scenes win when
+    /// both claim a program)
+    pub fn preset_for_program(&self, program: u8) -> Option<usize> {
+        if self.scene_for_program(program).is_some() {
+            return None;
+        }
+        let index = usize::from(program.checked_sub(self.preset_program?)?);
+        (index < FACTORY_PRESETS.len()).then_some(index)
+    }
+}
+
+/// CC values overriding parameters on the audio thread
+///
+/// Indexed like `MIDI_LEARN_TARGETS`. Values are normalized (0.0-1.0).
+#[derive(Default)]
+pub struct MidiOverrides {
+    values: [Option<f32>; MIDI_LEARN_TARGETS.len()],
+    /// Parameter value when the override was set
+    param_at_override: [f32; MIDI_LEARN_TARGETS.len()],
+    /// Scene picked by a program change, and the scene parameter's index
+    /// at the time
+    program_scene: Option<(usize, usize)>,
+}
+
+impl MidiOverrides {
+    /// Apply a CC to every parameter mapped to it
+    pub fn handle_cc(&mut self, map: &MidiMap, params: &GlicolVerbParams, cc: u8, value: f32) {
+        for mapping in map.mappings.iter().filter(|mapping| mapping.cc == cc) {
+            let Some(index) = target_index(&mapping.param_id) else {
+                continue;
+            };
+            self.values[index] = Some(value);
+            self.param_at_override[index] = target_normalized(params, index);
+        }
+    }
+
+    /// Switch scenes on a program change assigned to one
+    pub fn handle_program(&mut self, map: &MidiMap, params: &GlicolVerbParams, program: u8) {
+        if let Some(scene) = map.scene_for_program(program) {
+            self.select_scene(params, scene);
+        }
+    }
+
+    /// Run `scene` until the scene parameter moves, as a program change
+    /// does (also used by link mode)
+    pub fn select_scene(&mut self, params: &GlicolVerbParams, scene: usize) {
+        self.program_scene = Some((scene, params.scene.value().to_index()));
+    }
+
+    /// Scene to run: the last program change's pick, until the scene
+    /// parameter itself moves
+    pub fn scene(&mut self, params: &GlicolVerbParams) -> usize {
+        let param_scene = params.scene.value().to_index();
+        match self.program_scene {
+            Some((scene, at_override)) if at_override == param_scene => scene,
+            _ => {
+                self.program_scene = None;
+                param_scene
+            }
+        }
+    }
+
+    /// Normalized value of a target, honouring a live override
+    ///
+    /// Moving the parameter itself drops the override.
+    pub fn normalized(&mut self, params: &GlicolVerbParams, param_id: &str) -> f32 {
+        let Some(index) = target_index(param_id) else {
+            return 0.0;
+        };
+        let param_value = target_normalized(params, index);
+        if self.values[index].is_some() && param_value != self.param_at_override[index] {
+            self.values[index] = None;
+        }
+        self.values[index].unwrap_or(param_value)
+    }
+
+    /// Plain value of a float target, honouring a live override
+    pub fn float(&mut self, params: &GlicolVerbParams, param: &FloatParam, param_id: &str) -> f32 {
+        param.preview_plain(self.normalized(params, param_id))
+    }
+
+    /// Smoothed plain value of a float target; a live override jumps
+    /// straight to the CC value
+    pub fn smoothed(
+        &mut self,
+        params: &GlicolVerbParams,
+        param: &FloatParam,
+        param_id: &str,
+    ) -> f32 {
+        let normalized = self.normalized(params, param_id);
+        match target_index(param_id).and_then(|index| self.values[index]) {
+            Some(_) => param.preview_plain(normalized),
+            None => param.smoothed.previous_value(),
+        }
+    }
+
+    /// Value of a bool target, honouring a live override
+    pub fn bool(&mut self, params: &GlicolVerbParams, param_id: &str) -> bool {
+        self.normalized(params, param_id) >= 0.5
+    }
+}
+
+fn target_index(param_id: &str) -> Option<usize> {
+    MIDI_LEARN_TARGETS.iter().position(|id| *id == param_id)
+}
+
+/// Current normalized value of a target parameter
+fn target_normalized(params: &GlicolVerbParams, index: usize) -> f32 {
+    let ptr = match MIDI_LEARN_TARGETS[index] {
+        "knob1" => params.glicol.knob1.as_ptr(),
+        "knob2" => params.glicol.knob2.as_ptr(),
+        "knob3" => params.glicol.knob3.as_ptr(),
+        "knob4" => params.glicol.knob4.as_ptr(),
+        "knob5" => params.glicol.knob5.as_ptr(),
+        "knob6" => params.glicol.knob6.as_ptr(),
+        "knob7" => params.glicol.knob7.as_ptr(),
+        "knob8" => params.glicol.knob8.as_ptr(),
+        "expr" => params.glicol.expr.as_ptr(),
+        "drive" => params.glicol.drive.as_ptr(),
+        "rate" => params.glicol.rate.as_ptr(),
+        "mix" => params.glicol.mix.as_ptr(),
+        "feedback" => params.glicol.feedback.as_ptr(),
+        "delay_bypass" => params.delay.bypass.as_ptr(),
+        "macro1" => params.modulation.macro1.as_ptr(),
+        "macro2" => params.modulation.macro2.as_ptr(),
+        _ => params.eq.bypass.as_ptr(),
+    };
+    // SAFETY: the pointer comes from `params`, which outlives this call
+    unsafe { ptr.modulated_normalized_value() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn test_learn_replaces_previous_cc() {
+        let mut map = MidiMap::default();
+        map.learn("drive", 1);
+        map.learn("drive", 11);
+        map.learn("mix", 11);
+        assert_eq!(map.cc_for("drive"), Some(11));
+        assert_eq!(map.mappings.len(), 2);
+
+        map.clear("drive");
+        assert_eq!(map.cc_for("drive"), None);
+    }
+
+    #[test]
+    fn test_override_until_param_moves() {
+        let params = GlicolVerbParams::default();
+        let mut map = MidiMap::default();
+        map.mappings.push(MidiMapping {
+            cc: 7,
+            param_id: "knob1".to_string(),
+        });
+        let mut overrides = MidiOverrides::default();
+
+        assert_eq!(overrides.normalized(&params, "knob1"), 0.5);
+        overrides.handle_cc(&map, &params, 7, 1.0);
+        assert_eq!(overrides.normalized(&params, "knob1"), 1.0);
+        assert_eq!(overrides.float(&params, &params.glicol.knob1, "knob1"), 1.0);
+        assert_eq!(
+            overrides.smoothed(&params, &params.glicol.knob1, "knob1"),
+            1.0
+        );
+        overrides.handle_cc(&map, &params, 8, 0.0);
+        assert!(!overrides.bool(&params, "eq_bypass"));
+    }
+
+    #[test]
+    fn test_program_changes_pick_scenes() {
+        let params = GlicolVerbParams::default();
+        let mut map = MidiMap::default();
+        let mut overrides = MidiOverrides::default();
+        assert_eq!(overrides.scene(&params), 0);
+
+        overrides.handle_program(&map, &params, 2);
+        assert_eq!(overrides.scene(&params), 2);
+
+        // Unassigned programs are ignored
+        map.scene_programs[3] = None;
+        overrides.handle_program(&map, &params, 3);
+        assert_eq!(overrides.scene(&params), 2);
+
+        map.scene_programs[1] = Some(20);
+        assert_eq!(map.scene_for_program(20), Some(1));
+    }
+
+    #[test]
+    fn test_program_range_loads_factory_presets() {
+        let mut map = MidiMap::default();
+        assert_eq!(map.preset_for_program(15), None);
+        assert_eq!(map.preset_for_program(16), Some(0));
+        assert_eq!(map.preset_for_program(17), Some(1));
+        let last = DEFAULT_PRESET_PROGRAM + FACTORY_PRESETS.len() as u8 - 1;
+        assert_eq!(
+            map.preset_for_program(last),
+            Some(FACTORY_PRESETS.len() - 1)
+        );
+        assert_eq!(map.preset_for_program(last + 1), None);
+
+        // A scene assigned inside the range keeps its program
+        map.scene_programs[0] = Some(17);
+        assert_eq!(map.preset_for_program(17), None);
+
+        map.preset_program = None;
+        assert_eq!(map.preset_for_program(16), None);
+    }
+}
@@ -0,0 +1,516 @@
+//! Modulation routing: macros, LFOs and the envelope follower
+//!
+//! Each macro parameter drives a user-edited list of targets, and the
+//! modulation matrix adds LFO, input-envelope and expression pedal movement
+//! on top. The morph parameter blends every target toward the inactive A/B
+//! snapshot underneath all of that. As with MIDI overrides, NIH-plug can't
+//! move parameters from `process()`, so the modulated value of a target is
+//! computed here each block instead of read from the parameter. Glicol
+//! parameters (`~drive`, `~knob1`, ...) are injected when code is applied,
+//! so modulation of them lands on the next apply.
+
+use nih_plug::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of macro parameters
+pub const MACRO_COUNT: usize = 2;
+
+/// Parameters a macro or the envelope can drive (LFO targets are a subset)
+pub const MACRO_TARGETS: &[&str] = &[
+    "drive",
+    "rate",
+    "mix",
+    "feedback",
+    "knob1",
+    "knob2",
+    "knob3",
+    "knob4",
+    "knob5",
+    "knob6",
+    "knob7",
+    "knob8",
+    "delay_time",
+    "delay_feedback",
+    "delay_mix",
+    "delay_highcut",
+    "eq_low_freq",
+    "eq_low_gain",
+    "eq_mid_freq",
+    "eq_mid_gain",
+    "eq_mid_q",
+    "eq_high_freq",
+    "eq_high_gain",
+];
+
+/// Response of a mapping across the macro's travel
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum MacroCurve {
+    #[default]
+    Linear,
+    /// Slow start, fast finish
+    Exponential,
+    /// Fast start, slow finish
+    Logarithmic,
+}
+
+impl MacroCurve {
+    #[cfg(feature = "editor")]
+    pub const ALL: [MacroCurve; 3] = [
+        MacroCurve::Linear,
+        MacroCurve::Exponential,
+        MacroCurve::Logarithmic,
+    ];
+
+    #[cfg(feature = "editor")]
+    pub fn label(self) -> &'static str {
+        match self {
+            MacroCurve::Linear => "Linear",
+            MacroCurve::Exponential => "Exp",
+            MacroCurve::Logarithmic => "Log",
+        }
+    }
+
+    /// Shape a macro position (0.0-1.0)
+    pub fn apply(self, position: f32) -> f32 {
+        let position = position.clamp(0.0, 1.0);
+        match self {
+            MacroCurve::Linear => position,
+            MacroCurve::Exponential => position * position,
+            MacroCurve::Logarithmic => 1.0 - (1.0 - position) * (1.0 - position),
+        }
+    }
+}
+
+/// One macro -> parameter route
+///
+/// `min` and `max` are normalized values of the target, so a route can also
+/// run backwards.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MacroMapping {
+    pub target: String,
+    pub min: f32,
+    pub max: f32,
+    pub curve: MacroCurve,
+}
+
+impl MacroMapping {
+    pub fn new(target: &str) -> Self {
+        Self {
+            target: target.to_string(),
+            min: 0.0,
+            max: 1.0,
+            curve: MacroCurve::Linear,
+        }
+    }
+
+    /// Normalized target value for a macro position
+    pub fn normalized(&self, position: f32) -> f32 {
+        let shaped = self.curve.apply(position);
+        (self.min + (self.max - self.min) * shaped).clamp(0.0, 1.0)
+    }
+}
+
+/// Routes of every macro, indexed by macro
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MacroMap {
+    pub macros: [Vec<MacroMapping>; MACRO_COUNT],
+}
+
+/// Number of LFOs
+pub const LFO_COUNT: usize = 2;
+
+/// Parameters an LFO can modulate
+pub const LFO_TARGETS: &[&str] = &[
+    "knob1",
+    "knob2",
+    "knob3",
+    "knob4",
+    "delay_time",
+    "eq_low_gain",
+    "eq_mid_gain",
+    "eq_high_gain",
+];
+
+/// LFO waveform
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+    #[name = "Sample & Hold"]
+    SampleHold,
+}
+
+/// LFO period when synced to the host tempo
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum LfoDivision {
+    #[name = "1/16"]
+    Sixteenth,
+    #[name = "1/8"]
+    Eighth,
+    #[name = "1/4"]
+    Quarter,
+    #[name = "1/2"]
+    Half,
+    #[name = "1 bar"]
+    Bar,
+    #[name = "2 bars"]
+    TwoBars,
+}
+
+impl LfoDivision {
+    /// Length of one cycle in beats
+    pub fn beats(self) -> f32 {
+        match self {
+            LfoDivision::Sixteenth => 0.25,
+            LfoDivision::Eighth => 0.5,
+            LfoDivision::Quarter => 1.0,
+            LfoDivision::Half => 2.0,
+            LfoDivision::Bar => 4.0,
+            LfoDivision::TwoBars => 8.0,
+        }
+    }
+
+    /// Rate in Hz at `bpm`
+    pub fn rate_hz(self, bpm: f32) -> f32 {
+        bpm / 60.0 / self.beats()
+    }
+}
+
+/// Free-running LFO, advanced once per block
+pub struct Lfo {
+    /// Position in the cycle (0.0-1.0)
+    phase: f32,
+    /// Current sample & hold level
+    held: f32,
+    rng: u32,
+}
+
+impl Default for Lfo {
+    fn default() -> Self {
+        Self {
+            phase: 0.0,
+            held: 0.0,
+            rng: 0x9e37_79b9,
+        }
+    }
+}
+
+impl Lfo {
+    /// Output (-1.0 to 1.0) at the current phase
+    pub fn value(&self, shape: LfoShape) -> f32 {
+        let phase = self.phase;
+        match shape {
+            LfoShape::Sine => (phase * std::f32::consts::TAU).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            LfoShape::Saw => 2.0 * phase - 1.0,
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SampleHold => self.held,
+        }
+    }
+
+    /// Move the phase on by `seconds` at `rate_hz`, drawing a new sample &
+    /// hold level at each cycle start
+    pub fn advance(&mut self, rate_hz: f32, seconds: f32) {
+        self.phase += rate_hz * seconds;
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            // xorshift32
+            self.rng ^= self.rng << 13;
+            self.rng ^= self.rng >> 17;
+            self.rng ^= self.rng << 5;
+            self.held = (self.rng as f32 / u32::MAX as f32) * 2.0 - 1.0;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+}
+
+/// Number of modulation matrix sources
+pub const SOURCE_COUNT: usize = LFO_COUNT + 3;
+
+/// Where a matrix route takes its movement from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ModSource {
+    #[default]
+    Lfo1,
+    Lfo2,
+    /// Input envelope follower (0.0-1.0)
+    Envelope,
+    /// Expression pedal parameter (0.0-1.0)
+    Expression,
+    /// Envelope of the sidechain input (0.0-1.0)
+    Sidechain,
+}
+
+impl ModSource {
+    #[cfg(feature = "editor")]
+    pub const ALL: [ModSource; SOURCE_COUNT] = [
+        ModSource::Lfo1,
+        ModSource::Lfo2,
+        ModSource::Envelope,
+        ModSource::Expression,
+        ModSource::Sidechain,
+    ];
+
+    #[cfg(feature = "editor")]
+    pub fn label(self) -> &'static str {
+        match self {
+            ModSource::Lfo1 => "LFO 1",
+            ModSource::Lfo2 => "LFO 2",
+            ModSource::Envelope => "Envelope",
+            ModSource::Expression => "Expression",
+            ModSource::Sidechain => "Sidechain",
+        }
+    }
+
+    /// Parameters this source can modulate
+    pub fn targets(self) -> &'static [&'static str] {
+        match self {
+            ModSource::Lfo1 | ModSource::Lfo2 => LFO_TARGETS,
+            ModSource::Envelope | ModSource::Expression | ModSource::Sidechain => MACRO_TARGETS,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            ModSource::Lfo1 => 0,
+            ModSource::Lfo2 => 1,
+            ModSource::Envelope => 2,
+            ModSource::Expression => 3,
+            ModSource::Sidechain => 4,
+        }
+    }
+}
+
+/// One source -> parameter route of the modulation matrix
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModRoute {
+    pub source: ModSource,
+    pub target: String,
+    /// Normalized swing at full source output (-1.0 to 1.0; negative
+    /// inverts, e.g. to duck a parameter with the envelope)
+    pub amount: f32,
+}
+
+/// The modulation matrix
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModMatrix {
+    pub routes: Vec<ModRoute>,
+}
+
+/// Modulated target values on the audio thread
+///
+/// Indexed like `MACRO_TARGETS`. Values are normalized (0.0-1.0); matrix
+/// offsets are added on top of the macro (or parameter) value, and the
+/// parameter value itself is first morphed toward the other snapshot.
+pub struct ModTargets {
+    values: [Option<f32>; MACRO_TARGETS.len()],
+    offsets: [f32; MACRO_TARGETS.len()],
+    /// Values stored in the inactive A/B snapshot
+    morph: [Option<f32>; MACRO_TARGETS.len()],
+    /// How far toward `morph` parameters sit (0.0 = live value)
+    morph_weight: f32,
+}
+
+impl Default for ModTargets {
+    fn default() -> Self {
+        Self {
+            values: [None; MACRO_TARGETS.len()],
+            offsets: [0.0; MACRO_TARGETS.len()],
+            morph: [None; MACRO_TARGETS.len()],
+            morph_weight: 0.0,
+        }
+    }
+}
+
+impl ModTargets {
+    /// Recompute every macro-routed target from the macro positions
+    ///
+    /// When several routes drive the same target, the last one wins.
+    pub fn update_macros(&mut self, map: &MacroMap, positions: [f32; MACRO_COUNT]) {
+        self.values = [None; MACRO_TARGETS.len()];
+        for (mappings, position) in map.macros.iter().zip(positions) {
+            for mapping in mappings {
+                if let Some(index) = target_index(&mapping.target) {
+                    self.values[index] = Some(mapping.normalized(position));
+                }
+            }
+        }
+    }
+
+    /// Recompute the matrix offsets from the source outputs (indexed like
+    /// `ModSource::ALL`, LFOs already scaled by depth); routes to the same
+    /// target add up
+    pub fn update_matrix(&mut self, matrix: &ModMatrix, outputs: [f32; SOURCE_COUNT]) {
+        self.offsets = [0.0; MACRO_TARGETS.len()];
+        for route in &matrix.routes {
+            if !route.source.targets().contains(&route.target.as_str()) {
+                continue;
+            }
+            if let Some(index) = target_index(&route.target) {
+                self.offsets[index] += outputs[route.source.index()] * route.amount;
+            }
+        }
+    }
+
+    /// Set the morph toward the inactive snapshot's normalized values
+    /// (`None` when there is no snapshot to morph to)
+    pub fn update_morph(&mut self, other: Option<&BTreeMap<String, f32>>, weight: f32) {
+        self.morph_weight = weight.clamp(0.0, 1.0);
+        for (slot, id) in self.morph.iter_mut().zip(MACRO_TARGETS) {
+            *slot = other.and_then(|values| values.get(*id).copied());
+        }
+    }
+
+    /// Plain value of a float target: the macro value if a macro drives it,
+    /// otherwise `value` morphed toward the other snapshot, plus any matrix
+    /// offset
+    pub fn float(&self, param: &FloatParam, param_id: &str, value: f32) -> f32 {
+        let Some(index) = target_index(param_id) else {
+            return value;
+        };
+        let offset = self.offsets[index];
+        let morph = self.morph[index].filter(|_| self.morph_weight > 0.0);
+        let normalized = match (self.values[index], morph) {
+            (Some(normalized), _) => normalized,
+            (None, Some(other)) => {
+                let own = param.preview_normalized(value);
+                own + (other - own) * self.morph_weight
+            }
+            (None, None) if offset != 0.0 => param.preview_normalized(value),
+            (None, None) => return value,
+        };
+        param.preview_plain((normalized + offset).clamp(0.0, 1.0))
+    }
+
+    /// Plain value of a float target, falling back to the parameter itself
+    pub fn value(&self, param: &FloatParam, param_id: &str) -> f32 {
+        self.float(param, param_id, param.value())
+    }
+}
+
+fn target_index(param_id: &str) -> Option<usize> {
+    MACRO_TARGETS.iter().position(|id| *id == param_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::GlicolVerbParams;
+
+    #[test]
+    fn test_curves() {
+        assert_eq!(MacroCurve::Linear.apply(0.5), 0.5);
+        assert_eq!(MacroCurve::Exponential.apply(0.5), 0.25);
+        assert_eq!(MacroCurve::Logarithmic.apply(0.5), 0.75);
+        assert_eq!(MacroCurve::Exponential.apply(2.0), 1.0);
+    }
+
+    #[test]
+    fn test_routing() {
+        let params = GlicolVerbParams::default();
+        let mut map = MacroMap::default();
+        map.macros[0].push(MacroMapping::new("delay_mix"));
+        map.macros[0].push(MacroMapping {
+            min: 1.0,
+            max: 0.0,
+            ..MacroMapping::new("knob1")
+        });
+        let mut targets = ModTargets::default();
+        targets.update_macros(&map, [0.25, 0.0]);
+
+        assert_eq!(targets.float(&params.delay.mix, "delay_mix", 0.5), 0.25);
+        assert_eq!(targets.float(&params.glicol.knob1, "knob1", 0.5), 0.75);
+        // Unrouted targets keep their own value
+        assert_eq!(targets.float(&params.glicol.drive, "drive", 3.0), 3.0);
+
+        map.macros[0].clear();
+        targets.update_macros(&map, [0.25, 0.0]);
+        assert_eq!(targets.float(&params.glicol.knob1, "knob1", 0.5), 0.5);
+    }
+
+    #[test]
+    fn test_morph() {
+        let params = GlicolVerbParams::default();
+        let mut other = BTreeMap::new();
+        other.insert("delay_mix".to_string(), 1.0);
+        let mut targets = ModTargets::default();
+
+        targets.update_morph(Some(&other), 0.5);
+        assert_eq!(targets.float(&params.delay.mix, "delay_mix", 0.0), 0.5);
+        // Not in the snapshot: left alone
+        assert_eq!(targets.float(&params.glicol.knob1, "knob1", 0.3), 0.3);
+
+        // Macros still win
+        let mut map = MacroMap::default();
+        map.macros[0].push(MacroMapping::new("delay_mix"));
+        targets.update_macros(&map, [0.25, 0.0]);
+        assert_eq!(targets.float(&params.delay.mix, "delay_mix", 0.0), 0.25);
+
+        targets.update_macros(&MacroMap::default(), [0.0, 0.0]);
+        targets.update_morph(Some(&other), 0.0);
+        assert_eq!(targets.float(&params.delay.mix, "delay_mix", 0.2), 0.2);
+    }
+
+    #[test]
+    fn test_lfo_shapes() {
+        let mut lfo = Lfo::default();
+        lfo.advance(1.0, 0.25);
+        assert!((lfo.value(LfoShape::Sine) - 1.0).abs() < 1e-6);
+        assert_eq!(lfo.value(LfoShape::Triangle), 0.0);
+        assert_eq!(lfo.value(LfoShape::Saw), -0.5);
+        assert_eq!(lfo.value(LfoShape::Square), 1.0);
+
+        // Sample & hold only moves when a cycle starts
+        let held = lfo.value(LfoShape::SampleHold);
+        lfo.advance(1.0, 0.5);
+        assert_eq!(lfo.value(LfoShape::SampleHold), held);
+        lfo.advance(1.0, 0.5);
+        assert_ne!(lfo.value(LfoShape::SampleHold), held);
+        assert!((lfo.phase - 0.25).abs() < 1e-6);
+
+        assert_eq!(LfoDivision::Quarter.rate_hz(120.0), 2.0);
+    }
+
+    #[test]
+    fn test_matrix_routes() {
+        let params = GlicolVerbParams::default();
+        let mut matrix = ModMatrix::default();
+        for (source, target, amount) in [
+            (ModSource::Lfo1, "knob1", 0.25),
+            (ModSource::Lfo1, "knob1", 0.25),
+            (ModSource::Lfo2, "drive", 0.25),
+            (ModSource::Envelope, "delay_mix", -0.5),
+            (ModSource::Sidechain, "delay_feedback", -0.5),
+        ] {
+            matrix.routes.push(ModRoute {
+                source,
+                target: target.to_string(),
+                amount,
+            });
+        }
+        let mut targets = ModTargets::default();
+        targets.update_matrix(&matrix, [1.0, 1.0, 0.5, 0.0, 1.0]);
+
+        assert_eq!(targets.float(&params.glicol.knob1, "knob1", 0.25), 0.75);
+        assert_eq!(targets.float(&params.glicol.knob1, "knob1", 0.75), 1.0);
+        // Not an LFO target
+        assert_eq!(targets.float(&params.glicol.drive, "drive", 2.0), 2.0);
+        // The envelope ducks delay mix
+        assert_eq!(targets.float(&params.delay.mix, "delay_mix", 0.5), 0.25);
+        // And a loud sidechain ducks the delay feedback
+        let feedback = targets.float(&params.delay.feedback, "delay_feedback", 0.475);
+        assert!(feedback.abs() < 1e-6);
+    }
+}
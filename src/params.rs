@@ -1,125 +1,501 @@
 use nih_plug::prelude::*;
+#[cfg(feature = "editor")]
 use nih_plug_egui::EguiState;
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
+use crate::api::ApiSettings;
+use crate::dsp::test_signal::SignalKind;
+use crate::engine::{KnobNames, PARAM_NAMES};
+use crate::link::LinkSettings;
+use crate::midi::MidiMap;
+use crate::modulation::{LfoDivision, LfoShape, MacroMap, ModMatrix};
+use crate::preset::{AbSnapshots, Preset};
+use crate::recorder::RecorderSettings;
+use crate::shortcuts::ShortcutMap;
+use crate::state::{ChainState, SESSION_STATE_VERSION};
+
+/// A successfully applied code version (for the history timeline)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CodeVersion {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub code: String,
+}
+
+/// Number of code scenes
+pub const SCENE_COUNT: usize = 4;
+
+/// Code scene chosen by the `scene` parameter
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum SceneSlot {
+    #[name = "Scene 1"]
+    One,
+    #[name = "Scene 2"]
+    Two,
+    #[name = "Scene 3"]
+    Three,
+    #[name = "Scene 4"]
+    Four,
+}
+
+/// What feeds the engine from a stereo input
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum InputSource {
+    #[name = "Left"]
+    Left,
+    #[name = "Right"]
+    Right,
+    /// Average of both channels
+    #[name = "Sum"]
+    Sum,
+    /// Both channels kept apart
+    #[name = "Stereo"]
+    Stereo,
+}
+
+/// Fixed attenuation ahead of the input gain
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum InputPad {
+    #[name = "Off"]
+    Off,
+    #[name = "-10 dB"]
+    Minus10,
+    #[name = "-20 dB"]
+    Minus20,
+}
+
+impl InputPad {
+    /// Linear gain of the pad
+    pub fn gain(self) -> f32 {
+        match self {
+            InputPad::Off => 1.0,
+            InputPad::Minus10 => util::db_to_gain(-10.0),
+            InputPad::Minus20 => util::db_to_gain(-20.0),
+        }
+    }
+}
+
+/// Built-in source that can stand in for the instrument
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum TestSource {
+    #[name = "Off"]
+    Off,
+    #[name = "Sine"]
+    Sine,
+    #[name = "Noise"]
+    Noise,
+    /// Looped Karplus-Strong arpeggio
+    #[name = "Pluck"]
+    Pluck,
+    /// WAV file loaded from the editor
+    #[name = "Sample"]
+    Sample,
+}
+
+impl TestSource {
+    /// Generator source, `None` when off
+    pub fn kind(self) -> Option<SignalKind> {
+        match self {
+            TestSource::Off => None,
+            TestSource::Sine => Some(SignalKind::Sine),
+            TestSource::Noise => Some(SignalKind::Noise),
+            TestSource::Pluck => Some(SignalKind::Pluck),
+            TestSource::Sample => Some(SignalKind::Sample),
+        }
+    }
+}
+
+/// Whether the test signal replaces the input or is added to it
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum TestMix {
+    #[name = "Replace"]
+    Replace,
+    #[name = "Mix"]
+    Mix,
+}
+
+/// Rate the Glicol engine runs at, relative to the host
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum Oversampling {
+    #[name = "Off"]
+    Off,
+    #[name = "2x"]
+    X2,
+    #[name = "4x"]
+    X4,
+}
+
+impl Oversampling {
+    pub fn factor(self) -> usize {
+        match self {
+            Oversampling::Off => 1,
+            Oversampling::X2 => 2,
+            Oversampling::X4 => 4,
+        }
+    }
+}
+
+/// What the plugin sends to its output
+#[derive(Enum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputRouting {
+    /// Dry/wet mix
+    #[name = "Normal"]
+    Normal,
+    /// Processed signal only, for parallel routing in the host
+    #[name = "Wet Only"]
+    WetOnly,
+    #[name = "Dry Only"]
+    DryOnly,
+    /// Wet minus dry: what the patch adds
+    #[name = "Difference"]
+    Difference,
+}
+
+/// A named code slot
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CodeScene {
+    pub name: String,
+    pub code: String,
+}
+
+pub fn default_scenes() -> Vec<CodeScene> {
+    (1..=SCENE_COUNT)
+        .map(|number| CodeScene {
+            name: format!("Scene {}", number),
+            code: "out: ~input".to_string(),
+        })
+        .collect()
+}
+
+/// A recipe saved by the user from their own code
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UserRecipe {
+    pub icon: String,
+    pub name: String,
+    pub description: String,
+    pub code: String,
+}
+
+/// Order and visibility of the built-in recipes, by name
+///
+/// Built-ins missing from `order` (e.g. added in a newer version) are shown
+/// after the ordered ones.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RecipeLayout {
+    pub order: Vec<String>,
+    pub hidden: Vec<String>,
+}
+
+/// Built-in editor color themes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ThemeKind {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// Editor theme choice plus user color overrides (RGB)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThemeSettings {
+    pub kind: ThemeKind,
+    pub accent: Option<[u8; 3]>,
+    /// Knob indicator / highlight color
+    pub highlight: Option<[u8; 3]>,
+}
+
+/// Editor zoom, code font size and key bindings
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ViewSettings {
+    /// Scale factor for the whole UI
+    pub zoom: f32,
+    /// Point size of the monospace code font (before zoom)
+    pub code_font_size: f32,
+    /// Vim-style modal editing in the code editor
+    #[serde(default)]
+    pub vim_mode: bool,
+}
+
+impl Default for ViewSettings {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            code_font_size: 12.0,
+            vim_mode: false,
+        }
+    }
+}
+
+/// Parameter IDs excluded from randomize by the user
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParamLocks(pub BTreeSet<String>);
+
+#[cfg(feature = "editor")]
+impl ParamLocks {
+    pub fn is_locked(&self, param_id: &str) -> bool {
+        self.0.contains(param_id)
+    }
+
+    pub fn set_locked(&mut self, param_id: &str, locked: bool) {
+        if locked {
+            self.0.insert(param_id.to_string());
+        } else {
+            self.0.remove(param_id);
+        }
+    }
+}
+
 /// Plugin parameters
 #[derive(Params)]
 pub struct GlicolVerbParams {
     /// Editor state (window size, etc.)
+    #[cfg(feature = "editor")]
     #[persist = "editor-state"]
     pub editor_state: Arc<EguiState>,
 
-    /// Dry/Wet mix (0.0 = dry, 1.0 = wet)
-    #[id = "dry_wet"]
-    pub dry_wet: FloatParam,
+    /// Soft bypass - the host's bypass switch, crossfades to the input
+    #[id = "bypass"]
+    pub bypass: BoolParam,
 
-    /// Input gain in dB
-    #[id = "input_gain"]
-    pub input_gain: FloatParam,
+    /// Panic - hushes the wet signal while on (for host automation/MIDI)
+    #[id = "panic"]
+    pub panic: BoolParam,
 
-    /// Output gain in dB
-    #[id = "output_gain"]
-    pub output_gain: FloatParam,
+    /// Safe mode - clamps feedback and drive and engages the output limiter
+    #[id = "safe_mode"]
+    pub safe_mode: BoolParam,
 
-    // === Mappable Knobs (generic, user-assignable in Glicol code) ===
-    /// Knob 1 - maps to ~knob1 in Glicol code
-    #[id = "knob1"]
-    pub knob1: FloatParam,
+    /// Active code scene (switching loads that scene's code)
+    #[id = "scene"]
+    pub scene: EnumParam<SceneSlot>,
 
-    /// Knob 2 - maps to ~knob2 in Glicol code
-    #[id = "knob2"]
-    pub knob2: FloatParam,
+    // Groups only change how hosts list the parameters; IDs stay flat and
+    // unchanged, so existing sessions, presets and mappings still load
+    #[nested(group = "Core")]
+    pub core: CoreParams,
 
-    /// Knob 3 - maps to ~knob3 in Glicol code
-    #[id = "knob3"]
-    pub knob3: FloatParam,
+    #[nested(group = "Glicol")]
+    pub glicol: GlicolParams,
 
-    /// Knob 4 - maps to ~knob4 in Glicol code
-    #[id = "knob4"]
-    pub knob4: FloatParam,
+    #[nested(group = "Modulation")]
+    pub modulation: ModulationParams,
 
-    // === Effect Parameters (named, for common use cases) ===
-    /// Drive amount - maps to ~drive in Glicol code
-    #[id = "drive"]
-    pub drive: FloatParam,
+    #[nested(group = "EQ")]
+    pub eq: EqParams,
 
-    /// Feedback amount - maps to ~feedback in Glicol code
-    #[id = "feedback"]
-    pub feedback: FloatParam,
+    #[nested(group = "Delay")]
+    pub delay: DelayParams,
 
-    /// Effect mix - maps to ~mix in Glicol code
-    #[id = "mix"]
-    pub mix: FloatParam,
+    /// Persisted Glicol code (not a DAW automatable parameter)
+    #[persist = "glicol-code"]
+    pub code: Arc<RwLock<String>>,
 
-    /// Rate (Hz) - maps to ~rate in Glicol code
-    #[id = "rate"]
-    pub rate: FloatParam,
+    /// Code for each scene; the active one mirrors `code` once applied
+    #[persist = "scenes"]
+    pub scenes: Arc<RwLock<Vec<CodeScene>>>,
 
-    // === Delay Module Parameters ===
-    /// Delay bypass
-    #[id = "delay_bypass"]
-    pub delay_bypass: BoolParam,
+    /// User snippet library for `#include "name"` (name -> code)
+    #[persist = "snippets"]
+    pub snippets: Arc<RwLock<BTreeMap<String, String>>>,
 
-    /// Delay time in milliseconds
-    #[id = "delay_time"]
-    pub delay_time: FloatParam,
+    /// Every successfully applied code version, oldest first
+    #[persist = "code-versions"]
+    pub code_versions: Arc<RwLock<Vec<CodeVersion>>>,
 
-    /// Delay feedback amount
-    #[id = "delay_feedback"]
-    pub delay_feedback: FloatParam,
+    /// User presets (code + parameter values)
+    #[persist = "user-presets"]
+    pub user_presets: Arc<RwLock<Vec<Preset>>>,
 
-    /// Delay wet/dry mix
-    #[id = "delay_mix"]
-    pub delay_mix: FloatParam,
+    /// A/B comparison snapshots
+    #[persist = "ab-snapshots"]
+    pub ab_snapshots: Arc<RwLock<AbSnapshots>>,
 
-    /// Delay high-cut filter frequency
-    #[id = "delay_highcut"]
-    pub delay_highcut: FloatParam,
+    /// Recipes saved from the user's own code
+    #[persist = "user-recipes"]
+    pub user_recipes: Arc<RwLock<Vec<UserRecipe>>>,
 
-    // === EQ Module Parameters ===
-    /// EQ bypass
-    #[id = "eq_bypass"]
-    pub eq_bypass: BoolParam,
+    /// Order and visibility of the built-in recipes
+    #[persist = "recipe-layout"]
+    pub recipe_layout: Arc<RwLock<RecipeLayout>>,
 
-    /// EQ low shelf frequency
-    #[id = "eq_low_freq"]
-    pub eq_low_freq: FloatParam,
+    /// MIDI CC assignments from MIDI learn
+    #[persist = "midi-map"]
+    pub midi_map: Arc<RwLock<MidiMap>>,
 
-    /// EQ low shelf gain
-    #[id = "eq_low_gain"]
-    pub eq_low_gain: FloatParam,
+    /// Editor color theme
+    #[persist = "theme"]
+    pub theme: Arc<RwLock<ThemeSettings>>,
 
-    /// EQ mid peak frequency
-    #[id = "eq_mid_freq"]
-    pub eq_mid_freq: FloatParam,
+    /// Editor zoom, code font size and key bindings
+    #[persist = "view"]
+    pub view: Arc<RwLock<ViewSettings>>,
 
-    /// EQ mid peak gain
-    #[id = "eq_mid_gain"]
-    pub eq_mid_gain: FloatParam,
+    /// Keyboard shortcut bindings
+    #[persist = "shortcuts"]
+    pub shortcuts: Arc<RwLock<ShortcutMap>>,
 
-    /// EQ mid peak Q
-    #[id = "eq_mid_q"]
-    pub eq_mid_q: FloatParam,
+    /// Module order, per-module settings and preset info (versioned; the
+    /// key predates the other fields and is kept so old sessions migrate)
+    #[persist = "module-order"]
+    pub module_chain: Arc<RwLock<ChainState>>,
 
-    /// EQ high shelf frequency
-    #[id = "eq_high_freq"]
-    pub eq_high_freq: FloatParam,
+    /// User names for the mappable knobs
+    #[persist = "knob-names"]
+    pub knob_names: Arc<RwLock<KnobNames>>,
 
-    /// EQ high shelf gain
-    #[id = "eq_high_gain"]
-    pub eq_high_gain: FloatParam,
+    /// Targets driven by each macro
+    #[persist = "macro-map"]
+    pub macro_map: Arc<RwLock<MacroMap>>,
 
-    /// Persisted Glicol code (not a DAW automatable parameter)
-    #[persist = "glicol-code"]
-    pub code: Arc<RwLock<String>>,
+    /// LFO and envelope -> parameter routes
+    #[persist = "mod-matrix"]
+    pub mod_matrix: Arc<RwLock<ModMatrix>>,
+
+    /// Parameters the randomize action leaves alone
+    #[persist = "param-locks"]
+    pub param_locks: Arc<RwLock<ParamLocks>>,
+
+    /// WAV file played by the "Sample" test signal (empty for none)
+    #[persist = "test-sample"]
+    pub test_sample: Arc<RwLock<String>>,
+
+    /// Whether this instance shares code and scene with others, and with
+    /// which group
+    #[persist = "link"]
+    pub link: Arc<RwLock<LinkSettings>>,
+
+    /// Local HTTP API switch, port and token
+    #[persist = "api"]
+    pub api: Arc<RwLock<ApiSettings>>,
+
+    /// Recording folder and whether the input is recorded too
+    #[persist = "recorder"]
+    pub recorder: Arc<RwLock<RecorderSettings>>,
+
+    /// Layout version of the persisted fields, for `state::migrate_fields`
+    #[persist = "state-version"]
+    pub state_version: Arc<RwLock<u32>>,
 }
 
 impl Default for GlicolVerbParams {
     fn default() -> Self {
         Self {
+            #[cfg(feature = "editor")]
             editor_state: EguiState::from_size(1000, 500),
 
+            bypass: BoolParam::new("Bypass", false).make_bypass(),
+
+            panic: BoolParam::new("Panic", false),
+
+            safe_mode: BoolParam::new("Safe Mode", false),
+
+            scene: EnumParam::new("Scene", SceneSlot::One),
+
+            core: CoreParams::default(),
+            glicol: GlicolParams::default(),
+            modulation: ModulationParams::default(),
+            eq: EqParams::default(),
+            delay: DelayParams::default(),
+
+            code: Arc::new(RwLock::new(
+                "out: ~input".to_string(), // Pass-through
+            )),
+            scenes: Arc::new(RwLock::new(default_scenes())),
+
+            snippets: Arc::new(RwLock::new(BTreeMap::new())),
+            code_versions: Arc::new(RwLock::new(Vec::new())),
+            user_presets: Arc::new(RwLock::new(Vec::new())),
+            ab_snapshots: Arc::new(RwLock::new(AbSnapshots::default())),
+            user_recipes: Arc::new(RwLock::new(Vec::new())),
+            recipe_layout: Arc::new(RwLock::new(RecipeLayout::default())),
+            midi_map: Arc::new(RwLock::new(MidiMap::default())),
+            theme: Arc::new(RwLock::new(ThemeSettings::default())),
+            view: Arc::new(RwLock::new(ViewSettings::default())),
+            shortcuts: Arc::new(RwLock::new(ShortcutMap::default())),
+            module_chain: Arc::new(RwLock::new(ChainState::default())),
+            knob_names: Arc::new(RwLock::new(KnobNames::default())),
+            macro_map: Arc::new(RwLock::new(MacroMap::default())),
+            mod_matrix: Arc::new(RwLock::new(ModMatrix::default())),
+            param_locks: Arc::new(RwLock::new(ParamLocks::default())),
+            test_sample: Arc::new(RwLock::new(String::new())),
+            link: Arc::new(RwLock::new(LinkSettings::default())),
+            api: Arc::new(RwLock::new(ApiSettings::default())),
+            recorder: Arc::new(RwLock::new(RecorderSettings::default())),
+            state_version: Arc::new(RwLock::new(SESSION_STATE_VERSION)),
+        }
+    }
+}
+
+/// Levels, mix and routing around the engine
+#[derive(Params)]
+pub struct CoreParams {
+    /// Dry/Wet mix (0.0 = dry, 1.0 = wet)
+    #[id = "dry_wet"]
+    pub dry_wet: FloatParam,
+
+    /// Morph from the live state toward the inactive A/B snapshot
+    #[id = "morph"]
+    pub morph: FloatParam,
+
+    /// Delay the dry signal by the wet path's latency before mixing
+    #[id = "dry_latency_match"]
+    pub dry_latency_match: BoolParam,
+
+    /// Stereo width of the wet signal (0 = mono, 1 = as is, 2 = twice as wide)
+    #[id = "width"]
+    pub width: FloatParam,
+
+    /// Input gain in dB
+    #[id = "input_gain"]
+    pub input_gain: FloatParam,
+
+    /// Input pad, before the input gain
+    #[id = "input_pad"]
+    pub input_pad: EnumParam<InputPad>,
+
+    /// Flip the input polarity
+    #[id = "phase_invert"]
+    pub phase_invert: BoolParam,
+
+    /// Which input channels feed the engine and the dry path
+    #[id = "input_source"]
+    pub input_source: EnumParam<InputSource>,
+
+    /// Mix, wet only, dry only or wet minus dry
+    #[id = "output_routing"]
+    pub output_routing: EnumParam<OutputRouting>,
+
+    /// Output gain in dB
+    #[id = "output_gain"]
+    pub output_gain: FloatParam,
+
+    /// Trim the output to match the input's loudness
+    #[id = "auto_gain"]
+    pub auto_gain: BoolParam,
+
+    /// Run Glicol at 2x or 4x the host rate to keep distortion from
+    /// aliasing. Not automatable: changing it rebuilds the engine.
+    #[id = "oversampling"]
+    pub oversampling: EnumParam<Oversampling>,
+
+    /// Built-in test signal fed in ahead of the input gain, for editing
+    /// patches without an instrument
+    #[id = "test_signal"]
+    pub test_signal: EnumParam<TestSource>,
+
+    #[id = "test_signal_mix"]
+    pub test_signal_mix: EnumParam<TestMix>,
+
+    #[id = "test_signal_level"]
+    pub test_signal_level: FloatParam,
+}
+
+impl Default for CoreParams {
+    fn default() -> Self {
+        Self {
             dry_wet: FloatParam::new(
                 "Dry/Wet",
                 1.0, // Full wet by default
@@ -129,6 +505,19 @@ impl Default for GlicolVerbParams {
             .with_value_to_string(formatters::v2s_f32_percentage(0))
             .with_string_to_value(formatters::s2v_f32_percentage()),
 
+            morph: FloatParam::new("Morph", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            dry_latency_match: BoolParam::new("Match Dry Latency", true),
+
+            width: FloatParam::new("Width", 1.0, FloatRange::Linear { min: 0.0, max: 2.0 })
+                .with_smoother(SmoothingStyle::Linear(20.0))
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
             input_gain: FloatParam::new(
                 "Input Gain",
                 util::db_to_gain(0.0),
@@ -143,6 +532,14 @@ impl Default for GlicolVerbParams {
             .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
             .with_string_to_value(formatters::s2v_f32_gain_to_db()),
 
+            input_pad: EnumParam::new("Input Pad", InputPad::Off),
+
+            phase_invert: BoolParam::new("Phase Invert", false),
+
+            input_source: EnumParam::new("Input Source", InputSource::Sum),
+
+            output_routing: EnumParam::new("Output Routing", OutputRouting::Normal),
+
             output_gain: FloatParam::new(
                 "Output Gain",
                 util::db_to_gain(0.0),
@@ -157,7 +554,91 @@ impl Default for GlicolVerbParams {
             .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
             .with_string_to_value(formatters::s2v_f32_gain_to_db()),
 
-            // === Mappable Knobs ===
+            auto_gain: BoolParam::new("Auto Gain", false),
+
+            oversampling: EnumParam::new("Oversampling", Oversampling::Off).non_automatable(),
+
+            test_signal: EnumParam::new("Test Signal", TestSource::Off),
+
+            test_signal_mix: EnumParam::new("Test Signal Mix", TestMix::Replace),
+
+            test_signal_level: FloatParam::new(
+                "Test Signal Level",
+                util::db_to_gain(-6.0),
+                FloatRange::Skewed {
+                    min: util::db_to_gain(-40.0),
+                    max: util::db_to_gain(0.0),
+                    factor: FloatRange::gain_skew_factor(-40.0, 0.0),
+                },
+            )
+            .with_smoother(SmoothingStyle::Logarithmic(50.0))
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_gain_to_db(1))
+            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
+        }
+    }
+}
+
+/// Values injected into Glicol code as `~name`
+#[derive(Params)]
+pub struct GlicolParams {
+    /// Knob 1 - maps to ~knob1 in Glicol code
+    #[id = "knob1"]
+    pub knob1: FloatParam,
+
+    /// Knob 2 - maps to ~knob2 in Glicol code
+    #[id = "knob2"]
+    pub knob2: FloatParam,
+
+    /// Knob 3 - maps to ~knob3 in Glicol code
+    #[id = "knob3"]
+    pub knob3: FloatParam,
+
+    /// Knob 4 - maps to ~knob4 in Glicol code
+    #[id = "knob4"]
+    pub knob4: FloatParam,
+
+    /// Knob 5 - maps to ~knob5 in Glicol code
+    #[id = "knob5"]
+    pub knob5: FloatParam,
+
+    /// Knob 6 - maps to ~knob6 in Glicol code
+    #[id = "knob6"]
+    pub knob6: FloatParam,
+
+    /// Knob 7 - maps to ~knob7 in Glicol code
+    #[id = "knob7"]
+    pub knob7: FloatParam,
+
+    /// Knob 8 - maps to ~knob8 in Glicol code
+    #[id = "knob8"]
+    pub knob8: FloatParam,
+
+    /// Expression pedal - maps to ~expr in Glicol code, and a modulation
+    /// matrix source
+    #[id = "expr"]
+    pub expr: FloatParam,
+
+    /// Drive amount - maps to ~drive in Glicol code
+    #[id = "drive"]
+    pub drive: FloatParam,
+
+    /// Feedback amount - maps to ~feedback in Glicol code
+    #[id = "feedback"]
+    pub feedback: FloatParam,
+
+    /// Effect mix - maps to ~mix in Glicol code
+    #[id = "mix"]
+    pub mix: FloatParam,
+
+    /// Rate (Hz) - maps to ~rate in Glicol code
+    #[id = "rate"]
+    pub rate: FloatParam,
+}
+
+impl Default for GlicolParams {
+    fn default() -> Self {
+        Self {
             knob1: FloatParam::new("Knob 1", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
                 .with_smoother(SmoothingStyle::Linear(10.0))
                 .with_value_to_string(formatters::v2s_f32_rounded(2)),
@@ -174,7 +655,27 @@ impl Default for GlicolVerbParams {
                 .with_smoother(SmoothingStyle::Linear(10.0))
                 .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
-            // === Effect Parameters ===
+            knob5: FloatParam::new("Knob 5", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_smoother(SmoothingStyle::Linear(10.0))
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            knob6: FloatParam::new("Knob 6", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_smoother(SmoothingStyle::Linear(10.0))
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            knob7: FloatParam::new("Knob 7", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_smoother(SmoothingStyle::Linear(10.0))
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            knob8: FloatParam::new("Knob 8", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_smoother(SmoothingStyle::Linear(10.0))
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            // Heavy smoothing: pedals usually arrive as coarse 7-bit CCs
+            expr: FloatParam::new("Expression", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_smoother(SmoothingStyle::Linear(100.0))
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
             drive: FloatParam::new(
                 "Drive",
                 1.0, // No overdrive by default
@@ -216,59 +717,257 @@ impl Default for GlicolVerbParams {
             .with_smoother(SmoothingStyle::Linear(10.0))
             .with_unit(" Hz")
             .with_value_to_string(formatters::v2s_f32_rounded(2)),
+        }
+    }
+}
 
-            // === Delay Module ===
-            delay_bypass: BoolParam::new("Delay Bypass", false),
+impl GlicolParams {
+    /// Every injected parameter, in `PARAM_NAMES` order (`~sidechain`, the
+    /// last name, follows the aux input rather than a parameter)
+    pub fn injected(&self) -> [&FloatParam; PARAM_NAMES.len() - 1] {
+        [
+            &self.knob1,
+            &self.knob2,
+            &self.knob3,
+            &self.knob4,
+            &self.knob5,
+            &self.knob6,
+            &self.knob7,
+            &self.knob8,
+            &self.drive,
+            &self.feedback,
+            &self.mix,
+            &self.rate,
+            &self.expr,
+        ]
+    }
+}
 
-            delay_time: FloatParam::new(
-                "Delay Time",
-                250.0, // 250ms default
+/// Macros, LFOs and the envelope follower
+#[derive(Params)]
+pub struct ModulationParams {
+    /// Macro 1 - drives the targets in `macro_map.macros[0]`
+    #[id = "macro1"]
+    pub macro1: FloatParam,
+
+    /// Macro 2 - drives the targets in `macro_map.macros[1]`
+    #[id = "macro2"]
+    pub macro2: FloatParam,
+
+    /// LFO 1 rate in Hz (when not synced)
+    #[id = "lfo1_rate"]
+    pub lfo1_rate: FloatParam,
+
+    /// LFO 1 waveform
+    #[id = "lfo1_shape"]
+    pub lfo1_shape: EnumParam<LfoShape>,
+
+    /// LFO 1 depth, scaling every route of this LFO
+    #[id = "lfo1_depth"]
+    pub lfo1_depth: FloatParam,
+
+    /// LFO 1 follows the host tempo instead of `lfo1_rate`
+    #[id = "lfo1_sync"]
+    pub lfo1_sync: BoolParam,
+
+    /// LFO 1 cycle length when synced
+    #[id = "lfo1_division"]
+    pub lfo1_division: EnumParam<LfoDivision>,
+
+    /// LFO 2 rate in Hz (when not synced)
+    #[id = "lfo2_rate"]
+    pub lfo2_rate: FloatParam,
+
+    /// LFO 2 waveform
+    #[id = "lfo2_shape"]
+    pub lfo2_shape: EnumParam<LfoShape>,
+
+    /// LFO 2 depth, scaling every route of this LFO
+    #[id = "lfo2_depth"]
+    pub lfo2_depth: FloatParam,
+
+    /// LFO 2 follows the host tempo instead of `lfo2_rate`
+    #[id = "lfo2_sync"]
+    pub lfo2_sync: BoolParam,
+
+    /// LFO 2 cycle length when synced
+    #[id = "lfo2_division"]
+    pub lfo2_division: EnumParam<LfoDivision>,
+
+    /// Envelope attack in ms
+    #[id = "env_attack"]
+    pub env_attack: FloatParam,
+
+    /// Envelope release in ms
+    #[id = "env_release"]
+    pub env_release: FloatParam,
+
+    /// Gain in dB applied to the input level before it drives the matrix
+    #[id = "env_sensitivity"]
+    pub env_sensitivity: FloatParam,
+}
+
+impl Default for ModulationParams {
+    fn default() -> Self {
+        Self {
+            macro1: FloatParam::new("Macro 1", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            macro2: FloatParam::new("Macro 2", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            lfo1_rate: FloatParam::new(
+                "LFO 1 Rate",
+                1.0,
                 FloatRange::Skewed {
-                    min: 1.0,
-                    max: 2000.0,
-                    factor: FloatRange::skew_factor(-1.5), // More resolution at short delays
+                    min: 0.01,
+                    max: 20.0,
+                    factor: FloatRange::skew_factor(-2.0),
                 },
             )
-            .with_smoother(SmoothingStyle::Linear(50.0))
-            .with_unit(" ms")
-            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+            .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
-            delay_feedback: FloatParam::new(
-                "Delay Feedback",
-                0.3,
-                FloatRange::Linear {
-                    min: 0.0,
-                    max: 0.95,
-                },
+            lfo1_shape: EnumParam::new("LFO 1 Shape", LfoShape::Sine),
+
+            lfo1_depth: FloatParam::new(
+                "LFO 1 Depth",
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
             )
-            .with_smoother(SmoothingStyle::Linear(10.0))
             .with_unit(" %")
             .with_value_to_string(formatters::v2s_f32_percentage(0))
             .with_string_to_value(formatters::s2v_f32_percentage()),
 
-            delay_mix: FloatParam::new("Delay Mix", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
-                .with_smoother(SmoothingStyle::Linear(10.0))
-                .with_unit(" %")
-                .with_value_to_string(formatters::v2s_f32_percentage(0))
-                .with_string_to_value(formatters::s2v_f32_percentage()),
+            lfo1_sync: BoolParam::new("LFO 1 Sync", false),
 
-            delay_highcut: FloatParam::new(
-                "Delay High-Cut",
-                12000.0,
+            lfo1_division: EnumParam::new("LFO 1 Division", LfoDivision::Quarter),
+
+            lfo2_rate: FloatParam::new(
+                "LFO 2 Rate",
+                1.0,
                 FloatRange::Skewed {
-                    min: 1000.0,
-                    max: 20000.0,
-                    factor: FloatRange::skew_factor(-1.0),
+                    min: 0.01,
+                    max: 20.0,
+                    factor: FloatRange::skew_factor(-2.0),
                 },
             )
-            .with_smoother(SmoothingStyle::Linear(10.0))
             .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            lfo2_shape: EnumParam::new("LFO 2 Shape", LfoShape::Sine),
+
+            lfo2_depth: FloatParam::new(
+                "LFO 2 Depth",
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit(" %")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            lfo2_sync: BoolParam::new("LFO 2 Sync", false),
+
+            lfo2_division: EnumParam::new("LFO 2 Division", LfoDivision::Quarter),
+
+            env_attack: FloatParam::new(
+                "Envelope Attack",
+                5.0,
+                FloatRange::Skewed {
+                    min: 0.1,
+                    max: 100.0,
+                    factor: FloatRange::skew_factor(-1.5),
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            env_release: FloatParam::new(
+                "Envelope Release",
+                150.0,
+                FloatRange::Skewed {
+                    min: 10.0,
+                    max: 2000.0,
+                    factor: FloatRange::skew_factor(-1.5),
+                },
+            )
+            .with_unit(" ms")
             .with_value_to_string(formatters::v2s_f32_rounded(0)),
 
-            // === EQ Module ===
-            eq_bypass: BoolParam::new("EQ Bypass", false),
+            env_sensitivity: FloatParam::new(
+                "Envelope Sensitivity",
+                6.0,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 30.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+        }
+    }
+}
+
+/// 3-band EQ module
+#[derive(Params)]
+pub struct EqParams {
+    /// EQ bypass
+    #[id = "eq_bypass"]
+    pub bypass: BoolParam,
+
+    /// EQ low shelf on/off
+    #[id = "eq_low_enabled"]
+    pub low_enabled: BoolParam,
+
+    /// EQ mid peak on/off
+    #[id = "eq_mid_enabled"]
+    pub mid_enabled: BoolParam,
+
+    /// EQ high shelf on/off
+    #[id = "eq_high_enabled"]
+    pub high_enabled: BoolParam,
 
-            eq_low_freq: FloatParam::new(
+    /// EQ low shelf frequency
+    #[id = "eq_low_freq"]
+    pub low_freq: FloatParam,
+
+    /// EQ low shelf gain
+    #[id = "eq_low_gain"]
+    pub low_gain: FloatParam,
+
+    /// EQ mid peak frequency
+    #[id = "eq_mid_freq"]
+    pub mid_freq: FloatParam,
+
+    /// EQ mid peak gain
+    #[id = "eq_mid_gain"]
+    pub mid_gain: FloatParam,
+
+    /// EQ mid peak Q
+    #[id = "eq_mid_q"]
+    pub mid_q: FloatParam,
+
+    /// EQ high shelf frequency
+    #[id = "eq_high_freq"]
+    pub high_freq: FloatParam,
+
+    /// EQ high shelf gain
+    #[id = "eq_high_gain"]
+    pub high_gain: FloatParam,
+}
+
+impl Default for EqParams {
+    fn default() -> Self {
+        Self {
+            bypass: BoolParam::new("EQ Bypass", false),
+
+            low_enabled: BoolParam::new("EQ Low On", true),
+
+            mid_enabled: BoolParam::new("EQ Mid On", true),
+
+            high_enabled: BoolParam::new("EQ High On", true),
+
+            low_freq: FloatParam::new(
                 "EQ Low Freq",
                 200.0,
                 FloatRange::Skewed {
@@ -281,7 +980,7 @@ impl Default for GlicolVerbParams {
             .with_unit(" Hz")
             .with_value_to_string(formatters::v2s_f32_rounded(0)),
 
-            eq_low_gain: FloatParam::new(
+            low_gain: FloatParam::new(
                 "EQ Low Gain",
                 0.0,
                 FloatRange::SymmetricalSkewed {
@@ -295,7 +994,7 @@ impl Default for GlicolVerbParams {
             .with_unit(" dB")
             .with_value_to_string(formatters::v2s_f32_rounded(1)),
 
-            eq_mid_freq: FloatParam::new(
+            mid_freq: FloatParam::new(
                 "EQ Mid Freq",
                 1000.0,
                 FloatRange::Skewed {
@@ -308,7 +1007,7 @@ impl Default for GlicolVerbParams {
             .with_unit(" Hz")
             .with_value_to_string(formatters::v2s_f32_rounded(0)),
 
-            eq_mid_gain: FloatParam::new(
+            mid_gain: FloatParam::new(
                 "EQ Mid Gain",
                 0.0,
                 FloatRange::SymmetricalSkewed {
@@ -322,7 +1021,7 @@ impl Default for GlicolVerbParams {
             .with_unit(" dB")
             .with_value_to_string(formatters::v2s_f32_rounded(1)),
 
-            eq_mid_q: FloatParam::new(
+            mid_q: FloatParam::new(
                 "EQ Mid Q",
                 1.0,
                 FloatRange::Skewed {
@@ -334,7 +1033,7 @@ impl Default for GlicolVerbParams {
             .with_smoother(SmoothingStyle::Linear(10.0))
             .with_value_to_string(formatters::v2s_f32_rounded(2)),
 
-            eq_high_freq: FloatParam::new(
+            high_freq: FloatParam::new(
                 "EQ High Freq",
                 4000.0,
                 FloatRange::Skewed {
@@ -347,7 +1046,7 @@ impl Default for GlicolVerbParams {
             .with_unit(" Hz")
             .with_value_to_string(formatters::v2s_f32_rounded(0)),
 
-            eq_high_gain: FloatParam::new(
+            high_gain: FloatParam::new(
                 "EQ High Gain",
                 0.0,
                 FloatRange::SymmetricalSkewed {
@@ -360,10 +1059,112 @@ impl Default for GlicolVerbParams {
             .with_smoother(SmoothingStyle::Linear(10.0))
             .with_unit(" dB")
             .with_value_to_string(formatters::v2s_f32_rounded(1)),
+        }
+    }
+}
 
-            code: Arc::new(RwLock::new(
-                "out: ~input".to_string(), // Pass-through
-            )),
+/// Delay module
+#[derive(Params)]
+pub struct DelayParams {
+    /// Delay bypass
+    #[id = "delay_bypass"]
+    pub bypass: BoolParam,
+
+    /// Delay time in milliseconds
+    #[id = "delay_time"]
+    pub time: FloatParam,
+
+    /// Delay feedback amount
+    #[id = "delay_feedback"]
+    pub feedback: FloatParam,
+
+    /// Delay wet/dry mix
+    #[id = "delay_mix"]
+    pub mix: FloatParam,
+
+    /// Delay high-cut filter frequency
+    #[id = "delay_highcut"]
+    pub highcut: FloatParam,
+
+    /// Delay time follows the tempo instead of `time`
+    #[id = "delay_sync"]
+    pub sync: BoolParam,
+
+    /// Delay time when synced
+    #[id = "delay_division"]
+    pub division: EnumParam<LfoDivision>,
+}
+
+impl Default for DelayParams {
+    fn default() -> Self {
+        Self {
+            bypass: BoolParam::new("Delay Bypass", false),
+
+            time: FloatParam::new(
+                "Delay Time",
+                250.0, // 250ms default
+                FloatRange::Skewed {
+                    min: 1.0,
+                    max: 2000.0,
+                    factor: FloatRange::skew_factor(-1.5), // More resolution at short delays
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(50.0))
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            feedback: FloatParam::new(
+                "Delay Feedback",
+                0.3,
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 0.95,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0))
+            .with_unit(" %")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            mix: FloatParam::new("Delay Mix", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 })
+                .with_smoother(SmoothingStyle::Linear(10.0))
+                .with_unit(" %")
+                .with_value_to_string(formatters::v2s_f32_percentage(0))
+                .with_string_to_value(formatters::s2v_f32_percentage()),
+
+            highcut: FloatParam::new(
+                "Delay High-Cut",
+                12000.0,
+                FloatRange::Skewed {
+                    min: 1000.0,
+                    max: 20000.0,
+                    factor: FloatRange::skew_factor(-1.0),
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(10.0))
+            .with_unit(" Hz")
+            .with_value_to_string(formatters::v2s_f32_rounded(0)),
+
+            sync: BoolParam::new("Delay Sync", false),
+
+            division: EnumParam::new("Delay Division", LfoDivision::Eighth),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::PARAM_RANGES;
+
+    #[test]
+    fn test_injected_ranges_match_core() {
+        // Tools outside the plugin rely on core's copy of these ranges
+        let params = GlicolParams::default();
+        for (param, (min, default, max)) in params.injected().into_iter().zip(PARAM_RANGES) {
+            assert_eq!(param.default_plain_value(), default, "{}", param.name());
+            assert_eq!(param.preview_plain(0.0), min, "{}", param.name());
+            assert_eq!(param.preview_plain(1.0), max, "{}", param.name());
         }
     }
 }
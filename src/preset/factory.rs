@@ -0,0 +1,184 @@
+//! Factory preset bank
+//!
+//! Full patches (code + parameter values) grouped by category. Values are
+//! plain parameter values; anything not listed is reset to its default.
+//!
+//! NIH-plug has no host-facing preset list, so the bank is browsed in the
+//! editor and reached from the DAW through MIDI program changes (see
+//! `MidiMap::preset_for_program()`).
+
+#[cfg(feature = "editor")]
+use nih_plug::prelude::*;
+#[cfg(feature = "editor")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "editor")]
+use super::Preset;
+#[cfg(feature = "editor")]
+use crate::params::GlicolVerbParams;
+
+/// A built-in patch
+///
+/// Without the editor only the code is read (program changes).
+#[cfg_attr(not(feature = "editor"), allow(dead_code))]
+pub struct FactoryPreset {
+    pub category: &'static str,
+    pub name: &'static str,
+    pub code: &'static str,
+    /// Plain values keyed by parameter ID
+    pub params: &'static [(&'static str, f32)],
+}
+
+/// Category names in display order
+#[cfg(feature = "editor")]
+pub const CATEGORIES: &[&str] = &["Ambient", "Rhythmic", "Lo-Fi", "Synth-like"];
+
+pub const FACTORY_PRESETS: &[FactoryPreset] = &[
+    // === Ambient ===
+    FactoryPreset {
+        category: "Ambient",
+        name: "Cathedral",
+        code: "out: ~input >> plate ~mix",
+        params: &[("mix", 0.8), ("delay_time", 600.0), ("delay_feedback", 0.55), ("delay_mix", 0.4)],
+    },
+    FactoryPreset {
+        category: "Ambient",
+        name: "Frozen Lake",
+        code: "out: ~input >> lpf 3000.0 0.5 >> plate 0.9",
+        params: &[("delay_time", 900.0), ("delay_feedback", 0.7), ("delay_highcut", 4000.0)],
+    },
+    FactoryPreset {
+        category: "Ambient",
+        name: "Swell Pad",
+        code: "out: ~input >> plate 0.6 >> delayms 400.0 >> mul 0.7 >> add ~input",
+        params: &[("eq_high_gain", -4.0), ("delay_mix", 0.35)],
+    },
+    // === Rhythmic ===
+    FactoryPreset {
+        category: "Rhythmic",
+        name: "Chopper",
+        code: "out: ~input >> mul ~gate\n~gate: squ ~rate >> mul 0.5 >> add 0.5",
+        params: &[("rate", 6.0), ("delay_bypass", 1.0)],
+    },
+    FactoryPreset {
+        category: "Rhythmic",
+        name: "Dotted Eighths",
+        code: "out: ~input",
+        params: &[("delay_time", 375.0), ("delay_feedback", 0.45), ("delay_mix", 0.45)],
+    },
+    FactoryPreset {
+        category: "Rhythmic",
+        name: "Wah Pulse",
+        code: "out: ~input >> lpf ~freq 2.0\n~freq: saw ~rate >> mul 1500.0 >> add 1800.0",
+        params: &[("rate", 4.0), ("delay_bypass", 1.0)],
+    },
+    // === Lo-Fi ===
+    FactoryPreset {
+        category: "Lo-Fi",
+        name: "Cassette",
+        code: "out: ~input >> mul ~drive >> lpf 2500.0 0.5 >> delayms ~wow\n~wow: sin 0.7 >> mul 2.0 >> add 6.0",
+        params: &[("drive", 2.0), ("eq_low_gain", 3.0), ("eq_high_gain", -6.0), ("delay_bypass", 1.0)],
+    },
+    FactoryPreset {
+        category: "Lo-Fi",
+        name: "AM Radio",
+        code: "out: ~input >> hpf 500.0 0.7 >> lpf 3000.0 0.7 >> mul 2.0",
+        params: &[("eq_mid_gain", 6.0), ("eq_mid_freq", 1500.0), ("delay_bypass", 1.0)],
+    },
+    FactoryPreset {
+        category: "Lo-Fi",
+        name: "Fuzz Box",
+        code: "out: ~input >> mul ~drive >> lpf 1800.0 0.9",
+        params: &[("drive", 8.0), ("output_gain", 0.5), ("delay_bypass", 1.0)],
+    },
+    // === Synth-like ===
+    FactoryPreset {
+        category: "Synth-like",
+        name: "Ring Bells",
+        code: "out: ~input >> mul ~carrier\n~carrier: sin 440.0",
+        params: &[("delay_time", 300.0), ("delay_mix", 0.3)],
+    },
+    FactoryPreset {
+        category: "Synth-like",
+        name: "Resonant Sweep",
+        code: "out: ~input >> lpf ~freq 6.0\n~freq: sin ~rate >> mul 2500.0 >> add 2800.0",
+        params: &[("rate", 0.3), ("output_gain", 0.7)],
+    },
+    FactoryPreset {
+        category: "Synth-like",
+        name: "Octave Shimmer",
+        code: "out: ~input >> add ~ring >> plate 0.5\n~ring: ~input >> mul ~osc >> mul 0.5\n~osc: sin 220.0",
+        params: &[("mix", 0.5)],
+    },
+];
+
+#[cfg(feature = "editor")]
+impl FactoryPreset {
+    /// Convert to a full preset, filling unlisted params with their defaults
+    pub fn to_preset(&self, params: &GlicolVerbParams) -> Preset {
+        let values = params
+            .param_map()
+            .into_iter()
+            .filter(|(id, _, _)| !super::EXCLUDED_PARAM_IDS.contains(&id.as_str()))
+            .map(|(id, ptr, _)| {
+                let plain = self
+                    .params
+                    .iter()
+                    .find(|(param_id, _)| *param_id == id)
+                    .map(|&(_, value)| value);
+                // SAFETY: the pointers come from `params`, which outlives this call
+                let normalized = unsafe {
+                    match plain {
+                        Some(plain) => ptr.preview_normalized(plain),
+                        None => ptr.default_normalized_value(),
+                    }
+                };
+                (id, normalized)
+            })
+            .collect();
+
+        Preset {
+            name: self.name.to_string(),
+            code: self.code.to_string(),
+            params: values,
+            snippets: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "editor"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_presets_are_valid() {
+        let params = GlicolVerbParams::default();
+        let ids: Vec<String> = params
+            .param_map()
+            .into_iter()
+            .map(|(id, _, _)| id)
+            .collect();
+
+        for preset in FACTORY_PRESETS {
+            assert!(CATEGORIES.contains(&preset.category), "{}", preset.name);
+            assert!(preset.code.contains("out:"), "{}", preset.name);
+            for (id, _) in preset.params {
+                assert!(
+                    ids.iter().any(|known| known == id),
+                    "{}: {}",
+                    preset.name,
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unlisted_params_use_defaults() {
+        let params = GlicolVerbParams::default();
+        let preset = FACTORY_PRESETS[0].to_preset(&params);
+
+        assert!((preset.params["knob1"] - 0.5).abs() < 0.001);
+        assert!(preset.params["mix"] > 0.7);
+    }
+}
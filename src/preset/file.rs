@@ -0,0 +1,100 @@
+//! Preset files (`.glicolpreset`)
+//!
+//! A preset file is JSON wrapping a [`Preset`] with a format tag and a
+//! version number, so later versions can migrate or reject old files.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::Preset;
+
+/// File extension for exported presets
+pub const EXTENSION: &str = "glicolpreset";
+
+/// Tag identifying GlicolVerb preset files
+const FORMAT: &str = "glicol-verb-preset";
+
+/// Current preset file version
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct PresetFile {
+    format: String,
+    version: u32,
+    preset: Preset,
+}
+
+/// Serialize a preset to pretty-printed JSON
+pub fn to_json(preset: &Preset) -> String {
+    let file = PresetFile {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        preset: preset.clone(),
+    };
+    // Only strings and numbers in here, so this can't fail
+    serde_json::to_string_pretty(&file).unwrap_or_default()
+}
+
+/// Parse a preset file, checking the format tag and version
+pub fn from_json(json: &str) -> Result<Preset, String> {
+    let file: PresetFile =
+        serde_json::from_str(json).map_err(|e| format!("Not a valid preset file: {}", e))?;
+    if file.format != FORMAT {
+        return Err(format!("Unknown preset format '{}'", file.format));
+    }
+    if file.version > VERSION {
+        return Err(format!(
+            "Preset file version {} is newer than supported ({})",
+            file.version, VERSION
+        ));
+    }
+    Ok(file.preset)
+}
+
+/// Write a preset to disk
+pub fn save(preset: &Preset, path: &Path) -> Result<(), String> {
+    std::fs::write(path, to_json(preset)).map_err(|e| format!("Could not write preset: {}", e))
+}
+
+/// Read a preset from disk
+pub fn load(path: &Path) -> Result<Preset, String> {
+    let json =
+        std::fs::read_to_string(path).map_err(|e| format!("Could not read preset: {}", e))?;
+    from_json(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn test_preset() -> Preset {
+        Preset {
+            name: "Test".to_string(),
+            code: "#include \"drive\"\nout: ~input >> mul ~drive".to_string(),
+            params: BTreeMap::from([("drive".to_string(), 0.25)]),
+            snippets: BTreeMap::from([("drive".to_string(), "~drive: sin 0.5".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let preset = test_preset();
+        assert_eq!(from_json(&to_json(&preset)), Ok(preset));
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let json = to_json(&test_preset()).replace("\"version\": 1", "\"version\": 99");
+        assert!(from_json(&json).unwrap_err().contains("newer"));
+    }
+
+    #[test]
+    fn test_rejects_other_json() {
+        assert!(from_json("{\"name\": \"x\"}").is_err());
+        let json = to_json(&test_preset()).replace(FORMAT, "something-else");
+        assert!(from_json(&json)
+            .unwrap_err()
+            .contains("Unknown preset format"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Presets: Glicol code plus parameter values
+//!
+//! Parameter values are stored as normalized values keyed by parameter ID,
+//! so new parameters are picked up automatically and unknown IDs from
+//! older presets are ignored. Snippets the code `#include`s travel with it,
+//! so exported and shared presets load on machines without them.
+//!
+//! The audio thread only reads the A/B snapshots and factory code;
+//! capturing, loading and sharing presets belongs to the editor.
+
+#[cfg(feature = "editor")]
+use nih_plug::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "editor")]
+use crate::engine::included_snippets;
+use crate::params::GlicolVerbParams;
+
+pub mod factory;
+#[cfg(feature = "editor")]
+pub mod file;
+#[cfg(feature = "editor")]
+pub mod randomize;
+#[cfg(feature = "editor")]
+pub mod share;
+
+/// Parameter IDs that are momentary controls rather than patch settings
+///
+/// `scene` picks stored code, which would fight the preset's own code,
+/// `bypass` belongs to the host's bypass switch, `morph` blends between
+/// the A/B snapshots themselves, loading a patch must never turn
+/// `safe_mode` off and the test signal is a studio aid, not part of a patch.
+#[cfg(feature = "editor")]
+const EXCLUDED_PARAM_IDS: &[&str] = &[
+    "panic",
+    "scene",
+    "bypass",
+    "morph",
+    "safe_mode",
+    "test_signal",
+    "test_signal_mix",
+    "test_signal_level",
+];
+
+/// A complete patch: code and parameter values
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub code: String,
+    /// Normalized values keyed by parameter ID
+    pub params: BTreeMap<String, f32>,
+    /// Library snippets the code includes (name -> code)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub snippets: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "editor")]
+impl Preset {
+    /// Capture `code` (usually the editor's, applied or not) with the
+    /// current parameter values and the snippets it includes
+    pub fn capture(name: &str, code: &str, params: &GlicolVerbParams) -> Self {
+        let values = params
+            .param_map()
+            .into_iter()
+            .filter(|(id, _, _)| !EXCLUDED_PARAM_IDS.contains(&id.as_str()))
+            // SAFETY: the pointers come from `params`, which outlives this call
+            .map(|(id, ptr, _)| (id, unsafe { ptr.unmodulated_normalized_value() }))
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            code: code.to_string(),
+            params: values,
+            snippets: included_snippets(code, &params.snippets.read()),
+        }
+    }
+
+    /// Add the bundled snippets missing from `library`
+    ///
+    /// A snippet the library already has under the same name is kept as
+    /// is; the names of those that differ from the bundled code are
+    /// returned so the user can be told.
+    pub fn merge_snippets(&self, library: &mut BTreeMap<String, String>) -> Vec<String> {
+        let mut differing = Vec::new();
+        for (name, code) in &self.snippets {
+            match library.get(name) {
+                Some(existing) if existing != code => differing.push(name.clone()),
+                Some(_) => {}
+                None => {
+                    library.insert(name.clone(), code.clone());
+                }
+            }
+        }
+        differing
+    }
+
+    /// Set all stored parameter values through the host (GUI thread)
+    ///
+    /// The code is not touched; callers send it through the normal update path.
+    pub fn apply_params(&self, params: &GlicolVerbParams, setter: &ParamSetter) {
+        for (id, ptr, _) in params.param_map() {
+            let Some(&value) = self.params.get(&id) else {
+                continue;
+            };
+            // SAFETY: the pointers come from `params`, which outlives the editor
+            unsafe {
+                setter.raw_context.raw_begin_set_parameter(ptr);
+                setter.raw_context.raw_set_parameter_normalized(ptr, value);
+                setter.raw_context.raw_end_set_parameter(ptr);
+            }
+        }
+    }
+}
+
+/// Which A/B snapshot is being edited
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum AbSlot {
+    #[default]
+    A,
+    B,
+}
+
+/// Two full snapshots for A/B comparison
+///
+/// Edits always belong to the active slot: switching stores the current
+/// state there and returns the other slot's state to load.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AbSnapshots {
+    pub a: Option<Preset>,
+    pub b: Option<Preset>,
+    pub active: AbSlot,
+}
+
+#[cfg(feature = "editor")]
+impl AbSnapshots {
+    fn slot_mut(&mut self, slot: AbSlot) -> &mut Option<Preset> {
+        match slot {
+            AbSlot::A => &mut self.a,
+            AbSlot::B => &mut self.b,
+        }
+    }
+
+    /// Make `slot` active, returning the snapshot to load (if it differs)
+    ///
+    /// An empty slot starts out as a copy of the current state.
+    pub fn switch(&mut self, slot: AbSlot, current: Preset) -> Option<Preset> {
+        if slot == self.active {
+            return None;
+        }
+        *self.slot_mut(self.active) = Some(current.clone());
+        self.active = slot;
+        let target = self.slot_mut(slot);
+        match target {
+            Some(snapshot) => Some(snapshot.clone()),
+            None => {
+                *target = Some(current);
+                None
+            }
+        }
+    }
+
+    /// Overwrite B with the current state (when A is active) or with A
+    pub fn copy_a_to_b(&mut self, current: Preset) {
+        if self.active == AbSlot::A {
+            self.a = Some(current);
+        }
+        self.b = self.a.clone();
+    }
+}
+
+#[cfg(all(test, feature = "editor"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_defaults() {
+        let params = GlicolVerbParams::default();
+        let preset = Preset::capture("Init", "out: ~input", &params);
+
+        assert_eq!(preset.code, "out: ~input");
+        assert!(preset.snippets.is_empty());
+        assert!((preset.params["knob1"] - 0.5).abs() < 0.001);
+        assert!(preset.params.contains_key("eq_mid_q"));
+        assert!(!preset.params.contains_key("panic"));
+        assert!(!preset.params.contains_key("bypass"));
+        assert!(!preset.params.contains_key("morph"));
+        assert!(!preset.params.contains_key("safe_mode"));
+    }
+
+    fn snapshot(code: &str) -> Preset {
+        Preset {
+            name: String::new(),
+            code: code.to_string(),
+            params: BTreeMap::new(),
+            snippets: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_capture_bundles_included_snippets() {
+        let params = GlicolVerbParams::default();
+        params.snippets.write().extend([
+            ("wobble".to_string(), "~wobble: sin 2.0".to_string()),
+            ("unused".to_string(), "~unused: sin 1.0".to_string()),
+        ]);
+        let code = "#include \"wobble\"\nout: ~input >> mul ~wobble";
+        let preset = Preset::capture("Wobble", code, &params);
+        assert_eq!(
+            preset.snippets,
+            BTreeMap::from([("wobble".to_string(), "~wobble: sin 2.0".to_string())])
+        );
+
+        // Loading elsewhere adds what's missing and keeps the user's own
+        let mut library = BTreeMap::from([("wobble".to_string(), "~wobble: saw 2.0".to_string())]);
+        assert_eq!(preset.merge_snippets(&mut library), ["wobble"]);
+        assert_eq!(library["wobble"], "~wobble: saw 2.0");
+        let mut library = BTreeMap::new();
+        assert!(preset.merge_snippets(&mut library).is_empty());
+        assert_eq!(library, preset.snippets);
+    }
+
+    #[test]
+    fn test_ab_switching() {
+        let mut ab = AbSnapshots::default();
+
+        // First switch to an empty B keeps the current state
+        assert_eq!(ab.switch(AbSlot::B, snapshot("a")), None);
+        assert_eq!(ab.active, AbSlot::B);
+
+        // Edits made on B are stored when switching back
+        assert_eq!(ab.switch(AbSlot::A, snapshot("b")), Some(snapshot("a")));
+        assert_eq!(ab.switch(AbSlot::B, snapshot("a2")), Some(snapshot("b")));
+        assert_eq!(ab.switch(AbSlot::B, snapshot("b")), None);
+    }
+
+    #[test]
+    fn test_ab_copy_a_to_b() {
+        let mut ab = AbSnapshots::default();
+        ab.copy_a_to_b(snapshot("a"));
+        assert_eq!(ab.b, Some(snapshot("a")));
+    }
+}
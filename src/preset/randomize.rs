@@ -0,0 +1,126 @@
+//! Parameter randomization for patch exploration
+//!
+//! Sets every unlocked parameter to a random value. Gains, host controls and
+//! anything with a dangerous top end are kept inside ranges that stay
+//! playable; locks are persisted with the plugin state (`ParamLocks`).
+
+use nih_plug::prelude::*;
+
+use crate::lang::mutate::Rng;
+use crate::params::{GlicolVerbParams, ParamLocks};
+
+/// Parameters randomize never touches: momentary and host controls, levels
+/// and the macros (which would fight their own targets)
+const NEVER_RANDOMIZED: &[&str] = &[
+    "panic",
+    "scene",
+    "bypass",
+    "safe_mode",
+    "morph",
+    "input_gain",
+    "input_pad",
+    "phase_invert",
+    "input_source",
+    "output_gain",
+    "output_routing",
+    "auto_gain",
+    "oversampling",
+    "test_signal",
+    "test_signal_mix",
+    "test_signal_level",
+    "dry_latency_match",
+    "macro1",
+    "macro2",
+    "eq_bypass",
+    "eq_low_enabled",
+    "eq_mid_enabled",
+    "eq_high_enabled",
+    "delay_bypass",
+];
+
+/// Normalized ranges that keep a random patch usable; other parameters use
+/// their full range
+const SENSIBLE_RANGES: &[(&str, f32, f32)] = &[
+    ("dry_wet", 0.3, 1.0),
+    ("drive", 0.0, 0.5),
+    ("feedback", 0.0, 0.8),
+    ("delay_feedback", 0.0, 0.75),
+    ("delay_mix", 0.1, 0.6),
+    ("eq_low_gain", 0.25, 0.75),
+    ("eq_mid_gain", 0.25, 0.75),
+    ("eq_high_gain", 0.25, 0.75),
+    ("lfo1_depth", 0.0, 0.6),
+    ("lfo2_depth", 0.0, 0.6),
+];
+
+/// Whether randomize can ever touch `param_id` (and so offers a lock)
+pub fn is_randomizable(param_id: &str) -> bool {
+    !NEVER_RANDOMIZED.contains(&param_id)
+}
+
+/// Random normalized value for `param_id`
+fn random_normalized(param_id: &str, rng: &mut Rng) -> f32 {
+    let (min, max) = SENSIBLE_RANGES
+        .iter()
+        .find(|(id, _, _)| *id == param_id)
+        .map_or((0.0, 1.0), |&(_, min, max)| (min, max));
+    min + (max - min) * rng.unit() as f32
+}
+
+/// New normalized values for every unlocked, randomizable parameter
+pub fn random_values(
+    params: &GlicolVerbParams,
+    locks: &ParamLocks,
+    rng: &mut Rng,
+) -> Vec<(String, ParamPtr, f32)> {
+    params
+        .param_map()
+        .into_iter()
+        .filter(|(id, _, _)| is_randomizable(id) && !locks.is_locked(id))
+        .map(|(id, ptr, _)| {
+            let value = random_normalized(&id, rng);
+            (id, ptr, value)
+        })
+        .collect()
+}
+
+/// Randomize the unlocked parameters through the host (GUI thread)
+pub fn randomize(params: &GlicolVerbParams, setter: &ParamSetter, rng: &mut Rng) {
+    let values = random_values(params, &params.param_locks.read(), rng);
+    for (_, ptr, value) in values {
+        // SAFETY: the pointers come from `params`, which outlives the editor
+        unsafe {
+            setter.raw_context.raw_begin_set_parameter(ptr);
+            setter.raw_context.raw_set_parameter_normalized(ptr, value);
+            setter.raw_context.raw_end_set_parameter(ptr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_values_respect_locks_and_ranges() {
+        let params = GlicolVerbParams::default();
+        let mut locks = ParamLocks::default();
+        locks.set_locked("knob1", true);
+        let mut rng = Rng::new(7);
+
+        let values = random_values(&params, &locks, &mut rng);
+        let value_of = |param_id: &str| {
+            values
+                .iter()
+                .find(|(id, _, _)| id == param_id)
+                .map(|(_, _, value)| *value)
+        };
+        assert_eq!(value_of("knob1"), None);
+        assert_eq!(value_of("output_gain"), None);
+        assert!(value_of("knob2").is_some());
+        assert!((0.3..=1.0).contains(&value_of("dry_wet").unwrap()));
+
+        locks.set_locked("knob1", false);
+        assert!(!locks.is_locked("knob1"));
+    }
+}
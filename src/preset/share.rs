@@ -0,0 +1,97 @@
+//! Shareable patch strings
+//!
+//! A patch string is `glicolverb:<version>:` followed by URL-safe base64 of
+//! the compact preset JSON, short enough to paste into a chat message.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+
+use super::Preset;
+
+/// Prefix identifying GlicolVerb patch strings
+const PREFIX: &str = "glicolverb";
+
+/// Current patch string version
+const VERSION: u32 = 1;
+
+/// Encode a preset as a patch string
+pub fn encode(preset: &Preset) -> String {
+    // Only strings and numbers in here, so this can't fail
+    let json = serde_json::to_string(preset).unwrap_or_default();
+    format!("{}:{}:{}", PREFIX, VERSION, URL_SAFE_NO_PAD.encode(json))
+}
+
+/// Decode a patch string, checking the prefix, version and values
+pub fn decode(text: &str) -> Result<Preset, String> {
+    // Chat apps like to wrap long lines
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut parts = text.splitn(3, ':');
+    let (Some(PREFIX), Some(version), Some(payload)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("Not a GlicolVerb patch string".to_string());
+    };
+
+    let version: u32 = version
+        .parse()
+        .map_err(|_| "Patch string has an invalid version".to_string())?;
+    if version > VERSION {
+        return Err(format!(
+            "Patch string version {} is newer than supported ({})",
+            version, VERSION
+        ));
+    }
+
+    let json = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| "Patch string is damaged (invalid base64)".to_string())?;
+    let preset: Preset = serde_json::from_slice(&json)
+        .map_err(|_| "Patch string is damaged (invalid data)".to_string())?;
+
+    if let Some((id, _)) = preset
+        .params
+        .iter()
+        .find(|(_, value)| !(0.0..=1.0).contains(*value))
+    {
+        return Err(format!("Patch string has an invalid value for '{}'", id));
+    }
+    Ok(preset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn test_preset() -> Preset {
+        Preset {
+            name: "Shared".to_string(),
+            code: "out: ~input >> lpf 800.0 1.0".to_string(),
+            params: BTreeMap::from([("drive".to_string(), 0.5), ("mix".to_string(), 1.0)]),
+            snippets: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let text = encode(&test_preset());
+        assert!(text.starts_with("glicolverb:1:"));
+        assert_eq!(decode(&text), Ok(test_preset()));
+
+        // Line breaks inserted by chat apps are ignored
+        let (head, tail) = text.split_at(20);
+        assert_eq!(decode(&format!("{}\n  {}", head, tail)), Ok(test_preset()));
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        assert!(decode("hello").is_err());
+        assert!(decode("glicolverb:1:!!!").unwrap_err().contains("damaged"));
+
+        let newer = encode(&test_preset()).replacen(":1:", ":2:", 1);
+        assert!(decode(&newer).unwrap_err().contains("newer"));
+
+        let mut preset = test_preset();
+        preset.params.insert("mix".to_string(), f32::NAN);
+        assert!(decode(&encode(&preset)).is_err());
+    }
+}
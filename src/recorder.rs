@@ -0,0 +1,380 @@
+//! Output recorder
+//!
+//! The audio thread copies each buffer's output (and optionally its input)
+//! into a lock-free ring; a writer thread drains the ring into 32-bit float
+//! WAV files, so the disk never holds up the audio thread. Frames that don't
+//! fit because the writer fell behind are dropped and counted.
+
+#[cfg(feature = "editor")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "editor")]
+use std::io::{BufWriter, ErrorKind};
+#[cfg(feature = "editor")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "editor")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+#[cfg(feature = "editor")]
+use std::sync::Arc;
+#[cfg(feature = "editor")]
+use std::thread::{self, JoinHandle};
+#[cfg(feature = "editor")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "editor")]
+use hound::{SampleFormat, WavSpec, WavWriter};
+use parking_lot::Mutex;
+#[cfg(feature = "editor")]
+use ringbuf::traits::{Consumer, Split};
+use ringbuf::traits::{Observer, Producer};
+use ringbuf::HeapProd;
+#[cfg(feature = "editor")]
+use ringbuf::{HeapCons, HeapRb};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "editor")]
+use crate::telemetry::AtomicF32;
+
+/// Audio the ring holds before the writer has to catch up
+#[cfg(feature = "editor")]
+const RING_SECONDS: f32 = 2.0;
+
+/// How often the writer drains the ring
+#[cfg(feature = "editor")]
+const WRITER_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Numbered names tried for recordings started in the same second
+#[cfg(feature = "editor")]
+const MAX_NAME_ATTEMPTS: u32 = 100;
+
+#[cfg(feature = "editor")]
+type Wav = WavWriter<BufWriter<File>>;
+
+/// Recorder options (persisted)
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecorderSettings {
+    /// Folder new recordings go to (empty until the user picks one)
+    pub directory: String,
+    /// Also write the plugin's input, to a second file
+    pub include_input: bool,
+}
+
+/// A running writer thread and the flag that ends it
+#[cfg(feature = "editor")]
+struct Writer {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Result<(), String>>,
+}
+
+/// Shared between the audio thread, which pushes frames, and the editor,
+/// which starts and stops recordings (without the editor nothing is ever
+/// recorded and `push()` returns straight away)
+#[derive(Default)]
+pub struct Recorder {
+    /// Audio side of the ring while recording
+    producer: Mutex<Option<HeapProd<f32>>>,
+    /// Samples per frame: 2 for the output, 4 with the input (0 when idle)
+    channels: AtomicUsize,
+    #[cfg(feature = "editor")]
+    sample_rate: AtomicF32,
+    /// Frames recorded, and frames dropped because the writer fell behind
+    frames: AtomicUsize,
+    dropped: AtomicU32,
+    #[cfg(feature = "editor")]
+    writer: Mutex<Option<Writer>>,
+}
+
+impl Recorder {
+    /// Samples per frame `push()` expects, 0 when not recording
+    pub fn channels(&self) -> usize {
+        self.channels.load(Ordering::Acquire)
+    }
+
+    /// Stop recording and wait for the files to be finished (editor thread)
+    pub fn stop(&self) -> Result<(), String> {
+        self.channels.store(0, Ordering::Release);
+        // Everything pushed so far is in the ring once the producer is gone
+        drop(self.producer.lock().take());
+        #[cfg(feature = "editor")]
+        if let Some(writer) = self.writer.lock().take() {
+            writer.stop.store(true, Ordering::Release);
+            return writer
+                .handle
+                .join()
+                .map_err(|_| "The recorder stopped unexpectedly".to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Append interleaved frames of `channels` samples each (audio thread)
+    ///
+    /// Never blocks: skipped while the editor is starting or stopping a
+    /// recording, and dropped whole when the ring is too full.
+    pub fn push(&self, samples: &[f32], channels: usize) {
+        if channels == 0 || channels != self.channels() {
+            return;
+        }
+        let Some(mut producer) = self.producer.try_lock() else {
+            return;
+        };
+        let Some(producer) = producer.as_mut() else {
+            return;
+        };
+        let frames = samples.len() / channels;
+        if producer.vacant_len() < samples.len() {
+            self.dropped.fetch_add(frames as u32, Ordering::Relaxed);
+            return;
+        }
+        producer.push_slice(samples);
+        self.frames.fetch_add(frames, Ordering::Relaxed);
+    }
+}
+
+/// Editor side
+#[cfg(feature = "editor")]
+impl Recorder {
+    pub fn is_recording(&self) -> bool {
+        self.channels() != 0
+    }
+
+    /// Length of the current recording in seconds
+    pub fn elapsed(&self) -> f32 {
+        let sample_rate = self.sample_rate.load();
+        if sample_rate > 0.0 {
+            self.frames.load(Ordering::Relaxed) as f32 / sample_rate
+        } else {
+            0.0
+        }
+    }
+
+    /// Frames lost in the current recording
+    pub fn dropped(&self) -> u32 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Start recording into `directory` (editor thread)
+    ///
+    /// Returns the output file; with `include_input` the input goes next to
+    /// it with an `-input` suffix. Existing files are never overwritten.
+    pub fn start(
+        &self,
+        directory: &Path,
+        sample_rate: f32,
+        include_input: bool,
+    ) -> Result<PathBuf, String> {
+        if self.is_recording() {
+            return Err("Already recording".to_string());
+        }
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let (output_path, output, input) =
+            create_files(directory, stamp, sample_rate, include_input)?;
+
+        let channels = if include_input { 4 } else { 2 };
+        let ring = HeapRb::<f32>::new((sample_rate * RING_SECONDS) as usize * channels);
+        let (producer, consumer) = ring.split();
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let stop = stop.clone();
+            thread::Builder::new()
+                .name("glicolverb-recorder".to_string())
+                .spawn(move || write_loop(consumer, output, input, channels, &stop))
+                .map_err(|e| format!("Couldn't start the recorder: {}", e))?
+        };
+
+        self.frames.store(0, Ordering::Relaxed);
+        self.dropped.store(0, Ordering::Relaxed);
+        self.sample_rate.store(sample_rate);
+        *self.writer.lock() = Some(Writer { stop, handle });
+        *self.producer.lock() = Some(producer);
+        self.channels.store(channels, Ordering::Release);
+        Ok(output_path)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // Finish the files if the plugin goes away mid-recording
+        let _ = self.stop();
+    }
+}
+
+/// Create the output (and input) file under an unused name
+///
+/// `glicolverb-<seconds>.wav` first, then `glicolverb-<seconds>-2.wav` and
+/// so on for recordings started within the same second.
+#[cfg(feature = "editor")]
+fn create_files(
+    directory: &Path,
+    stamp: u64,
+    sample_rate: f32,
+    include_input: bool,
+) -> Result<(PathBuf, Wav, Option<Wav>), String> {
+    for attempt in 1..=MAX_NAME_ATTEMPTS {
+        let stem = match attempt {
+            1 => format!("glicolverb-{}", stamp),
+            n => format!("glicolverb-{}-{}", stamp, n),
+        };
+        let output_path = directory.join(format!("{}.wav", stem));
+        let Some(output) = create_wav(&output_path, sample_rate)? else {
+            continue;
+        };
+        if !include_input {
+            return Ok((output_path, output, None));
+        }
+        let input_path = directory.join(format!("{}-input.wav", stem));
+        match create_wav(&input_path, sample_rate)? {
+            Some(input) => return Ok((output_path, output, Some(input))),
+            None => {
+                // Keep the pair together under the next name
+                drop(output);
+                let _ = std::fs::remove_file(&output_path);
+            }
+        }
+    }
+    Err(format!(
+        "Couldn't find a free file name in {}",
+        directory.display()
+    ))
+}
+
+/// Create a new WAV file, or `None` if `path` already exists
+#[cfg(feature = "editor")]
+fn create_wav(path: &Path, sample_rate: f32) -> Result<Option<Wav>, String> {
+    let spec = WavSpec {
+        channels: 2,
+        sample_rate: sample_rate.round() as u32,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let create_error =
+        |e: &dyn std::fmt::Display| format!("Couldn't create {}: {}", path.display(), e);
+    let file = match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(None),
+        Err(e) => return Err(create_error(&e)),
+    };
+    WavWriter::new(BufWriter::new(file), spec)
+        .map(Some)
+        .map_err(|e| create_error(&e))
+}
+
+/// Drain the ring into the files until stopped, then finish them
+#[cfg(feature = "editor")]
+fn write_loop(
+    mut consumer: HeapCons<f32>,
+    mut output: Wav,
+    mut input: Option<Wav>,
+    channels: usize,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    let write_error = |e: hound::Error| format!("Couldn't write the recording: {}", e);
+    let mut chunk = vec![0.0; 1024 * channels];
+    loop {
+        // Read the flag first so the last drain sees everything pushed
+        let stopping = stop.load(Ordering::Acquire);
+        loop {
+            let count = consumer.pop_slice(&mut chunk);
+            if count == 0 {
+                break;
+            }
+            for frame in chunk[..count].chunks_exact(channels) {
+                output.write_sample(frame[0]).map_err(write_error)?;
+                output.write_sample(frame[1]).map_err(write_error)?;
+                if let Some(input) = &mut input {
+                    input.write_sample(frame[2]).map_err(write_error)?;
+                    input.write_sample(frame[3]).map_err(write_error)?;
+                }
+            }
+        }
+        if stopping {
+            break;
+        }
+        thread::sleep(WRITER_INTERVAL);
+    }
+    output.finalize().map_err(write_error)?;
+    if let Some(input) = input {
+        input.finalize().map_err(write_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "editor"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_output_and_input() {
+        let directory = std::env::temp_dir().join(format!("glicolverb-rec-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let recorder = Recorder::default();
+
+        // Nothing is taken while idle
+        recorder.push(&[1.0; 8], 4);
+        let path = recorder.start(&directory, 48000.0, true).unwrap();
+        assert_eq!(recorder.channels(), 4);
+        recorder.push(&[0.5, -0.5, 0.25, -0.25].repeat(100), 4);
+        // Frames of the wrong width are ignored
+        recorder.push(&[1.0; 6], 2);
+        recorder.stop().unwrap();
+        assert!(!recorder.is_recording());
+
+        let output: Vec<f32> = hound::WavReader::open(&path)
+            .unwrap()
+            .into_samples()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(output.len(), 200);
+        assert_eq!(&output[..2], &[0.5, -0.5]);
+
+        let input_path = path.with_file_name(format!(
+            "{}-input.wav",
+            path.file_stem().unwrap().to_str().unwrap()
+        ));
+        let input: Vec<f32> = hound::WavReader::open(&input_path)
+            .unwrap()
+            .into_samples()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(&input[..2], &[0.25, -0.25]);
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_recordings_in_the_same_second_get_their_own_files() {
+        let directory =
+            std::env::temp_dir().join(format!("glicolverb-names-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let (first, _, _) = create_files(&directory, 1000, 48000.0, true).unwrap();
+        let (second, _, input) = create_files(&directory, 1000, 48000.0, true).unwrap();
+        assert_eq!(first, directory.join("glicolverb-1000.wav"));
+        assert_eq!(second, directory.join("glicolverb-1000-2.wav"));
+        assert!(input.is_some());
+        assert!(directory.join("glicolverb-1000-2-input.wav").exists());
+
+        // A stray input file pushes the pair on to the next name
+        std::fs::write(directory.join("glicolverb-1000-3-input.wav"), b"").unwrap();
+        let (third, _, _) = create_files(&directory, 1000, 48000.0, true).unwrap();
+        assert_eq!(third, directory.join("glicolverb-1000-4.wav"));
+        assert!(!directory.join("glicolverb-1000-3.wav").exists());
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_full_ring_drops_whole_buffers() {
+        let recorder = Recorder::default();
+        let ring = HeapRb::<f32>::new(8);
+        let (producer, _consumer) = ring.split();
+        *recorder.producer.lock() = Some(producer);
+        recorder.channels.store(2, Ordering::Release);
+
+        recorder.push(&[0.0; 6], 2);
+        recorder.push(&[0.0; 6], 2);
+        assert_eq!(recorder.frames.load(Ordering::Relaxed), 3);
+        assert_eq!(recorder.dropped(), 3);
+    }
+}
@@ -0,0 +1,209 @@
+//! Keyboard shortcut map
+//!
+//! Persisted with the plugin state and matched by the editor. Keys are
+//! stored by their egui name (e.g. "Enter", "F11", "]") so the map stays
+//! readable in saved projects.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Editor commands that can be bound to a key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    Apply,
+    Panic,
+    ToggleEqBypass,
+    ToggleDelayBypass,
+    NextScene,
+    PreviousScene,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    FocusMode,
+    CheatSheet,
+    Find,
+    Replace,
+    Format,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Apply,
+        Action::Panic,
+        Action::ToggleEqBypass,
+        Action::ToggleDelayBypass,
+        Action::NextScene,
+        Action::PreviousScene,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ZoomReset,
+        Action::FocusMode,
+        Action::CheatSheet,
+        Action::Find,
+        Action::Replace,
+        Action::Format,
+    ];
+
+    #[cfg(feature = "editor")]
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Apply => "Apply code",
+            Action::Panic => "Panic",
+            Action::ToggleEqBypass => "Toggle EQ bypass",
+            Action::ToggleDelayBypass => "Toggle delay bypass",
+            Action::NextScene => "Next scene",
+            Action::PreviousScene => "Previous scene",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::ZoomReset => "Reset zoom",
+            Action::FocusMode => "Full-screen code",
+            Action::CheatSheet => "Shortcut cheat sheet",
+            Action::Find => "Find in code",
+            Action::Replace => "Find and replace",
+            Action::Format => "Format code",
+        }
+    }
+}
+
+/// A key plus modifiers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shortcut {
+    /// egui key name
+    pub key: String,
+    /// Ctrl (Cmd on macOS)
+    pub command: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Shortcut {
+    pub fn new(key: &str, command: bool, shift: bool, alt: bool) -> Self {
+        Self {
+            key: key.to_string(),
+            command,
+            shift,
+            alt,
+        }
+    }
+
+    /// Human-readable form, e.g. "Ctrl+Shift+E"
+    #[cfg(feature = "editor")]
+    pub fn format(&self) -> String {
+        let mut text = String::new();
+        if self.command {
+            text.push_str(if cfg!(target_os = "macos") {
+                "Cmd+"
+            } else {
+                "Ctrl+"
+            });
+        }
+        if self.alt {
+            text.push_str("Alt+");
+        }
+        if self.shift {
+            text.push_str("Shift+");
+        }
+        text.push_str(&self.key);
+        text
+    }
+
+    /// Whether the shortcut would type into a focused text field
+    ///
+    /// Such shortcuts are ignored while the code editor has focus.
+    #[cfg(feature = "editor")]
+    pub fn types_text(&self) -> bool {
+        let function_key =
+            self.key.len() > 1 && self.key.starts_with('F') && self.key[1..].parse::<u8>().is_ok();
+        !self.command && !self.alt && !function_key
+    }
+}
+
+/// Action -> shortcut bindings
+///
+/// `None` means the user left the action unbound; actions missing from the
+/// map (added after it was saved) use their default.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShortcutMap {
+    pub bindings: BTreeMap<Action, Option<Shortcut>>,
+}
+
+impl Default for ShortcutMap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| (action, Some(default_shortcut(action))))
+            .collect();
+        Self { bindings }
+    }
+}
+
+#[cfg(feature = "editor")]
+impl ShortcutMap {
+    pub fn get(&self, action: Action) -> Option<Shortcut> {
+        match self.bindings.get(&action) {
+            Some(binding) => binding.clone(),
+            None => Some(default_shortcut(action)),
+        }
+    }
+
+    /// Bind `action`, unbinding any other action that used the same shortcut
+    ///
+    /// Returns the action that lost its binding.
+    pub fn set(&mut self, action: Action, shortcut: Shortcut) -> Option<Action> {
+        let displaced = Action::ALL
+            .iter()
+            .copied()
+            .find(|&other| other != action && self.get(other).as_ref() == Some(&shortcut));
+        if let Some(other) = displaced {
+            self.bindings.insert(other, None);
+        }
+        self.bindings.insert(action, Some(shortcut));
+        displaced
+    }
+}
+
+fn default_shortcut(action: Action) -> Shortcut {
+    match action {
+        Action::Apply => Shortcut::new("Enter", true, false, false),
+        Action::Panic => Shortcut::new(".", true, false, false),
+        Action::ToggleEqBypass => Shortcut::new("E", true, true, false),
+        Action::ToggleDelayBypass => Shortcut::new("D", true, true, false),
+        Action::NextScene => Shortcut::new("]", true, false, false),
+        Action::PreviousScene => Shortcut::new("[", true, false, false),
+        Action::ZoomIn => Shortcut::new("=", true, false, false),
+        Action::ZoomOut => Shortcut::new("-", true, false, false),
+        Action::ZoomReset => Shortcut::new("0", true, false, false),
+        Action::FocusMode => Shortcut::new("F11", false, false, false),
+        Action::CheatSheet => Shortcut::new("?", false, false, false),
+        Action::Find => Shortcut::new("F", true, false, false),
+        Action::Replace => Shortcut::new("H", true, false, false),
+        Action::Format => Shortcut::new("F", true, true, false),
+    }
+}
+
+#[cfg(all(test, feature = "editor"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_text_keys() {
+        let shortcut = Shortcut::new("E", true, true, false);
+        assert!(shortcut.format().ends_with("+Shift+E"));
+        assert!(!shortcut.types_text());
+        assert!(Shortcut::new("?", false, false, false).types_text());
+        assert!(!Shortcut::new("F11", false, false, false).types_text());
+    }
+
+    #[test]
+    fn test_rebinding_displaces_conflicts() {
+        let mut map = ShortcutMap::default();
+        let f11 = Shortcut::new("F11", false, false, false);
+        assert_eq!(map.set(Action::Apply, f11.clone()), Some(Action::FocusMode));
+        assert_eq!(map.get(Action::Apply), Some(f11));
+        assert_eq!(map.get(Action::FocusMode), None);
+
+        // Actions unknown to an older saved map fall back to their default
+        map.bindings.remove(&Action::CheatSheet);
+        assert!(map.get(Action::CheatSheet).is_some());
+    }
+}
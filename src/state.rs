@@ -0,0 +1,253 @@
+//! Versioned session state
+//!
+//! The persisted fields as a whole (code, scenes, MIDI map, module chain
+//! and the rest) carry a layout version under `state-version`. When a
+//! session is loaded, `migrate_fields` rewrites the stored JSON of older
+//! layouts step by step before nih-plug deserializes it, so a field can
+//! change shape, move or be derived from others without breaking recall.
+//!
+//! The module chain also versions itself: it used to be persisted as a
+//! bare `ModuleOrder` array and now lives in a `ChainState` that also
+//! holds per-module settings that aren't parameters and the preset the
+//! session came from.
+
+use nih_plug::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::dsp::{ModuleKind, ModuleOrder};
+use crate::params::default_scenes;
+
+/// Layout of the persisted fields written by this build. Bump it and add a
+/// step to `migrate_fields` whenever a persisted field changes in a way
+/// `#[serde(default)]` can't absorb.
+pub const SESSION_STATE_VERSION: u32 = 1;
+
+/// Persist key holding the layout version
+const VERSION_KEY: &str = "state-version";
+
+/// Chain format written by this build
+pub const CHAIN_STATE_VERSION: u32 = 1;
+
+/// Bring the persisted fields of a loaded session up to
+/// `SESSION_STATE_VERSION`
+///
+/// `fields` maps persist keys to their stored JSON. Sessions from before
+/// versioning have no version key and count as version 0. Returns the
+/// version the session was saved with.
+pub fn migrate_fields(fields: &mut BTreeMap<String, String>) -> u32 {
+    let saved = fields
+        .get(VERSION_KEY)
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or(0);
+    if saved > SESSION_STATE_VERSION {
+        nih_warn!(
+            "Session saved by a newer version (state {}), loading what this one understands",
+            saved
+        );
+        return saved;
+    }
+
+    if saved < 1 {
+        seed_scenes(fields);
+    }
+
+    fields.insert(VERSION_KEY.to_string(), SESSION_STATE_VERSION.to_string());
+    saved
+}
+
+/// Version 0 to 1: sessions from before scenes only stored the running
+/// code, which becomes scene 1 instead of being lost on the first switch
+fn seed_scenes(fields: &mut BTreeMap<String, String>) {
+    if fields.contains_key("scenes") {
+        return;
+    }
+    let Some(code) = fields
+        .get("glicol-code")
+        .and_then(|json| serde_json::from_str::<String>(json).ok())
+    else {
+        return;
+    };
+    let mut scenes = default_scenes();
+    scenes[0].code = code;
+    if let Ok(json) = serde_json::to_string(&scenes) {
+        fields.insert("scenes".to_string(), json);
+    }
+}
+
+/// Module chain configuration saved with the session
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredChainState")]
+pub struct ChainState {
+    pub version: u32,
+    pub order: ModuleOrder,
+    /// Settings beyond a module's parameters, for modules that have any
+    pub modules: BTreeMap<ModuleKind, ModuleSettings>,
+    /// Preset the session was last loaded from
+    pub preset: Option<PresetInfo>,
+}
+
+impl Default for ChainState {
+    fn default() -> Self {
+        Self {
+            version: CHAIN_STATE_VERSION,
+            order: ModuleOrder::default(),
+            modules: BTreeMap::new(),
+            preset: None,
+        }
+    }
+}
+
+/// Per-module state that doesn't fit a parameter
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleSettings {
+    /// Files the module loads (an impulse response, say), keyed by role
+    #[serde(default)]
+    pub files: BTreeMap<String, PathBuf>,
+    /// Save the module's audio buffers with the session. Off by default,
+    /// since they can make the host's project file very large.
+    #[serde(default)]
+    pub store_buffers: bool,
+}
+
+/// Where the current patch came from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresetInfo {
+    pub name: String,
+    /// Unix time in seconds
+    pub loaded_at: u64,
+}
+
+#[cfg(feature = "editor")]
+impl PresetInfo {
+    pub fn now(name: &str) -> Self {
+        let loaded_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self {
+            name: name.to_string(),
+            loaded_at,
+        }
+    }
+}
+
+/// Every shape the chain state has been saved in
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredChainState {
+    Versioned(VersionedChainState),
+    /// Before version 1 only the order was stored
+    Legacy(ModuleOrder),
+}
+
+#[derive(Deserialize)]
+struct VersionedChainState {
+    version: u32,
+    order: ModuleOrder,
+    #[serde(default)]
+    modules: BTreeMap<ModuleKind, ModuleSettings>,
+    #[serde(default)]
+    preset: Option<PresetInfo>,
+}
+
+impl From<StoredChainState> for ChainState {
+    fn from(stored: StoredChainState) -> Self {
+        match stored {
+            StoredChainState::Versioned(state) => Self {
+                version: state.version,
+                order: state.order,
+                modules: state.modules,
+                preset: state.preset,
+            }
+            .migrate(),
+            StoredChainState::Legacy(order) => Self {
+                version: 0,
+                order,
+                ..Self::default()
+            }
+            .migrate(),
+        }
+    }
+}
+
+impl ChainState {
+    /// Bring a loaded state up to `CHAIN_STATE_VERSION`
+    ///
+    /// Each step upgrades one version; add new steps as the format grows.
+    fn migrate(mut self) -> Self {
+        if self.version == 0 {
+            // Bare order: nothing else to fill in
+            self.version = 1;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_fields() -> BTreeMap<String, String> {
+        BTreeMap::from([(
+            "glicol-code".to_string(),
+            serde_json::to_string("out: ~input >> plate 0.3").unwrap(),
+        )])
+    }
+
+    #[test]
+    fn test_seeds_scenes_from_unversioned_code() {
+        let mut fields = legacy_fields();
+        assert_eq!(migrate_fields(&mut fields), 0);
+        assert_eq!(fields[VERSION_KEY], SESSION_STATE_VERSION.to_string());
+
+        let scenes: Vec<crate::params::CodeScene> =
+            serde_json::from_str(&fields["scenes"]).unwrap();
+        assert_eq!(scenes[0].code, "out: ~input >> plate 0.3");
+        assert_eq!(scenes[1].code, "out: ~input");
+    }
+
+    #[test]
+    fn test_current_sessions_untouched() {
+        let mut fields = legacy_fields();
+        migrate_fields(&mut fields);
+        fields.insert("scenes".to_string(), "[]".to_string());
+
+        let before = fields.clone();
+        assert_eq!(migrate_fields(&mut fields), SESSION_STATE_VERSION);
+        assert_eq!(fields, before);
+    }
+
+    #[test]
+    fn test_migrates_legacy_order() {
+        let state: ChainState = serde_json::from_str(r#"["Delay","Glicol","Eq"]"#).unwrap();
+        assert_eq!(state.version, CHAIN_STATE_VERSION);
+        assert_eq!(
+            state.order.0,
+            [ModuleKind::Delay, ModuleKind::Glicol, ModuleKind::Eq]
+        );
+        assert!(state.modules.is_empty());
+        assert_eq!(state.preset, None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut state = ChainState::default();
+        state.order.move_module(2, 0);
+        state.modules.insert(
+            ModuleKind::Delay,
+            ModuleSettings {
+                store_buffers: true,
+                ..ModuleSettings::default()
+            },
+        );
+        state.preset = Some(PresetInfo {
+            name: "Shimmer".to_string(),
+            loaded_at: 1_700_000_000,
+        });
+
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: ChainState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, state);
+    }
+}
@@ -0,0 +1,302 @@
+//! Lock-free audio -> GUI telemetry
+//!
+//! The audio thread publishes values into atomics once per buffer; the GUI
+//! reads them whenever it repaints. Nothing here blocks or allocates.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+use crate::diagnostics::Diagnostics;
+
+/// RMS integration time
+const RMS_WINDOW_MS: f32 = 300.0;
+
+/// Number of recent samples kept for the spectrum analyzer (power of two)
+pub const TAP_SIZE: usize = 2048;
+
+/// An `f32` stored as bits in an `AtomicU32`
+#[derive(Default)]
+pub struct AtomicF32(AtomicU32);
+
+impl AtomicF32 {
+    pub fn load(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn store(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Read the value and reset it to zero
+    #[cfg(feature = "editor")]
+    pub fn take(&self) -> f32 {
+        f32::from_bits(self.0.swap(0.0f32.to_bits(), Ordering::Relaxed))
+    }
+
+    /// Raise the stored value to `value` if it is larger (non-negative values)
+    pub fn fetch_max(&self, value: f32) {
+        // Non-negative floats order the same as their bit patterns
+        self.0.fetch_max(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Published levels for one signal point (linear gain)
+#[derive(Default)]
+pub struct LevelMeter {
+    /// Highest peak since the GUI last took it
+    pub peak: AtomicF32,
+    pub rms: AtomicF32,
+}
+
+/// Circular buffer of recent mono samples, readable from the GUI
+///
+/// Reads may tear across a buffer boundary, which is fine for display.
+pub struct SampleTap {
+    samples: Box<[AtomicF32]>,
+    write_pos: AtomicUsize,
+}
+
+impl Default for SampleTap {
+    fn default() -> Self {
+        Self {
+            samples: (0..TAP_SIZE).map(|_| AtomicF32::default()).collect(),
+            write_pos: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl SampleTap {
+    /// Append a sample (audio thread only)
+    #[inline]
+    pub fn push(&self, value: f32) {
+        let pos = self.write_pos.load(Ordering::Relaxed);
+        self.samples[pos].store(value);
+        self.write_pos
+            .store((pos + 1) % TAP_SIZE, Ordering::Relaxed);
+    }
+
+    /// Copy out the last `TAP_SIZE` samples, oldest first
+    #[cfg(feature = "editor")]
+    pub fn snapshot(&self) -> Vec<f32> {
+        let start = self.write_pos.load(Ordering::Relaxed);
+        (0..TAP_SIZE)
+            .map(|i| self.samples[(start + i) % TAP_SIZE].load())
+            .collect()
+    }
+}
+
+/// Everything the audio thread reports to the editor
+#[derive(Default)]
+pub struct Telemetry {
+    /// After input gain
+    pub input: LevelMeter,
+    /// Straight out of Glicol
+    pub wet: LevelMeter,
+    /// Final plugin output
+    pub output: LevelMeter,
+
+    /// Output stereo correlation (-1.0 to 1.0)
+    pub correlation: AtomicF32,
+
+    pub sample_rate: AtomicF32,
+    /// Set by the editor while the analyzer or goniometer is visible
+    pub taps_enabled: AtomicBool,
+    pub input_tap: SampleTap,
+    pub output_left_tap: SampleTap,
+    pub output_right_tap: SampleTap,
+
+    /// Output samples the Glicol bridge couldn't deliver (total)
+    pub underruns: AtomicU32,
+    /// Share of the buffer period spent in `process()` (smoothed, 0.0-1.0+)
+    pub cpu_load: AtomicF32,
+    /// Host tempo in BPM (0.0 when the host doesn't report one)
+    pub tempo: AtomicF32,
+    /// Plugin latency reported to the host, in samples
+    pub latency_samples: AtomicU32,
+    /// Output trim applied by auto gain, in dB
+    pub auto_gain_db: AtomicF32,
+    /// Events for the diagnostics log
+    pub diagnostics: Diagnostics,
+}
+
+/// Smoothed processing load
+pub struct CpuMeter {
+    load: f32,
+}
+
+impl CpuMeter {
+    /// Weight of each new buffer in the running average
+    const SMOOTHING: f32 = 0.1;
+
+    pub fn new() -> Self {
+        Self { load: 0.0 }
+    }
+
+    /// Add one buffer's processing time and return the smoothed load
+    pub fn update(&mut self, elapsed_secs: f32, buffer_secs: f32) -> f32 {
+        if buffer_secs > 0.0 {
+            let load = elapsed_secs / buffer_secs;
+            self.load += (load - self.load) * Self::SMOOTHING;
+        }
+        self.load
+    }
+}
+
+/// Audio-thread side of a `LevelMeter`
+pub struct MeterAccumulator {
+    peak: f32,
+    mean_square: f32,
+    coeff: f32,
+}
+
+impl MeterAccumulator {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut meter = Self {
+            peak: 0.0,
+            mean_square: 0.0,
+            coeff: 0.0,
+        };
+        meter.set_sample_rate(sample_rate);
+        meter
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        let window_samples = RMS_WINDOW_MS * 0.001 * sample_rate;
+        self.coeff = (-1.0 / window_samples).exp();
+    }
+
+    pub fn reset(&mut self) {
+        self.peak = 0.0;
+        self.mean_square = 0.0;
+    }
+
+    #[inline]
+    pub fn add(&mut self, left: f32, right: f32) {
+        self.peak = self.peak.max(left.abs()).max(right.abs());
+        let square = (left * left + right * right) * 0.5;
+        self.mean_square = square + self.coeff * (self.mean_square - square);
+    }
+
+    /// Hand the levels of this buffer to the GUI
+    pub fn publish(&mut self, meter: &LevelMeter) {
+        meter.peak.fetch_max(self.peak);
+        meter.rms.store(self.mean_square.sqrt());
+        self.peak = 0.0;
+    }
+}
+
+/// Audio-thread side of the correlation meter
+pub struct CorrelationAccumulator {
+    left_right: f32,
+    left_square: f32,
+    right_square: f32,
+    coeff: f32,
+}
+
+impl CorrelationAccumulator {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut meter = Self {
+            left_right: 0.0,
+            left_square: 0.0,
+            right_square: 0.0,
+            coeff: 0.0,
+        };
+        meter.set_sample_rate(sample_rate);
+        meter
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        let window_samples = RMS_WINDOW_MS * 0.001 * sample_rate;
+        self.coeff = (-1.0 / window_samples).exp();
+    }
+
+    pub fn reset(&mut self) {
+        self.left_right = 0.0;
+        self.left_square = 0.0;
+        self.right_square = 0.0;
+    }
+
+    #[inline]
+    pub fn add(&mut self, left: f32, right: f32) {
+        self.left_right = left * right + self.coeff * (self.left_right - left * right);
+        self.left_square = left * left + self.coeff * (self.left_square - left * left);
+        self.right_square = right * right + self.coeff * (self.right_square - right * right);
+    }
+
+    /// Pearson correlation of the recent signal; silence reads as mono (1.0)
+    pub fn correlation(&self) -> f32 {
+        let energy = (self.left_square * self.right_square).sqrt();
+        if energy < 1e-10 {
+            return 1.0;
+        }
+        (self.left_right / energy).clamp(-1.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn test_atomic_f32_max_and_take() {
+        let value = AtomicF32::default();
+        value.fetch_max(0.25);
+        value.fetch_max(0.1);
+        assert_eq!(value.take(), 0.25);
+        assert_eq!(value.load(), 0.0);
+    }
+
+    #[cfg(feature = "editor")]
+    #[test]
+    fn test_tap_snapshot_is_oldest_first() {
+        let tap = SampleTap::default();
+        for i in 0..(TAP_SIZE + 3) {
+            tap.push(i as f32);
+        }
+        let samples = tap.snapshot();
+        assert_eq!(samples[0], 3.0);
+        assert_eq!(samples[TAP_SIZE - 1], (TAP_SIZE + 2) as f32);
+    }
+
+    #[test]
+    fn test_correlation() {
+        let mut mono = CorrelationAccumulator::new(48000.0);
+        let mut inverted = CorrelationAccumulator::new(48000.0);
+        for i in 0..4800 {
+            let x = (i as f32 * 0.05).sin();
+            mono.add(x, x);
+            inverted.add(x, -x);
+        }
+        assert!((mono.correlation() - 1.0).abs() < 0.001);
+        assert!((inverted.correlation() + 1.0).abs() < 0.001);
+        assert_eq!(CorrelationAccumulator::new(48000.0).correlation(), 1.0);
+    }
+
+    #[test]
+    fn test_sine_levels() {
+        let sample_rate = 48000.0;
+        let mut accumulator = MeterAccumulator::new(sample_rate);
+        let meter = LevelMeter::default();
+
+        for i in 0..48000 {
+            let x = (i as f32 * 440.0 * std::f32::consts::TAU / sample_rate).sin() * 0.5;
+            accumulator.add(x, x);
+        }
+        accumulator.publish(&meter);
+
+        assert!((meter.peak.load() - 0.5).abs() < 0.01);
+        assert!((meter.rms.load() - 0.5 / 2.0f32.sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cpu_meter_converges() {
+        let mut meter = CpuMeter::new();
+        let mut load = 0.0;
+        for _ in 0..200 {
+            load = meter.update(0.001, 0.004);
+        }
+        assert!((load - 0.25).abs() < 0.01);
+        // A zero-length buffer leaves the reading alone
+        assert_eq!(meter.update(0.001, 0.0), load);
+    }
+}
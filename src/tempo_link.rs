@@ -0,0 +1,129 @@
+//! Ableton Link tempo for the standalone app
+//!
+//! The standalone app has no host transport, so it joins the Link session
+//! on the local network instead: the Glicol engine's BPM, synced LFOs and
+//! the synced delay follow the session tempo, and a tempo set in the editor
+//! reaches every other peer. Link is on by default there and can be turned
+//! off under Settings, which falls back to 120 BPM. Plugin builds, and
+//! builds without the `standalone` feature, never join and use the host
+//! tempo as before.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "standalone")]
+use std::sync::OnceLock;
+
+/// Tempo a session this app starts runs at
+#[cfg(feature = "standalone")]
+const DEFAULT_TEMPO: f64 = 120.0;
+
+/// Link session membership, shared by the plugin and editor
+#[derive(Default)]
+pub struct TempoLink {
+    /// Created when running standalone, so plugin instances don't start
+    /// Link's network threads
+    #[cfg(feature = "standalone")]
+    link: OnceLock<rusty_link::AblLink>,
+    /// Running as the standalone app
+    available: AtomicBool,
+}
+
+/// The audio thread's copy of the session state, allocated up front
+pub struct LinkSession {
+    #[cfg(feature = "standalone")]
+    state: rusty_link::SessionState,
+}
+
+impl Default for LinkSession {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "standalone")]
+            state: rusty_link::SessionState::new(),
+        }
+    }
+}
+
+impl TempoLink {
+    /// Join the Link session (from `initialize()` of the standalone app)
+    pub fn start(&self) {
+        if self.available.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        #[cfg(feature = "standalone")]
+        self.link
+            .get_or_init(|| rusty_link::AblLink::new(DEFAULT_TEMPO))
+            .enable(true);
+    }
+
+    /// Session tempo for this block (audio thread), `None` while Link is off
+    ///
+    /// Capturing the audio session state doesn't allocate or block.
+    pub fn audio_tempo(&self, session: &mut LinkSession) -> Option<f64> {
+        #[cfg(feature = "standalone")]
+        if let Some(link) = self.link.get().filter(|link| link.is_enabled()) {
+            link.capture_audio_session_state(&mut session.state);
+            return Some(session.state.tempo());
+        }
+        #[cfg(not(feature = "standalone"))]
+        let _ = session;
+        None
+    }
+}
+
+/// Settings panel side
+#[cfg(feature = "editor")]
+impl TempoLink {
+    /// Whether Link can be used here (the standalone app)
+    pub fn is_available(&self) -> bool {
+        self.available.load(Ordering::Relaxed)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(feature = "standalone")]
+        if let Some(link) = self.link.get() {
+            return link.is_enabled();
+        }
+        false
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        #[cfg(feature = "standalone")]
+        if let Some(link) = self.link.get() {
+            link.enable(enabled);
+        }
+        #[cfg(not(feature = "standalone"))]
+        let _ = enabled;
+    }
+
+    /// Number of other apps in the session
+    pub fn peers(&self) -> u64 {
+        #[cfg(feature = "standalone")]
+        if let Some(link) = self.link.get() {
+            return link.num_peers();
+        }
+        0
+    }
+
+    /// Session tempo for the editor, `None` while Link is off
+    pub fn tempo(&self) -> Option<f64> {
+        #[cfg(feature = "standalone")]
+        if let Some(link) = self.link.get().filter(|link| link.is_enabled()) {
+            let mut state = rusty_link::SessionState::new();
+            link.capture_app_session_state(&mut state);
+            return Some(state.tempo());
+        }
+        None
+    }
+
+    /// Change the session tempo for every peer (editor thread)
+    pub fn set_tempo(&self, bpm: f64) {
+        #[cfg(feature = "standalone")]
+        if let Some(link) = self.link.get().filter(|link| link.is_enabled()) {
+            let mut state = rusty_link::SessionState::new();
+            link.capture_app_session_state(&mut state);
+            state.set_tempo(bpm, link.clock_micros());
+            link.commit_app_session_state(&state);
+        }
+        #[cfg(not(feature = "standalone"))]
+        let _ = bpm;
+    }
+}
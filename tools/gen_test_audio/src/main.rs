@@ -1,124 +1,180 @@
-use hound::{WavSpec, WavWriter};
-use std::f32::consts::PI;
+//! Test audio generator
+//!
+//! Writes Karplus-Strong plucked-string material to a WAV file. Without
+//! `--scale` it writes the plugin's E minor test arpeggio (the
+//! `test_audio/test_guitar.wav` loop); with a scale, notes from it are
+//! played in a pattern at a tempo for as long as asked.
+//!
+//! `--signal` switches to measurement signals (sweeps, noise, impulses) for
+//! reading frequency and impulse responses off rendered output.
+
+mod measure;
+mod sequence;
+
+use clap::{Parser, ValueEnum};
+use glicol_verb_core::dsp::test_signal::{arpeggio, pluck_sequence, ARPEGGIO_SECONDS};
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+use sequence::{arrange, parse_note, schedule, Pattern, Scale};
+
+/// What to generate
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Signal {
+    /// Plucked strings (see `--scale`)
+    Pluck,
+    /// Logarithmic sine sweep (see `--start-freq`, `--end-freq`)
+    Sweep,
+    White,
+    Pink,
+    /// Unit impulses (see `--interval`)
+    Impulse,
+}
 
-const SAMPLE_RATE: u32 = 44100;
+#[derive(Parser)]
+#[command(about = "Generate plucked-string and measurement test audio")]
+struct Args {
+    #[arg(long, value_enum, default_value_t = Signal::Pluck)]
+    signal: Signal,
 
-fn main() {
-    let spec = WavSpec {
-        channels: 1,
-        sample_rate: SAMPLE_RATE,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
-    };
+    /// Note set to play (default: the plugin's E minor arpeggio)
+    #[arg(long, value_enum)]
+    scale: Option<Scale>,
 
-    let output_path = "test_guitar.wav";
-    let mut writer = WavWriter::create(output_path, spec).expect("Failed to create WAV file");
-
-    // Generate a simple chord progression: E minor arpeggio
-    // Notes: E2 (82Hz), G2 (98Hz), B2 (123Hz), E3 (165Hz)
-    let notes = [
-        (82.41, 0.0),   // E2 at 0s
-        (98.00, 0.3),   // G2 at 0.3s
-        (123.47, 0.6),  // B2 at 0.6s
-        (164.81, 0.9),  // E3 at 0.9s
-        (196.00, 1.2),  // G3 at 1.2s
-        (246.94, 1.5),  // B3 at 1.5s
-        (329.63, 1.8),  // E4 at 1.8s
-        (246.94, 2.4),  // B3 at 2.4s
-        (196.00, 2.7),  // G3 at 2.7s
-        (164.81, 3.0),  // E3 at 3.0s
-        (123.47, 3.3),  // B2 at 3.3s
-        (98.00, 3.6),   // G2 at 3.6s
-        (82.41, 3.9),   // E2 at 3.9s
-    ];
-
-    let duration_secs = 5.0;
-    let total_samples = (SAMPLE_RATE as f32 * duration_secs) as usize;
-    let mut output = vec![0.0f32; total_samples];
-
-    // Generate each note using Karplus-Strong synthesis
-    for (freq, start_time) in notes {
-        let start_sample = (start_time * SAMPLE_RATE as f32) as usize;
-        let note_samples = karplus_strong(freq, 1.8, 0.996); // longer decay, high damping
-
-        for (i, &sample) in note_samples.iter().enumerate() {
-            let idx = start_sample + i;
-            if idx < total_samples {
-                output[idx] += sample * 0.4; // Mix level
-            }
-        }
-    }
+    /// Lowest note of the scale
+    #[arg(long, default_value = "E2")]
+    root: String,
 
-    // Normalize and add slight compression
-    let max_val = output.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
-    if max_val > 0.0 {
-        for sample in &mut output {
-            *sample /= max_val;
-            // Soft clipping for natural compression
-            *sample = (*sample * 0.8).tanh();
-        }
-    }
+    /// Octaves the scale spans
+    #[arg(long, default_value_t = 2)]
+    octaves: u32,
 
-    // Write to WAV
-    for sample in output {
-        let amplitude = (sample * i16::MAX as f32) as i16;
-        writer.write_sample(amplitude).expect("Failed to write sample");
-    }
+    /// Order the notes are played in
+    #[arg(long, value_enum, default_value_t = Pattern::UpDown)]
+    pattern: Pattern,
 
-    writer.finalize().expect("Failed to finalize WAV");
-    println!("Generated: {}", output_path);
-    println!("Duration: {}s, Sample rate: {}Hz, Mono", duration_secs, SAMPLE_RATE);
-}
+    /// Notes per minute (chords come every 4 beats)
+    #[arg(long, default_value_t = 200.0)]
+    tempo: f32,
 
-/// Karplus-Strong plucked string synthesis
-/// Returns samples for a single note
-fn karplus_strong(frequency: f32, duration_secs: f32, decay: f32) -> Vec<f32> {
-    let num_samples = (SAMPLE_RATE as f32 * duration_secs) as usize;
-    let period = (SAMPLE_RATE as f32 / frequency) as usize;
-
-    // Initialize with noise burst (simulates pick attack)
-    let mut buffer: Vec<f32> = (0..period)
-        .map(|i| {
-            // Mix of noise and initial harmonic content
-            let noise = (simple_hash(i as u32) as f32 / u32::MAX as f32) * 2.0 - 1.0;
-            let harmonic = (2.0 * PI * i as f32 / period as f32).sin();
-            noise * 0.7 + harmonic * 0.3
-        })
-        .collect();
-
-    let mut output = Vec::with_capacity(num_samples);
-    let mut index = 0;
-
-    // Attack envelope
-    let attack_samples = (0.003 * SAMPLE_RATE as f32) as usize; // 3ms attack
-
-    for i in 0..num_samples {
-        let sample = buffer[index];
-
-        // Apply attack envelope
-        let envelope = if i < attack_samples {
-            i as f32 / attack_samples as f32
-        } else {
-            1.0
-        };
+    /// Seed for `--pattern random` and the noise signals
+    #[arg(long, default_value_t = 1)]
+    seed: u32,
+
+    /// Sweep start frequency in Hz
+    #[arg(long, default_value_t = 20.0)]
+    start_freq: f32,
+
+    /// Sweep end frequency in Hz (capped below Nyquist)
+    #[arg(long, default_value_t = 20000.0)]
+    end_freq: f32,
+
+    /// Peak level of sweeps and noise in dBFS
+    #[arg(long, default_value_t = -6.0, allow_negative_numbers = true)]
+    level: f32,
+
+    /// Seconds between impulses (one impulse at the start when 0)
+    #[arg(long, default_value_t = 0.0)]
+    interval: f32,
+
+    /// Length in seconds; patterns loop to fill it
+    #[arg(long, default_value_t = ARPEGGIO_SECONDS)]
+    duration: f32,
+
+    #[arg(long, default_value_t = 44100)]
+    sample_rate: u32,
 
-        output.push(sample * envelope);
+    /// 16 or 24 for integer samples, 32 for float
+    #[arg(long, default_value_t = 16)]
+    bits: u16,
 
-        // Lowpass filter (average with next sample) + decay
-        let next_index = (index + 1) % period;
-        buffer[index] = (buffer[index] + buffer[next_index]) * 0.5 * decay;
+    /// WAV file to write
+    #[arg(short, long, default_value = "test_guitar.wav")]
+    output: String,
+}
 
-        index = next_index;
+fn main() {
+    let args = Args::parse();
+    if let Err(error) = run(&args) {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
     }
+}
 
-    output
+fn run(args: &Args) -> Result<(), String> {
+    if args.tempo <= 0.0 || args.duration <= 0.0 {
+        return Err("--tempo and --duration must be positive".to_string());
+    }
+    if ![16, 24, 32].contains(&args.bits) {
+        return Err("--bits must be 16, 24 or 32".to_string());
+    }
+    let sample_rate = args.sample_rate as f32;
+    let len = (sample_rate * args.duration) as usize;
+    let level = 10.0f32.powf(args.level / 20.0);
+    let samples = match args.signal {
+        Signal::Pluck => pluck(args, sample_rate)?,
+        Signal::Sweep => {
+            let end_freq = args.end_freq.min(sample_rate * 0.49);
+            if args.start_freq <= 0.0 || args.start_freq >= end_freq {
+                return Err("--start-freq must be above 0 and below --end-freq".to_string());
+            }
+            measure::log_sweep(args.start_freq, end_freq, sample_rate, args.duration, level)
+        }
+        Signal::White => measure::white_noise(len, args.seed, level),
+        Signal::Pink => measure::pink_noise(len, args.seed, level),
+        Signal::Impulse => measure::impulses(len, (args.interval * sample_rate) as usize),
+    };
+    write_wav(&args.output, &samples, args.sample_rate, args.bits)?;
+
+    println!("Generated: {}", args.output);
+    println!(
+        "Duration: {}s, Sample rate: {}Hz, {}-bit, Mono",
+        args.duration, args.sample_rate, args.bits
+    );
+    Ok(())
 }
 
-/// Simple deterministic hash for reproducible "random" noise
-fn simple_hash(mut x: u32) -> u32 {
-    x = x.wrapping_mul(0x45d9f3b);
-    x ^= x >> 16;
-    x = x.wrapping_mul(0x45d9f3b);
-    x ^= x >> 16;
-    x
+/// Plucked notes from the scale, or the plugin's arpeggio
+fn pluck(args: &Args, sample_rate: f32) -> Result<Vec<f32>, String> {
+    Ok(match args.scale {
+        Some(scale) => {
+            let notes = scale.notes(parse_note(&args.root)?, args.octaves.max(1));
+            let (pass, pass_beats) = arrange(&notes, args.pattern, args.seed);
+            let events = schedule(args.pattern, &pass, pass_beats, args.tempo, args.duration);
+            pluck_sequence(&events, sample_rate, args.duration)
+        }
+        None => {
+            // The arpeggio loops if asked for more than one pass
+            let pass = arpeggio(sample_rate);
+            let total = (sample_rate * args.duration) as usize;
+            pass.iter().copied().cycle().take(total).collect()
+        }
+    })
+}
+
+fn write_wav(path: &str, samples: &[f32], sample_rate: u32, bits: u16) -> Result<(), String> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: bits,
+        sample_format: if bits == 32 {
+            SampleFormat::Float
+        } else {
+            SampleFormat::Int
+        },
+    };
+    let write_error = |e: hound::Error| format!("Couldn't write {}: {}", path, e);
+    let mut writer = WavWriter::create(path, spec).map_err(write_error)?;
+    if bits == 32 {
+        for &sample in samples {
+            writer.write_sample(sample).map_err(write_error)?;
+        }
+    } else {
+        let scale = ((1i32 << (bits - 1)) - 1) as f32;
+        for &sample in samples {
+            writer
+                .write_sample((sample * scale) as i32)
+                .map_err(write_error)?;
+        }
+    }
+    writer.finalize().map_err(write_error)
 }
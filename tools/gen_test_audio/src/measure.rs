@@ -0,0 +1,159 @@
+//! Measurement signals: sine sweeps, noise and impulses
+//!
+//! Unlike the plucked material these are written exactly as generated (no
+//! normalizing or clipping), so responses can be computed from renders.
+
+use std::f32::consts::{PI, TAU};
+
+/// Fade at each end of a sweep, so it starts and stops without clicks
+const SWEEP_FADE_SECONDS: f32 = 0.005;
+
+/// Exponential (logarithmic) sine sweep from `start_freq` to `end_freq`
+///
+/// Spends equal time per octave, as used for deconvolving impulse responses.
+pub fn log_sweep(
+    start_freq: f32,
+    end_freq: f32,
+    sample_rate: f32,
+    seconds: f32,
+    level: f32,
+) -> Vec<f32> {
+    let len = (sample_rate * seconds) as usize;
+    let rate = (end_freq / start_freq).ln();
+    let fade = ((SWEEP_FADE_SECONDS * sample_rate) as usize).max(1);
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / sample_rate;
+            let phase = TAU * start_freq * seconds / rate * ((t / seconds * rate).exp() - 1.0);
+            let edge = i.min(len - 1 - i);
+            let gain = if edge < fade {
+                0.5 - 0.5 * (PI * edge as f32 / fade as f32).cos()
+            } else {
+                1.0
+            };
+            phase.sin() * gain * level
+        })
+        .collect()
+}
+
+/// Uniform white noise peaking at `level`
+pub fn white_noise(len: usize, seed: u32, level: f32) -> Vec<f32> {
+    let mut random = Random::new(seed);
+    (0..len).map(|_| random.next_bipolar() * level).collect()
+}
+
+/// Pink (-3 dB/octave) noise peaking at `level`
+///
+/// White noise through Paul Kellet's refined pink filter, scaled so the
+/// loudest sample sits at `level`.
+pub fn pink_noise(len: usize, seed: u32, level: f32) -> Vec<f32> {
+    let mut random = Random::new(seed);
+    let mut b = [0.0f32; 7];
+    let mut output: Vec<f32> = (0..len)
+        .map(|_| {
+            let white = random.next_bipolar();
+            b[0] = 0.99886 * b[0] + white * 0.0555179;
+            b[1] = 0.99332 * b[1] + white * 0.0750759;
+            b[2] = 0.96900 * b[2] + white * 0.1538520;
+            b[3] = 0.86650 * b[3] + white * 0.3104856;
+            b[4] = 0.55000 * b[4] + white * 0.5329522;
+            b[5] = -0.7616 * b[5] - white * 0.0168980;
+            let pink = b[..6].iter().sum::<f32>() + b[6] + white * 0.5362;
+            b[6] = white * 0.115926;
+            pink
+        })
+        .collect();
+    let peak = output.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
+    if peak > 0.0 {
+        for sample in &mut output {
+            *sample *= level / peak;
+        }
+    }
+    output
+}
+
+/// Unit impulses every `interval` samples (one at the start when 0)
+pub fn impulses(len: usize, interval: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| match interval {
+            0 => (i == 0) as u8 as f32,
+            _ => (i % interval == 0) as u8 as f32,
+        })
+        .collect()
+}
+
+/// xorshift32, reproducible for a given seed
+struct Random(u32);
+
+impl Random {
+    fn new(seed: u32) -> Self {
+        Self(seed.max(1))
+    }
+
+    /// Uniform in -1.0..=1.0
+    fn next_bipolar(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Zero crossings in `samples`, for a rough frequency reading
+    fn crossings(samples: &[f32]) -> usize {
+        samples
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count()
+    }
+
+    #[test]
+    fn test_sweep_rises_exponentially() {
+        let sample_rate = 48000.0;
+        let sweep = log_sweep(100.0, 1600.0, sample_rate, 4.0, 0.5);
+        assert_eq!(sweep.len(), 192000);
+        assert!(sweep.iter().all(|x| x.abs() <= 0.5));
+        // An octave a second: the last second is three octaves above the
+        // first, so it crosses zero about eight times as often
+        let second = sample_rate as usize;
+        let first = crossings(&sweep[..second]);
+        let last = crossings(&sweep[3 * second..]);
+        assert!((last as f32 / first as f32 - 8.0).abs() < 0.5);
+        // Faded in and out
+        assert_eq!(sweep[0], 0.0);
+        assert!(sweep[sweep.len() - 1].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_noise_is_reproducible_and_bounded() {
+        let white = white_noise(10000, 3, 0.5);
+        assert_eq!(white, white_noise(10000, 3, 0.5));
+        assert!(white.iter().all(|x| x.abs() <= 0.5));
+        let pink = pink_noise(10000, 3, 0.5);
+        let peak = pink.iter().map(|x| x.abs()).fold(0.0f32, f32::max);
+        assert!((peak - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pink_noise_has_less_top_end() {
+        // Differences emphasize highs: relatively smaller for pink noise
+        let roughness = |samples: &[f32]| {
+            let energy: f32 = samples.iter().map(|x| x * x).sum();
+            let diff: f32 = samples.windows(2).map(|p| (p[1] - p[0]).powi(2)).sum();
+            diff / energy
+        };
+        let white = white_noise(48000, 9, 0.5);
+        let pink = pink_noise(48000, 9, 0.5);
+        assert!(roughness(&pink) < roughness(&white) * 0.5);
+    }
+
+    #[test]
+    fn test_impulses() {
+        assert_eq!(impulses(5, 0), vec![1.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(impulses(5, 2), vec![1.0, 0.0, 1.0, 0.0, 1.0]);
+    }
+}
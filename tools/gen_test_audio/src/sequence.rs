@@ -0,0 +1,202 @@
+//! Note sequences: scales, patterns and note names
+
+use clap::ValueEnum;
+
+/// Note sets to build sequences from
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Scale {
+    Major,
+    Minor,
+    Pentatonic,
+    Blues,
+    Chromatic,
+    MajorTriad,
+    MinorTriad,
+}
+
+impl Scale {
+    /// Semitones above the root within one octave
+    fn intervals(self) -> &'static [i32] {
+        match self {
+            Self::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Self::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Self::Pentatonic => &[0, 3, 5, 7, 10],
+            Self::Blues => &[0, 3, 5, 6, 7, 10],
+            Self::Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            Self::MajorTriad => &[0, 4, 7],
+            Self::MinorTriad => &[0, 3, 7],
+        }
+    }
+
+    /// MIDI notes from `root` up through `octaves` octaves, ending on the
+    /// root at the top
+    pub fn notes(self, root: i32, octaves: u32) -> Vec<i32> {
+        let mut notes: Vec<i32> = (0..octaves as i32)
+            .flat_map(|octave| {
+                self.intervals()
+                    .iter()
+                    .map(move |interval| root + octave * 12 + interval)
+            })
+            .collect();
+        notes.push(root + octaves as i32 * 12);
+        notes
+    }
+}
+
+/// Order the scale's notes are played in
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Pattern {
+    Up,
+    Down,
+    /// Up, then back down without repeating the ends
+    UpDown,
+    /// Random notes from the scale (see `--seed`)
+    Random,
+    /// Every note strummed together, once a bar
+    Chord,
+}
+
+/// Delay between strings in a strummed chord
+const STRUM_SECONDS: f32 = 0.015;
+
+/// Beats per chord in `Pattern::Chord`
+const CHORD_BEATS: f32 = 4.0;
+
+/// One pass of `notes` in `pattern`: (MIDI note, start in beats), and the
+/// pass length in beats
+pub fn arrange(notes: &[i32], pattern: Pattern, seed: u32) -> (Vec<(i32, f32)>, f32) {
+    let ordered: Vec<i32> = match pattern {
+        Pattern::Up => notes.to_vec(),
+        Pattern::Down => notes.iter().rev().copied().collect(),
+        Pattern::UpDown => {
+            let mut ordered = notes.to_vec();
+            if notes.len() > 2 {
+                ordered.extend(notes[1..notes.len() - 1].iter().rev());
+            }
+            ordered
+        }
+        Pattern::Random => {
+            let mut state = seed.max(1);
+            (0..notes.len())
+                .map(|_| {
+                    // xorshift32
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    notes[state as usize % notes.len()]
+                })
+                .collect()
+        }
+        Pattern::Chord => {
+            let chord = notes.iter().map(|&note| (note, 0.0)).collect();
+            return (chord, CHORD_BEATS);
+        }
+    };
+    let len = ordered.len() as f32;
+    let events = ordered
+        .into_iter()
+        .enumerate()
+        .map(|(beat, note)| (note, beat as f32))
+        .collect();
+    (events, len)
+}
+
+/// Loop one pass over `seconds`: (frequency, start in seconds)
+pub fn schedule(
+    pattern: Pattern,
+    pass: &[(i32, f32)],
+    pass_beats: f32,
+    tempo: f32,
+    seconds: f32,
+) -> Vec<(f32, f32)> {
+    let beat_seconds = 60.0 / tempo;
+    let pass_seconds = pass_beats * beat_seconds;
+    let mut events = Vec::new();
+    let mut pass_start = 0.0;
+    while pass_start < seconds {
+        for (string, &(note, beat)) in pass.iter().enumerate() {
+            let mut start = pass_start + beat * beat_seconds;
+            if pattern == Pattern::Chord {
+                start += string as f32 * STRUM_SECONDS;
+            }
+            if start < seconds {
+                events.push((midi_to_freq(note), start));
+            }
+        }
+        pass_start += pass_seconds;
+    }
+    events
+}
+
+pub fn midi_to_freq(note: i32) -> f32 {
+    440.0 * 2.0f32.powf((note - 69) as f32 / 12.0)
+}
+
+/// MIDI note of a name like `E2`, `F#3` or `Bb1`
+pub fn parse_note(name: &str) -> Result<i32, String> {
+    let invalid = || format!("'{}' isn't a note like E2, F#3 or Bb1", name);
+    let mut chars = name.chars();
+    let pitch_class = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some('C') => 0,
+        Some('D') => 2,
+        Some('E') => 4,
+        Some('F') => 5,
+        Some('G') => 7,
+        Some('A') => 9,
+        Some('B') => 11,
+        _ => return Err(invalid()),
+    };
+    let rest = chars.as_str();
+    let (accidental, octave) = match rest.strip_prefix('#') {
+        Some(octave) => (1, octave),
+        None => match rest.strip_prefix('b') {
+            Some(octave) => (-1, octave),
+            None => (0, rest),
+        },
+    };
+    let octave: i32 = octave.parse().map_err(|_| invalid())?;
+    Ok((octave + 1) * 12 + pitch_class + accidental)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note() {
+        assert_eq!(parse_note("A4"), Ok(69));
+        assert_eq!(parse_note("E2"), Ok(40));
+        assert_eq!(parse_note("F#3"), Ok(54));
+        assert_eq!(parse_note("bb1"), Ok(34));
+        assert!(parse_note("H2").is_err());
+        assert!(parse_note("E").is_err());
+    }
+
+    #[test]
+    fn test_minor_triad_up_down() {
+        // The plugin's arpeggio: E2 G2 B2 E3 G3 B3 E4 and back down
+        let notes = Scale::MinorTriad.notes(40, 2);
+        assert_eq!(notes, vec![40, 43, 47, 52, 55, 59, 64]);
+        let (pass, beats) = arrange(&notes, Pattern::UpDown, 1);
+        let order: Vec<i32> = pass.iter().map(|&(note, _)| note).collect();
+        assert_eq!(order, vec![40, 43, 47, 52, 55, 59, 64, 59, 55, 52, 47, 43]);
+        assert_eq!(beats, 12.0);
+    }
+
+    #[test]
+    fn test_schedule_loops_to_fill() {
+        let (pass, beats) = arrange(&[60, 64], Pattern::Up, 1);
+        // Two beats a pass at 120 BPM: notes every 0.5 s
+        let events = schedule(Pattern::Up, &pass, beats, 120.0, 2.0);
+        let starts: Vec<f32> = events.iter().map(|&(_, start)| start).collect();
+        assert_eq!(starts, vec![0.0, 0.5, 1.0, 1.5]);
+    }
+
+    #[test]
+    fn test_random_stays_in_scale() {
+        let notes = Scale::Pentatonic.notes(45, 1);
+        let (pass, _) = arrange(&notes, Pattern::Random, 7);
+        assert!(pass.iter().all(|(note, _)| notes.contains(note)));
+        assert_eq!(pass, arrange(&notes, Pattern::Random, 7).0);
+    }
+}
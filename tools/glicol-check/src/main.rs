@@ -0,0 +1,364 @@
+//! Patch and preset validator
+//!
+//! Checks `.glicol` files and `.glicolpreset` exports (or folders of them):
+//! each patch goes through the editor's validation pass, is compiled, and
+//! is rendered for a moment with the Glicol module alone (no limiter to hide
+//! anything) on a silent input. Patches that fail to compile, output NaN or
+//! infinity, or get louder than `--max-peak` fail the check; the exit code
+//! is 1 if any patch failed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use glicol_verb_core::dsp::StereoSample;
+use glicol_verb_core::engine::{preprocess, validate, PARAM_NAMES, PARAM_RANGES};
+use glicol_verb_core::render::{render_offline, RenderParams};
+use serde::Deserialize;
+
+/// Extension of plain patches
+const PATCH_EXTENSION: &str = "glicol";
+
+/// Extension of exported presets (see the plugin's `preset::file`)
+const PRESET_EXTENSION: &str = "glicolpreset";
+
+/// Tag and newest version of preset files this tool reads
+const PRESET_FORMAT: &str = "glicol-verb-preset";
+const PRESET_VERSION: u32 = 1;
+
+const SAMPLE_RATE: f32 = 48000.0;
+
+#[derive(Parser)]
+#[command(about = "Check Glicol patches and GlicolVerb presets for errors and runaway output")]
+struct Args {
+    /// Patch and preset files, or folders to search
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Seconds to render each patch
+    #[arg(long, default_value_t = 2.0)]
+    seconds: f32,
+
+    /// Start the input with a unit impulse, to excite feedback paths
+    #[arg(long)]
+    impulse: bool,
+
+    /// Also render with every knob at the bottom and top of its range
+    #[arg(long)]
+    extremes: bool,
+
+    /// Loudest peak allowed, in dBFS
+    #[arg(long, default_value_t = 6.0, allow_negative_numbers = true)]
+    max_peak: f32,
+
+    /// Folder of `.glicol` snippets for `#include "name"`
+    #[arg(long, value_name = "DIR")]
+    include: Option<PathBuf>,
+
+    /// Fail on warnings too
+    #[arg(long)]
+    deny_warnings: bool,
+}
+
+/// What a check found
+#[derive(Debug, Default, PartialEq)]
+struct Report {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// Knob settings a patch is rendered with
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Setting {
+    Defaults,
+    Minimum,
+    Maximum,
+}
+
+impl Setting {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Defaults => "default knobs",
+            Self::Minimum => "knobs at minimum",
+            Self::Maximum => "knobs at maximum",
+        }
+    }
+}
+
+/// Peak level and whether any sample wasn't a number
+#[derive(Debug, PartialEq)]
+struct Levels {
+    peak: f32,
+    non_finite: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    let snippets = match &args.include {
+        Some(dir) => match load_snippets(dir) {
+            Ok(snippets) => snippets,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                std::process::exit(1);
+            }
+        },
+        None => BTreeMap::new(),
+    };
+
+    let mut files = Vec::new();
+    for path in &args.paths {
+        if let Err(error) = collect_files(path, &mut files) {
+            eprintln!("error: {}", error);
+            std::process::exit(1);
+        }
+    }
+
+    let mut failed = 0;
+    for file in &files {
+        let report = check_file(file, &args, &snippets);
+        for warning in &report.warnings {
+            println!("{}: warning: {}", file.display(), warning);
+        }
+        for error in &report.errors {
+            println!("{}: error: {}", file.display(), error);
+        }
+        if !report.errors.is_empty() || (args.deny_warnings && !report.warnings.is_empty()) {
+            failed += 1;
+        }
+    }
+    println!("Checked {} patches, {} failed", files.len(), failed);
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// `path` itself, or the patches and presets under it
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let read_error = |e: std::io::Error| format!("Couldn't read {}: {}", path.display(), e);
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+        .map_err(read_error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()
+        .map_err(read_error)?;
+    entries.sort();
+    for entry in entries {
+        let is_patch = entry
+            .extension()
+            .is_some_and(|ext| ext == PATCH_EXTENSION || ext == PRESET_EXTENSION);
+        if entry.is_dir() {
+            collect_files(&entry, files)?;
+        } else if is_patch {
+            files.push(entry);
+        }
+    }
+    Ok(())
+}
+
+fn check_file(path: &Path, args: &Args, snippets: &BTreeMap<String, String>) -> Report {
+    let mut report = Report::default();
+    let code = match std::fs::read_to_string(path) {
+        Ok(text) if path.extension().is_some_and(|ext| ext == PRESET_EXTENSION) => {
+            preset_code(&text)
+        }
+        Ok(text) => Ok((text, BTreeMap::new())),
+        Err(e) => Err(format!("Couldn't read: {}", e)),
+    };
+    match code {
+        Ok((code, bundled)) if bundled.is_empty() => check_code(&code, args, snippets, &mut report),
+        Ok((code, bundled)) => {
+            // Like the plugin, snippets in the library win over bundled ones
+            let mut snippets = snippets.clone();
+            for (name, snippet) in bundled {
+                snippets.entry(name).or_insert(snippet);
+            }
+            check_code(&code, args, &snippets, &mut report)
+        }
+        Err(error) => report.errors.push(error),
+    }
+    report
+}
+
+/// The code of a preset file and the snippets bundled with it
+fn preset_code(json: &str) -> Result<(String, BTreeMap<String, String>), String> {
+    #[derive(Deserialize)]
+    struct PresetFile {
+        format: String,
+        version: u32,
+        preset: Preset,
+    }
+    #[derive(Deserialize)]
+    struct Preset {
+        code: String,
+        #[serde(default)]
+        snippets: BTreeMap<String, String>,
+    }
+    let file: PresetFile =
+        serde_json::from_str(json).map_err(|e| format!("Not a valid preset file: {}", e))?;
+    if file.format != PRESET_FORMAT {
+        return Err(format!("Unknown preset format '{}'", file.format));
+    }
+    if file.version > PRESET_VERSION {
+        return Err(format!(
+            "Preset file version {} is newer than supported ({})",
+            file.version, PRESET_VERSION
+        ));
+    }
+    Ok((file.preset.code, file.preset.snippets))
+}
+
+fn check_code(code: &str, args: &Args, snippets: &BTreeMap<String, String>, report: &mut Report) {
+    let expanded = match preprocess(code, snippets) {
+        Ok(expanded) => expanded,
+        Err(error) => return report.errors.push(error),
+    };
+    match validate(&expanded) {
+        Ok(Some(warning)) => report.warnings.push(warning),
+        Ok(None) => {}
+        Err(error) => return report.errors.push(error),
+    }
+
+    let mut input = vec![0.0; (args.seconds.max(0.0) * SAMPLE_RATE) as usize];
+    if args.impulse {
+        if let Some(first) = input.first_mut() {
+            *first = 1.0;
+        }
+    }
+    let settings: &[Setting] = if args.extremes {
+        &[Setting::Defaults, Setting::Minimum, Setting::Maximum]
+    } else {
+        &[Setting::Defaults]
+    };
+    let max_peak = 10.0f32.powf(args.max_peak / 20.0);
+    for &setting in settings {
+        let params = render_params(setting, snippets);
+        let output = match render_offline(&input, code, &params) {
+            Ok(output) => output,
+            Err(e) => {
+                let location = match (e.line, e.column) {
+                    (Some(line), Some(column)) => format!("line {}:{}: ", line, column),
+                    (Some(line), None) => format!("line {}: ", line),
+                    _ => String::new(),
+                };
+                // Compile errors don't depend on the knobs
+                return report.errors.push(format!("{}{}", location, e.message));
+            }
+        };
+        let levels = measure(&output);
+        if levels.non_finite {
+            report
+                .errors
+                .push(format!("outputs NaN or infinity ({})", setting.label()));
+        } else if levels.peak > max_peak {
+            report.errors.push(format!(
+                "peaks at {:+.1} dBFS, above {:+.1} ({})",
+                20.0 * levels.peak.log10(),
+                args.max_peak,
+                setting.label()
+            ));
+        }
+    }
+}
+
+/// The Glicol module alone, fully wet, with the knobs at `setting`
+fn render_params(setting: Setting, snippets: &BTreeMap<String, String>) -> RenderParams {
+    let mut params = RenderParams::new(SAMPLE_RATE);
+    params.snippets = snippets.clone();
+    for (name, (min, default, max)) in PARAM_NAMES.iter().zip(PARAM_RANGES) {
+        let value = match setting {
+            Setting::Defaults => default,
+            Setting::Minimum => min,
+            Setting::Maximum => max,
+        };
+        params.injector.set(name, value);
+    }
+    params
+}
+
+fn measure(output: &[StereoSample]) -> Levels {
+    output.iter().fold(
+        Levels {
+            peak: 0.0,
+            non_finite: false,
+        },
+        |levels, sample| Levels {
+            peak: levels.peak.max(sample.left.abs()).max(sample.right.abs()),
+            non_finite: levels.non_finite || !sample.left.is_finite() || !sample.right.is_finite(),
+        },
+    )
+}
+
+/// Every `*.glicol` file in `dir`, named by its file stem
+fn load_snippets(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let read_error = |e: std::io::Error| format!("Couldn't read {}: {}", dir.display(), e);
+    let mut snippets = BTreeMap::new();
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.extension().is_some_and(|ext| ext == PATCH_EXTENSION) {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            let code = std::fs::read_to_string(&path).map_err(read_error)?;
+            snippets.insert(name.into_owned(), code);
+        }
+    }
+    Ok(snippets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_code() {
+        let json = r#"{"format": "glicol-verb-preset", "version": 1,
+            "preset": {"name": "Test", "code": "out: ~input", "params": {}}}"#;
+        assert_eq!(
+            preset_code(json),
+            Ok(("out: ~input".to_string(), BTreeMap::new()))
+        );
+        let bundled = json.replace(
+            r#""params": {}"#,
+            r#""params": {}, "snippets": {"wobble": "~wobble: sin 2.0"}"#,
+        );
+        assert_eq!(
+            preset_code(&bundled).unwrap().1["wobble"],
+            "~wobble: sin 2.0"
+        );
+        assert!(preset_code(&json.replace("glicol-verb-preset", "other")).is_err());
+        assert!(preset_code(&json.replace("\"version\": 1", "\"version\": 99")).is_err());
+        assert!(preset_code("{}").is_err());
+    }
+
+    #[test]
+    fn test_measure() {
+        let levels = measure(&[StereoSample::new(0.5, -0.75), StereoSample::new(0.1, 0.2)]);
+        assert_eq!(
+            levels,
+            Levels {
+                peak: 0.75,
+                non_finite: false
+            }
+        );
+        assert!(measure(&[StereoSample::new(f32::NAN, 0.0)]).non_finite);
+        assert!(measure(&[StereoSample::new(0.0, f32::INFINITY)]).non_finite);
+    }
+
+    #[test]
+    fn test_knob_settings() {
+        let params = render_params(Setting::Maximum, &BTreeMap::new());
+        assert_eq!((params.injector.knob1, params.injector.drive), (1.0, 10.0));
+        let params = render_params(Setting::Defaults, &BTreeMap::new());
+        assert_eq!(params.injector.knob1, 0.5);
+    }
+
+    #[test]
+    fn test_validation_errors_skip_the_render() {
+        let args = Args::parse_from(["glicol-check", "patch.glicol"]);
+        let mut report = Report::default();
+        check_code("~out: ~input", &args, &BTreeMap::new(), &mut report);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.warnings.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+//! Offline patch renderer
+//!
+//! Runs a WAV file through a `.glicol` patch and the plugin's signal chain
+//! (`glicol_verb_core::render::render_offline`) and writes the result, for
+//! regression tests and for sharing what a patch sounds like. The input is
+//! mixed down to mono like the plugin's input and rendered at its own
+//! sample rate; the output is stereo.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use glicol_verb_core::dsp::eq::EqBands;
+use glicol_verb_core::dsp::test_signal::TestSample;
+use glicol_verb_core::dsp::StereoSample;
+use glicol_verb_core::engine::{KNOB_COUNT, PARAM_NAMES};
+use glicol_verb_core::render::{render_offline, DelaySettings, RenderParams};
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+#[derive(Parser)]
+#[command(about = "Render a WAV file through a Glicol patch and the GlicolVerb chain")]
+struct Args {
+    /// WAV file to process
+    input: PathBuf,
+
+    /// Glicol code to run
+    code: PathBuf,
+
+    /// WAV file to write
+    #[arg(short, long, default_value = "rendered.wav")]
+    output: PathBuf,
+
+    /// Value for an injected parameter, e.g. `knob1=0.5` or `drive=2` (repeatable)
+    #[arg(short, long = "param", value_name = "NAME=VALUE")]
+    params: Vec<String>,
+
+    /// Custom name for a knob, e.g. `3=shimmer` for `~shimmer` (repeatable)
+    #[arg(long = "knob-name", value_name = "N=NAME")]
+    knob_names: Vec<String>,
+
+    /// Folder of `.glicol` snippets for `#include "name"`
+    #[arg(long, value_name = "DIR")]
+    include: Option<PathBuf>,
+
+    /// Input gain in dB
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    input_gain: f32,
+
+    /// Output gain in dB
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    output_gain: f32,
+
+    /// 0 = dry only, 1 = wet only
+    #[arg(long, default_value_t = 1.0)]
+    dry_wet: f32,
+
+    /// Stereo width of the wet signal (0 = mono, 1 = unchanged, 2 = wide)
+    #[arg(long, default_value_t = 1.0)]
+    width: f32,
+
+    /// Engine oversampling factor (1, 2 or 4)
+    #[arg(long, default_value_t = 1)]
+    oversampling: usize,
+
+    /// Run the output limiter
+    #[arg(long)]
+    limiter: bool,
+
+    /// EQ low shelf as FREQ:GAIN_DB (any EQ band turns the EQ on)
+    #[arg(long, value_name = "FREQ:GAIN")]
+    eq_low: Option<String>,
+
+    /// EQ mid peak as FREQ:GAIN_DB:Q
+    #[arg(long, value_name = "FREQ:GAIN:Q")]
+    eq_mid: Option<String>,
+
+    /// EQ high shelf as FREQ:GAIN_DB
+    #[arg(long, value_name = "FREQ:GAIN")]
+    eq_high: Option<String>,
+
+    /// Delay time in ms (turns the delay on)
+    #[arg(long, value_name = "MS")]
+    delay: Option<f32>,
+
+    #[arg(long, default_value_t = 0.3)]
+    delay_feedback: f32,
+
+    #[arg(long, default_value_t = 0.5)]
+    delay_mix: f32,
+
+    /// Delay high-cut in Hz
+    #[arg(long, default_value_t = 12000.0)]
+    delay_highcut: f32,
+
+    /// Seconds of silence rendered after the input, for tails
+    #[arg(long, default_value_t = 0.0)]
+    tail: f32,
+
+    /// 16 or 24 for integer samples, 32 for float
+    #[arg(long, default_value_t = 32)]
+    bits: u16,
+}
+
+fn main() {
+    let args = Args::parse();
+    if let Err(error) = run(&args) {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    if ![16, 24, 32].contains(&args.bits) {
+        return Err("--bits must be 16, 24 or 32".to_string());
+    }
+    let code = std::fs::read_to_string(&args.code)
+        .map_err(|e| format!("Couldn't read {}: {}", args.code.display(), e))?;
+    let input = TestSample::load(&args.input)?;
+    let params = render_params(args, input.sample_rate)?;
+
+    let mut samples = input.samples;
+    samples.resize(
+        samples.len() + (args.tail.max(0.0) * input.sample_rate) as usize,
+        0.0,
+    );
+    let output = render_offline(&samples, &code, &params).map_err(|e| {
+        // file:line:col, as compilers print them
+        let mut location = args.code.display().to_string();
+        if let Some(line) = e.line {
+            location.push_str(&format!(":{}", line));
+            if let Some(column) = e.column {
+                location.push_str(&format!(":{}", column));
+            }
+        }
+        format!("{}: {}", location, e.message)
+    })?;
+
+    write_wav(&args.output, &output, input.sample_rate as u32, args.bits)?;
+    println!(
+        "Rendered {} through {} to {} ({:.2}s)",
+        args.input.display(),
+        args.code.display(),
+        args.output.display(),
+        output.len() as f32 / input.sample_rate
+    );
+    Ok(())
+}
+
+fn render_params(args: &Args, sample_rate: f32) -> Result<RenderParams, String> {
+    let mut params = RenderParams::new(sample_rate);
+    params.input_gain = db_to_gain(args.input_gain);
+    params.output_gain = db_to_gain(args.output_gain);
+    params.dry_wet = args.dry_wet.clamp(0.0, 1.0);
+    params.width = args.width.clamp(0.0, 2.0);
+    if ![1, 2, 4].contains(&args.oversampling) {
+        return Err("--oversampling must be 1, 2 or 4".to_string());
+    }
+    params.oversampling = args.oversampling;
+    params.limiter = args.limiter;
+
+    for assignment in &args.params {
+        let (name, value) = parse_assignment(assignment)?;
+        let value = value
+            .parse()
+            .map_err(|_| format!("'{}' needs a number after '='", assignment))?;
+        if !params.injector.set(name, value) {
+            return Err(format!(
+                "Unknown parameter '{}' (one of {})",
+                name,
+                PARAM_NAMES.join(", ")
+            ));
+        }
+    }
+    for assignment in &args.knob_names {
+        let (knob, name) = parse_assignment(assignment)?;
+        match knob.parse::<usize>() {
+            Ok(knob) if (1..=KNOB_COUNT).contains(&knob) => {
+                params.injector.knob_names.set(knob - 1, name)
+            }
+            _ => {
+                return Err(format!(
+                    "'{}' needs a knob from 1 to {}",
+                    assignment, KNOB_COUNT
+                ))
+            }
+        }
+    }
+    if let Some(dir) = &args.include {
+        params.snippets = load_snippets(dir)?;
+    }
+    params.eq = eq_bands(args)?;
+    params.delay = args.delay.map(|time_ms| DelaySettings {
+        time_ms,
+        feedback: args.delay_feedback,
+        mix: args.delay_mix,
+        highcut: args.delay_highcut,
+    });
+    Ok(params)
+}
+
+/// The EQ from `--eq-low/mid/high`, `None` when no band is given
+///
+/// Bands left out are off; the others default to the plugin's settings.
+fn eq_bands(args: &Args) -> Result<Option<EqBands>, String> {
+    if args.eq_low.is_none() && args.eq_mid.is_none() && args.eq_high.is_none() {
+        return Ok(None);
+    }
+    let mut bands = EqBands {
+        low_freq: 200.0,
+        low_gain: 0.0,
+        mid_freq: 1000.0,
+        mid_gain: 0.0,
+        mid_q: 1.0,
+        high_freq: 4000.0,
+        high_gain: 0.0,
+        low_enabled: false,
+        mid_enabled: false,
+        high_enabled: false,
+    };
+    if let Some(low) = &args.eq_low {
+        let [freq, gain] = parse_numbers(low, "--eq-low")?;
+        (bands.low_freq, bands.low_gain, bands.low_enabled) = (freq, gain, true);
+    }
+    if let Some(mid) = &args.eq_mid {
+        let [freq, gain, q] = parse_numbers(mid, "--eq-mid")?;
+        (bands.mid_freq, bands.mid_gain, bands.mid_q) = (freq, gain, q);
+        bands.mid_enabled = true;
+    }
+    if let Some(high) = &args.eq_high {
+        let [freq, gain] = parse_numbers(high, "--eq-high")?;
+        (bands.high_freq, bands.high_gain, bands.high_enabled) = (freq, gain, true);
+    }
+    Ok(Some(bands))
+}
+
+/// Every `*.glicol` file in `dir`, named by its file stem
+fn load_snippets(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let read_error = |e: std::io::Error| format!("Couldn't read {}: {}", dir.display(), e);
+    let mut snippets = BTreeMap::new();
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.extension().is_some_and(|ext| ext == "glicol") {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            let code = std::fs::read_to_string(&path).map_err(read_error)?;
+            snippets.insert(name.into_owned(), code);
+        }
+    }
+    Ok(snippets)
+}
+
+/// Split `name=value`
+fn parse_assignment(assignment: &str) -> Result<(&str, &str), String> {
+    assignment
+        .split_once('=')
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .ok_or_else(|| format!("Expected NAME=VALUE, got '{}'", assignment))
+}
+
+/// `N` colon-separated numbers, e.g. `200:-3`
+fn parse_numbers<const N: usize>(text: &str, option: &str) -> Result<[f32; N], String> {
+    let invalid = || format!("{} expects {} numbers separated by ':'", option, N);
+    let numbers: Vec<f32> = text
+        .split(':')
+        .map(|part| part.trim().parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    numbers.try_into().map_err(|_| invalid())
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
+}
+
+fn write_wav(
+    path: &Path,
+    samples: &[StereoSample],
+    sample_rate: u32,
+    bits: u16,
+) -> Result<(), String> {
+    let spec = WavSpec {
+        channels: 2,
+        sample_rate,
+        bits_per_sample: bits,
+        sample_format: if bits == 32 {
+            SampleFormat::Float
+        } else {
+            SampleFormat::Int
+        },
+    };
+    let write_error = |e: hound::Error| format!("Couldn't write {}: {}", path.display(), e);
+    let mut writer = WavWriter::create(path, spec).map_err(write_error)?;
+    let scale = if bits == 32 {
+        1.0
+    } else {
+        ((1i32 << (bits - 1)) - 1) as f32
+    };
+    for sample in samples {
+        for value in [sample.left, sample.right] {
+            if bits == 32 {
+                writer.write_sample(value).map_err(write_error)?;
+            } else {
+                let value = (value.clamp(-1.0, 1.0) * scale) as i32;
+                writer.write_sample(value).map_err(write_error)?;
+            }
+        }
+    }
+    writer.finalize().map_err(write_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(parse_assignment("knob1 = 0.5"), Ok(("knob1", "0.5")));
+        assert!(parse_assignment("knob1").is_err());
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        assert_eq!(parse_numbers::<2>("200:-3", "--eq-low"), Ok([200.0, -3.0]));
+        assert_eq!(
+            parse_numbers::<3>("1000:4:0.7", "--eq-mid"),
+            Ok([1000.0, 4.0, 0.7])
+        );
+        assert!(parse_numbers::<3>("1000:4", "--eq-mid").is_err());
+        assert!(parse_numbers::<2>("low:4", "--eq-low").is_err());
+    }
+
+    #[test]
+    fn test_params_from_args() {
+        let args = Args::parse_from([
+            "glicol-render",
+            "in.wav",
+            "patch.glicol",
+            "-p",
+            "drive=2",
+            "--knob-name",
+            "3=shimmer",
+            "--eq-mid",
+            "800:3:2",
+            "--delay",
+            "300",
+            "--input-gain",
+            "-6",
+        ]);
+        let params = render_params(&args, 48000.0).unwrap();
+        assert_eq!(params.injector.drive, 2.0);
+        assert_eq!(params.injector.knob_names.get(2), Some("shimmer"));
+        let eq = params.eq.unwrap();
+        assert!(eq.mid_enabled && !eq.low_enabled && !eq.high_enabled);
+        assert_eq!((eq.mid_freq, eq.mid_gain, eq.mid_q), (800.0, 3.0, 2.0));
+        assert_eq!(params.delay.map(|delay| delay.time_ms), Some(300.0));
+        assert!((params.input_gain - 0.501).abs() < 0.001);
+
+        let args = Args::parse_from(["glicol-render", "in.wav", "p.glicol", "-p", "tone=1"]);
+        assert!(render_params(&args, 48000.0).is_err());
+    }
+}